@@ -3,6 +3,7 @@ use std::fs;
 use utoipa::OpenApi;
 
 use audiocloud_api::api::*;
+use audiocloud_api::examples::examples;
 
 fn export_cloud_openapi() {
     use audiocloud_api::cloud::*;
@@ -21,29 +22,17 @@ fn export_cloud_openapi() {
 }
 
 fn export_audio_engine_openapi() {
-    use audiocloud_api::audio_engine::*;
+    use audiocloud_api::audio_engine::openapi;
 
     fs::write("openapi_audio_engine.json",
-              openapi_with_schemas_to_json(EngineApi::openapi(), schemas(), json!([
-                  openapi_set_version("3.1.0"),
-                  openapi_add_apache_license(),
-                  openapi_set_info_title("Audio Cloud Audio Engine"),
-                  openapi_create_empty_servers(),
-                  openapi_add_server("http://localhost:7300", "Local development")
-              ])).expect("API convert to JSON")).expect("Write JSON to file");
+              openapi([("http://localhost:7300", "Local development")]).expect("API convert to JSON")).expect("Write JSON to file");
 }
 
 fn export_instance_driver_openapi() {
-    use audiocloud_api::instance_driver::*;
+    use audiocloud_api::instance_driver::openapi;
 
     fs::write("openapi_instance_driver.json",
-              openapi_with_schemas_to_json(InstanceDriverApi::openapi(), schemas(), json!([
-                  openapi_set_version("3.1.0"),
-                  openapi_add_apache_license(),
-                  openapi_set_info_title("Audio Cloud Instance Driver"),
-                  openapi_create_empty_servers(),
-                  openapi_add_server("http://localhost:7400", "Local development")
-              ])).expect("API convert to JSON")).expect("Write JSON to file");
+              openapi([("http://localhost:7400", "Local development")]).expect("API convert to JSON")).expect("Write JSON to file");
 }
 
 fn export_domain_openapi() {
@@ -60,9 +49,35 @@ fn export_domain_openapi() {
               ])).expect("API convert to JSON")).expect("Write JSON to file");
 }
 
+#[cfg(feature = "chaos")]
+fn export_chaos_openapi() {
+    use audiocloud_api::domain::chaos::*;
+
+    fs::write("openapi_chaos.json",
+              openapi_with_schemas_to_json(ChaosApi::openapi(), schemas(), json!([
+                  openapi_set_version("3.1.0"),
+                  openapi_add_apache_license(),
+                  openapi_set_info_title("Audio Cloud Domain Failure Injection"),
+                  openapi_create_empty_servers(),
+                  openapi_add_server("http://localhost:7200", "Local development")
+              ])).expect("API convert to JSON")).expect("Write JSON to file");
+}
+
+fn export_examples() {
+    let examples = examples().into_iter()
+                             .map(|(name, (schema, example))| (name, json!({ "schema": schema, "example": example })))
+                             .collect::<serde_json::Map<_, _>>();
+
+    fs::write("examples.json",
+              serde_json::to_string_pretty(&examples).expect("Examples convert to JSON")).expect("Write JSON to file");
+}
+
 fn main() {
     export_cloud_openapi();
     export_audio_engine_openapi();
     export_instance_driver_openapi();
     export_domain_openapi();
+    export_examples();
+    #[cfg(feature = "chaos")]
+    export_chaos_openapi();
 }
@@ -76,6 +76,7 @@ mod test {
     use serde_json::json;
 
     use crate::api::codec::{Codec, Json};
+    use crate::SerializableResult;
 
     #[test]
     pub fn test_err_json() {
@@ -98,4 +99,18 @@ mod test {
                        "Ok": 18
                    }));
     }
+
+    #[test]
+    pub fn test_serializable_result_json() {
+        let value = SerializableResult::<u32, String>::from(Err("Error message".to_owned()));
+        let msg = Json.serialize(&value).expect("serialize");
+        let jsvalue: serde_json::Value = serde_json::from_slice(&msg).expect("deserialize");
+        assert_eq!(jsvalue,
+                   json!({
+                       "error": "Error message"
+                   }));
+
+        let value: SerializableResult<u32, String> = Json.deserialize(br#"{"ok": 18}"#).expect("deserialize");
+        assert_eq!(value.into_result(), Ok(18));
+    }
 }
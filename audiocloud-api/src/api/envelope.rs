@@ -0,0 +1,91 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{now, AppId, DomainId, EngineId, FixedInstanceId, Request, RequestId, Timestamp};
+
+/// A message with metadata to correlate requests and responses and to trace them across services
+///
+/// Trace and span ids follow the W3C trace context format: 32 and 16 lowercase hexadecimal digits.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct Envelope<T> {
+    /// Id of the request, shared by the request and all its responses
+    pub request_id: RequestId,
+    /// Id of the distributed trace the message is part of
+    #[serde(default)]
+    pub trace_id:   Option<String>,
+    /// Id of the span that sent the message
+    #[serde(default)]
+    pub span_id:    Option<String>,
+    /// Service or client that sent the message
+    #[serde(default)]
+    pub sender:     Option<MessageSender>,
+    /// Time the message was created
+    pub created_at: Timestamp,
+    /// The message
+    pub message:    T,
+}
+
+impl<T> Envelope<T> {
+    pub fn new(request_id: RequestId, message: T) -> Self {
+        Self { request_id,
+               trace_id: None,
+               span_id: None,
+               sender: None,
+               created_at: now(),
+               message }
+    }
+
+    /// Envelope a new request, with an id from [`RequestId::generate`]
+    pub fn request(message: T) -> Self {
+        Self::new(RequestId::generate(), message)
+    }
+
+    pub fn with_trace(mut self, trace_id: String, span_id: String) -> Self {
+        self.trace_id = Some(trace_id);
+        self.span_id = Some(span_id);
+        self
+    }
+
+    pub fn with_sender(mut self, sender: MessageSender) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    /// Envelope a reply to this message, with the same request and trace ids
+    ///
+    /// The span id is left empty, as the reply is usually sent from a new span.
+    pub fn reply<U>(&self, message: U) -> Envelope<U> {
+        Envelope { request_id: self.request_id.clone(),
+                   trace_id: self.trace_id.clone(),
+                   span_id: None,
+                   sender: None,
+                   created_at: now(),
+                   message }
+    }
+
+    /// Replace the message, keeping the metadata
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Envelope<U> {
+        Envelope { request_id: self.request_id,
+                   trace_id:   self.trace_id,
+                   span_id:    self.span_id,
+                   sender:     self.sender,
+                   created_at: self.created_at,
+                   message:    f(self.message), }
+    }
+}
+
+impl<T> Request for Envelope<T> where T: Request
+{
+    type Response = T::Response;
+}
+
+/// Service or client sending an [`Envelope`]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageSender {
+    Cloud,
+    Domain { domain_id: DomainId },
+    Engine { engine_id: EngineId },
+    InstanceDriver { instance_id: FixedInstanceId },
+    App { app_id: AppId },
+}
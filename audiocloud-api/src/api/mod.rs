@@ -1,10 +1,15 @@
 use schemars::schema::RootSchema;
-use serde_json::{json, Value};
+use serde_json::json;
+#[cfg(feature = "openapi")]
 use utoipa::openapi::OpenApi;
 
 pub use codec::*;
+pub use envelope::*;
+pub use version::*;
 
 pub mod codec;
+pub mod envelope;
+pub mod version;
 
 pub fn merge_schemas(x: impl Iterator<Item = RootSchema>) -> RootSchema {
     let mut root = RootSchema::default();
@@ -25,7 +30,8 @@ pub fn merge_schemas(x: impl Iterator<Item = RootSchema>) -> RootSchema {
     root
 }
 
-pub fn openapi_with_schemas_to_json(api: OpenApi, merged: RootSchema, patch: Value) -> anyhow::Result<String> {
+#[cfg(feature = "openapi")]
+pub fn openapi_with_schemas_to_json(api: OpenApi, merged: RootSchema, patch: serde_json::Value) -> anyhow::Result<String> {
     let mut api: serde_json::Value = serde_json::from_str(&api.to_json()?)?;
 
     let schemas = serde_json::to_value(&merged.definitions)?;
@@ -41,6 +47,23 @@ pub fn openapi_with_schemas_to_json(api: OpenApi, merged: RootSchema, patch: Val
     Ok(serde_json::to_string_pretty(&api)?.replace("#/definitions/", "#/components/schemas/"))
 }
 
+/// Complete OpenAPI 3.1 document of a service, with the schemas as components and the given `(url, description)` servers
+#[cfg(feature = "openapi")]
+pub fn openapi_document<'a>(api: OpenApi,
+                            merged: RootSchema,
+                            title: &str,
+                            servers: impl IntoIterator<Item = (&'a str, &'a str)>)
+                            -> anyhow::Result<String> {
+    let patches = [openapi_set_version("3.1.0"),
+                   openapi_add_apache_license(),
+                   openapi_set_info_title(title),
+                   openapi_create_empty_servers()].into_iter()
+                                                  .chain(servers.into_iter().map(|(url, description)| openapi_add_server(url, description)))
+                                                  .collect();
+
+    openapi_with_schemas_to_json(api, merged, serde_json::Value::Array(patches))
+}
+
 pub fn openapi_set_version(version: &str) -> serde_json::Value {
     json!({
         "op": "replace",
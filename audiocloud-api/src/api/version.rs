@@ -0,0 +1,74 @@
+use std::fmt::{Display, Formatter};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Version of the API schema of this crate, taken from the crate major and minor version
+pub const API_VERSION: ApiVersion = ApiVersion { major: parse_version_number(env!("CARGO_PKG_VERSION_MAJOR")),
+                                                 minor: parse_version_number(env!("CARGO_PKG_VERSION_MINOR")), };
+
+/// Version of the API schema
+///
+/// Services exchange the versions they support with [`NegotiateVersion`] when connecting, and then use the
+/// highest version both support.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema)]
+pub struct ApiVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ApiVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// Highest version present in both lists, if any
+    pub fn negotiate(ours: &[ApiVersion], theirs: &[ApiVersion]) -> Option<ApiVersion> {
+        ours.iter().filter(|version| theirs.contains(version)).max().copied()
+    }
+}
+
+impl Display for ApiVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+const fn parse_version_number(number: &str) -> u32 {
+    let bytes = number.as_bytes();
+    let mut value = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value * 10 + (bytes[i] - b'0') as u32;
+        i += 1;
+    }
+
+    value
+}
+
+/// Versions of the API supported by the service opening a connection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct NegotiateVersion {
+    /// Supported versions, in any order
+    pub supported: Vec<ApiVersion>,
+}
+
+impl NegotiateVersion {
+    /// Respond with the highest version supported by both sides
+    pub fn respond(&self, supported: &[ApiVersion]) -> VersionNegotiated {
+        match ApiVersion::negotiate(supported, &self.supported) {
+            Some(version) => VersionNegotiated::Agreed { version },
+            None => VersionNegotiated::NoCommonVersion { supported: supported.to_vec(), },
+        }
+    }
+}
+
+/// Outcome of a version negotiation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionNegotiated {
+    /// Both sides will use this version
+    Agreed { version: ApiVersion },
+    /// No version is supported by both sides, the versions supported by the responding side are listed
+    NoCommonVersion { supported: Vec<ApiVersion> },
+}
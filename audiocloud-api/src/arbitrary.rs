@@ -0,0 +1,404 @@
+//! Proptest strategies for core API types, used to property test validation and modification logic
+//!
+//! Generated task specs respect the invariants checked by [`TaskSpec::validate`]: connections only
+//! reference existing nodes, channel masks fit the channel counts of the connected pads, and volume and
+//! pan are within range. Modifications are not constrained this way, but draw node and connection ids
+//! from the same small pools as generated task specs, so they regularly refer to existing nodes.
+
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Utc};
+use proptest::collection::{hash_map, hash_set, vec};
+use proptest::option;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::{
+    ChannelMask, CompressedAudio, ConnectionKind, ConnectionValues, ControlChannels, DiffStamped, DynamicInstanceNode,
+    DynamicInstanceNodeId, InputPadId, InstanceParameters, MediaChannels, MixerNode, MixerNodeId, Model, ModelCapability,
+    ModelElementScope, ModelId, ModelInput, ModelOutput, ModelParameter, ModelParameterRole, ModelValue, ModelValueOption, ModelValueUnit,
+    ModifyTaskSpec, MultiChannelValue, NodeConnection, NodeConnectionId, OutputPadId, PanLaw, ParameterId, PlayId, ResourceId,
+    StreamingPacket, TaskSpec, TimeRange, Timecode, TimecodeFormat, TrackNode, TrackNodeId, DEFAULT_MAX_CONNECTION_VOLUME,
+    MAX_CONNECTION_DELAY_MS,
+};
+
+const MAX_TRACKS: usize = 4;
+const MAX_MIXERS: usize = 3;
+const MAX_DYNAMIC_INSTANCES: usize = 3;
+const MAX_CONNECTIONS: usize = 8;
+const MAX_MIXER_CHANNELS: usize = 8;
+
+fn track_id(index: usize) -> TrackNodeId {
+    TrackNodeId::new(format!("track-{index}"))
+}
+
+fn mixer_id(index: usize) -> MixerNodeId {
+    MixerNodeId::new(format!("mixer-{index}"))
+}
+
+fn dynamic_id(index: usize) -> DynamicInstanceNodeId {
+    DynamicInstanceNodeId::new(format!("dynamic-{index}"))
+}
+
+fn connection_id(index: usize) -> NodeConnectionId {
+    NodeConnectionId::new(format!("connection-{index}"))
+}
+
+fn model_id(index: usize) -> ModelId {
+    ModelId::new("arbitrary".to_owned(), format!("model-{index}"))
+}
+
+fn media_channels() -> impl Strategy<Value = MediaChannels> {
+    prop_oneof![Just(MediaChannels::Mono), Just(MediaChannels::Stereo)]
+}
+
+fn pan_law() -> impl Strategy<Value = PanLaw> {
+    prop_oneof![Just(PanLaw::ConstantPower),
+                Just(PanLaw::Compromise),
+                Just(PanLaw::Linear),
+                Just(PanLaw::Balance)]
+}
+
+fn connection_kind() -> impl Strategy<Value = ConnectionKind> {
+    prop_oneof![Just(ConnectionKind::Audio),
+                Just(ConnectionKind::Midi),
+                Just(ConnectionKind::Sidechain)]
+}
+
+fn timecode() -> impl Strategy<Value = Timecode> {
+    let format = select(vec![TimecodeFormat::Fps23_976,
+                             TimecodeFormat::Fps24,
+                             TimecodeFormat::Fps25,
+                             TimecodeFormat::Fps29_97,
+                             TimecodeFormat::Fps29_97DropFrame,
+                             TimecodeFormat::Fps30,
+                             TimecodeFormat::Fps50,
+                             TimecodeFormat::Fps59_94,
+                             TimecodeFormat::Fps59_94DropFrame,
+                             TimecodeFormat::Fps60]);
+
+    (format, 0..100_000_000u64).prop_map(|(format, frames)| Timecode::new(format, frames))
+}
+
+/// Channel masks that fit within `channels` channels (which must not be zero)
+pub fn channel_mask_within(channels: usize) -> BoxedStrategy<ChannelMask> {
+    if channels >= 2 {
+        prop_oneof![(0..channels).prop_map(ChannelMask::Mono),
+                    (0..channels / 2).prop_map(ChannelMask::Stereo)].boxed()
+    } else {
+        (0..channels).prop_map(ChannelMask::Mono).boxed()
+    }
+}
+
+impl Arbitrary for ChannelMask {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        channel_mask_within(MAX_MIXER_CHANNELS)
+    }
+}
+
+impl Arbitrary for TimeRange {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Time ranges within a week, which may be empty or end before they start
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        const EPOCH: i64 = 1_600_000_000;
+        const WEEK: i64 = 7 * 24 * 3600;
+
+        (0..WEEK, 0..WEEK).prop_map(|(from, to)| {
+                              TimeRange::new(Utc.timestamp_opt(EPOCH + from, 0).unwrap(),
+                                             Utc.timestamp_opt(EPOCH + to, 0).unwrap())
+                          })
+                          .boxed()
+    }
+}
+
+impl Arbitrary for Model {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let input = prop_oneof![4 => Just(ModelInput::Audio(ControlChannels::Generic)),
+                                1 => Just(ModelInput::Sidechain),
+                                1 => Just(ModelInput::Midi)];
+        let output = prop_oneof![4 => Just(ModelOutput::Audio(ControlChannels::Generic)), 1 => Just(ModelOutput::Midi)];
+        let resource = select(vec![ResourceId::Memory, ResourceId::CPU, ResourceId::GPU, ResourceId::AntelopeDSP]);
+        let capability = select(vec![ModelCapability::Identify, ModelCapability::SelfTest, ModelCapability::Calibration]);
+
+        (vec(input, 0..4),
+         vec(output, 0..4),
+         vec((resource, 0.0..16.0f64), 0..3),
+         0..4usize,
+         any::<bool>(),
+         hash_set(capability, 0..2)).prop_map(|(inputs, outputs, resources, num_parameters, media, capabilities)| {
+                                        let parameters = (0..num_parameters).map(|index| {
+                                                                                (ParameterId::new(format!("parameter-{index}")),
+                                                                                 ModelParameter { scope:   ModelElementScope::Global,
+                                                                                                  unit:    ModelValueUnit::Unitless,
+                                                                                                  role:    ModelParameterRole::NoRole,
+                                                                                                  values:  vec![ModelValueOption::num_range(0.0, 1.0)],
+                                                                                                  default: None,
+                                                                                                  linked:  false,
+                                                                                                  ui:      None, })
+
+                                                                            })
+                                                                            .collect();
+
+                                        Model { resources: resources.into_iter().collect(),
+                                                inputs,
+                                                outputs,
+                                                parameters,
+                                                reports: Default::default(),
+                                                media,
+                                                capabilities,
+                                                nominal_latency_ms: None }
+                                    })
+                                    .boxed()
+    }
+}
+
+fn instance_parameters() -> impl Strategy<Value = InstanceParameters> {
+    let parameter = (0..4usize).prop_map(|index| ParameterId::new(format!("parameter-{index}")));
+    let value = vec(option::of((0.0..=1.0f64).prop_map(ModelValue::Number)), 0..3).prop_map(MultiChannelValue);
+
+    hash_map(parameter, value, 0..4).prop_map(InstanceParameters)
+}
+
+fn connection(sources: Vec<(OutputPadId, usize)>, destinations: Vec<(InputPadId, usize)>) -> impl Strategy<Value = NodeConnection> {
+    (select(sources), select(destinations)).prop_flat_map(|((from, from_count), (to, to_count))| {
+                                               (Just(from),
+                                                Just(to),
+                                                channel_mask_within(from_count),
+                                                channel_mask_within(to_count),
+                                                (0.0..=DEFAULT_MAX_CONNECTION_VOLUME, -1.0..=1.0f64),
+                                                option::of(pan_law()),
+                                                0.0..=MAX_CONNECTION_DELAY_MS)
+                                           })
+                                           .prop_map(|(from, to, from_channels, to_channels, (volume, pan), pan_law, delay_ms)| {
+                                               NodeConnection { from,
+                                                                to,
+                                                                kind: ConnectionKind::Audio,
+                                                                from_channels,
+                                                                to_channels,
+                                                                volume,
+                                                                pan,
+                                                                pan_law,
+                                                                delay_ms,
+                                                                automation: Default::default() }
+                                           })
+}
+
+/// Valid task specs with dynamic instances of `models`
+///
+/// The generated specs pass [`TaskSpec::validate`] given the same `models`. Every spec has at least one track.
+pub fn task_spec(models: HashMap<ModelId, Model>) -> BoxedStrategy<TaskSpec> {
+    let model_ids = models.keys().cloned().collect::<Vec<_>>();
+    let dynamic = if model_ids.is_empty() {
+        Just(vec![]).boxed()
+    } else {
+        vec(select(model_ids), 0..=MAX_DYNAMIC_INSTANCES).boxed()
+    };
+
+    (vec(media_channels(), 1..=MAX_TRACKS),
+     vec((1..=MAX_MIXER_CHANNELS, 1..=MAX_MIXER_CHANNELS), 0..=MAX_MIXERS),
+     dynamic,
+     pan_law(),
+     option::of(timecode())).prop_flat_map(move |(tracks, mixers, dynamic, pan_law, timecode_start)| {
+                                let mut sources = vec![];
+                                let mut destinations = vec![];
+
+                                for (index, channels) in tracks.iter().enumerate() {
+                                    sources.push((track_id(index).source(), channels.num_channels()));
+                                }
+
+                                for (index, (input_channels, output_channels)) in mixers.iter().enumerate() {
+                                    sources.push((mixer_id(index).output_flow(), *output_channels));
+                                    destinations.push((mixer_id(index).input_flow(), *input_channels));
+                                }
+
+                                for (index, model_id) in dynamic.iter().enumerate() {
+                                    let model = &models[model_id];
+                                    let (inputs, outputs) = (model.get_audio_input_channel_count(), model.get_audio_output_channel_count());
+                                    if outputs > 0 {
+                                        sources.push((dynamic_id(index).output_flow(), outputs));
+                                    }
+                                    if inputs > 0 {
+                                        destinations.push((dynamic_id(index).input_flow(), inputs));
+                                    }
+                                }
+
+                                let connections = if destinations.is_empty() {
+                                    Just(vec![]).boxed()
+                                } else {
+                                    vec(connection(sources, destinations), 0..=MAX_CONNECTIONS).boxed()
+                                };
+
+                                let tracks = tracks.into_iter()
+                                                   .enumerate()
+                                                   .map(|(index, channels)| (track_id(index), TrackNode::new(channels)))
+                                                   .collect::<HashMap<_, _>>();
+
+                                let mixers = mixers.into_iter()
+                                                   .enumerate()
+                                                   .map(|(index, (input_channels, output_channels))| {
+                                                       (mixer_id(index), MixerNode { input_channels, output_channels })
+                                                   })
+                                                   .collect::<HashMap<_, _>>();
+
+                                let dynamic = dynamic.into_iter()
+                                                     .enumerate()
+                                                     .map(|(index, model_id)| {
+                                                         (dynamic_id(index),
+                                                          DynamicInstanceNode { model_id,
+                                                                                parameters: Default::default(),
+                                                                                parameter_automation: Default::default() })
+                                                     })
+                                                     .collect::<HashMap<_, _>>();
+
+                                (Just(tracks), Just(mixers), Just(dynamic), connections).prop_map(move |(tracks, mixers, dynamic, connections)| {
+                                    TaskSpec { tracks,
+                                               mixers,
+                                               dynamic,
+                                               fixed: Default::default(),
+                                               recorders: Default::default(),
+                                               connections: connections.into_iter()
+                                                                       .enumerate()
+                                                                       .map(|(index, connection)| (connection_id(index), connection))
+                                                                       .collect(),
+                                               pan_law,
+                                               timecode_start,
+                                               tempo_map: Default::default(),
+                                               revision: 0 }
+                                })
+                            })
+                            .boxed()
+}
+
+/// Models paired with valid task specs using them
+pub fn task_spec_with_models() -> impl Strategy<Value = (HashMap<ModelId, Model>, TaskSpec)> {
+    vec(any::<Model>(), 0..3).prop_flat_map(|models| {
+                                 let models = models.into_iter()
+                                                    .enumerate()
+                                                    .map(|(index, model)| (model_id(index), model))
+                                                    .collect::<HashMap<_, _>>();
+
+                                 (Just(models.clone()), task_spec(models))
+                             })
+}
+
+impl Arbitrary for TaskSpec {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Task specs with tracks and mixers only, valid without any models
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        task_spec(HashMap::new())
+    }
+}
+
+impl Arbitrary for ModifyTaskSpec {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let track = (0..=MAX_TRACKS).prop_map(track_id);
+        let mixer = (0..=MAX_MIXERS).prop_map(mixer_id);
+        let connection = (0..=MAX_CONNECTIONS).prop_map(connection_id);
+        let source = prop_oneof![track.clone().prop_map(TrackNodeId::source),
+                                 mixer.clone().prop_map(MixerNodeId::output_flow),
+                                 (0..=MAX_DYNAMIC_INSTANCES).prop_map(|index| dynamic_id(index).output_flow())];
+        let destination = prop_oneof![mixer.clone().prop_map(MixerNodeId::input_flow),
+                                      (0..=MAX_DYNAMIC_INSTANCES).prop_map(|index| dynamic_id(index).input_flow())];
+        let values = (option::of(0.0..=DEFAULT_MAX_CONNECTION_VOLUME), option::of(-1.0..=1.0f64), option::of(pan_law()));
+
+        prop_oneof![(track.clone(), media_channels()).prop_map(|(track_id, channels)| ModifyTaskSpec::AddTrack { track_id, channels }),
+                    track.prop_map(|track_id| ModifyTaskSpec::DeleteTrack { track_id }),
+                    (mixer.clone(), 1..=MAX_MIXER_CHANNELS, 1..=MAX_MIXER_CHANNELS).prop_map(|(mixer_id, input_channels, output_channels)| {
+                                                                                       ModifyTaskSpec::AddMixer { mixer_id,
+                                                                                                                  spec: MixerNode { input_channels,
+                                                                                                                                    output_channels } }
+                                                                                   }),
+                    mixer.prop_map(|mixer_id| ModifyTaskSpec::DeleteMixer { mixer_id }),
+                    (connection.clone(), source, destination, connection_kind(), any::<ChannelMask>(), any::<ChannelMask>(), -1.0..8.0f64, -2.0..2.0f64)
+                        .prop_map(|(connection_id, from, to, kind, from_channels, to_channels, volume, pan)| {
+                            ModifyTaskSpec::AddConnection { connection_id,
+                                                            from,
+                                                            to,
+                                                            kind,
+                                                            from_channels,
+                                                            to_channels,
+                                                            volume,
+                                                            pan }
+                        }),
+                    (connection.clone(), values).prop_map(|(connection_id, (volume, pan, pan_law))| {
+                                                    ModifyTaskSpec::SetConnectionParameterValues { connection_id,
+                                                                                                   values: ConnectionValues { volume,
+                                                                                                                              pan,
+                                                                                                                              pan_law } }
+                                                }),
+                    (connection.clone(), -10.0..=MAX_CONNECTION_DELAY_MS + 10.0).prop_map(|(connection_id, delay_ms)| {
+                                                                                     ModifyTaskSpec::SetConnectionDelay { connection_id,
+                                                                                                                          delay_ms }
+                                                                                 }),
+                    connection.prop_map(|connection_id| ModifyTaskSpec::DeleteConnection { connection_id }),
+                    (0..=MAX_DYNAMIC_INSTANCES, instance_parameters()).prop_map(|(index, values)| {
+                                                                          ModifyTaskSpec::SetDynamicInstanceParameterValues { dynamic_id: dynamic_id(index),
+                                                                                                                              values }
+                                                                      }),
+                    (0..=MAX_DYNAMIC_INSTANCES, instance_parameters()).prop_map(|(index, defaults)| {
+                                                                          ModifyTaskSpec::ResetNodeParameters { node_id: dynamic_id(index).into(),
+                                                                                                                defaults }
+                                                                      }),
+                    pan_law().prop_map(|pan_law| ModifyTaskSpec::SetPanLaw { pan_law }),
+                    option::of(timecode()).prop_map(|timecode_start| ModifyTaskSpec::SetTimecodeStart { timecode_start })].boxed()
+    }
+}
+
+impl Arbitrary for StreamingPacket {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Streaming packets with checksummed audio at consecutive stream positions
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<u64>(),
+         0..u64::MAX / 2,
+         0..u32::MAX as u64,
+         0.0..3600.0f64,
+         vec((vec(any::<u8>(), 0..256), 1..1024usize, 0..MAX_MIXERS), 0..4)).prop_map(|(play_id, serial, streaming_pos, timeline_pos, audio)| {
+                                                                let play_id = PlayId::new(play_id);
+                                                                let created_at = crate::now();
+                                                                let mut stream_pos = HashMap::new();
+                                                                let audio = audio.into_iter()
+                                                                                 .map(|(buffer, num_samples, mixer)| {
+                                                                                     let stream_pos = stream_pos.entry(mixer).or_insert(streaming_pos);
+                                                                                     let audio = CompressedAudio { play_id,
+                                                                                                                   mixer_id: mixer_id(mixer),
+                                                                                                                   timeline_pos,
+                                                                                                                   timecode: None,
+                                                                                                                   stream_pos: *stream_pos,
+                                                                                                                   buffer: buffer.into(),
+                                                                                                                   num_samples,
+                                                                                                                   last: false,
+                                                                                                                   crc32: None }.with_checksum();
+                                                                                     *stream_pos += num_samples as u64;
+                                                                                     DiffStamped::new(created_at, audio)
+                                                                                 })
+                                                                                 .collect();
+
+                                                                StreamingPacket { play_id,
+                                                                                  created_at,
+                                                                                  audio,
+                                                                                  instance_metering: Default::default(),
+                                                                                  pad_metering: Default::default(),
+                                                                                  node_latencies: Default::default(),
+                                                                                  timeline_pos,
+                                                                                  timecode: None,
+                                                                                  streaming_pos,
+                                                                                  serial }
+                                                            })
+                                                            .boxed()
+    }
+}
@@ -3,11 +3,11 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::audio_engine::EngineError;
-use crate::cloud::domains::FixedInstanceRouting;
-use crate::common::change::{ModifyTaskSpec, UpdateTaskPlay};
-use crate::common::media::{PlayId, RenderId, RequestPlay, RequestRender};
-use crate::common::task::TaskSpec;
-use crate::{AppMediaObjectId, AppTaskId, DynamicInstanceNodeId, FixedInstanceId, Request, SerializableResult};
+use crate::common::change::{ModifyTaskSpec, TaskSpecDiff, UpdateTaskPlay};
+use crate::common::instance::FixedInstanceRouting;
+use crate::common::media::{DeleteReason, PlayId, RenderId, RequestPlay, RequestRender, StopReason};
+use crate::common::task::{InstanceParameters, TaskSpec};
+use crate::{AppMediaObjectId, AppTaskId, DynamicInstanceNodeId, EngineId, Envelope, FixedInstanceId, Request, SerializableResult};
 
 /// Command sent to the Audio Engine
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -26,6 +26,13 @@ pub enum EngineCommand {
         /// Current media state
         media_ready: HashMap<AppMediaObjectId, String>,
     },
+    /// Update the task specification with the changes since the revision the engine has
+    PatchSpec {
+        /// Task id
+        task_id: AppTaskId,
+        /// Changes to the task specification
+        diff:    TaskSpecDiff,
+    },
     /// Update media state of a task
     Media {
         /// Task id
@@ -57,8 +64,8 @@ pub enum EngineCommand {
         task_id:    AppTaskId,
         /// Dynamic instance node id
         dynamic_id: DynamicInstanceNodeId,
-        /// Parameters to be set
-        values:     serde_json::Value,
+        /// Parameters to be set, merged into the existing values
+        values:     InstanceParameters,
     },
     /// Render the task
     Render {
@@ -87,6 +94,9 @@ pub enum EngineCommand {
         task_id:   AppTaskId,
         /// Render id
         render_id: RenderId,
+        /// Why the render is cancelled
+        #[serde(default)]
+        reason:    StopReason,
     },
     /// Stop playing the task
     StopPlay {
@@ -94,14 +104,36 @@ pub enum EngineCommand {
         task_id: AppTaskId,
         /// Play id
         play_id: PlayId,
+        /// Why playback is stopped
+        #[serde(default)]
+        reason:  StopReason,
     },
     /// Close (remove) the session
     Close {
         /// Task id
         task_id: AppTaskId,
+        /// Why the task is removed
+        #[serde(default)]
+        reason:  DeleteReason,
     },
 }
 
 impl Request for EngineCommand {
     type Response = SerializableResult<(), EngineError>;
 }
+
+/// An [`EngineCommand`] addressed to one engine, when several engines share a connection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EngineCommandEnvelope {
+    /// Engine that should execute the command
+    pub engine_id: EngineId,
+    /// The command to execute
+    pub command:   EngineCommand,
+}
+
+impl Request for EngineCommandEnvelope {
+    type Response = SerializableResult<(), EngineError>;
+}
+
+/// An [`EngineCommandEnvelope`] with request correlation and tracing metadata
+pub type TracedEngineCommand = Envelope<EngineCommandEnvelope>;
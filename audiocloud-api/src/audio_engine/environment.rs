@@ -1,6 +1,7 @@
 /// Set media presence
 ///
 /// The Audio Engine needs to map AppMediaObjectId on track items to
+#[cfg(feature = "openapi")]
 #[utoipa::path(
  put,
  path = "/v1/media",
@@ -16,6 +17,7 @@ pub(crate) fn set_media() {}
 /// The Audio Engine needs to map FixedInstanceNode to I/O on the audio interface it is bound
 /// to. For example, an instance may be bound to channels 0 and 1 or to channels 5 and 6 and
 /// the Audio Engine needs to know to route the audio correctly.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
  put,
  path = "/v1/instances",
@@ -25,3 +27,17 @@ pub(crate) fn set_media() {}
   (status = 404, description = "Not found", body = EngineError),
  ))]
 pub(crate) fn set_instances() {}
+
+/// Get sample clock status
+///
+/// Report the clock source and lock state of the audio interface the Audio Engine is bound to,
+/// so clock misconfigurations can be detected before they result in audible artifacts.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+ get,
+ path = "/v1/clock",
+ responses(
+  (status = 200, description = "Success", body = ClockStatus),
+  (status = 404, description = "Not found", body = EngineError),
+ ))]
+pub(crate) fn get_clock() {}
@@ -2,9 +2,11 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::audio_engine::CompressedAudio;
-use crate::common::media::{PlayId, RenderId};
-use crate::{AppTaskId, DynamicInstanceNodeId, InputPadId, NodePadId, OutputPadId, PadMetering};
+use crate::audio_engine::{ClockStatus, CompressedAudio};
+use crate::common::media::{MediaDiagnostics, PlayId, RenderAnalysis, RenderId, StopReason};
+use crate::{
+    AppMediaObjectId, AppTaskId, DynamicInstanceNodeId, EngineId, Envelope, InputPadId, NodePadId, OutputPadId, PadMetering, RecorderNodeId,
+};
 
 /// Event emitted by the audio engine
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -14,6 +16,9 @@ pub enum EngineEvent {
     Stopped {
         /// Task id
         task_id: AppTaskId,
+        /// Why the task stopped
+        #[serde(default)]
+        reason:  StopReason,
     },
     /// The task is playing
     Playing {
@@ -28,6 +33,24 @@ pub enum EngineEvent {
         /// Reports generated by dynamic instance nodes
         dynamic_reports: HashMap<DynamicInstanceNodeId, serde_json::Value>,
     },
+    /// Playback wrapped around to the start of the loop region
+    Looped {
+        /// Task id
+        task_id:   AppTaskId,
+        /// Play id
+        play_id:   PlayId,
+        /// Number of times the loop region has been played so far
+        iteration: u32,
+    },
+    /// Playback crossed the punch-in or punch-out position
+    Punched {
+        /// Task id
+        task_id:    AppTaskId,
+        /// Play id
+        play_id:    PlayId,
+        /// True if processing is now heard, false if the task now plays bypassed
+        punched_in: bool,
+    },
     /// Playing failed with an error
     PlayingFailed {
         /// Task id
@@ -54,6 +77,9 @@ pub enum EngineEvent {
         render_id: RenderId,
         /// Path to the media file on the audio engine computer
         path:      String,
+        /// Loudness analysis of the rendered file, if the engine analyzed it
+        #[serde(default)]
+        analysis:  Option<RenderAnalysis>,
     },
     /// Rendering failed with an error
     RenderingFailed {
@@ -64,6 +90,23 @@ pub enum EngineEvent {
         /// Error details
         error:     String,
     },
+    /// A recorder node finished recording a take, when playing stopped or the recorder was deleted
+    Recorded {
+        /// Task id
+        task_id:     AppTaskId,
+        /// Play id during which the take was recorded
+        play_id:     PlayId,
+        /// Recorder node id
+        recorder_id: RecorderNodeId,
+        /// Media object created for the take, named by the recorder naming template
+        object_id:   AppMediaObjectId,
+        /// Take number, counting from 1
+        take:        u32,
+        /// Path to the media file on the audio engine computer
+        path:        String,
+        /// Duration of the recording in seconds
+        duration:    f64,
+    },
     /// A general error has happened on at task
     Error {
         /// Task id
@@ -71,18 +114,48 @@ pub enum EngineEvent {
         /// Error details
         error:   String,
     },
+    /// Media used by a task could not be decoded cleanly
+    MediaDiagnostics {
+        /// Task id
+        task_id:     AppTaskId,
+        /// Decoder diagnostics
+        diagnostics: MediaDiagnostics,
+    },
+    /// The sample clock source or lock state changed, or a clock dropout was detected
+    ClockChanged {
+        /// Clock status after the change
+        status: ClockStatus,
+    },
 }
 
 impl EngineEvent {
-    pub fn task_id(&self) -> &AppTaskId {
+    /// Task the event relates to, or None for events relating to the whole engine
+    pub fn task_id(&self) -> Option<&AppTaskId> {
         match self {
-            EngineEvent::Stopped { task_id } => task_id,
-            EngineEvent::Playing { task_id, .. } => task_id,
-            EngineEvent::PlayingFailed { task_id, .. } => task_id,
-            EngineEvent::Rendering { task_id, .. } => task_id,
-            EngineEvent::RenderingFinished { task_id, .. } => task_id,
-            EngineEvent::RenderingFailed { task_id, .. } => task_id,
-            EngineEvent::Error { task_id, .. } => task_id,
+            EngineEvent::Stopped { task_id, .. } => Some(task_id),
+            EngineEvent::Playing { task_id, .. } => Some(task_id),
+            EngineEvent::Looped { task_id, .. } => Some(task_id),
+            EngineEvent::Punched { task_id, .. } => Some(task_id),
+            EngineEvent::PlayingFailed { task_id, .. } => Some(task_id),
+            EngineEvent::Rendering { task_id, .. } => Some(task_id),
+            EngineEvent::RenderingFinished { task_id, .. } => Some(task_id),
+            EngineEvent::RenderingFailed { task_id, .. } => Some(task_id),
+            EngineEvent::Recorded { task_id, .. } => Some(task_id),
+            EngineEvent::Error { task_id, .. } => Some(task_id),
+            EngineEvent::MediaDiagnostics { task_id, .. } => Some(task_id),
+            EngineEvent::ClockChanged { .. } => None,
         }
     }
 }
+
+/// An [`EngineEvent`] with the engine that emitted it, when several engines share a connection
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EngineEventEnvelope {
+    /// Engine that emitted the event
+    pub engine_id: EngineId,
+    /// The event
+    pub event:     EngineEvent,
+}
+
+/// An [`EngineEventEnvelope`] with request correlation and tracing metadata
+pub type TracedEngineEvent = Envelope<EngineEventEnvelope>;
@@ -6,15 +6,19 @@ use schemars::schema::RootSchema;
 use schemars::{schema_for, JsonSchema};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+#[cfg(feature = "openapi")]
 use utoipa::OpenApi;
 
 pub use command::*;
 pub use event::*;
 
+pub use crate::common::media::CompressedAudio;
 use crate::common::media::PlayId;
+#[cfg(feature = "openapi")]
+use crate::openapi_document;
 use crate::{
-    merge_schemas, AppId, AppMediaObjectId, AppTaskId, FixedInstanceId, MediaObject, ModifyTaskError, RenderId, TaskId, TaskPlayState,
-    TaskSpec,
+    merge_schemas, AppId, AppMediaObjectId, AppTaskId, CodedError, ErrorCode, FixedInstanceId, MediaObject, ModifyTaskError, RenderId,
+    TaskId, TaskPlayState, TaskSpec,
 };
 
 pub mod command;
@@ -22,16 +26,6 @@ pub mod environment;
 pub mod event;
 pub mod tasks;
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct CompressedAudio {
-    pub play_id:      PlayId,
-    pub timeline_pos: f64,
-    pub stream_pos:   u64,
-    pub buffer:       bytes::Bytes,
-    pub num_samples:  usize,
-    pub last:         bool,
-}
-
 #[derive(Debug, Clone, Error, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum EngineError {
@@ -51,6 +45,20 @@ pub enum EngineError {
     RPC(String),
 }
 
+impl ErrorCode for EngineError {
+    fn error_code(&self) -> &'static str {
+        use EngineError::*;
+
+        match self {
+            TrackNotFound(..) => "track_not_found",
+            ItemNotFound(..) => "item_not_found",
+            ModifyTask(..) => "modify_task",
+            InternalError(..) => "internal_error",
+            RPC(..) => "rpc",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskReplaced {
@@ -100,6 +108,50 @@ pub struct SetMedia {
     media: HashMap<AppMediaObjectId, MediaObject>,
 }
 
+/// Source of the sample clock driving the audio interface of an engine
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClockSource {
+    /// Internal oscillator of the audio interface
+    Internal,
+    /// External word clock input
+    WordClock,
+    /// Clock recovered from an ADAT input
+    Adat,
+    /// Clock recovered from an S/PDIF input
+    Spdif,
+    /// Clock recovered from an AES/EBU input
+    Aes,
+    /// Clock recovered from a MADI input
+    Madi,
+}
+
+/// Status of the sample clock of an engine
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ClockStatus {
+    /// Currently selected clock source
+    pub source:      ClockSource,
+    /// Sample rate the interface is running at
+    pub sample_rate: usize,
+    /// True if the interface is locked to the clock source
+    pub locked:      bool,
+    /// Number of clock dropouts detected since the engine started
+    pub dropouts:    u64,
+}
+
+/// Load of an engine, reported with every heartbeat
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+pub struct EngineLoad {
+    /// Fraction of the audio processing budget in use, from 0 to 1
+    pub cpu:              f64,
+    /// Memory used by the engine in bytes
+    pub memory:           u64,
+    /// Number of tasks loaded on the engine
+    pub active_tasks:     usize,
+    /// Number of buffer underruns detected since the engine started
+    pub buffer_underruns: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum InstancesUpdated {
@@ -145,6 +197,7 @@ pub enum TaskRenderCancelled {
     Cancelled { task_id: AppTaskId, render_id: RenderId },
 }
 
+#[cfg(feature = "openapi")]
 #[derive(OpenApi)]
 #[openapi(paths(tasks::set_spec,
                 tasks::modify_spec,
@@ -152,19 +205,29 @@ pub enum TaskRenderCancelled {
                 tasks::list,
                 tasks::play,
                 tasks::seek,
+                tasks::change_mixer,
                 tasks::stop_playing,
                 tasks::cancel_render,
                 tasks::render,
                 environment::set_media,
-                environment::set_instances))]
+                environment::set_instances,
+                environment::get_clock))]
 pub struct EngineApi;
 
+/// OpenAPI document of the audio engine, served on the given `(url, description)` servers
+#[cfg(feature = "openapi")]
+pub fn openapi<'a>(servers: impl IntoIterator<Item = (&'a str, &'a str)>) -> anyhow::Result<String> {
+    openapi_document(EngineApi::openapi(), schemas(), "Audio Cloud Audio Engine", servers)
+}
+
 pub fn schemas() -> RootSchema {
     merge_schemas([schema_for!(EngineError),
+                   schema_for!(CodedError<EngineError>),
                    schema_for!(TaskReplaced),
                    schema_for!(TaskDeleted),
                    schema_for!(TaskModified),
                    schema_for!(TaskPlaying),
+                   schema_for!(TaskMixerChanged),
                    schema_for!(TaskSought),
                    schema_for!(TaskPlayStopped),
                    schema_for!(TaskRendering),
@@ -174,17 +237,26 @@ pub fn schemas() -> RootSchema {
                    schema_for!(EngineFixedInstance),
                    schema_for!(SetInstances),
                    schema_for!(SetMedia),
+                   schema_for!(ClockStatus),
+                   schema_for!(EngineLoad),
                    schema_for!(TaskWithStatusList),
                    schema_for!(TaskWithStatus),
                    schema_for!(SetMedia),
                    schema_for!(SetInstances),
                    schema_for!(AppId),
                    schema_for!(TaskId),
+                   schema_for!(crate::EngineId),
                    schema_for!(crate::RequestPlay),
+                   schema_for!(crate::PlayClick),
+                   schema_for!(crate::PlayTimecode),
                    schema_for!(crate::RequestSeek),
                    schema_for!(crate::RequestChangeMixer),
                    schema_for!(crate::RequestStopPlay),
+                   schema_for!(crate::RequestRender),
+                   schema_for!(crate::RenderOutputFormat),
+                   schema_for!(crate::RenderAnalysis),
                    schema_for!(crate::RequestCancelRender),
                    schema_for!(crate::ModifyTaskSpec),
-                   schema_for!(crate::TaskSpec)].into_iter())
+                   schema_for!(crate::TaskSpec),
+                   schema_for!(crate::TaskSpecDiff)].into_iter())
 }
@@ -2,15 +2,17 @@
 ///
 /// Create or update a task by providing its spec. Changing the spec even trivially could result
 /// in a rendering or playback interruption.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
  put,
- path = "/v1/tasks/{app_id}/{task_id}",
+ path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}",
  request_body = TaskSpec,
  responses(
   (status = 200, description = "Success", body = TaskReplaced),
   (status = 401, description = "Not authorized", body = EngineError),
  ),
  params(
+  ("engine_id" = EngineId, Path, description = "Engine id"),
   ("app_id" = AppId, Path, description = "App id"),
   ("task_id" = TaskId, Path, description = "Task id")
  ))]
@@ -20,9 +22,10 @@ pub(crate) fn set_spec() {}
 ///
 /// Apply a modification to an existing spec. Changing the spec even trivially could result in a
 /// rendering or playback interruption. The task must exist in order to be modified.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
  patch,
- path = "/v1/tasks/{app_id}/{task_id}",
+ path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}",
  request_body = ModifyTaskSpec,
  responses(
   (status = 200, description = "Success", body = TaskModified),
@@ -30,6 +33,7 @@ pub(crate) fn set_spec() {}
   (status = 404, description = "Not found", body = EngineError),
  ),
  params(
+  ("engine_id" = EngineId, Path, description = "Engine id"),
   ("app_id" = AppId, Path, description = "App id"),
   ("task_id" = TaskId, Path, description = "Task id")
  ))]
@@ -39,14 +43,16 @@ pub(crate) fn modify_spec() {}
 ///
 /// Delete an existing task spec. This will interrupt any playback or rendering and will free
 /// resources associated with the task (such as instances or locks on media files).
+#[cfg(feature = "openapi")]
 #[utoipa::path(
  delete,
- path = "/v1/tasks/{app_id}/{task_id}",
+ path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}",
  responses(
   (status = 200, description = "Success", body = TaskDeleted),
   (status = 404, description = "Not found", body = EngineError),
  ),
  params(
+  ("engine_id" = EngineId, Path, description = "Engine id"),
   ("app_id" = AppId, Path, description = "App id"),
   ("task_id" = TaskId, Path, description = "Task id")
  ))]
@@ -54,13 +60,17 @@ pub(crate) fn delete() {}
 
 /// List tasks
 ///
-/// Return a list of all current tasks and their play status.
+/// Return a list of all current tasks on the engine and their play status.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
  get,
- path = "/v1/tasks",
+ path = "/v1/engines/{engine_id}/tasks",
  responses(
   (status = 200, description = "Success", body = TaskWithStatusList),
   (status = 404, description = "Not found", body = EngineError),
+ ),
+ params(
+  ("engine_id" = EngineId, Path, description = "Engine id")
  ))]
 pub(crate) fn list() {}
 
@@ -68,15 +78,17 @@ pub(crate) fn list() {}
 ///
 /// Start playing a task that is stopped. The request will return when the task has started to play
 /// or with an error.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
-  path = "/v1/tasks/{app_id}/{task_id}/transport/play",
+  path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}/transport/play",
   request_body = RequestPlay,
   responses(
     (status = 200, description = "Success", body = TaskPlaying),
     (status = 404, description = "Not found", body = EngineError),
   ),
   params(
+    ("engine_id" = EngineId, Path, description = "Engine id"),
     ("app_id" = AppId, Path, description = "App id"),
     ("task_id" = TaskId, Path, description = "Task id")
   ))]
@@ -85,32 +97,36 @@ pub(crate) fn play() {}
 /// Seek while task is playing
 ///
 /// If the task is playing, change the playing position.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
-  path = "/v1/tasks/{app_id}/{task_id}/transport/seek",
+  path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}/transport/seek",
   request_body = RequestSeek,
   responses(
     (status = 200, description = "Success", body = TaskSought),
     (status = 404, description = "Task Not found", body = EngineError),
   ),
   params(
+    ("engine_id" = EngineId, Path, description = "Engine id"),
     ("app_id" = AppId, Path, description = "App id"),
     ("task_id" = TaskId, Path, description = "Task id")
   ))]
 pub(crate) fn seek() {}
 
-/// Change the selected mixer
+/// Change the monitored mixers
 ///
-/// If the task is playing, change the mixer that is used to derive monitoring.
+/// If the task is playing, replace the mixers that are used to derive monitoring and their gains.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
-  path = "/v1/tasks/{app_id}/{task_id}/transport/mixer",
+  path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}/transport/mixer",
   request_body = RequestChangeMixer,
   responses(
     (status = 200, description = "Success", body = TaskMixerChanged),
     (status = 404, description = "Task or mixer Not found", body = EngineError),
   ),
   params(
+    ("engine_id" = EngineId, Path, description = "Engine id"),
     ("app_id" = AppId, Path, description = "App id"),
     ("task_id" = TaskId, Path, description = "Task id")
   ))]
@@ -119,15 +135,17 @@ pub(crate) fn change_mixer() {}
 /// Stop playing a task
 ///
 /// Request to stop a track if the task is playing.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
-  path = "/v1/tasks/{app_id}/{task_id}/transport/stop",
+  path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}/transport/stop",
   request_body = RequestStopPlay,
   responses(
     (status = 200, description = "Success", body = TaskPlayStopped),
     (status = 404, description = "Task or mixer Not found", body = EngineError),
   ),
   params(
+    ("engine_id" = EngineId, Path, description = "Engine id"),
     ("app_id" = AppId, Path, description = "App id"),
     ("task_id" = TaskId, Path, description = "Task id")
   ))]
@@ -136,15 +154,17 @@ pub(crate) fn stop_playing() {}
 /// Cancel rendering a task
 ///
 /// Request to stop (cancel) rendering if the task is rendering.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
-  path = "/v1/tasks/{app_id}/{task_id}/transport/cancel",
+  path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}/transport/cancel",
   request_body = RequestCancelRender,
   responses(
     (status = 200, description = "Success", body = TaskRenderCancelled),
     (status = 404, description = "Task or mixer Not found", body = EngineError),
   ),
   params(
+    ("engine_id" = EngineId, Path, description = "Engine id"),
     ("app_id" = AppId, Path, description = "App id"),
     ("task_id" = TaskId, Path, description = "Task id")
   ))]
@@ -156,15 +176,17 @@ pub(crate) fn cancel_render() {}
 /// does not care if the media files are present and will happily execute a render even when no
 /// files (or instances) are ready. The caller to this API should make sure that any such
 /// preconditions are met.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
-  path = "/v1/tasks/{app_id}/{task_id}/transport/render",
+  path = "/v1/engines/{engine_id}/tasks/{app_id}/{task_id}/transport/render",
   request_body = RequestRender,
   responses(
     (status = 200, description = "Success", body = TaskRendering),
     (status = 404, description = "Task or mixer Not found", body = EngineError),
   ),
   params(
+    ("engine_id" = EngineId, Path, description = "Engine id"),
     ("app_id" = AppId, Path, description = "App id"),
     ("task_id" = TaskId, Path, description = "Task id")
   ))]
@@ -3,30 +3,42 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::AppId;
+use crate::{AppId, StorageQuota, StorageUsage};
 
 /// Returned information about an app
 #[derive(Deserialize, Serialize, Debug, JsonSchema)]
 pub struct GetAppResponse {
     /// App Id
-    pub id:          AppId,
+    pub id:            AppId,
     /// If true, the app is enabled - it may make responses to the cloud API
-    pub enabled:     bool,
+    pub enabled:       bool,
     /// App owner/administrator email
-    pub admin_email: String,
+    pub admin_email:   String,
     /// The URL used to resolve object IDs to media information
-    pub media_url:   String,
+    pub media_url:     String,
+    /// Limits on media storage used by the app, null if unlimited
+    #[serde(default)]
+    pub storage_quota: Option<StorageQuota>,
+    /// Media storage currently used by the app
+    #[serde(default)]
+    pub storage_usage: StorageUsage,
 }
 
 /// Request to update app
 #[derive(Deserialize, Serialize, Debug, JsonSchema)]
 pub struct UpdateApp {
     /// If not null, enable or disable the app
-    pub enabled:     Option<bool>,
+    pub enabled:       Option<bool>,
     /// If not null, overwrite the administrator's email
-    pub admin_email: Option<String>,
+    pub admin_email:   Option<String>,
     /// If not null, overwrite the URL used for resolving object IDs to media information
-    pub media_url:   Option<String>,
+    pub media_url:     Option<String>,
+    /// If present, overwrite the media storage quota, null removes the quota so storage is unlimited
+    ///
+    /// If absent, the quota is left unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "::serde_with::rust::double_option")]
+    #[schemars(with = "Option<StorageQuota>")]
+    pub storage_quota: Option<Option<StorageQuota>>,
 }
 
 /// The App has been updated
@@ -40,6 +52,7 @@ pub enum AppUpdated {
 /// Get app details
 ///
 /// Get details of a registered app. Only administrators and app owners may do this.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   get,
   path = "/v1/apps/{app_id}", 
@@ -57,6 +70,7 @@ pub(crate) fn get_app() {}
 ///
 /// Update details of a registered app. Only administrators and app owners may do this. If the media
 /// URL is changed, it will only be used for newly submitted upload and download jobs.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   patch,
   path = "/v1/apps/{app_id}",
@@ -2,13 +2,20 @@
 
 use std::collections::{HashMap, HashSet};
 
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::cloud::tasks::{AvailabilityResult, MaintenanceConflict};
+use crate::cloud::CloudError;
+use crate::common::change::MapDiff;
+pub use crate::common::instance::{FixedInstanceRouting, FixedInstanceRoutingMap};
+use crate::common::media::{StorageQuota, StorageUsage};
 use crate::common::model::{Model, ResourceId};
-use crate::common::task::Task;
+use crate::common::payload::PayloadSecurity;
+use crate::common::task::{Task, DEFAULT_MAX_CONNECTION_VOLUME};
 use crate::newtypes::{AppId, AppTaskId, DomainId, FixedInstanceId, ModelId};
-use crate::time::{TimeRange, Timestamp};
+use crate::time::{now, TimeRange, Timestamp};
 use crate::EngineId;
 
 /// Used by domain for booting
@@ -16,47 +23,192 @@ use crate::EngineId;
 #[serde(rename_all = "snake_case")]
 pub struct DomainConfig {
     /// Id of the domain
-    pub domain_id:            DomainId,
+    pub domain_id:             DomainId,
     /// Fixed instances configured on the domain
     #[serde(default)]
-    pub fixed_instances:      HashMap<FixedInstanceId, DomainFixedInstanceConfig>,
+    pub fixed_instances:       HashMap<FixedInstanceId, DomainFixedInstanceConfig>,
     /// Dynamic instances configured on the domain, with associated limits
     #[serde(default)]
-    pub dynamic_instances:    HashMap<ModelId, DynamicInstanceLimits>,
+    pub dynamic_instances:     HashMap<ModelId, DynamicInstanceLimits>,
     /// Engines configured on the domain
     #[serde(default)]
-    pub engines:              HashMap<EngineId, DomainEngineConfig>,
+    pub engines:               HashMap<EngineId, DomainEngineConfig>,
     /// Currently configured tasks
     #[serde(default)]
-    pub tasks:                HashMap<AppTaskId, Task>,
+    pub tasks:                 HashMap<AppTaskId, Task>,
     /// Configured maintenance time windows during which the domain should not serve requests
     #[serde(default)]
-    pub maintenance:          Vec<Maintenance>,
+    pub maintenance:           Vec<Maintenance>,
     /// Apps allowed to access the domain
     #[serde(default)]
-    pub apps:                 HashSet<AppId>,
+    pub apps:                  HashSet<AppId>,
+    /// Media storage quota of apps without a quota in `app_storage_quotas`, or null if unlimited
+    #[serde(default)]
+    pub default_storage_quota: Option<StorageQuota>,
+    /// Media storage quotas of individual apps, overriding the default quota
+    #[serde(default)]
+    pub app_storage_quotas:    HashMap<AppId, StorageQuota>,
     /// Maximum number of concurrent tasks (when lower than the sum of tasks available on engines)
     #[serde(default)]
-    pub max_concurrent_tasks: Option<usize>,
+    pub max_concurrent_tasks:  Option<usize>,
     /// Minimum Task length
     #[serde(default = "default_min_task_length")]
-    pub min_task_len_ms:      i64,
+    pub min_task_len_ms:       i64,
+    /// Maximum volume factor allowed on task connections
+    #[serde(default = "default_max_connection_volume")]
+    pub max_connection_volume: f64,
     /// Source for commands from the cloud to the domain
     #[serde(default)]
-    pub command_source:       DomainCommandSource,
+    pub command_source:        DomainCommandSource,
     /// Sink for events from the domain to the cloud
     #[serde(default)]
-    pub event_sink:           DomainEventSink,
+    pub event_sink:            DomainEventSink,
+    /// Signing or encryption of payloads on the command source and event sink
+    #[serde(default)]
+    pub payload_security:      PayloadSecurity,
     /// Source of model information for the domain (can include unused models)
-    pub models:               DomainModelSource,
+    pub models:                DomainModelSource,
     /// The public host or IP where domain API is visible to the outside world
-    pub public_host:          String,
+    pub public_host:           String,
+}
+
+impl DomainConfig {
+    /// Find an engine with enough resources to satisfy `required`, trying engines in id order
+    ///
+    /// If no engine fits, the error reports the shortfall on the engine that came closest, which is the engine with the
+    /// smallest relative shortfall on its most lacking resource.
+    pub fn find_engine_for_resources(&self, required: &HashMap<ResourceId, f64>) -> Result<&EngineId, CloudError> {
+        let mut engines = self.engines.iter().collect::<Vec<_>>();
+        engines.sort_by_key(|(engine_id, _)| *engine_id);
+
+        let mut closest: Option<(f64, CloudError)> = None;
+
+        for (engine_id, engine) in engines {
+            match engine.largest_shortfall(required) {
+                None => return Ok(engine_id),
+                Some((shortfall, error)) => {
+                    if closest.as_ref().is_none_or(|(best, _)| shortfall < *best) {
+                        closest = Some((shortfall, error));
+                    }
+                }
+            }
+        }
+
+        Err(closest.map(|(_, error)| error).unwrap_or_else(|| {
+                                                CloudError::InternalInconsistency { message: format!("No engines configured on domain {}",
+                                                                                                     self.domain_id), }
+                                            }))
+    }
+
+    /// Tasks with reservations overlapping any occurrence of the maintenance, sorted by task id
+    ///
+    /// If `instance_id` is not null, the maintenance is on that instance only and only tasks reserving it are affected.
+    pub fn tasks_affected_by_maintenance(&self, maintenance: &Maintenance, instance_id: Option<&FixedInstanceId>) -> Vec<AppTaskId> {
+        let mut affected =
+            self.tasks
+                .iter()
+                .filter(|(_, task)| maintenance.intersects(&task.reservations.time_range()))
+                .filter(|(_, task)| instance_id.is_none_or(|instance_id| task.reservations.fixed_instances.contains(instance_id)))
+                .map(|(task_id, _)| task_id.clone())
+                .collect::<Vec<_>>();
+
+        affected.sort();
+        affected
+    }
+
+    /// Media storage quota of an app on this domain, or null if unlimited
+    pub fn storage_quota(&self, app_id: &AppId) -> Option<&StorageQuota> {
+        self.app_storage_quotas.get(app_id).or(self.default_storage_quota.as_ref())
+    }
+
+    /// Check that the storage used by an app, usually including a pending upload, is within its quota
+    pub fn check_storage_quota(&self, app_id: &AppId, usage: &StorageUsage) -> Result<(), CloudError> {
+        match self.storage_quota(app_id) {
+            Some(quota) => quota.check(app_id, usage),
+            None => Ok(()),
+        }
+    }
+
+    /// Tasks and maintenance conflicting with a reservation of `fixed_instances` during `time`
+    ///
+    /// Tasks conflict only if they reserve any of the same fixed instances.
+    pub fn check_availability(&self, time: &TimeRange, fixed_instances: &HashSet<FixedInstanceId>) -> AvailabilityResult {
+        let mut conflicting_tasks = self.tasks
+                                        .iter()
+                                        .filter(|(_, task)| task.reservations.time_range().intersects(time))
+                                        .filter(|(_, task)| !task.reservations.fixed_instances.is_disjoint(fixed_instances))
+                                        .map(|(task_id, _)| task_id.clone())
+                                        .collect::<Vec<_>>();
+        conflicting_tasks.sort();
+
+        let domain_maintenance = self.maintenance.iter().map(|maintenance| (None, maintenance));
+        let instance_maintenance = fixed_instances.iter()
+                                                  .filter_map(|instance_id| Some((instance_id, self.fixed_instances.get(instance_id)?)))
+                                                  .flat_map(|(instance_id, instance)| {
+                                                      instance.maintenance.iter().map(move |maintenance| (Some(instance_id), maintenance))
+                                                  });
+
+        let conflicting_maintenance =
+            domain_maintenance.chain(instance_maintenance)
+                              .filter(|(_, maintenance)| maintenance.intersects(time))
+                              .map(|(instance_id, maintenance)| MaintenanceConflict { instance_id: instance_id.cloned(),
+                                                                                      maintenance: maintenance.clone(), })
+                              .collect();
+
+        AvailabilityResult { conflicting_tasks,
+                             conflicting_maintenance }
+    }
+}
+
+/// Changes to a domain configuration, applied by a running domain without restarting
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+pub struct DomainConfigUpdate {
+    /// Fixed instances added, reconfigured or removed
+    #[serde(default)]
+    pub fixed_instances: MapDiff<FixedInstanceId, DomainFixedInstanceConfig>,
+    /// Engines added, reconfigured or removed
+    #[serde(default)]
+    pub engines:         MapDiff<EngineId, DomainEngineConfig>,
+    /// Apps newly allowed to access the domain
+    #[serde(default)]
+    pub grant_apps:      HashSet<AppId>,
+    /// Apps no longer allowed to access the domain
+    #[serde(default)]
+    pub revoke_apps:     HashSet<AppId>,
+}
+
+impl DomainConfigUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.fixed_instances.is_empty() && self.engines.is_empty() && self.grant_apps.is_empty() && self.revoke_apps.is_empty()
+    }
+}
+
+impl DomainConfig {
+    /// Changes that turn the instances, engines and app access of this configuration into those of `other`
+    pub fn diff(&self, other: &DomainConfig) -> DomainConfigUpdate {
+        DomainConfigUpdate { fixed_instances: MapDiff::between(&self.fixed_instances, &other.fixed_instances),
+                             engines:         MapDiff::between(&self.engines, &other.engines),
+                             grant_apps:      other.apps.difference(&self.apps).cloned().collect(),
+                             revoke_apps:     self.apps.difference(&other.apps).cloned().collect(), }
+    }
+
+    /// Apply changes to the configuration
+    pub fn apply_update(&mut self, update: DomainConfigUpdate) {
+        update.fixed_instances.apply(&mut self.fixed_instances);
+        update.engines.apply(&mut self.engines);
+        self.apps.retain(|app_id| !update.revoke_apps.contains(app_id));
+        self.apps.extend(update.grant_apps);
+    }
 }
 
 fn default_min_task_length() -> i64 {
     5_000
 }
 
+fn default_max_connection_volume() -> f64 {
+    DEFAULT_MAX_CONNECTION_VOLUME
+}
+
 /// Source of commands for domains
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -66,15 +218,24 @@ pub enum DomainCommandSource {
     /// Consume a kafka topic
     Kafka {
         /// Topic where commands to the domain will be sent
-        topic:    String,
+        topic:             String,
         /// Kafka broker list to be used for commands and events
-        brokers:  String,
+        brokers:           String,
         /// Username used to consume commands
-        username: String,
+        username:          String,
         /// SASL SCRAM password used to consume commands
-        password: String,
+        password:          String,
         /// Read after this offset from event stream, or default to the latest one persisted
-        offset:   Option<i64>,
+        offset:            Option<i64>,
+        /// Consumer group used to commit offsets, or null to not commit offsets to kafka
+        #[serde(default)]
+        group_id:          Option<String>,
+        /// Last offset the domain finished processing, persisted so it can catch up after reconnecting
+        #[serde(default)]
+        committed_offset:  Option<i64>,
+        /// When the last command was processed
+        #[serde(default)]
+        last_processed_at: Option<Timestamp>,
     },
 }
 
@@ -102,6 +263,9 @@ pub enum DomainEventSink {
         username: String,
         /// SASL SCRAM password used to produce events
         password: String,
+        /// Filter on events produced to the topic
+        #[serde(default)]
+        filter:   EventFilter,
     },
 }
 
@@ -111,6 +275,80 @@ impl Default for DomainEventSink {
     }
 }
 
+/// Filter on domain events sent to an event sink
+///
+/// Allows keeping high-volume events (such as metering) local to the domain while lifecycle events
+/// are sent to the cloud. The default filter passes all events.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EventFilter {
+    /// Kinds of events to pass, or empty to pass events of all kinds
+    #[serde(default)]
+    pub kinds:                HashSet<DomainEventKind>,
+    /// Fraction of metering events to pass for every task, between 0 (none) and 1 (all)
+    #[serde(default = "default_metering_sample_rate")]
+    pub metering_sample_rate: f64,
+    /// Minimum severity of events to pass
+    #[serde(default)]
+    pub min_severity:         EventSeverity,
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        Self { kinds:                HashSet::new(),
+               metering_sample_rate: default_metering_sample_rate(),
+               min_severity:         EventSeverity::default(), }
+    }
+}
+
+fn default_metering_sample_rate() -> f64 {
+    1.0
+}
+
+/// Kind of a domain event, used for filtering
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DomainEventKind {
+    /// Fixed instance state changed
+    InstanceState,
+    /// Fixed instance reported an error
+    InstanceError,
+    /// Connection between a fixed instance driver and its hardware changed
+    InstanceConnection,
+    /// Task play state changed
+    TaskPlayState,
+    /// Task streaming packet with audio and metering
+    TaskMetering,
+    /// Task stream lost, repeated or corrupted packets
+    TaskStreamIntegrity,
+    /// Task render exceeded its maximum duration
+    TaskRenderTimedOut,
+    /// Task media could not be decoded cleanly
+    TaskMediaDiagnostics,
+    /// Task was deleted
+    TaskDeleted,
+    /// Task finished, with a summary of the run
+    TaskFinished,
+    /// Media object upload or download changed
+    MediaJob,
+    /// Engine reported that it is alive, with its load
+    EngineHeartbeat,
+    /// Dynamic instance license pool was exhausted
+    LicensePoolExhausted,
+    /// Request to extend the reservation of a task was granted or denied
+    TaskTimeExtension,
+}
+
+/// Severity of a domain event, used for filtering
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventSeverity {
+    #[default]
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
 /// Source for models
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -149,6 +387,42 @@ pub struct DomainEngineConfig {
     pub sample_rate:          usize,
 }
 
+impl DomainEngineConfig {
+    /// Check that the engine has enough resources to satisfy `required`
+    ///
+    /// If several resources are lacking, the error reports the one with the largest relative shortfall.
+    pub fn check_resources(&self, required: &HashMap<ResourceId, f64>) -> Result<(), CloudError> {
+        match self.largest_shortfall(required) {
+            Some((_, error)) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// The resource lacking the most relative to the requested amount, as a fraction of the request and an error
+    ///
+    /// Ties are broken by resource id, so the result does not depend on the order of `required`.
+    fn largest_shortfall(&self, required: &HashMap<ResourceId, f64>) -> Option<(f64, CloudError)> {
+        let mut resources = required.iter().collect::<Vec<_>>();
+        resources.sort_by_key(|(resource, _)| *resource);
+
+        let mut largest: Option<(f64, CloudError)> = None;
+        for (resource, requested) in resources {
+            let available = self.resources.get(resource).copied().unwrap_or_default();
+            if *requested > available {
+                let shortfall = (requested - available) / requested;
+                if largest.as_ref().is_none_or(|(most, _)| shortfall > *most) {
+                    largest = Some((shortfall,
+                                    CloudError::OutOfResource { resource: *resource,
+                                                                available,
+                                                                requested: *requested }));
+                }
+            }
+        }
+
+        largest
+    }
+}
+
 /// Limits on dynamic instances
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct DynamicInstanceLimits {
@@ -159,6 +433,88 @@ pub struct DynamicInstanceLimits {
     pub max_instances: usize,
 }
 
+impl DynamicInstanceLimits {
+    /// Create an empty license pool for a model, sized by these limits
+    pub fn license_pool(&self, model_id: ModelId) -> LicensePool {
+        LicensePool::new(model_id, self.max_instances)
+    }
+}
+
+/// Accounting of licenses (seats) for dynamic instances of a model
+///
+/// Every running dynamic instance occupies a seat in the pool of its model. When all seats are in
+/// use, the pool is exhausted and no more instances may be started, regardless of resources left.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct LicensePool {
+    /// Model the licenses are valid for
+    pub model_id: ModelId,
+    /// Total number of seats in the pool
+    pub total:    usize,
+    /// Number of seats currently in use
+    #[serde(default)]
+    pub in_use:   usize,
+}
+
+impl LicensePool {
+    pub fn new(model_id: ModelId, total: usize) -> Self {
+        Self { model_id,
+               total,
+               in_use: 0 }
+    }
+
+    /// Number of seats that can still be borrowed
+    pub fn available(&self) -> usize {
+        self.total.saturating_sub(self.in_use)
+    }
+
+    /// True if no more seats can be borrowed
+    pub fn is_exhausted(&self) -> bool {
+        self.available() == 0
+    }
+
+    /// Borrow seats from the pool, failing without side effects if not enough are available
+    pub fn borrow_seats(&mut self, seats: usize) -> Result<(), CloudError> {
+        let available = self.available();
+        if seats > available {
+            return Err(CloudError::OutOfLicenses { model_id: self.model_id.clone(),
+                                                   requested: seats,
+                                                   available });
+        }
+
+        self.in_use += seats;
+        Ok(())
+    }
+
+    /// Return previously borrowed seats to the pool
+    pub fn return_seats(&mut self, seats: usize) {
+        self.in_use = self.in_use.saturating_sub(seats);
+    }
+
+    /// Divide the available seats of this pool between engines
+    ///
+    /// Each engine gets an equal share of the available seats, with the remainder going to the engines
+    /// first in the iteration order. Seats already in use are not handed out again, so the resulting
+    /// pools have no seats in use.
+    pub fn divide(&self, engines: impl IntoIterator<Item = EngineId>) -> HashMap<EngineId, LicensePool> {
+        let engines = engines.into_iter().collect::<Vec<_>>();
+        if engines.is_empty() {
+            return HashMap::new();
+        }
+
+        let available = self.available();
+        let share = available / engines.len();
+        let remainder = available % engines.len();
+
+        engines.into_iter()
+               .enumerate()
+               .map(|(index, engine_id)| {
+                   let total = share + if index < remainder { 1 } else { 0 };
+                   (engine_id, LicensePool::new(self.model_id.clone(), total))
+               })
+               .collect()
+    }
+}
+
 /// Configuration of a fixed instance
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DomainFixedInstanceConfig {
@@ -185,18 +541,27 @@ pub struct DomainFixedInstanceConfig {
     /// Maintenance windows on this instance
     #[serde(default)]
     pub maintenance:   Vec<Maintenance>,
+    /// Physical location of the instance within the domain
+    #[serde(default)]
+    pub location:      Option<InstanceLocation>,
+    /// Tags used to allocate the instance to nodes with an instance selector
+    #[serde(default)]
+    pub tags:          HashSet<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
-pub struct FixedInstanceRouting {
-    pub send_count:     usize,
-    pub send_channel:   usize,
-    pub return_count:   usize,
-    pub return_channel: usize,
+/// Physical location of a fixed instance, used to present hardware spatially
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, JsonSchema)]
+pub struct InstanceLocation {
+    /// Room where the instance is located
+    pub room: String,
+    /// Rack within the room, if the instance is rack mounted
+    #[serde(default)]
+    pub rack: Option<String>,
+    /// Slot (rack unit) within the rack, counted from the top
+    #[serde(default)]
+    pub slot: Option<u32>,
 }
 
-pub type FixedInstanceRoutingMap = HashMap<FixedInstanceId, FixedInstanceRouting>;
-
 /// Instance power settings
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DomainPowerInstanceConfig {
@@ -247,9 +612,129 @@ pub struct GetDomainResponse {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct Maintenance {
     /// Time during which maintenance is taking place (may overlap with others)
-    pub time:   TimeRange,
+    ///
+    /// For recurring maintenance, this is the first occurrence.
+    pub time:       TimeRange,
     /// Human readable string about it, or URL to a web page detailing more information
-    pub reason: String,
+    pub reason:     String,
+    /// If not null, the maintenance repeats according to the rule
+    #[serde(default)]
+    pub recurrence: Option<MaintenanceRecurrence>,
+}
+
+impl Maintenance {
+    /// Occurrences of the maintenance intersecting `range`, in order
+    pub fn occurrences_within(&self, range: &TimeRange) -> Vec<TimeRange> {
+        self.candidates(range).filter(|occurrence| occurrence.intersects(range)).collect()
+    }
+
+    /// True if any occurrence of the maintenance intersects `range`
+    pub fn intersects(&self, range: &TimeRange) -> bool {
+        self.candidates(range).any(|occurrence| occurrence.intersects(range))
+    }
+
+    /// Occurrences ending after `range` starts, up to the first one starting after it ends
+    fn candidates(&self, range: &TimeRange) -> impl Iterator<Item = TimeRange> + '_ {
+        let range = *range;
+        let once = self.recurrence.is_none().then_some(self.time);
+        let recurring = self.recurrence
+                            .iter()
+                            .flat_map(move |recurrence| recurrence.occurrences_after(self.time, range.from));
+
+        once.into_iter()
+            .chain(recurring)
+            .take_while(move |occurrence| occurrence.from < range.to)
+    }
+}
+
+/// Rule repeating a maintenance window, similar to an iCalendar `RRULE`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct MaintenanceRecurrence {
+    /// Period of the repetition
+    pub frequency: RecurrenceFrequency,
+    /// Number of periods between occurrences, for example 2 with a weekly frequency repeats every other week
+    #[serde(default = "default_recurrence_interval")]
+    pub interval:  u32,
+    /// If not null, no occurrences start after this time
+    #[serde(default)]
+    pub until:     Option<Timestamp>,
+}
+
+fn default_recurrence_interval() -> u32 {
+    1
+}
+
+impl MaintenanceRecurrence {
+    /// All occurrences, starting with `first`
+    ///
+    /// Occurrences are unbounded unless `until` is set. Monthly occurrences falling on days past the
+    /// end of a month are moved to its last day.
+    pub fn occurrences(&self, first: TimeRange) -> impl Iterator<Item = TimeRange> + '_ {
+        self.occurrences_from(first, 0)
+    }
+
+    /// Occurrences ending after `time`, like [`MaintenanceRecurrence::occurrences`]
+    ///
+    /// Occurrences ending earlier are skipped without expanding them, so times long after `first` are as cheap to
+    /// query as times close to it.
+    pub fn occurrences_after(&self, first: TimeRange, time: Timestamp) -> impl Iterator<Item = TimeRange> + '_ {
+        self.occurrences_from(first, self.occurrences_ending_before(first, time))
+            .skip_while(move |occurrence| occurrence.to <= time)
+    }
+
+    fn occurrences_from(&self, first: TimeRange, index: u32) -> impl Iterator<Item = TimeRange> + '_ {
+        (index..).map_while(move |index| self.occurrence(first, index))
+                 .take_while(|occurrence| self.until.is_none_or(|until| occurrence.from <= until))
+    }
+
+    /// Occurrence at `index`, or null if it is past the range of timestamps
+    fn occurrence(&self, first: TimeRange, index: u32) -> Option<TimeRange> {
+        let periods = index.checked_mul(self.interval.max(1))?;
+        let from = match self.frequency {
+            RecurrenceFrequency::Daily => first.from.checked_add_signed(Duration::days(periods as i64))?,
+            RecurrenceFrequency::Weekly => first.from.checked_add_signed(Duration::weeks(periods as i64))?,
+            RecurrenceFrequency::Monthly => add_months(first.from, periods)?,
+        };
+
+        Some(TimeRange::new(from, from.checked_add_signed(first.len())?))
+    }
+
+    /// Number of occurrences that certainly end at or before `time`
+    fn occurrences_ending_before(&self, first: TimeRange, time: Timestamp) -> u32 {
+        let last_from = match time.checked_sub_signed(first.len()) {
+            Some(last_from) if last_from > first.from => last_from,
+            _ => return 0,
+        };
+
+        let periods = match self.frequency {
+            RecurrenceFrequency::Daily => (last_from - first.from).num_days(),
+            RecurrenceFrequency::Weekly => (last_from - first.from).num_weeks(),
+            // months before the one of `last_from`, as occurrences past the end of a month move to its last day
+            RecurrenceFrequency::Monthly => {
+                (i64::from(last_from.year()) - i64::from(first.from.year())) * 12 + i64::from(last_from.month())
+                - i64::from(first.from.month())
+                - 1
+            }
+        };
+
+        u32::try_from(periods.max(0) / i64::from(self.interval.max(1))).unwrap_or(u32::MAX)
+    }
+}
+
+fn add_months(timestamp: Timestamp, months: u32) -> Option<Timestamp> {
+    let naive = timestamp.naive_utc();
+    let date = naive.date().checked_add_months(Months::new(months))?;
+
+    Some(DateTime::from_utc(date.and_time(naive.time()), Utc))
+}
+
+/// Period of a maintenance recurrence
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
 }
 
 /// Fixed instance summary for apps
@@ -263,6 +748,9 @@ pub struct AppFixedInstance {
     pub sidecars:    HashSet<ModelId>,
     /// Configured maintenance time windows during which the instance should not serve requests
     pub maintenance: Vec<Maintenance>,
+    /// Physical location of the instance within the domain
+    #[serde(default)]
+    pub location:    Option<InstanceLocation>,
 }
 
 impl From<DomainFixedInstanceConfig> for AppFixedInstance {
@@ -271,21 +759,77 @@ impl From<DomainFixedInstanceConfig> for AppFixedInstance {
                                         power,
                                         media,
                                         maintenance,
+                                        location,
                                         .. } = instance;
         Self { power: power.is_some(),
                media: media.is_some(),
                maintenance,
-               sidecars }
+               sidecars,
+               location }
     }
 }
 
 /// Add maintenance to an object
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct AddMaintenance {
-    /// When is it taking place
-    pub time:   TimeRange,
+    /// When is it taking place, the first occurrence if the maintenance is recurring
+    pub time:            TimeRange,
     /// WHat is the reason for maintenance (human readable string or URL with more information
-    pub reason: String,
+    pub reason:          String,
+    /// If not null, the maintenance repeats according to the rule
+    #[serde(default)]
+    pub recurrence:      Option<MaintenanceRecurrence>,
+    /// What to do with tasks reserved during the maintenance
+    #[serde(default)]
+    pub conflict_policy: MaintenanceConflictPolicy,
+}
+
+impl AddMaintenance {
+    /// The maintenance window to store
+    pub fn maintenance(&self) -> Maintenance {
+        Maintenance { time:       self.time,
+                      reason:     self.reason.clone(),
+                      recurrence: self.recurrence.clone(), }
+    }
+
+    /// Resolve conflicts with the tasks affected by the maintenance, according to the conflict policy
+    ///
+    /// Returns the tasks that need to be cancelled before the maintenance starts.
+    pub fn resolve_conflicts(&self, affected: Vec<AppTaskId>) -> Result<Vec<AppTaskId>, CloudError> {
+        match self.conflict_policy {
+            MaintenanceConflictPolicy::Reject if !affected.is_empty() => Err(CloudError::MaintenanceConflict { task_ids: affected }),
+            MaintenanceConflictPolicy::Reject | MaintenanceConflictPolicy::AllowOverlap => Ok(vec![]),
+            MaintenanceConflictPolicy::CancelTasks { notice_ms } => {
+                let notice_until =
+                    i64::try_from(notice_ms).ok()
+                                            .and_then(|notice_ms| now().checked_add_signed(Duration::milliseconds(notice_ms)));
+
+                if !affected.is_empty() && notice_until.is_none_or(|notice_until| self.time.from < notice_until) {
+                    return Err(CloudError::MaintenanceNoticeTooShort { starts_at: self.time.from,
+                                                                       notice_ms,
+                                                                       task_ids: affected });
+                }
+
+                Ok(affected)
+            }
+        }
+    }
+}
+
+/// How to resolve conflicts between added maintenance and tasks reserved during the maintenance
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceConflictPolicy {
+    /// Do not add the maintenance if any task is affected
+    Reject,
+    /// Cancel the affected tasks, if the maintenance starts at least `notice_ms` milliseconds from now
+    CancelTasks {
+        /// Minimum notice given to the affected tasks, in milliseconds
+        notice_ms: u64,
+    },
+    /// Add the maintenance and keep the affected tasks, as maintenance was added before policies existed
+    #[default]
+    AllowOverlap,
 }
 
 /// Clear maintenance from an object
@@ -309,6 +853,7 @@ pub enum DomainUpdated {
 ///
 /// Get details about a domain. Available to owners, administrators and apps where the app has
 /// permission to access domain details.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 get,
 path = "/v1/domains/{domain_id}",
@@ -327,6 +872,7 @@ pub(crate) fn get_domain() {}
 /// When a domain starts in cloud mode, it will get the details of its configuration from the cloud.
 /// This endpoint delivers all of the cloud information about the domain, including instances,
 /// audio engines and cloud synchronization endpoints.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 get,
 path = "/v1/domains/{domain_id}/config",
@@ -344,7 +890,9 @@ pub(crate) fn get_domain_config() {}
 ///
 /// Add a designated time of maitnenance to the whole domain. When a domain is in maintenance, it
 /// cannot serve API requests or process tasks. Apps will not be able to create bookings against the
-/// domain that intersect with maintenance windows.
+/// domain that intersect with maintenance windows. Tasks already reserved during the maintenance
+/// are handled according to the conflict policy of the request.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 post,
 path = "/v1/domains/{domain_id}/maintenance",
@@ -353,6 +901,7 @@ responses(
 (status = 200, description = "Success", body = DomainUpdated),
 (status = 401, description = "Not authorized", body = CloudError),
 (status = 404, description = "Not found", body = CloudError),
+(status = 409, description = "Conflicts with reserved tasks", body = CloudError),
 ),
 params(
 ("domain_id" = DomainId, Path, description = "Domain to add maintenance to"),
@@ -362,6 +911,7 @@ pub(crate) fn add_domain_maintenance() {}
 /// Clear domain maintenance time
 ///
 /// Clear any maitnenance on the domain that matches the time predicates provided.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 delete,
 path = "/v1/domains/{domain_id}/maintenance",
@@ -380,7 +930,9 @@ pub(crate) fn clear_domain_maintenance() {}
 ///
 /// Add a designated time of maitnenance to an instance in a domain. When an instance is in
 /// maintenance, it cannot process tasks. Apps will not be able to create bookings against the
-/// instance that intersect with maintenance windows.
+/// instance that intersect with maintenance windows. Tasks already reserved on the instance during
+/// the maintenance are handled according to the conflict policy of the request.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 post,
 path = "/v1/domains/{domain_id}/instances/{manufacturer}/{name}/{instance}/maintenance",
@@ -389,6 +941,7 @@ responses(
 (status = 200, description = "Success", body = DomainUpdated),
 (status = 401, description = "Not authorized", body = CloudError),
 (status = 404, description = "Not found", body = CloudError),
+(status = 409, description = "Conflicts with reserved tasks", body = CloudError),
 ),
 params(
 ("domain_id" = DomainId, Path, description = "Domain hosting the instance"),
@@ -401,6 +954,7 @@ pub(crate) fn add_fixed_instance_maintenance() {}
 /// Clear instance maintenance time
 ///
 /// Clear any maitnenance on the instance that matches the time predicates provided.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 delete,
 path = "/v1/domains/{domain_id}/instances/{manufacturer}/{name}/{instance}/maintenance",
@@ -2,8 +2,10 @@ use crate::AppMediaObjectId;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::common::media::MediaJobState;
-use crate::common::{AppId, DomainId, MediaObjectId, TaskId};
+use crate::common::media::{MediaChecksum, MediaJobState, StorageQuota, StorageUsage};
+use crate::common::task::{MediaChannels, TrackMediaFormat};
+use crate::common::time::Timestamp;
+use crate::common::{AppId, AuditEntryId, DomainId, MediaObjectId, MultipartUploadId, TaskId};
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -21,6 +23,12 @@ pub enum ReportMediaJobProgress {
         media_id: MediaObjectId,
         state:    MediaJobState,
     },
+    /// Reporting waveform peaks generation progress
+    GeneratePeaks {
+        app_id:   AppId,
+        media_id: MediaObjectId,
+        state:    MediaJobState,
+    },
 }
 
 /// Confirming upload is created
@@ -30,6 +38,144 @@ pub enum UploadCreated {
     Created { media_id: AppMediaObjectId, domain_id: DomainId },
 }
 
+/// Confirming waveform peaks generation is created
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PeaksGenerationCreated {
+    Created { media_id: AppMediaObjectId, domain_id: DomainId },
+}
+
+/// Current media storage usage of an app
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct AppMediaUsage {
+    /// App Id
+    pub app_id: AppId,
+    /// Storage currently used by the app's media objects
+    pub usage:  StorageUsage,
+    /// Storage quota of the app, null if unlimited
+    pub quota:  Option<StorageQuota>,
+}
+
+/// Minimum size of a multipart upload part, except for the last one, in bytes
+pub const MIN_MULTIPART_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Maximum number of parts of a multipart upload
+pub const MAX_MULTIPART_PARTS: usize = 10_000;
+
+/// Start a resumable upload of a media object in parts
+///
+/// Parts may be uploaded in any order and retried individually, which makes moving large multitrack
+/// files reliable over poor connections.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreateMultipartUpload {
+    pub channels:    MediaChannels,
+    pub format:      TrackMediaFormat,
+    pub seconds:     f64,
+    pub sample_rate: usize,
+    /// Total size of the media object, in bytes
+    pub bytes:       u64,
+    /// Requested size of each part, the cloud may choose a larger size to stay within [`MAX_MULTIPART_PARTS`]
+    pub part_size:   u64,
+    /// If not null, the domain verifies the assembled media against the checksum
+    #[serde(default)]
+    pub checksum:    Option<MediaChecksum>,
+}
+
+impl CreateMultipartUpload {
+    /// Byte ranges of the parts, at least [`MIN_MULTIPART_PART_SIZE`] long and at most [`MAX_MULTIPART_PARTS`] of them
+    pub fn part_ranges(&self) -> Vec<ByteRange> {
+        let min_part_size = self.bytes.div_ceil(MAX_MULTIPART_PARTS as u64);
+        let part_size = self.part_size.max(MIN_MULTIPART_PART_SIZE).max(min_part_size);
+
+        (0..self.bytes).step_by(part_size as usize)
+                       .map(|start| ByteRange { start,
+                                                end: (start + part_size).min(self.bytes) })
+                       .collect()
+    }
+}
+
+/// Range of bytes within a media object, `start` inclusive and `end` exclusive
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end:   u64,
+}
+
+impl ByteRange {
+    pub fn len(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A part of a multipart upload
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UploadPart {
+    /// Index of the part, starting at zero
+    pub index:      usize,
+    /// Bytes of the media object contained in the part
+    pub byte_range: ByteRange,
+    /// Pre-signed URL where the part content should be uploaded with `PUT`
+    pub url:        String,
+}
+
+/// Confirming multipart upload is created
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MultipartUploadCreated {
+    pub upload_id:  MultipartUploadId,
+    pub media_id:   AppMediaObjectId,
+    pub domain_id:  DomainId,
+    /// Parts to upload, covering the whole media object in order
+    pub parts:      Vec<UploadPart>,
+    /// Time after which unfinished uploads are discarded
+    pub expires_at: Timestamp,
+}
+
+/// Progress of a multipart upload, used to resume it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MultipartUploadStatus {
+    pub upload_id:      MultipartUploadId,
+    /// All parts of the upload, with fresh URLs
+    pub parts:          Vec<UploadPart>,
+    /// Parts that were received, they do not need to be uploaded again
+    pub uploaded_parts: Vec<UploadedPart>,
+    /// Time after which the unfinished upload is discarded
+    pub expires_at:     Timestamp,
+}
+
+impl MultipartUploadStatus {
+    /// Indices of the parts that still need to be uploaded
+    pub fn missing_parts(&self) -> Vec<usize> {
+        self.parts
+            .iter()
+            .map(|part| part.index)
+            .filter(|index| !self.uploaded_parts.iter().any(|uploaded| uploaded.index == *index))
+            .collect()
+    }
+}
+
+/// A part received by the cloud
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UploadedPart {
+    /// Index of the part
+    pub index: usize,
+    /// Entity tag returned by the storage when the part was uploaded
+    pub etag:  String,
+}
+
+/// Finish a multipart upload once all parts are uploaded
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CompleteMultipartUpload {
+    /// All parts of the upload, in order
+    pub parts:      Vec<UploadedPart>,
+    /// URL to notify when the media object has been transferred to the domain
+    #[serde(default)]
+    pub notify_url: Option<String>,
+}
+
 /// Confirming download is created
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -37,16 +183,29 @@ pub enum DownloadCreated {
     Created { media_id: AppMediaObjectId, domain_id: DomainId },
 }
 
+/// Confirming multipart upload is aborted
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MultipartUploadAborted {
+    Aborted { upload_id: MultipartUploadId },
+}
+
 /// Confirming media object is scheduled for deletion
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum MediaObjectDeleted {
-    Deleted { media_id: AppMediaObjectId },
+    Deleted {
+        media_id: AppMediaObjectId,
+        /// Audit entry recording the deletion, if audited
+        #[serde(default)]
+        audit_id: Option<AuditEntryId>,
+    },
 }
 
 /// Uplod a media object
 ///
 /// Upload or replace content of a domain object from an app's private storage.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   put,
   path = "/v1/domains/{domain_id}/media/{app_id}/{object_id}/upload",
@@ -54,6 +213,7 @@ pub enum MediaObjectDeleted {
   responses(
     (status = 200, description = "Success", body = UploadCreated),
     (status = 401, description = "Not authorized", body = CloudError),
+    (status = 403, description = "Storage quota of the app exceeded", body = CloudError),
     (status = 404, description = "Domain, app or task not found", body = CloudError),
   ),
   params(
@@ -63,9 +223,94 @@ pub enum MediaObjectDeleted {
   ))]
 pub(crate) fn upload_media_object() {}
 
+/// Create a multipart upload
+///
+/// Start a resumable upload of a media object in parts. Once all parts are uploaded to their URLs, the
+/// upload is completed and the assembled media object is transferred to the domain.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/domains/{domain_id}/media/{app_id}/{object_id}/multipart",
+  request_body = CreateMultipartUpload,
+  responses(
+    (status = 200, description = "Success", body = MultipartUploadCreated),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 403, description = "Storage quota of the app exceeded", body = CloudError),
+    (status = 404, description = "Domain or app not found", body = CloudError),
+  ),
+  params(
+    ("domain_id" = DomainId, Path, description = "Domain to upload the file to"),
+    ("app_id" = AppId, Path, description = "Owner of the file"),
+    ("object_id" = MediaObjectId, Path, description = "File object ID"),
+  ))]
+pub(crate) fn create_multipart_upload() {}
+
+/// Get multipart upload status
+///
+/// Get the parts that were already received, and fresh URLs for all parts, to resume an interrupted upload.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  get,
+  path = "/v1/domains/{domain_id}/media/{app_id}/{object_id}/multipart/{upload_id}",
+  responses(
+    (status = 200, description = "Success", body = MultipartUploadStatus),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "Domain, app or upload not found", body = CloudError),
+  ),
+  params(
+    ("domain_id" = DomainId, Path, description = "Domain to upload the file to"),
+    ("app_id" = AppId, Path, description = "Owner of the file"),
+    ("object_id" = MediaObjectId, Path, description = "File object ID"),
+    ("upload_id" = MultipartUploadId, Path, description = "Multipart upload ID"),
+  ))]
+pub(crate) fn get_multipart_upload() {}
+
+/// Complete a multipart upload
+///
+/// Assemble the uploaded parts into the media object and transfer it to the domain.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/domains/{domain_id}/media/{app_id}/{object_id}/multipart/{upload_id}/complete",
+  request_body = CompleteMultipartUpload,
+  responses(
+    (status = 200, description = "Success", body = UploadCreated),
+    (status = 400, description = "Parts are missing or do not match", body = CloudError),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "Domain, app or upload not found", body = CloudError),
+  ),
+  params(
+    ("domain_id" = DomainId, Path, description = "Domain to upload the file to"),
+    ("app_id" = AppId, Path, description = "Owner of the file"),
+    ("object_id" = MediaObjectId, Path, description = "File object ID"),
+    ("upload_id" = MultipartUploadId, Path, description = "Multipart upload ID"),
+  ))]
+pub(crate) fn complete_multipart_upload() {}
+
+/// Abort a multipart upload
+///
+/// Discard an unfinished upload and all parts uploaded so far.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  delete,
+  path = "/v1/domains/{domain_id}/media/{app_id}/{object_id}/multipart/{upload_id}",
+  responses(
+    (status = 200, description = "Success", body = MultipartUploadAborted),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "Domain, app or upload not found", body = CloudError),
+  ),
+  params(
+    ("domain_id" = DomainId, Path, description = "Domain to upload the file to"),
+    ("app_id" = AppId, Path, description = "Owner of the file"),
+    ("object_id" = MediaObjectId, Path, description = "File object ID"),
+    ("upload_id" = MultipartUploadId, Path, description = "Multipart upload ID"),
+  ))]
+pub(crate) fn abort_multipart_upload() {}
+
 /// Download a media object
 ///
 /// Download a media object from a domain to an app's private storage.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   put,
   path = "/v1/domains/{domain_id}/media/{app_id}/{object_id}/download",
@@ -82,9 +327,31 @@ pub(crate) fn upload_media_object() {}
   ))]
 pub(crate) fn download_media_object() {}
 
+/// Generate waveform peaks
+///
+/// Generate waveform peaks of a media object on a domain, for displaying waveform overviews. Progress is
+/// reported like uploads and downloads, and the peaks are described on the media object when done.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  put,
+  path = "/v1/domains/{domain_id}/media/{app_id}/{object_id}/peaks",
+  request_body = GeneratePeaks,
+  responses(
+    (status = 200, description = "Success", body = PeaksGenerationCreated),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "Domain, app or object not found", body = CloudError),
+  ),
+  params(
+    ("domain_id" = DomainId, Path, description = "Domain the media object is on"),
+    ("app_id" = AppId, Path, description = "Owner of the file"),
+    ("object_id" = MediaObjectId, Path, description = "File object ID"),
+  ))]
+pub(crate) fn generate_media_peaks() {}
+
 /// Delete a media object
 ///
 /// Delete a media object form all domains that have a copy.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   delete,
   path = "/v1/apps/{app_id}/media/{object_id}",
@@ -100,9 +367,28 @@ pub(crate) fn download_media_object() {}
   ))]
 pub(crate) fn delete_media_object() {}
 
+/// Get media storage usage
+///
+/// Get the storage currently used by media objects of an app, along with its quota. Only
+/// administrators and app owners may do this.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  get,
+  path = "/v1/apps/{app_id}/media/usage",
+  responses(
+    (status = 200, description = "Success", body = AppMediaUsage),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "App not found", body = CloudError),
+  ),
+  params(
+    ("app_id" = AppId, Path, description = "App to get usage of"),
+  ))]
+pub(crate) fn get_app_media_usage() {}
+
 /// Update upload/download progress
 ///
 /// Used by domains to communicate upload or download progress.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   put,
   path = "/v1/domains/{domain_id}/media/{app_id}/{object_id}/report",
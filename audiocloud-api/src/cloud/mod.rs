@@ -1,150 +1,34 @@
 //! API definitions for the Cloud
 
-use std::collections::HashSet;
-
 use schemars::schema::RootSchema;
-use schemars::{schema_for, JsonSchema};
-use serde::{Deserialize, Serialize};
-use thiserror::Error;
+use schemars::schema_for;
+#[cfg(feature = "openapi")]
 use utoipa::OpenApi;
 
-use crate::common::change::ModifyTaskError;
-use crate::common::model::ResourceId;
-use crate::{
-    merge_schemas, AppId, AppMediaObjectId, AppTaskId, ChannelMask, DomainId, DynamicInstanceNodeId, FixedInstanceId, FixedInstanceNodeId,
-    MixerNodeId, ModelId, NodeConnectionId, TrackNodeId,
-};
+pub use crate::common::error::CloudError;
+use crate::merge_schemas;
 
 pub mod apps;
 pub mod domains;
 pub mod media;
+#[cfg(feature = "models-registry")]
 pub mod models;
+pub mod presets;
+pub mod scheduling;
 pub mod tasks;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Error, JsonSchema)]
-#[serde(rename_all = "snake_case", tag = "type")]
-pub enum CloudError {
-    #[error("API Key not found")]
-    ApiKeyNotFound,
-
-    #[error("App file {id} not found")]
-    AppFileNotFound { id: AppMediaObjectId },
-
-    #[error("App {id} not found")]
-    AppNotFound { id: AppId },
-
-    #[error("{task_id} is an invalid app task ID")]
-    InvalidAppTaskId { task_id: String },
-
-    #[error("{object_id} is an invalid app media object ID")]
-    InvalidAppMediaObjectId { object_id: String },
-
-    #[error("At least a segment of a reservation needs to be in the future")]
-    OnlyFutureReservations,
-
-    #[error("Task time must be well-formed")]
-    TimeMalformed,
-
-    #[error("Task requested duration {requested} is smaller than domain minimum task duration time {minimum} ms")]
-    DurationTooShort { minimum: f64, requested: f64 },
-
-    #[error("Too many overlapping tasks reserved on domain, maximum is {max}")]
-    TooManyTasks { max: usize },
-
-    #[error("Detected internal inconsistency: {message}")]
-    InternalInconsistency { message: String },
-
-    #[error("Instances overlapping: {instance_ids:?}")]
-    OverlappingFixedInstances { instance_ids: HashSet<FixedInstanceId> },
-
-    #[error("Connection error: {connection_id}: {error}")]
-    ConnectionError {
-        connection_id: NodeConnectionId,
-        error:         Box<CloudError>,
-    },
-
-    #[error("Channel mask {mask:?} is invalid for channel count {channels}")]
-    ChannelMaskIncompatible { mask: ChannelMask, channels: usize },
-
-    #[error("Mixer instance node not found: {mixer_node_id}")]
-    MixerNodeNotFound { mixer_node_id: MixerNodeId },
-
-    #[error("Mixer instance node not found: {track_node_id}")]
-    TrackNodeNotFound { track_node_id: TrackNodeId },
-
-    #[error("Fixd instance node not found: {fixed_node_id}")]
-    FixedInstanceNodeNotFound { fixed_node_id: FixedInstanceNodeId },
-
-    #[error("Dynamic instance node not found: {dynamic_node_id}")]
-    DynamicInstanceNodeNotFound { dynamic_node_id: DynamicInstanceNodeId },
-
-    #[error("Domain {domain_id} unknown")]
-    DomainNotFound { domain_id: DomainId },
-
-    #[error("Instance {instance_id} unknown")]
-    InstanceNotFound { instance_id: FixedInstanceId },
-
-    #[error("Model {model_id} unknown")]
-    ModelNotFound { model_id: ModelId },
-
-    #[error("Model {model_id} of a dynamic instance required by node {node_id} is not supported on domain {domain_id}")]
-    DynamicInstanceNotSupported {
-        node_id:   DynamicInstanceNodeId,
-        domain_id: DomainId,
-        model_id:  ModelId,
-    },
-
-    #[error("Fixed instance {instance_id} required by fixed instance node {node_id} is not supported on domain {domain_id}")]
-    FixedInstanceNotSupported {
-        node_id:     FixedInstanceNodeId,
-        domain_id:   DomainId,
-        instance_id: FixedInstanceId,
-    },
-
-    #[error("Fixed instance {instance_id} required by fixed instance node {node_id} is not avaialble to app {app_id} on domain {domain_id}")]
-    FixedInstanceAccessDenied {
-        node_id:     FixedInstanceNodeId,
-        domain_id:   DomainId,
-        instance_id: FixedInstanceId,
-        app_id:      AppId,
-    },
-
-    #[error("Out of {resource} resource. Requested {requested} available {available}")]
-    OutOfResource {
-        resource:  ResourceId,
-        available: f64,
-        requested: f64,
-    },
-
-    #[error("Task {task_id} was not found")]
-    TaskNotFound { task_id: AppTaskId },
-
-    #[error("Task could not be modified: {error}")]
-    TaskModification {
-        #[from]
-        error: ModifyTaskError,
-    },
-
-    #[error("Database error: {message}")]
-    Database { message: String },
-
-    #[error("Authentication failed: {message}")]
-    Authentication { message: String },
-
-    #[error("Authorization failed: {message}")]
-    Authorization { message: String },
-
-    #[error("All retries exhausted while trying to obtain a lock")]
-    BlockingLock,
-}
-
+#[cfg(feature = "openapi")]
 #[derive(OpenApi)]
 #[openapi(paths(apps::get_app,
                 apps::update_app,
                 tasks::create_task,
+                tasks::check_availability,
+                tasks::get_task_history,
+                tasks::get_task_run_summary,
                 tasks::adjust_task_time,
                 tasks::delete_task,
                 tasks::modify_task_spec,
+                tasks::bulk_modify_tasks,
                 domains::get_domain,
                 domains::get_domain_config,
                 domains::add_domain_maintenance,
@@ -152,13 +36,24 @@ pub enum CloudError {
                 domains::add_fixed_instance_maintenance,
                 domains::clear_fixed_instance_maintenance,
                 media::upload_media_object,
+                media::create_multipart_upload,
+                media::get_multipart_upload,
+                media::complete_multipart_upload,
+                media::abort_multipart_upload,
                 media::download_media_object,
+                media::generate_media_peaks,
                 media::delete_media_object,
-                media::report_media_job_progress))]
+                media::get_app_media_usage,
+                media::report_media_job_progress,
+                presets::create_model_preset,
+                presets::list_model_presets,
+                presets::get_model_preset,
+                presets::delete_model_preset))]
 pub struct CloudApi;
 
 pub fn schemas() -> RootSchema {
     merge_schemas([schema_for!(CloudError),
+                   schema_for!(crate::CodedError<CloudError>),
                    schema_for!(crate::ModifyTaskError),
                    schema_for!(crate::AppId),
                    schema_for!(crate::DomainId),
@@ -168,14 +63,29 @@ pub fn schemas() -> RootSchema {
                    schema_for!(crate::MixerNode),
                    schema_for!(crate::DynamicInstanceNode),
                    schema_for!(crate::FixedInstanceNode),
+                   schema_for!(crate::RecorderNode),
+                   schema_for!(crate::RecorderMediaNaming),
+                   schema_for!(crate::CompositeNode),
                    schema_for!(crate::NodeConnection),
+                   schema_for!(crate::PanLaw),
+                   schema_for!(crate::Timecode),
+                   schema_for!(crate::TempoMap),
+                   schema_for!(crate::TempoMapError),
+                   schema_for!(crate::PlayClick),
+                   schema_for!(crate::PlayTimecode),
                    schema_for!(crate::TaskPermissions),
+                   schema_for!(crate::TaskKeyGrant),
+                   schema_for!(crate::KeySource),
+                   schema_for!(crate::AuditEntry),
                    schema_for!(crate::TrackMedia),
                    schema_for!(crate::TaskSpec),
                    schema_for!(crate::ModifyTaskSpec),
                    schema_for!(crate::ModifyTask),
                    schema_for!(crate::Model),
                    schema_for!(crate::MediaJobState),
+                   schema_for!(crate::MediaJobErrorKind),
+                   schema_for!(crate::MediaDiagnostics),
+                   schema_for!(crate::MediaChecksum),
                    schema_for!(crate::UploadToDomain),
                    schema_for!(crate::DownloadFromDomain),
                    schema_for!(apps::GetAppResponse),
@@ -183,24 +93,71 @@ pub fn schemas() -> RootSchema {
                    schema_for!(apps::AppUpdated),
                    schema_for!(tasks::CreateTask),
                    schema_for!(tasks::TaskCreated),
+                   schema_for!(tasks::InstanceAllocation),
+                   schema_for!(crate::InstanceSelector),
                    schema_for!(tasks::TaskUpdated),
                    schema_for!(tasks::TaskDeleted),
                    schema_for!(tasks::AdjustTaskTime),
                    schema_for!(tasks::ModifyTaskList),
+                   schema_for!(tasks::TaskFilter),
+                   schema_for!(tasks::BulkModifyTasks),
+                   schema_for!(tasks::BulkTasksModified),
+                   schema_for!(tasks::BulkModifyResult),
+                   schema_for!(tasks::CheckAvailability),
+                   schema_for!(tasks::AvailabilityResult),
+                   schema_for!(tasks::MaintenanceConflict),
+                   schema_for!(tasks::TaskRunSummary),
+                   schema_for!(tasks::TaskHistory),
+                   schema_for!(tasks::TaskFinalState),
+                   schema_for!(tasks::RenderSummary),
+                   schema_for!(tasks::RenderOutcome),
+                   schema_for!(tasks::TaskRunStatistics),
                    schema_for!(domains::DomainMediaInstanceConfig),
                    schema_for!(domains::DomainPowerInstanceConfig),
                    schema_for!(domains::GetDomainResponse),
                    schema_for!(domains::DomainConfig),
+                   schema_for!(domains::DomainConfigUpdate),
                    schema_for!(domains::DomainUpdated),
                    schema_for!(domains::AddMaintenance),
+                   schema_for!(domains::MaintenanceConflictPolicy),
                    schema_for!(domains::ClearMaintenance),
                    schema_for!(domains::Maintenance),
+                   schema_for!(domains::MaintenanceRecurrence),
+                   schema_for!(domains::RecurrenceFrequency),
                    schema_for!(domains::AppFixedInstance),
                    schema_for!(domains::DomainFixedInstanceConfig),
+                   schema_for!(domains::InstanceLocation),
                    schema_for!(domains::DynamicInstanceLimits),
+                   schema_for!(domains::LicensePool),
                    schema_for!(domains::DomainEngineConfig),
+                   schema_for!(domains::EventFilter),
+                   schema_for!(crate::PayloadSecurity),
+                   schema_for!(crate::PayloadEnvelope),
+                   schema_for!(crate::PayloadSecurityError),
                    schema_for!(media::DownloadCreated),
                    schema_for!(media::UploadCreated),
+                   schema_for!(media::PeaksGenerationCreated),
+                   schema_for!(crate::GeneratePeaks),
+                   schema_for!(crate::MediaPeaks),
+                   schema_for!(media::CreateMultipartUpload),
+                   schema_for!(media::ByteRange),
+                   schema_for!(media::UploadPart),
+                   schema_for!(media::MultipartUploadCreated),
+                   schema_for!(media::MultipartUploadStatus),
+                   schema_for!(media::UploadedPart),
+                   schema_for!(media::CompleteMultipartUpload),
+                   schema_for!(media::MultipartUploadAborted),
+                   schema_for!(crate::MultipartUploadId),
                    schema_for!(media::MediaObjectDeleted),
-                   schema_for!(media::ReportMediaJobProgress)].into_iter())
+                   schema_for!(media::ReportMediaJobProgress),
+                   schema_for!(media::AppMediaUsage),
+                   schema_for!(crate::StorageQuota),
+                   schema_for!(crate::StorageUsage),
+                   schema_for!(crate::PresetId),
+                   schema_for!(presets::PresetMetadata),
+                   schema_for!(presets::ModelPreset),
+                   schema_for!(presets::ModelPresetList),
+                   schema_for!(presets::CreateModelPreset),
+                   schema_for!(presets::ModelPresetCreated),
+                   schema_for!(presets::ModelPresetDeleted)].into_iter())
 }
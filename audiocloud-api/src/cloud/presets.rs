@@ -0,0 +1,169 @@
+//! Cloud APIs for the model preset catalog
+//!
+//! Presets are parameter values of a model stored in the cloud, so apps can share settings of
+//! hardware and software instances.
+
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{AppId, CloudError, InstanceParameters, Model, ModelId, PresetId, Timestamp, ToParameterMap};
+
+/// Descriptive information about a preset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PresetMetadata {
+    /// Human readable name of the preset
+    pub name:        String,
+    /// Name of the person or organization that created the preset
+    pub author:      String,
+    /// Longer description, for example the material the preset is intended for
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Tags used to search for presets
+    #[serde(default)]
+    pub tags:        HashSet<String>,
+}
+
+/// A preset stored in the catalog
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ModelPreset {
+    /// Preset Id
+    pub preset_id:  PresetId,
+    /// Model the preset applies to
+    pub model_id:   ModelId,
+    /// App that created the preset
+    pub app_id:     AppId,
+    /// Descriptive information
+    pub metadata:   PresetMetadata,
+    /// Parameter values
+    pub parameters: InstanceParameters,
+    /// When the preset was created
+    pub created_at: Timestamp,
+}
+
+pub type ModelPresetList = Vec<ModelPreset>;
+
+/// Request to add a preset to the catalog
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CreateModelPreset {
+    /// Descriptive information
+    pub metadata:   PresetMetadata,
+    /// Parameter values, validated against the model
+    pub parameters: InstanceParameters,
+}
+
+impl CreateModelPreset {
+    /// Create a preset from typed (generated) model parameters
+    pub fn new(metadata: PresetMetadata, parameters: &impl ToParameterMap) -> Self {
+        Self { metadata,
+               parameters: parameters.to_parameter_map().into() }
+    }
+
+    /// Check the parameter values against the model the preset is created for
+    pub fn validate(&self, model_id: &ModelId, model: &Model) -> Result<(), CloudError> {
+        self.parameters
+            .validate(model)
+            .map_err(|error| CloudError::InvalidPresetParameters { model_id: model_id.clone(),
+                                                                   error })
+    }
+}
+
+/// Confirmation that a preset was added to the catalog
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelPresetCreated {
+    /// Created normally
+    Created {
+        /// Model the preset applies to
+        model_id:  ModelId,
+        /// Id of the new preset
+        preset_id: PresetId,
+    },
+}
+
+/// Confirmation that a preset was removed from the catalog
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelPresetDeleted {
+    /// Deleted normally
+    Deleted {
+        /// Model the preset applied to
+        model_id:  ModelId,
+        /// Id of the deleted preset
+        preset_id: PresetId,
+    },
+}
+
+/// Create a preset
+///
+/// Add a preset to the catalog of a model. The parameter values are validated against the model.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/models/{model_id}/presets",
+  request_body = CreateModelPreset,
+  responses(
+    (status = 200, description = "Success", body = ModelPresetCreated),
+    (status = 400, description = "Invalid parameter values", body = CloudError),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "Model not found", body = CloudError),
+  ),
+  params(
+    ("model_id" = ModelId, Path, description = "Model id, as manufacturer:name"),
+  ))]
+pub(crate) fn create_model_preset() {}
+
+/// List presets
+///
+/// List presets in the catalog of a model, optionally only the ones tagged with all of the tags.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  get,
+  path = "/v1/models/{model_id}/presets",
+  responses(
+    (status = 200, description = "Success", body = ModelPresetList),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "Model not found", body = CloudError),
+  ),
+  params(
+    ("model_id" = ModelId, Path, description = "Model id, as manufacturer:name"),
+    ("tag" = Option<Vec<String>>, Query, description = "If set, only presets having all of the tags"),
+  ))]
+pub(crate) fn list_model_presets() {}
+
+/// Get a preset
+///
+/// Get the metadata and parameter values of a preset.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  get,
+  path = "/v1/models/{model_id}/presets/{preset_id}",
+  responses(
+    (status = 200, description = "Success", body = ModelPreset),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "Model or preset not found", body = CloudError),
+  ),
+  params(
+    ("model_id" = ModelId, Path, description = "Model id, as manufacturer:name"),
+    ("preset_id" = PresetId, Path, description = "Preset id"),
+  ))]
+pub(crate) fn get_model_preset() {}
+
+/// Delete a preset
+///
+/// Remove a preset from the catalog. Only the app that created the preset may do this.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  delete,
+  path = "/v1/models/{model_id}/presets/{preset_id}",
+  responses(
+    (status = 200, description = "Success", body = ModelPresetDeleted),
+    (status = 401, description = "Not authorized", body = CloudError),
+    (status = 404, description = "Model or preset not found", body = CloudError),
+  ),
+  params(
+    ("model_id" = ModelId, Path, description = "Model id, as manufacturer:name"),
+    ("preset_id" = PresetId, Path, description = "Preset id"),
+  ))]
+pub(crate) fn delete_model_preset() {}
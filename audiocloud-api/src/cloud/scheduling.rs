@@ -0,0 +1,52 @@
+//! Scheduling helpers shared by the cloud and user interfaces
+
+use std::collections::HashSet;
+
+use chrono::Duration;
+
+use crate::cloud::domains::Maintenance;
+use crate::time::{TimeRange, Timestamp};
+use crate::{FixedInstanceId, Task};
+
+/// How far past the earliest start a free slot is searched for, in days
+pub const SCHEDULING_HORIZON_DAYS: i64 = 366;
+
+/// Earliest reservation of `duration`, starting at or after `not_before`, that conflicts with neither tasks nor maintenance
+///
+/// Like [`DomainConfig::check_availability`](crate::cloud::domains::DomainConfig::check_availability), tasks conflict only if they
+/// reserve any of the same fixed `instances`, while all of the `maintenance` conflicts. Returns null if `duration` is
+/// not positive or there is no free slot ending within [`SCHEDULING_HORIZON_DAYS`] of `not_before`.
+pub fn find_next_available(not_before: Timestamp,
+                           duration: Duration,
+                           instances: &HashSet<FixedInstanceId>,
+                           existing: &[Task],
+                           maintenance: &[Maintenance])
+                           -> Option<TimeRange> {
+    if duration <= Duration::zero() {
+        return None;
+    }
+
+    let horizon = not_before.checked_add_signed(Duration::days(SCHEDULING_HORIZON_DAYS))?;
+    let mut start = not_before;
+
+    loop {
+        let candidate = TimeRange::new(start, start.checked_add_signed(duration)?);
+        if candidate.to > horizon {
+            return None;
+        }
+
+        let tasks = existing.iter()
+                            .filter(|task| !task.reservations.fixed_instances.is_disjoint(instances))
+                            .map(|task| task.reservations.time_range())
+                            .filter(|reserved| reserved.intersects(&candidate));
+
+        let maintenance = maintenance.iter()
+                                     .flat_map(|maintenance| maintenance.occurrences_within(&candidate));
+
+        // every conflict ends after the candidate starts, so the search always moves forward
+        match tasks.chain(maintenance).map(|conflict| conflict.to).max() {
+            None => return Some(candidate),
+            Some(conflict_end) => start = conflict_end,
+        }
+    }
+}
@@ -1,9 +1,16 @@
+use std::collections::HashSet;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::cloud::domains::Maintenance;
 use crate::common::change::ModifyTask;
-use crate::time::Timestamp;
-use crate::{AppId, CreateTaskReservation, CreateTaskSecurity, CreateTaskSpec, DomainId, TaskId};
+use crate::common::media::{DeleteReason, RenderAnalysis, RenderId, RenderOutputFormat, StopReason};
+use crate::time::{TimeRange, Timestamp};
+use crate::{
+    AppId, AppMediaObjectId, AppTaskId, AuditEntryId, CloudError, CreateTaskReservation, CreateTaskSecurity, CreateTaskSpec, DomainId,
+    FixedInstanceId, FixedInstanceNodeId, SerializableResult, Task, TaskId, TaskLabels, TaskMetadata, TaskReservation, TaskSpecDiff,
+};
 
 /// Create a task
 ///
@@ -23,6 +30,31 @@ pub struct CreateTask {
     pub security:     CreateTaskSecurity,
     /// When true, do not actually create a task, just validate the process
     pub dry_run:      bool,
+    /// Descriptive metadata, for example project, client or engineer
+    #[serde(default)]
+    pub metadata:     TaskMetadata,
+    /// Labels used to search for tasks
+    #[serde(default)]
+    pub labels:       TaskLabels,
+}
+
+impl From<CreateTask> for Task {
+    fn from(source: CreateTask) -> Self {
+        let CreateTask { domain_id,
+                         reservations,
+                         spec,
+                         security,
+                         metadata,
+                         labels,
+                         .. } = source;
+
+        Self { domain_id,
+               reservations: reservations.into(),
+               spec: spec.into(),
+               security: security.into(),
+               metadata,
+               labels }
+    }
 }
 
 /// Task created successfully
@@ -32,19 +64,39 @@ pub enum TaskCreated {
     /// Created normally
     Created {
         /// App creating the task
-        app_id:  AppId,
+        app_id:      AppId,
         /// Task Id
-        task_id: TaskId,
+        task_id:     TaskId,
+        /// Audit entry recording the creation, if audited
+        #[serde(default)]
+        audit_id:    Option<AuditEntryId>,
+        /// Instances allocated to fixed instance nodes with an instance selector
+        #[serde(default)]
+        allocations: Vec<InstanceAllocation>,
     },
     /// Validated successfully, but not created
     DryRun {
         /// App creating the task
-        app_id:  AppId,
+        app_id:      AppId,
         /// Task Id
-        task_id: TaskId,
+        task_id:     TaskId,
+        /// Instances that would be allocated to fixed instance nodes with an instance selector
+        #[serde(default)]
+        allocations: Vec<InstanceAllocation>,
     },
 }
 
+/// Fixed instance allocated by the cloud to a fixed instance node with an instance selector
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct InstanceAllocation {
+    /// Fixed instance node
+    pub fixed_node_id: FixedInstanceNodeId,
+    /// Instance set on the node when the task was submitted
+    pub preferred:     FixedInstanceId,
+    /// Instance allocated to the node, which replaces the preferred instance in the task spec
+    pub allocated:     FixedInstanceId,
+}
+
 /// Task was updated successfully
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -52,11 +104,25 @@ pub enum TaskUpdated {
     /// Updated normally
     Updated {
         /// App creating the task
+        app_id:   AppId,
+        /// Task Id
+        task_id:  TaskId,
+        /// New version to be used with `If-Matches` when submitting further modifications
+        version:  u64,
+        /// Audit entry recording the update, if audited
+        #[serde(default)]
+        audit_id: Option<AuditEntryId>,
+    },
+    /// Validated successfully, but not updated
+    DryRun {
+        /// App owning the task
         app_id:  AppId,
         /// Task Id
         task_id: TaskId,
-        /// New version to be used with `If-Matches` when submitting further modifications
+        /// Version the task would have after the modifications
         version: u64,
+        /// Changes the modifications would make to the task spec
+        diff:    TaskSpecDiff,
     },
 }
 
@@ -67,11 +133,14 @@ pub enum TaskDeleted {
     /// Deleted normally
     Deleted {
         /// App creating the task
-        app_id:  AppId,
+        app_id:   AppId,
         /// Task Id
-        task_id: TaskId,
+        task_id:  TaskId,
         /// Version when deleted
-        version: u64,
+        version:  u64,
+        /// Audit entry recording the deletion, if audited
+        #[serde(default)]
+        audit_id: Option<AuditEntryId>,
     },
 }
 
@@ -84,13 +153,210 @@ pub struct AdjustTaskTime {
     pub to:   Option<Timestamp>,
 }
 
+impl AdjustTaskTime {
+    /// Time range of a reservation after the adjustment, or `TimeMalformed` if it would not start before it ends
+    pub fn apply_to(&self, reservation: &TaskReservation) -> Result<TimeRange, CloudError> {
+        let range = TimeRange::new(self.from.unwrap_or(reservation.from), self.to.unwrap_or(reservation.to));
+        range.validate()?;
+
+        Ok(range)
+    }
+}
+
 /// A list of tasks
 pub type ModifyTaskList = Vec<ModifyTask>;
 
+/// Selects tasks of an app for a bulk operation
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct TaskFilter {
+    /// Tasks to select, or empty to select all tasks of the app
+    #[serde(default)]
+    pub task_ids:  HashSet<TaskId>,
+    /// If set, only tasks executing on the domain
+    #[serde(default)]
+    pub domain_id: Option<DomainId>,
+    /// If set, only tasks with a reservation including this time
+    #[serde(default)]
+    pub active_at: Option<Timestamp>,
+    /// Only tasks having all of the labels
+    #[serde(default)]
+    pub labels:    TaskLabels,
+}
+
+impl TaskFilter {
+    pub fn matches(&self, task_id: &TaskId, task: &Task) -> bool {
+        (self.task_ids.is_empty() || self.task_ids.contains(task_id))
+        && self.domain_id.as_ref().is_none_or(|domain_id| domain_id == &task.domain_id)
+        && self.active_at
+               .is_none_or(|active_at| task.reservations.from <= active_at && active_at < task.reservations.to)
+        && task.has_labels(&self.labels)
+    }
+}
+
+/// Apply the same modifications to all tasks of an app selected by a filter
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct BulkModifyTasks {
+    /// Tasks to modify
+    pub filter:        TaskFilter,
+    /// Modifications to apply to every task, in order
+    pub modifications: ModifyTaskList,
+}
+
+/// Outcome of a bulk modification, per selected task
+///
+/// Tasks are modified independently, so some may be modified while others fail.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct BulkTasksModified {
+    /// Result for every selected task, sorted by task id
+    pub results: Vec<BulkModifyResult>,
+}
+
+impl BulkTasksModified {
+    pub fn all_succeeded(&self) -> bool {
+        self.results.iter().all(|result| result.result.is_ok())
+    }
+}
+
+/// Outcome of a bulk modification of a single task
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+pub struct BulkModifyResult {
+    /// Task id
+    pub task_id: TaskId,
+    /// Result of modifying the task
+    pub result:  SerializableResult<TaskUpdated, CloudError>,
+}
+
+/// Check if a reservation would conflict with existing tasks or maintenance, before creating a task
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct CheckAvailability {
+    /// Domain that would be executing the task
+    pub domain_id:       DomainId,
+    /// Time of the reservation
+    pub time:            TimeRange,
+    /// Fixed instances that would be reserved
+    #[serde(default)]
+    pub fixed_instances: HashSet<FixedInstanceId>,
+}
+
+/// Conflicts of a reservation, the reservation is available if there are none
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct AvailabilityResult {
+    /// Tasks reserving any of the fixed instances during the time, sorted by task id
+    pub conflicting_tasks:       Vec<AppTaskId>,
+    /// Maintenance of the domain or any of the fixed instances during the time
+    pub conflicting_maintenance: Vec<MaintenanceConflict>,
+}
+
+impl AvailabilityResult {
+    pub fn is_available(&self) -> bool {
+        self.conflicting_tasks.is_empty() && self.conflicting_maintenance.is_empty()
+    }
+}
+
+/// Record of a finished task, kept after the task is deleted
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct TaskRunSummary {
+    /// Task id
+    pub id:          AppTaskId,
+    /// Domain that executed the task
+    pub domain_id:   DomainId,
+    /// Reserved time of the task
+    pub time:        TimeRange,
+    /// Time at which the task finished
+    pub finished_at: Timestamp,
+    /// How the task finished
+    pub final_state: TaskFinalState,
+    /// Renders requested during the task, in the order they were requested
+    #[serde(default)]
+    pub renders:     Vec<RenderSummary>,
+    /// Usage statistics of the task
+    #[serde(default)]
+    pub statistics:  TaskRunStatistics,
+    /// Labels of the task
+    #[serde(default)]
+    pub labels:      TaskLabels,
+}
+
+/// Task run history, most recently finished first
+pub type TaskHistory = Vec<TaskRunSummary>;
+
+/// How a task finished
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskFinalState {
+    /// The reservation ended
+    Expired,
+    /// The task was deleted before the reservation ended
+    Deleted {
+        /// Why the task was deleted
+        reason: DeleteReason,
+    },
+}
+
+/// Outcome of a render requested during a task
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct RenderSummary {
+    /// Render id
+    pub render_id:     RenderId,
+    /// Media object the render was written to
+    pub object_id:     AppMediaObjectId,
+    /// Format of the rendered media
+    pub output_format: RenderOutputFormat,
+    /// Time spent rendering, in milliseconds
+    pub duration_ms:   u64,
+    /// How the render ended
+    pub outcome:       RenderOutcome,
+    /// Loudness analysis of the rendered media, if the render succeeded and was analyzed
+    #[serde(default)]
+    pub analysis:      Option<RenderAnalysis>,
+}
+
+/// How a render ended
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderOutcome {
+    /// The media object was written
+    Succeeded,
+    /// The render failed with an error
+    Failed {
+        /// Error details
+        error: String,
+    },
+    /// The render was cancelled or exceeded its maximum duration
+    Cancelled {
+        /// Why the render was cancelled
+        reason: StopReason,
+    },
+}
+
+/// Usage statistics of a task run, for billing and analytics
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Default)]
+pub struct TaskRunStatistics {
+    /// Time spent playing, in milliseconds
+    pub played_ms:       u64,
+    /// Time spent rendering, in milliseconds
+    pub rendered_ms:     u64,
+    /// Number of times playback was started
+    pub plays:           u64,
+    /// Fixed instances used by the task
+    pub fixed_instances: HashSet<FixedInstanceId>,
+}
+
+/// Maintenance overlapping a checked reservation
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct MaintenanceConflict {
+    /// Fixed instance under maintenance, or null if the maintenance is on the whole domain
+    pub instance_id: Option<FixedInstanceId>,
+    /// The maintenance window
+    pub maintenance: Maintenance,
+}
+
 /// Create a task
 ///
 /// The task will be checked against exclusivity with other tasks, as well as resources and other
-/// limits imposed by the domain configuration.
+/// limits imposed by the domain configuration. Fixed instance nodes with an instance selector are
+/// allocated any available matching instance, reported in the response.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 post,
 path = "/v1/apps/{app_id}/tasks",
@@ -106,10 +372,69 @@ params(
 ))]
 pub(crate) fn create_task() {}
 
+/// List the task run history of an app
+///
+/// Summaries of finished tasks, including deleted ones, most recently finished first.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+get,
+path = "/v1/apps/{app_id}/tasks/history",
+responses(
+(status = 200, description = "Success", body = TaskHistory),
+(status = 401, description = "Not authorized", body = CloudError),
+(status = 404, description = "App not found", body = CloudError),
+),
+params(
+("app_id" = AppId, Path, description = "App owning the tasks"),
+("from" = Option<Timestamp>, Query, description = "If set, only tasks finished at or after this time"),
+("to" = Option<Timestamp>, Query, description = "If set, only tasks finished before this time"),
+("label" = Option<Vec<String>>, Query, description = "If set, only tasks having all of the labels"),
+))]
+pub(crate) fn get_task_history() {}
+
+/// Get the run summary of a finished task
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+get,
+path = "/v1/apps/{app_id}/tasks/{task_id}/history",
+responses(
+(status = 200, description = "Success", body = TaskRunSummary),
+(status = 401, description = "Not authorized", body = CloudError),
+(status = 404, description = "App or task not found, or task has not finished", body = CloudError),
+),
+params(
+("app_id" = AppId, Path, description = "App owning the task"),
+("task_id" = TaskId, Path, description = "Finished task"),
+))]
+pub(crate) fn get_task_run_summary() {}
+
+/// Check reservation availability
+///
+/// Report tasks and maintenance that would conflict with a task reserving the fixed instances during
+/// the time, without creating a task. Availability is not guaranteed until the task is created.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+post,
+path = "/v1/apps/{app_id}/tasks/availability",
+request_body = CheckAvailability,
+responses(
+(status = 200, description = "Success", body = AvailabilityResult),
+(status = 401, description = "Not authorized", body = CloudError),
+(status = 404, description = "App or domain not found", body = CloudError),
+),
+params(
+("app_id" = AppId, Path, description = "The app for which we are checking availability")
+))]
+pub(crate) fn check_availability() {}
+
 /// Modify existing task spec
 ///
 /// Submit modifications to the task. This generic request can be used to update most aspects of the
 /// session: adjusting parameters, creating, deleting, reconnecting nodes, changing media, etc.
+///
+/// With `dry_run` set, the modifications are validated against the current spec and the would-be
+/// version and changes are returned, but the task is not modified.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 put,
 path = "/v1/apps/{app_id}/tasks/{task_id}/spec",
@@ -123,12 +448,33 @@ params(
 ("app_id" = AppId, Path, description = "App owning the task"),
 ("task_id" = TaskId, Path, description = "Task to be updated"),
 ("If-Match" = u64, Header, description = "The task version for"),
+("dry_run" = Option<bool>, Query, description = "Validate the modifications without applying them"),
 ))]
 pub(crate) fn modify_task_spec() {}
 
+/// Modify multiple tasks
+///
+/// Apply the same modifications to all tasks of the app selected by the filter, for example to mute
+/// every task of an installation. Each task is modified independently and has its own result.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+post,
+path = "/v1/apps/{app_id}/tasks/bulk-modify",
+request_body = BulkModifyTasks,
+responses(
+(status = 200, description = "Success, see results of individual tasks", body = BulkTasksModified),
+(status = 401, description = "Not authorized", body = CloudError),
+(status = 404, description = "App not found", body = CloudError),
+),
+params(
+("app_id" = AppId, Path, description = "App owning the tasks"),
+))]
+pub(crate) fn bulk_modify_tasks() {}
+
 /// Modify existing task time
 ///
 /// Submit modifications to the task reservation time. Can be used to extend, move start or end early.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 put,
 path = "/v1/apps/{app_id}/tasks/{task_id}/time",
@@ -149,6 +495,7 @@ pub(crate) fn adjust_task_time() {}
 /// Delete a task
 ///
 /// Delete a task and release all referenced resources.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
 delete,
 path = "/v1/apps/{app_id}/tasks/{task_id}",
@@ -0,0 +1,96 @@
+//! Audit log of mutating operations
+//!
+//! Every operation that changes apps, domains, tasks or media is recorded as an [`AuditEntry`]. Responses
+//! to mutating requests carry the id of the entry, so downstream storage can link them.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::common::time::{now, Timestamp};
+use crate::newtypes::{AppId, AppMediaObjectId, AppTaskId, AuditEntryId, DomainId, FixedInstanceId, SecureKey};
+
+/// A record of who changed what, and when
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuditEntry {
+    /// Audit entry id
+    pub id:      AuditEntryId,
+    /// Who performed the operation
+    pub actor:   AuditActor,
+    /// What kind of operation was performed
+    pub action:  AuditAction,
+    /// What the operation was performed on
+    pub target:  AuditTarget,
+    /// When the operation was performed
+    pub at:      Timestamp,
+    /// Operation specific details, for example the submitted modifications
+    #[serde(default)]
+    pub details: serde_json::Value,
+}
+
+impl AuditEntry {
+    /// New entry with a random id, performed now and without details
+    pub fn new(actor: AuditActor, action: AuditAction, target: AuditTarget) -> Self {
+        Self { id: AuditEntryId::random(),
+               actor,
+               action,
+               target,
+               at: now(),
+               details: serde_json::Value::Null }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// Who performed an audited operation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditActor {
+    /// An app, authenticated with its credentials
+    App { app_id: AppId },
+    /// A client controlling a task directly with a secure key
+    SecureKey {
+        /// Task the key grants access to
+        task_id:     AppTaskId,
+        /// SHA-256 digest of the key, so the log does not reveal the key
+        fingerprint: String,
+    },
+    /// An administrator
+    Admin { name: String },
+}
+
+impl AuditActor {
+    /// Actor using a secure key on a task, identified by the fingerprint of the key
+    pub fn secure_key(task_id: AppTaskId, key: &SecureKey) -> Self {
+        Self::SecureKey { task_id,
+                          fingerprint: hex::encode(Sha256::digest(key.as_bytes())) }
+    }
+}
+
+/// Kind of an audited operation
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// The target was created
+    Create,
+    /// The target was modified
+    Modify,
+    /// The target was deleted
+    Delete,
+    /// Playback or rendering of the target was started or stopped
+    Transport,
+}
+
+/// What an audited operation was performed on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditTarget {
+    App { app_id: AppId },
+    Domain { domain_id: DomainId },
+    Task { task_id: AppTaskId },
+    MediaObject { media_id: AppMediaObjectId },
+    FixedInstance { instance_id: FixedInstanceId },
+}
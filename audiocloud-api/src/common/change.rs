@@ -8,18 +8,27 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::common::media::{PlayId, RenderId, RequestPlay, RequestRender};
-use crate::common::task::TaskPermissions;
+use crate::common::media::{
+    deserialize_optional_monitored_mixers, LoopRegion, MonitoredMixers, PlayId, PunchRange, RenderId, RequestPlay, RequestRender,
+};
 use crate::common::task::{
-    ConnectionValues, DynamicInstanceNode, FixedInstanceNode, MediaChannels, MixerNode, NodeConnection, Task, TaskSpec, TimeSegment,
-    TrackMedia, TrackNode, UpdateTaskTrackMedia,
+    is_delay_valid, is_pan_valid, is_volume_valid, AutomationLane, CompositeNode, ConnectionAutomation, ConnectionKind, ConnectionValues,
+    DynamicInstanceNode, FixedInstanceNode, InstanceParameters, MediaChannels, MixerNode, NodeConnection, PanLaw, ParameterAutomation,
+    RecorderNode, Task, TaskSpec, TimeSegment, TrackMedia, TrackNode, UpdateTaskTrack, UpdateTaskTrackMedia, DEFAULT_MAX_CONNECTION_VOLUME,
+    MAX_CONNECTION_DELAY_MS,
 };
-use crate::common::time::Timestamped;
+use crate::common::task::{KeySource, TaskKeyGrant};
+use crate::common::tempo::{TempoMap, TempoMapError};
+use crate::common::time::{Timestamp, Timestamped};
+use crate::common::timecode::Timecode;
 use crate::newtypes::{
-    DynamicInstanceNodeId, FixedInstanceId, FixedInstanceNodeId, MediaObjectId, MixerNodeId, NodeConnectionId, SecureKey, TrackMediaId,
-    TrackNodeId,
+    CompositeNodeId, DynamicInstanceNodeId, FixedInstanceId, FixedInstanceNodeId, MediaObjectId, MixerNodeId, NodeConnectionId,
+    RecorderNodeId, SecureKey, TaskSnapshotId, TrackMediaId, TrackNodeId,
+};
+use crate::{
+    json_schema_new_type, AppMediaObjectId, ChannelMask, CloudError, InputPadId, Model, OutputPadId, ParameterId, TaskNodeId,
+    TaskReservation, TaskSecurity, ToParameterMap,
 };
-use crate::{json_schema_new_type, AppMediaObjectId, ChannelMask, InputPadId, OutputPadId, TaskNodeId, TaskSecurity};
 
 use self::ModifyTaskError::*;
 
@@ -52,6 +61,13 @@ pub enum ModifyTaskSpec {
         /// Media object update
         update:   UpdateTaskTrackMedia,
     },
+    /// Update gain, mute or solo of a track node
+    UpdateTrack {
+        /// Track node id
+        track_id: TrackNodeId,
+        /// Track update
+        update:   UpdateTaskTrack,
+    },
     /// Delete track media from a track node
     DeleteTrackMedia {
         /// Track node id
@@ -78,6 +94,18 @@ pub enum ModifyTaskSpec {
         /// Dynamic instance node processing specification
         spec:       DynamicInstanceNode,
     },
+    /// Add the nodes and connections of a composite node to the task
+    AddComposite {
+        /// Composite node id, prefixing the ids of its nodes and connections
+        composite_id: CompositeNodeId,
+        /// Composite node specification
+        spec:         CompositeNode,
+    },
+    /// Delete all nodes of a composite node from the task (including all referencing connections)
+    DeleteComposite {
+        /// Composite node id
+        composite_id: CompositeNodeId,
+    },
     /// Add a mixer node to the task
     AddMixer {
         /// Mixer node id
@@ -90,6 +118,20 @@ pub enum ModifyTaskSpec {
         /// Moxer node id
         mixer_id: MixerNodeId,
     },
+    /// Add a recorder node to the task, capturing the audio connected to it while the task plays
+    AddRecorder {
+        /// Recorder node id
+        recorder_id: RecorderNodeId,
+        /// Recorder node specification
+        spec:        RecorderNode,
+    },
+    /// Delete a recorder node from the task (including all referencing connections)
+    ///
+    /// Media objects already recorded are kept.
+    DeleteRecorder {
+        /// Recorder node id
+        recorder_id: RecorderNodeId,
+    },
     /// Delete a fixed instance node from the task (including all referencing connections)
     DeleteFixedInstance {
         /// Fixed instance node id
@@ -110,9 +152,12 @@ pub enum ModifyTaskSpec {
         /// Connection id
         connection_id: NodeConnectionId,
         /// Source node pad
-        from: OutputPadId,
+        from:          OutputPadId,
         /// Destination node pad
-        to: InputPadId,
+        to:            InputPadId,
+        /// Kind of media routed through the connection
+        #[serde(default)]
+        kind:          ConnectionKind,
         /// Source channel mask
         from_channels: ChannelMask,
         /// Destination channel mask
@@ -129,19 +174,78 @@ pub enum ModifyTaskSpec {
         /// Values (parameters) on the connection
         values:        ConnectionValues,
     },
+    /// Set the delay on a connection, to time align parallel processing paths
+    SetConnectionDelay {
+        /// Connection id
+        connection_id: NodeConnectionId,
+        /// Delay in milliseconds
+        delay_ms:      f64,
+    },
+    /// Set or clear automation of connection volume and pan over the task timeline
+    SetConnectionAutomation {
+        /// Connection id
+        connection_id: NodeConnectionId,
+        /// Automation replacing the current automation of the connection, empty to clear it
+        automation:    ConnectionAutomation,
+    },
+    /// Set the pan law used by connections that do not override it
+    SetPanLaw {
+        /// New pan law
+        pan_law: PanLaw,
+    },
+    /// Set or clear the timecode at the start of the task timeline
+    SetTimecodeStart {
+        /// New timecode start, or null to clear it
+        timecode_start: Option<Timecode>,
+    },
+    /// Replace the tempo map of the task, an empty map removes the tempo
+    SetTempoMap {
+        /// New tempo map
+        tempo_map: TempoMap,
+    },
     /// Set fixed instance node values
     SetFixedInstanceParameterValues {
         /// Fixed instance node id
         fixed_id: FixedInstanceNodeId,
-        /// Values to set
-        values:   serde_json::Value,
+        /// Values to set, merged into the existing values
+        values:   InstanceParameters,
     },
     /// Set dynamic instance node values
     SetDynamicInstanceParameterValues {
         /// Dynamic instance node id
         dynamic_id: DynamicInstanceNodeId,
-        /// Values to set
-        values:     serde_json::Value,
+        /// Values to set, merged into the existing values
+        values:     InstanceParameters,
+    },
+    /// Replace the parameter values of a fixed or dynamic instance node with the model defaults
+    ///
+    /// Build with [`ModifyTaskSpec::reset_node_parameters`] to use [`Model::default_parameters`].
+    ResetNodeParameters {
+        /// Fixed or dynamic instance node id
+        node_id:  TaskNodeId,
+        /// Default values of the node model, replacing all existing values
+        defaults: InstanceParameters,
+    },
+    /// Set or clear automation of an instance parameter over the task timeline
+    ///
+    /// Values of the automation are validated against the model when the task spec is validated.
+    SetParameterAutomation {
+        /// Fixed or dynamic instance node id
+        node_id:      TaskNodeId,
+        /// Automated parameter
+        parameter_id: ParameterId,
+        /// Automation replacing the current automation of the parameter, or null to clear it
+        automation:   Option<AutomationLane>,
+    },
+    /// Restore parameter values and automation of instance nodes from a snapshot
+    ///
+    /// Parameters and automation of restored nodes are replaced, not merged.
+    ApplySnapshot {
+        /// Parameter state to restore
+        parameters: TaskSnapshotParameters,
+        /// Fixed or dynamic instance nodes to restore, or empty to restore every node in the snapshot
+        #[serde(default)]
+        nodes:      HashSet<TaskNodeId>,
     },
 }
 
@@ -150,22 +254,53 @@ impl ModifyTaskSpec {
         match self {
             ModifyTaskSpec::AddTrack { .. } => "add_track",
             ModifyTaskSpec::AddTrackMedia { .. } => "add_track_media",
+            ModifyTaskSpec::UpdateTrack { .. } => "update_track",
             ModifyTaskSpec::UpdateTrackMedia { .. } => "update_track_media",
             ModifyTaskSpec::DeleteTrackMedia { .. } => "delete_track_media",
             ModifyTaskSpec::DeleteTrack { .. } => "delete_track",
             ModifyTaskSpec::AddFixedInstance { .. } => "add_fixed_instance",
             ModifyTaskSpec::AddDynamicInstance { .. } => "add_dynamic_instance",
+            ModifyTaskSpec::AddComposite { .. } => "add_composite",
+            ModifyTaskSpec::DeleteComposite { .. } => "delete_composite",
             ModifyTaskSpec::AddMixer { .. } => "add_mixer",
             ModifyTaskSpec::DeleteMixer { .. } => "delete_mixer",
+            ModifyTaskSpec::AddRecorder { .. } => "add_recorder",
+            ModifyTaskSpec::DeleteRecorder { .. } => "delete_recorder",
             ModifyTaskSpec::AddConnection { .. } => "add_mixer_input",
             ModifyTaskSpec::SetConnectionParameterValues { .. } => "set_input_values",
+            ModifyTaskSpec::SetConnectionDelay { .. } => "set_connection_delay",
+            ModifyTaskSpec::SetConnectionAutomation { .. } => "set_connection_automation",
+            ModifyTaskSpec::SetPanLaw { .. } => "set_pan_law",
+            ModifyTaskSpec::SetTimecodeStart { .. } => "set_timecode_start",
+            ModifyTaskSpec::SetTempoMap { .. } => "set_tempo_map",
             ModifyTaskSpec::SetFixedInstanceParameterValues { .. } => "set_fixed_instance_parameter_values",
             ModifyTaskSpec::SetDynamicInstanceParameterValues { .. } => "set_dynamic_instance_parameter_values",
+            ModifyTaskSpec::ResetNodeParameters { .. } => "reset_node_parameters",
+            ModifyTaskSpec::SetParameterAutomation { .. } => "set_parameter_automation",
+            ModifyTaskSpec::ApplySnapshot { .. } => "apply_snapshot",
             ModifyTaskSpec::DeleteFixedInstance { .. } => "delete_fixed_instance",
             ModifyTaskSpec::DeleteDynamicInstance { .. } => "delete_dynamic_instance",
             ModifyTaskSpec::DeleteConnection { .. } => "delete_connection",
         }
     }
+
+    /// Update fixed instance node parameters with (partial) typed parameter values
+    pub fn set_fixed_instance_parameters(fixed_id: FixedInstanceNodeId, parameters: &impl ToParameterMap) -> Self {
+        Self::SetFixedInstanceParameterValues { fixed_id,
+                                                values: parameters.to_parameter_map().into() }
+    }
+
+    /// Update dynamic instance node parameters with (partial) typed parameter values
+    pub fn set_dynamic_instance_parameters(dynamic_id: DynamicInstanceNodeId, parameters: &impl ToParameterMap) -> Self {
+        Self::SetDynamicInstanceParameterValues { dynamic_id,
+                                                  values: parameters.to_parameter_map().into() }
+    }
+
+    /// Reset fixed or dynamic instance node parameters to the defaults of the node model
+    pub fn reset_node_parameters(node_id: TaskNodeId, model: &Model) -> Self {
+        Self::ResetNodeParameters { node_id,
+                                    defaults: model.default_parameters().into() }
+    }
 }
 
 /// Modify a task
@@ -182,7 +317,7 @@ pub enum ModifyTask {
         /// Secure key to add or overwrite
         key:      SecureKey,
         /// Permissions to set for the secure key
-        security: TaskPermissions,
+        security: TaskKeyGrant,
     },
     /// Revoke task security
     RevokeSecurity {
@@ -198,7 +333,7 @@ pub enum DesiredTaskPlayState {
     /// Play, with sample rate conversion
     Play(RequestPlay),
 
-    /// Rendering is always a F32 WAV at full sample rate, so nothing else needs to happen here
+    /// Render, in the output format of the request
     Render(RequestRender),
 
     /// Stopped
@@ -221,21 +356,64 @@ impl DesiredTaskPlayState {
     pub fn is_playing_of(&self, play: &RequestPlay) -> bool {
         matches!(self, DesiredTaskPlayState::Play(desired_play) if desired_play == play)
     }
+
+    /// Time at which playing or rendering should start, if scheduled
+    pub fn scheduled_at(&self) -> Option<Timestamp> {
+        match self {
+            DesiredTaskPlayState::Play(play) => play.scheduled_at,
+            DesiredTaskPlayState::Render(render) => render.scheduled_at,
+            DesiredTaskPlayState::Stopped => None,
+        }
+    }
+
+    /// Check that the desired state can be applied to a task with the spec and reservation
+    ///
+    /// Play requests are checked with [`TaskSpec::validate_play`], with monitored mixer and click gains limited to
+    /// `0..=max_volume`, and a scheduled start must be within the reservation.
+    pub fn validate(&self, spec: &TaskSpec, reservation: &TaskReservation, max_volume: f64) -> Result<(), CloudError> {
+        self.validate_schedule(reservation)?;
+
+        match self {
+            DesiredTaskPlayState::Play(play) => spec.validate_play(play, max_volume),
+            DesiredTaskPlayState::Render(_) | DesiredTaskPlayState::Stopped => Ok(()),
+        }
+    }
+
+    /// Check that a scheduled start is within the task reservation
+    pub fn validate_schedule(&self, reservation: &TaskReservation) -> Result<(), CloudError> {
+        match self.scheduled_at() {
+            Some(scheduled_at) if !reservation.time_range().contains(scheduled_at) => {
+                Err(CloudError::ScheduledOutsideReservation { scheduled_at,
+                                                              from: reservation.from,
+                                                              to: reservation.to })
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Update task play configuration
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct UpdateTaskPlay {
     /// Play identifier
-    pub play_id:  PlayId,
-    /// If not null, change the mixer node monitored during playback
-    pub mixer_id: Option<MixerNodeId>,
+    pub play_id:     PlayId,
+    /// If not null, replace the mixer nodes monitored during playback
+    ///
+    /// The single `mixer_id` of older updates is accepted and monitored at unity gain.
+    #[serde(default, alias = "mixer_id", deserialize_with = "deserialize_optional_monitored_mixers")]
+    pub mixers:      Option<MonitoredMixers>,
     /// If not null, change the time segment within the task timeline
-    pub segment:  Option<TimeSegment>,
+    pub segment:     Option<TimeSegment>,
     /// if not null, seek to a specified location within the task timeline
-    pub start_at: Option<f64>,
+    pub start_at:    Option<f64>,
     /// If not null, overwrite if the task playback is looping or not
-    pub looping:  Option<bool>,
+    pub looping:     Option<bool>,
+    /// If not null, replace the looped region
+    #[serde(default)]
+    pub loop_region: Option<LoopRegion>,
+    /// If not null, replace the punch range, a range without bounds punches in for the whole playback
+    #[serde(default)]
+    pub punch:       Option<PunchRange>,
 }
 
 pub struct SuccessfulRenderNotification {
@@ -249,6 +427,8 @@ pub type RenderNotification = Result<SuccessfulRenderNotification, String>;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskPlayState {
+    /// Instances and media are prepared, waiting for the scheduled start
+    Scheduled(DesiredTaskPlayState),
     PreparingToPlay(RequestPlay),
     PreparingToRender(RequestRender),
     Playing(RequestPlay),
@@ -261,6 +441,7 @@ pub enum TaskPlayState {
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Display, IsVariant, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskPlayStateSummary {
+    Scheduled,
     PreparingToPlay,
     PreparingToRender,
     Playing,
@@ -273,6 +454,7 @@ pub enum TaskPlayStateSummary {
 impl<'a> Into<TaskPlayStateSummary> for &'a TaskPlayState {
     fn into(self) -> TaskPlayStateSummary {
         match self {
+            TaskPlayState::Scheduled(_) => TaskPlayStateSummary::Scheduled,
             TaskPlayState::PreparingToPlay(_) => TaskPlayStateSummary::PreparingToPlay,
             TaskPlayState::PreparingToRender(_) => TaskPlayStateSummary::PreparingToRender,
             TaskPlayState::Playing(_) => TaskPlayStateSummary::Playing,
@@ -301,6 +483,16 @@ impl TaskPlayState {
         matches!(self, Self::Stopped)
     }
 
+    /// Check that a play update applies to the active play, and is valid for it as checked by [`TaskSpec::validate_play_update`]
+    pub fn validate_update(&self, spec: &TaskSpec, update: &UpdateTaskPlay, max_volume: f64) -> Result<(), CloudError> {
+        match self {
+            Self::PreparingToPlay(play) | Self::Playing(play) if play.play_id == update.play_id => {
+                spec.validate_play_update(play, update, max_volume)
+            }
+            _ => Err(CloudError::PlayNotActive { play_id: update.play_id }),
+        }
+    }
+
     pub fn satisfies(&self, desired: &DesiredTaskPlayState) -> bool {
         match (self, desired) {
             (Self::Playing(playing), DesiredTaskPlayState::Play(desired_playing)) => playing == desired_playing,
@@ -339,6 +531,8 @@ pub enum ModifyTaskError {
     DynamicInstanceExists { node_id: DynamicInstanceNodeId },
     #[error("Mixer node {node_id} already exists")]
     MixerExists { node_id: MixerNodeId },
+    #[error("Recorder node {node_id} already exists")]
+    RecorderExists { node_id: RecorderNodeId },
 
     #[error("Track {node_id} does not exist")]
     TrackDoesNotExist { node_id: TrackNodeId },
@@ -348,6 +542,8 @@ pub enum ModifyTaskError {
     DynamicInstanceDoesNotExist { node_id: DynamicInstanceNodeId },
     #[error("Mixer {node_id} does not exist")]
     MixerDoesNotExist { node_id: MixerNodeId },
+    #[error("Recorder {node_id} does not exist")]
+    RecorderDoesNotExist { node_id: RecorderNodeId },
     #[error("Connection {connection_id} does not exist")]
     ConnectionDoesNotExist { connection_id: NodeConnectionId },
     #[error("Connection {connection_id} already exist")]
@@ -358,13 +554,85 @@ pub enum ModifyTaskError {
         message:       String,
     },
 
+    #[error("Connection {connection_id} volume {volume} is out of range, must be between 0 and {max}")]
+    VolumeOutOfRange {
+        connection_id: NodeConnectionId,
+        volume:        f64,
+        max:           f64,
+    },
+    #[error("Connection {connection_id} pan {pan} must be between -1 and 1")]
+    PanOutOfRange {
+        connection_id: NodeConnectionId,
+        pan:           f64,
+    },
+    #[error("Connection {connection_id} delay {delay_ms} ms must be between 0 and {MAX_CONNECTION_DELAY_MS} ms")]
+    DelayOutOfRange {
+        connection_id: NodeConnectionId,
+        delay_ms:      f64,
+    },
+
+    #[error("Connection {connection_id} automation must have points at increasing, non-negative times")]
+    AutomationMalformed { connection_id: NodeConnectionId },
+
+    #[error("Track {node_id} gain {gain} is out of range, must be between 0 and {max}")]
+    TrackGainOutOfRange { node_id: TrackNodeId, gain: f64, max: f64 },
+
+    #[error("Tempo map is invalid: {error}")]
+    InvalidTempoMap { error: TempoMapError },
+
     #[error("Media {media_id} on track node {node_id} already exists")]
     MediaExists { node_id: TrackNodeId, media_id: TrackMediaId },
     #[error("Media {media_id} on track node {node_id} does not exist")]
     MediaDoesNotExist { node_id: TrackNodeId, media_id: TrackMediaId },
 
+    #[error("Node {node_id:?} has no parameters")]
+    NodeHasNoParameters { node_id: TaskNodeId },
+    #[error("Parameter {parameter_id} automation of node {node_id:?} must have points at increasing, non-negative times")]
+    ParameterAutomationMalformed {
+        node_id:      TaskNodeId,
+        parameter_id: ParameterId,
+    },
+    #[error("Node {node_id:?} is not in the snapshot")]
+    NodeNotInSnapshot { node_id: TaskNodeId },
+
+    #[error("Composite node {composite_id} has no instance nodes")]
+    CompositeEmpty { composite_id: CompositeNodeId },
+    #[error("Connection {connection_id} of composite node {composite_id} references a node outside of the composite")]
+    CompositeConnectionEscapes {
+        composite_id:  CompositeNodeId,
+        connection_id: NodeConnectionId,
+    },
+    #[error("Composite node {composite_id} does not exist")]
+    CompositeDoesNotExist { composite_id: CompositeNodeId },
+    #[error("Composite node id {composite_id} must not contain '/'")]
+    CompositeIdMalformed { composite_id: CompositeNodeId },
+    #[error("Node id {node_id:?} must not contain '/', which is reserved for nodes of composites")]
+    NodeIdReserved { node_id: TaskNodeId },
+
     #[error("Refusing to add connection - cycle detected")]
     CycleDetected,
+
+    #[error("Modification {index} of the transaction failed: {error}")]
+    TransactionFailed { index: usize, error: Box<ModifyTaskError> },
+
+    #[error("Diff applies to revision {expected}, but the task spec is at revision {actual}")]
+    RevisionMismatch { expected: u64, actual: u64 },
+
+    #[error("Permissions for key {key} expired at {valid_until}")]
+    SecurityExpired { key: SecureKey, valid_until: Timestamp },
+    #[error("Permissions for key {key} allow a malformed source {key_source:?}")]
+    MalformedKeySource { key: SecureKey, key_source: KeySource },
+}
+
+/// Outcome of a successfully applied transaction of modifications
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AppliedTransaction {
+    /// Number of modifications applied
+    pub applied:         usize,
+    /// Revision of the task spec before the transaction
+    pub revision_before: u64,
+    /// Revision of the task spec after the transaction
+    pub revision:        u64,
 }
 
 impl Task {
@@ -398,7 +666,8 @@ impl Task {
         Ok(())
     }
 
-    pub fn set_security(&mut self, key: SecureKey, security: TaskPermissions) -> Result<(), ModifyTaskError> {
+    pub fn set_security(&mut self, key: SecureKey, security: TaskKeyGrant) -> Result<(), ModifyTaskError> {
+        security.validate(&key)?;
         self.security.security.insert(key, security);
         self.security.revision += 1;
         Ok(())
@@ -411,6 +680,172 @@ impl Task {
     }
 }
 
+/// Changes to the entries of a map
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MapDiff<K: Eq + Hash, V> {
+    /// Entries that were added or changed, with their new values
+    pub upsert: HashMap<K, V>,
+    /// Keys of entries that were removed
+    pub remove: HashSet<K>,
+}
+
+impl<K: Eq + Hash, V> Default for MapDiff<K, V> {
+    fn default() -> Self {
+        Self { upsert: HashMap::new(),
+               remove: HashSet::new(), }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone + PartialEq> MapDiff<K, V> {
+    pub fn between(from: &HashMap<K, V>, to: &HashMap<K, V>) -> Self {
+        Self { upsert: to.iter()
+                         .filter(|(key, value)| from.get(key) != Some(value))
+                         .map(|(key, value)| (key.clone(), value.clone()))
+                         .collect(),
+               remove: from.keys().filter(|key| !to.contains_key(key)).cloned().collect(), }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.upsert.is_empty() && self.remove.is_empty()
+    }
+
+    pub fn apply(self, map: &mut HashMap<K, V>) {
+        map.retain(|key, _| !self.remove.contains(key));
+        map.extend(self.upsert);
+    }
+}
+
+/// Changes between two revisions of a task spec, smaller than the full spec for most updates
+///
+/// Nodes and connections are replaced as a whole when any of their values change.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaskSpecDiff {
+    /// Revision of the task spec the diff applies to
+    pub base_revision:  u64,
+    /// Revision of the task spec after the diff is applied
+    pub revision:       u64,
+    /// Changes to track nodes
+    pub tracks:         MapDiff<TrackNodeId, TrackNode>,
+    /// Changes to mixer nodes
+    pub mixers:         MapDiff<MixerNodeId, MixerNode>,
+    /// Changes to dynamic instance nodes
+    pub dynamic:        MapDiff<DynamicInstanceNodeId, DynamicInstanceNode>,
+    /// Changes to fixed instance nodes
+    pub fixed:          MapDiff<FixedInstanceNodeId, FixedInstanceNode>,
+    /// Changes to recorder nodes
+    #[serde(default)]
+    pub recorders:      MapDiff<RecorderNodeId, RecorderNode>,
+    /// Changes to connections
+    pub connections:    MapDiff<NodeConnectionId, NodeConnection>,
+    /// Pan law after the diff is applied
+    pub pan_law:        PanLaw,
+    /// Timecode start after the diff is applied
+    pub timecode_start: Option<Timecode>,
+    /// Tempo map after the diff is applied
+    #[serde(default)]
+    pub tempo_map:      TempoMap,
+}
+
+impl TaskSpec {
+    /// Changes that turn this task spec into `other`
+    pub fn diff(&self, other: &TaskSpec) -> TaskSpecDiff {
+        TaskSpecDiff { base_revision:  self.revision,
+                       revision:       other.revision,
+                       tracks:         MapDiff::between(&self.tracks, &other.tracks),
+                       mixers:         MapDiff::between(&self.mixers, &other.mixers),
+                       dynamic:        MapDiff::between(&self.dynamic, &other.dynamic),
+                       fixed:          MapDiff::between(&self.fixed, &other.fixed),
+                       recorders:      MapDiff::between(&self.recorders, &other.recorders),
+                       connections:    MapDiff::between(&self.connections, &other.connections),
+                       pan_law:        other.pan_law,
+                       timecode_start: other.timecode_start,
+                       tempo_map:      other.tempo_map.clone(), }
+    }
+
+    /// Apply changes computed by [`TaskSpec::diff`] against the current revision of this task spec
+    pub fn apply_diff(&mut self, diff: TaskSpecDiff) -> Result<(), ModifyTaskError> {
+        if diff.base_revision != self.revision {
+            return Err(RevisionMismatch { expected: diff.base_revision,
+                                          actual:   self.revision, });
+        }
+
+        diff.tracks.apply(&mut self.tracks);
+        diff.mixers.apply(&mut self.mixers);
+        diff.dynamic.apply(&mut self.dynamic);
+        diff.fixed.apply(&mut self.fixed);
+        diff.recorders.apply(&mut self.recorders);
+        diff.connections.apply(&mut self.connections);
+        self.pan_law = diff.pan_law;
+        self.timecode_start = diff.timecode_start;
+        self.tempo_map = diff.tempo_map;
+        self.revision = diff.revision;
+
+        Ok(())
+    }
+}
+
+/// Parameter values of a task, captured to be restored later
+///
+/// Snapshots are kept by the domain for the lifetime of the task.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaskSnapshot {
+    /// Snapshot id
+    pub snapshot_id: TaskSnapshotId,
+    /// Human readable name of the snapshot
+    #[serde(default)]
+    pub name:        Option<String>,
+    /// When the snapshot was taken
+    pub created_at:  Timestamp,
+    /// Revision of the task spec the snapshot was taken from
+    pub revision:    u64,
+    /// Captured parameter state
+    pub parameters:  TaskSnapshotParameters,
+}
+
+/// Parameter state of all instance nodes of a task
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TaskSnapshotParameters {
+    /// Parameter state of fixed instance nodes
+    #[serde(default)]
+    pub fixed:   HashMap<FixedInstanceNodeId, NodeParameterSnapshot>,
+    /// Parameter state of dynamic instance nodes
+    #[serde(default)]
+    pub dynamic: HashMap<DynamicInstanceNodeId, NodeParameterSnapshot>,
+}
+
+/// Parameter state of a single instance node
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct NodeParameterSnapshot {
+    /// Parameter values
+    pub parameters:           InstanceParameters,
+    /// Parameter automation
+    #[serde(default)]
+    pub parameter_automation: ParameterAutomation,
+}
+
+impl NodeParameterSnapshot {
+    fn capture(parameters: &InstanceParameters, parameter_automation: &ParameterAutomation) -> Self {
+        Self { parameters:           parameters.clone(),
+               parameter_automation: parameter_automation.clone(), }
+    }
+}
+
+impl TaskSpec {
+    /// Capture the parameter state of every fixed and dynamic instance node
+    pub fn snapshot_parameters(&self) -> TaskSnapshotParameters {
+        let fixed = self.fixed
+                        .iter()
+                        .map(|(id, node)| (id.clone(), NodeParameterSnapshot::capture(&node.parameters, &node.parameter_automation)))
+                        .collect();
+        let dynamic = self.dynamic
+                          .iter()
+                          .map(|(id, node)| (id.clone(), NodeParameterSnapshot::capture(&node.parameters, &node.parameter_automation)))
+                          .collect();
+
+        TaskSnapshotParameters { fixed, dynamic }
+    }
+}
+
 impl TaskSpec {
     pub fn get_fixed_instance_ids(&self) -> HashSet<&FixedInstanceId> {
         self.fixed.values().map(|fixed| &fixed.instance_id).collect()
@@ -423,14 +858,92 @@ impl TaskSpec {
             .collect()
     }
 
+    /// Media objects placed on the task timeline within `segment`, for example to prefetch media ahead of playback
+    pub fn media_needed_in(&self, segment: TimeSegment) -> HashSet<&MediaObjectId> {
+        self.tracks
+            .values()
+            .flat_map(|track| track.media.values())
+            .filter(|media| media.timeline_segment.intersects(&segment))
+            .map(|media| &media.object_id)
+            .collect()
+    }
+
+    /// Media objects used by each track node
+    pub fn media_by_track(&self) -> HashMap<&TrackNodeId, HashSet<&MediaObjectId>> {
+        self.tracks
+            .iter()
+            .map(|(track_id, track)| (track_id, track.media.values().map(|media| &media.object_id).collect()))
+            .collect()
+    }
+
+    /// Apply all modifications, or none of them if any fails
+    ///
+    /// Modifications are applied in order to a copy of the spec, which replaces the spec only if all of
+    /// them succeed. On failure, the error contains the index of the failed modification.
+    pub fn apply_transaction(&mut self, modifications: Vec<ModifyTaskSpec>) -> Result<AppliedTransaction, ModifyTaskError> {
+        self.apply_transaction_with_max_volume(modifications, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    /// Apply all modifications, or none of them if any fails, with connection volumes limited to `0..=max_volume`
+    pub fn apply_transaction_with_max_volume(&mut self,
+                                             modifications: Vec<ModifyTaskSpec>,
+                                             max_volume: f64)
+                                             -> Result<AppliedTransaction, ModifyTaskError> {
+        let mut spec = self.clone();
+        let applied = modifications.len();
+
+        for (index, modify) in modifications.into_iter().enumerate() {
+            spec.modify_with_max_volume(modify, max_volume)
+                .map_err(|error| TransactionFailed { index,
+                                                     error: Box::new(error) })?;
+        }
+
+        let revision_before = self.revision;
+        *self = spec;
+
+        Ok(AppliedTransaction { applied,
+                                revision_before,
+                                revision: self.revision })
+    }
+
+    /// Validate a transaction against this spec without applying it
+    ///
+    /// Returns the changes the transaction would make, including the revision it would produce.
+    pub fn preview_transaction(&self, modifications: Vec<ModifyTaskSpec>) -> Result<TaskSpecDiff, ModifyTaskError> {
+        self.preview_transaction_with_max_volume(modifications, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    /// Validate a transaction against this spec without applying it, with connection volumes limited to `0..=max_volume`
+    pub fn preview_transaction_with_max_volume(&self,
+                                               modifications: Vec<ModifyTaskSpec>,
+                                               max_volume: f64)
+                                               -> Result<TaskSpecDiff, ModifyTaskError> {
+        let mut spec = self.clone();
+        spec.apply_transaction_with_max_volume(modifications, max_volume)?;
+
+        Ok(self.diff(&spec))
+    }
+
     pub fn modify(&mut self, modify: ModifyTaskSpec) -> Result<(), ModifyTaskError> {
+        self.modify_with_max_volume(modify, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    /// Apply a modification, with connection volumes and track gains limited to `0..=max_volume`
+    ///
+    /// The limit should be the domain's [`max_connection_volume`](crate::cloud::domains::DomainConfig::max_connection_volume),
+    /// so that modifications are checked the same as [`TaskSpec::validate_with_max_volume`].
+    pub fn modify_with_max_volume(&mut self, modify: ModifyTaskSpec, max_volume: f64) -> Result<(), ModifyTaskError> {
         match modify {
             ModifyTaskSpec::AddFixedInstance { fixed_id: mixer_id,
                                                spec: process, } => self.add_fixed_instance(mixer_id, process),
             ModifyTaskSpec::AddDynamicInstance { dynamic_id: mixer_id,
                                                  spec: process, } => self.add_dynamic_instance(mixer_id, process),
+            ModifyTaskSpec::AddComposite { composite_id, spec } => self.add_composite_with_max_volume(composite_id, spec, max_volume),
+            ModifyTaskSpec::DeleteComposite { composite_id } => self.delete_composite(composite_id),
             ModifyTaskSpec::AddMixer { mixer_id, spec: channels } => self.add_mixer(mixer_id, channels),
             ModifyTaskSpec::DeleteMixer { mixer_id } => self.delete_mixer(mixer_id),
+            ModifyTaskSpec::AddRecorder { recorder_id, spec } => self.add_recorder(recorder_id, spec),
+            ModifyTaskSpec::DeleteRecorder { recorder_id } => self.delete_recorder(recorder_id),
             ModifyTaskSpec::SetFixedInstanceParameterValues { fixed_id: id, values } => {
                 self.set_fixed_instance_parameter_values(id, values)
             }
@@ -438,13 +951,26 @@ impl TaskSpec {
             ModifyTaskSpec::SetDynamicInstanceParameterValues { dynamic_id: id, values } => {
                 self.set_dynamic_instance_parameter_values(id, values)
             }
+            ModifyTaskSpec::ResetNodeParameters { node_id, defaults } => self.reset_node_parameters(node_id, defaults),
+            ModifyTaskSpec::SetParameterAutomation { node_id,
+                                                     parameter_id,
+                                                     automation, } => self.set_parameter_automation(node_id, parameter_id, automation),
+            ModifyTaskSpec::ApplySnapshot { parameters, nodes } => self.apply_snapshot(parameters, nodes),
             ModifyTaskSpec::AddTrack { track_id, channels } => self.add_track(track_id, channels),
             ModifyTaskSpec::DeleteTrackMedia { track_id, media_id } => self.delete_track_media(track_id, media_id),
             ModifyTaskSpec::DeleteTrack { track_id } => self.delete_track(track_id),
             ModifyTaskSpec::SetConnectionParameterValues { connection_id, values } => {
-                self.set_connection_parameter_values(connection_id, values)
+                self.set_connection_parameter_values_with_max_volume(connection_id, values, max_volume)
+            }
+            ModifyTaskSpec::SetConnectionDelay { connection_id, delay_ms } => self.set_connection_delay(connection_id, delay_ms),
+            ModifyTaskSpec::SetConnectionAutomation { connection_id, automation } => {
+                self.set_connection_automation_with_max_volume(connection_id, automation, max_volume)
             }
+            ModifyTaskSpec::SetPanLaw { pan_law } => self.set_pan_law(pan_law),
+            ModifyTaskSpec::SetTimecodeStart { timecode_start } => self.set_timecode_start(timecode_start),
+            ModifyTaskSpec::SetTempoMap { tempo_map } => self.set_tempo_map(tempo_map),
             ModifyTaskSpec::AddTrackMedia { track_id, media_id, spec } => self.add_track_media(track_id, media_id, spec),
+            ModifyTaskSpec::UpdateTrack { track_id, update } => self.update_track_with_max_volume(track_id, update, max_volume),
             ModifyTaskSpec::UpdateTrackMedia { track_id,
                                                media_id,
                                                update, } => self.update_track_media(track_id, media_id, update),
@@ -454,14 +980,34 @@ impl TaskSpec {
             ModifyTaskSpec::AddConnection { connection_id,
                                             from,
                                             to,
+                                            kind,
                                             from_channels,
                                             to_channels,
                                             volume,
-                                            pan, } => self.add_connection(connection_id, from, to, from_channels, to_channels, volume, pan),
+                                            pan, } => self.add_connection_with_max_volume(connection_id,
+                                                                                          NodeConnection { from,
+                                                                                                           to,
+                                                                                                           kind,
+                                                                                                           from_channels,
+                                                                                                           to_channels,
+                                                                                                           volume,
+                                                                                                           pan,
+                                                                                                           pan_law: None,
+                                                                                                           delay_ms: 0.0,
+                                                                                                           automation: Default::default() },
+                                                                                          max_volume),
         }
     }
 
     pub fn add_fixed_instance(&mut self, fixed_id: FixedInstanceNodeId, instance: FixedInstanceNode) -> Result<(), ModifyTaskError> {
+        if !CompositeNodeId::is_valid_part(&fixed_id) {
+            return Err(NodeIdReserved { node_id: fixed_id.into() });
+        }
+
+        self.insert_fixed_instance(fixed_id, instance)
+    }
+
+    fn insert_fixed_instance(&mut self, fixed_id: FixedInstanceNodeId, instance: FixedInstanceNode) -> Result<(), ModifyTaskError> {
         if self.fixed.contains_key(&fixed_id) {
             return Err(FixedInstanceExists { node_id: fixed_id });
         }
@@ -473,6 +1019,14 @@ impl TaskSpec {
     }
 
     pub fn add_dynamic_instance(&mut self, dynamic_id: DynamicInstanceNodeId, dynamic: DynamicInstanceNode) -> Result<(), ModifyTaskError> {
+        if !CompositeNodeId::is_valid_part(&dynamic_id) {
+            return Err(NodeIdReserved { node_id: dynamic_id.into(), });
+        }
+
+        self.insert_dynamic_instance(dynamic_id, dynamic)
+    }
+
+    fn insert_dynamic_instance(&mut self, dynamic_id: DynamicInstanceNodeId, dynamic: DynamicInstanceNode) -> Result<(), ModifyTaskError> {
         if self.dynamic.contains_key(&dynamic_id) {
             return Err(DynamicInstanceExists { node_id: dynamic_id });
         }
@@ -483,6 +1037,77 @@ impl TaskSpec {
         Ok(())
     }
 
+    /// Add the nodes and connections of a composite, with ids mangled by the composite id
+    pub fn add_composite(&mut self, composite_id: CompositeNodeId, composite: CompositeNode) -> Result<(), ModifyTaskError> {
+        self.add_composite_with_max_volume(composite_id, composite, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    /// Add the nodes and connections of a composite, with connection volumes limited to `0..=max_volume`
+    pub fn add_composite_with_max_volume(&mut self,
+                                         composite_id: CompositeNodeId,
+                                         composite: CompositeNode,
+                                         max_volume: f64)
+                                         -> Result<(), ModifyTaskError> {
+        composite.validate(&composite_id)?;
+
+        let mut spec = self.clone();
+        for (fixed_id, fixed) in composite.fixed {
+            spec.insert_fixed_instance(composite_id.fixed(&fixed_id), fixed)?;
+        }
+        for (dynamic_id, dynamic) in composite.dynamic {
+            spec.insert_dynamic_instance(composite_id.dynamic(&dynamic_id), dynamic)?;
+        }
+        for (connection_id, mut connection) in composite.connections {
+            connection.from = composite_id.output_pad(&connection.from);
+            connection.to = composite_id.input_pad(&connection.to);
+            spec.add_connection_with_max_volume(composite_id.connection(&connection_id), connection, max_volume)?;
+        }
+
+        spec.revision = self.revision + 1;
+        *self = spec;
+
+        Ok(())
+    }
+
+    /// Delete the nodes of a composite and all connections referencing them
+    pub fn delete_composite(&mut self, composite_id: CompositeNodeId) -> Result<(), ModifyTaskError> {
+        let nodes = self.fixed
+                        .keys()
+                        .filter(|fixed_id| composite_id.unmangle(fixed_id).is_some())
+                        .cloned()
+                        .map(TaskNodeId::from)
+                        .chain(self.dynamic
+                                   .keys()
+                                   .filter(|dynamic_id| composite_id.unmangle(dynamic_id).is_some())
+                                   .cloned()
+                                   .map(TaskNodeId::from))
+                        .collect::<Vec<_>>();
+
+        if nodes.is_empty() {
+            return Err(CompositeDoesNotExist { composite_id });
+        }
+
+        for node_id in &nodes {
+            match node_id {
+                TaskNodeId::FixedInstance(fixed_id) => {
+                    self.fixed.remove(fixed_id);
+                }
+                TaskNodeId::DynamicInstance(dynamic_id) => {
+                    self.dynamic.remove(dynamic_id);
+                }
+                TaskNodeId::Mixer(_) | TaskNodeId::Track(_) | TaskNodeId::Recorder(_) => {}
+            }
+        }
+        self.connections.retain(|_, connection| {
+                            !nodes.iter()
+                                  .any(|node_id| connection.from.references(node_id) || connection.to.references(node_id))
+                        });
+
+        self.revision += 1;
+
+        Ok(())
+    }
+
     pub fn add_mixer(&mut self, mixer_id: MixerNodeId, mixer: MixerNode) -> Result<(), ModifyTaskError> {
         if self.mixers.contains_key(&mixer_id) {
             return Err(MixerExists { node_id: mixer_id });
@@ -505,6 +1130,28 @@ impl TaskSpec {
         Ok(())
     }
 
+    pub fn add_recorder(&mut self, recorder_id: RecorderNodeId, recorder: RecorderNode) -> Result<(), ModifyTaskError> {
+        if self.recorders.contains_key(&recorder_id) {
+            return Err(RecorderExists { node_id: recorder_id });
+        }
+
+        self.recorders.insert(recorder_id, recorder);
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    pub fn delete_recorder(&mut self, recorder_id: RecorderNodeId) -> Result<(), ModifyTaskError> {
+        if self.recorders.remove(&recorder_id).is_none() {
+            return Err(RecorderDoesNotExist { node_id: recorder_id });
+        }
+
+        self.delete_connections_referencing(&TaskNodeId::Recorder(recorder_id));
+        self.revision += 1;
+
+        Ok(())
+    }
+
     pub fn is_connected(&self, from: &OutputPadId, to: &InputPadId) -> bool {
         self.connections
             .iter()
@@ -515,6 +1162,21 @@ impl TaskSpec {
                                            connection_id: NodeConnectionId,
                                            values: ConnectionValues)
                                            -> Result<(), ModifyTaskError> {
+        self.set_connection_parameter_values_with_max_volume(connection_id, values, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    pub fn set_connection_parameter_values_with_max_volume(&mut self,
+                                                           connection_id: NodeConnectionId,
+                                                           values: ConnectionValues,
+                                                           max_volume: f64)
+                                                           -> Result<(), ModifyTaskError> {
+        if let Some(volume) = values.volume {
+            check_connection_volume(&connection_id, volume, max_volume)?;
+        }
+        if let Some(pan) = values.pan {
+            check_connection_pan(&connection_id, pan)?;
+        }
+
         let connection = self.connections
                              .get_mut(&connection_id)
                              .ok_or(ConnectionDoesNotExist { connection_id })?;
@@ -524,6 +1186,48 @@ impl TaskSpec {
         if let Some(pan) = values.pan {
             connection.pan = pan;
         }
+        if let Some(pan_law) = values.pan_law {
+            connection.pan_law = Some(pan_law);
+        }
+
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    pub fn set_connection_delay(&mut self, connection_id: NodeConnectionId, delay_ms: f64) -> Result<(), ModifyTaskError> {
+        if !is_delay_valid(delay_ms) {
+            return Err(DelayOutOfRange { connection_id, delay_ms });
+        }
+
+        let connection = self.connections
+                             .get_mut(&connection_id)
+                             .ok_or(ConnectionDoesNotExist { connection_id })?;
+        connection.delay_ms = delay_ms;
+
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    pub fn set_connection_automation(&mut self,
+                                     connection_id: NodeConnectionId,
+                                     automation: ConnectionAutomation)
+                                     -> Result<(), ModifyTaskError> {
+        self.set_connection_automation_with_max_volume(connection_id, automation, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    pub fn set_connection_automation_with_max_volume(&mut self,
+                                                     connection_id: NodeConnectionId,
+                                                     automation: ConnectionAutomation,
+                                                     max_volume: f64)
+                                                     -> Result<(), ModifyTaskError> {
+        check_connection_automation(&connection_id, &automation, max_volume)?;
+
+        let connection = self.connections
+                             .get_mut(&connection_id)
+                             .ok_or(ConnectionDoesNotExist { connection_id })?;
+        connection.automation = automation;
 
         self.revision += 1;
 
@@ -532,25 +1236,126 @@ impl TaskSpec {
 
     pub fn set_fixed_instance_parameter_values(&mut self,
                                                node_id: FixedInstanceNodeId,
-                                               parameters: serde_json::Value)
+                                               values: InstanceParameters)
                                                -> Result<(), ModifyTaskError> {
         let fixed = self.fixed.get_mut(&node_id).ok_or(FixedInstanceDoesNotExist { node_id })?;
-        // fixed.parameters.extend(parameters.into_iter());
+        fixed.parameters.merge(values);
+
+        self.revision += 1;
+
         Ok(())
     }
 
     pub fn set_dynamic_instance_parameter_values(&mut self,
                                                  node_id: DynamicInstanceNodeId,
-                                                 values: serde_json::Value)
+                                                 values: InstanceParameters)
                                                  -> Result<(), ModifyTaskError> {
         let dynamic = self.dynamic.get_mut(&node_id).ok_or(DynamicInstanceDoesNotExist { node_id })?;
-        // dynamic.parameters.extend(parameters.into_iter());
+        dynamic.parameters.merge(values);
+
+        self.revision += 1;
 
+        Ok(())
+    }
+
+    pub fn reset_node_parameters(&mut self, node_id: TaskNodeId, defaults: InstanceParameters) -> Result<(), ModifyTaskError> {
+        let parameters = match &node_id {
+            TaskNodeId::FixedInstance(fixed_id) => {
+                &mut self.fixed
+                         .get_mut(fixed_id)
+                         .ok_or_else(|| FixedInstanceDoesNotExist { node_id: fixed_id.clone() })?
+                         .parameters
+            }
+            TaskNodeId::DynamicInstance(dynamic_id) => {
+                &mut self.dynamic
+                         .get_mut(dynamic_id)
+                         .ok_or_else(|| DynamicInstanceDoesNotExist { node_id: dynamic_id.clone(), })?
+                         .parameters
+            }
+            TaskNodeId::Mixer(_) | TaskNodeId::Track(_) | TaskNodeId::Recorder(_) => return Err(NodeHasNoParameters { node_id }),
+        };
+
+        *parameters = defaults;
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    pub fn set_parameter_automation(&mut self,
+                                    node_id: TaskNodeId,
+                                    parameter_id: ParameterId,
+                                    automation: Option<AutomationLane>)
+                                    -> Result<(), ModifyTaskError> {
+        if automation.as_ref().is_some_and(|lane| !lane.is_well_formed()) {
+            return Err(ParameterAutomationMalformed { node_id, parameter_id });
+        }
+
+        let parameter_automation = match &node_id {
+            TaskNodeId::FixedInstance(fixed_id) => {
+                &mut self.fixed
+                         .get_mut(fixed_id)
+                         .ok_or_else(|| FixedInstanceDoesNotExist { node_id: fixed_id.clone() })?
+                         .parameter_automation
+            }
+            TaskNodeId::DynamicInstance(dynamic_id) => {
+                &mut self.dynamic
+                         .get_mut(dynamic_id)
+                         .ok_or_else(|| DynamicInstanceDoesNotExist { node_id: dynamic_id.clone(), })?
+                         .parameter_automation
+            }
+            TaskNodeId::Mixer(_) | TaskNodeId::Track(_) | TaskNodeId::Recorder(_) => return Err(NodeHasNoParameters { node_id }),
+        };
+
+        match automation {
+            Some(automation) => parameter_automation.insert(parameter_id, automation),
+            None => parameter_automation.remove(&parameter_id),
+        };
         self.revision += 1;
 
         Ok(())
     }
 
+    pub fn apply_snapshot(&mut self, mut snapshot: TaskSnapshotParameters, nodes: HashSet<TaskNodeId>) -> Result<(), ModifyTaskError> {
+        let nodes = if nodes.is_empty() {
+            snapshot.fixed
+                    .keys()
+                    .cloned()
+                    .map(TaskNodeId::from)
+                    .chain(snapshot.dynamic.keys().cloned().map(TaskNodeId::from))
+                    .collect()
+        } else {
+            nodes
+        };
+
+        let mut spec = self.clone();
+        for node_id in nodes {
+            let (parameters, parameter_automation, restored) = match &node_id {
+                TaskNodeId::FixedInstance(fixed_id) => {
+                    let fixed = spec.fixed
+                                    .get_mut(fixed_id)
+                                    .ok_or_else(|| FixedInstanceDoesNotExist { node_id: fixed_id.clone() })?;
+                    (&mut fixed.parameters, &mut fixed.parameter_automation, snapshot.fixed.remove(fixed_id))
+                }
+                TaskNodeId::DynamicInstance(dynamic_id) => {
+                    let dynamic = spec.dynamic
+                                      .get_mut(dynamic_id)
+                                      .ok_or_else(|| DynamicInstanceDoesNotExist { node_id: dynamic_id.clone(), })?;
+                    (&mut dynamic.parameters, &mut dynamic.parameter_automation, snapshot.dynamic.remove(dynamic_id))
+                }
+                TaskNodeId::Mixer(_) | TaskNodeId::Track(_) | TaskNodeId::Recorder(_) => return Err(NodeHasNoParameters { node_id }),
+            };
+
+            let restored = restored.ok_or(NodeNotInSnapshot { node_id })?;
+            *parameters = restored.parameters;
+            *parameter_automation = restored.parameter_automation;
+        }
+
+        spec.revision += 1;
+        *self = spec;
+
+        Ok(())
+    }
+
     pub fn delete_connections_referencing(&mut self, node_id: &TaskNodeId) {
         self.connections
             .retain(|_, value| !(value.from.references(node_id) || value.to.references(node_id)));
@@ -563,9 +1368,7 @@ impl TaskSpec {
             return Err(TrackExists { node_id: track_id });
         }
 
-        self.tracks.insert(track_id,
-                           TrackNode { channels,
-                                       media: Default::default() });
+        self.tracks.insert(track_id, TrackNode::new(channels));
 
         self.revision += 1;
 
@@ -652,26 +1455,79 @@ impl TaskSpec {
         }
     }
 
-    pub fn add_connection(&mut self,
-                          connection_id: NodeConnectionId,
-                          from: OutputPadId,
-                          to: InputPadId,
-                          from_channels: ChannelMask,
-                          to_channels: ChannelMask,
-                          volume: f64,
-                          pan: f64)
-                          -> Result<(), ModifyTaskError> {
+    pub fn add_connection(&mut self, connection_id: NodeConnectionId, connection: NodeConnection) -> Result<(), ModifyTaskError> {
+        self.add_connection_with_max_volume(connection_id, connection, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    pub fn add_connection_with_max_volume(&mut self,
+                                          connection_id: NodeConnectionId,
+                                          connection: NodeConnection,
+                                          max_volume: f64)
+                                          -> Result<(), ModifyTaskError> {
         if self.connections.contains_key(&connection_id) {
             return Err(ConnectionExists { connection_id });
         }
 
-        self.connections.insert(connection_id,
-                                NodeConnection { from,
-                                                 to,
-                                                 from_channels,
-                                                 to_channels,
-                                                 volume,
-                                                 pan });
+        check_connection_volume(&connection_id, connection.volume, max_volume)?;
+        check_connection_pan(&connection_id, connection.pan)?;
+        check_connection_automation(&connection_id, &connection.automation, max_volume)?;
+        if !is_delay_valid(connection.delay_ms) {
+            return Err(DelayOutOfRange { connection_id,
+                                         delay_ms: connection.delay_ms });
+        }
+
+        let (from_node_id, to_node_id) = (connection.from.node_id(), connection.to.node_id());
+        if from_node_id == to_node_id || self.is_reachable(&to_node_id, &from_node_id) {
+            return Err(CycleDetected);
+        }
+
+        self.connections.insert(connection_id, connection);
+
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    pub fn set_pan_law(&mut self, pan_law: PanLaw) -> Result<(), ModifyTaskError> {
+        self.pan_law = pan_law;
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    pub fn set_timecode_start(&mut self, timecode_start: Option<Timecode>) -> Result<(), ModifyTaskError> {
+        self.timecode_start = timecode_start;
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    pub fn set_tempo_map(&mut self, tempo_map: TempoMap) -> Result<(), ModifyTaskError> {
+        tempo_map.validate().map_err(|error| InvalidTempoMap { error })?;
+
+        self.tempo_map = tempo_map;
+        self.revision += 1;
+
+        Ok(())
+    }
+
+    pub fn update_track(&mut self, track_id: TrackNodeId, update: UpdateTaskTrack) -> Result<(), ModifyTaskError> {
+        self.update_track_with_max_volume(track_id, update, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    pub fn update_track_with_max_volume(&mut self,
+                                        track_id: TrackNodeId,
+                                        update: UpdateTaskTrack,
+                                        max_volume: f64)
+                                        -> Result<(), ModifyTaskError> {
+        if let Some(gain) = update.gain.filter(|gain| !is_volume_valid(*gain, max_volume)) {
+            return Err(TrackGainOutOfRange { node_id: track_id,
+                                             gain,
+                                             max: max_volume });
+        }
+
+        let track = self.tracks.get_mut(&track_id).ok_or(TrackDoesNotExist { node_id: track_id })?;
+        track.update(update);
 
         self.revision += 1;
 
@@ -697,6 +1553,43 @@ impl TaskSpec {
     }
 }
 
+fn check_connection_volume(connection_id: &NodeConnectionId, volume: f64, max_volume: f64) -> Result<(), ModifyTaskError> {
+    if is_volume_valid(volume, max_volume) {
+        Ok(())
+    } else {
+        Err(VolumeOutOfRange { connection_id: connection_id.clone(),
+                               volume,
+                               max: max_volume })
+    }
+}
+
+fn check_connection_pan(connection_id: &NodeConnectionId, pan: f64) -> Result<(), ModifyTaskError> {
+    if is_pan_valid(pan) {
+        Ok(())
+    } else {
+        Err(PanOutOfRange { connection_id: connection_id.clone(),
+                            pan })
+    }
+}
+
+fn check_connection_automation(connection_id: &NodeConnectionId,
+                               automation: &ConnectionAutomation,
+                               max_volume: f64)
+                               -> Result<(), ModifyTaskError> {
+    if !automation.is_well_formed() {
+        return Err(AutomationMalformed { connection_id: connection_id.clone(), });
+    }
+
+    for volume in automation.volume.iter().flat_map(AutomationLane::values) {
+        check_connection_volume(connection_id, volume, max_volume)?;
+    }
+    for pan in automation.pan.iter().flat_map(AutomationLane::values) {
+        check_connection_pan(connection_id, pan)?;
+    }
+
+    Ok(())
+}
+
 fn security_changes(rv: &mut Vec<ModifyTask>, existing: &TaskSecurity, new: &TaskSecurity) {
     let changes = hashmap_changes(&existing.security, &new.security);
     for (key, security) in changes.changed.into_iter().chain(changes.added.into_iter()) {
@@ -1,32 +1,388 @@
-use std::fmt::Debug;
+use std::collections::HashSet;
+use std::fmt::{Debug, Display};
 
 use anyhow::anyhow;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+use crate::common::change::ModifyTaskError;
+use crate::common::media::{PlayId, RenderOutputFormat, StorageQuota, StorageUsage};
+use crate::common::model::{ModelValidationError, ResourceId};
+use crate::common::tempo::TempoMapError;
+use crate::{
+    AppId, AppMediaObjectId, AppTaskId, ChannelMask, ConnectionKind, DomainId, DynamicInstanceNodeId, FixedInstanceId, FixedInstanceNodeId,
+    InstanceSelector, MixerNodeId, ModelId, NodeConnectionId, PresetId, RecorderNodeId, TaskNodeId, Timestamp, TrackNodeId,
+};
+
+/// Error with a stable, machine-readable code
+///
+/// Clients should branch on the code instead of parsing the human-readable message, which may change.
+pub trait ErrorCode {
+    /// Code of the kind of error in snake case, for example `task_not_found`
+    fn error_code(&self) -> &'static str;
+}
+
+/// Error serialized together with its code and human-readable message
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct CodedError<E> {
+    /// Machine-readable code of the error, see [`ErrorCode`]
+    pub code:    String,
+    /// Human-readable message describing the error
+    pub message: String,
+    /// The error itself
+    #[serde(flatten)]
+    pub error:   E,
+}
+
+impl<E> From<E> for CodedError<E> where E: ErrorCode + Display
+{
+    fn from(error: E) -> Self {
+        Self { code: error.error_code().to_owned(),
+               message: error.to_string(),
+               error }
+    }
+}
+
+/// Result that serializes with stable `ok` and `error` tags, used in API responses and messages
+///
+/// Unlike [`Result`], which serializes as `Ok` or `Err`, the tags are the same as those used by the
+/// other enums of the API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SerializableResult<T, E> {
     Ok(T),
-    Error(E),
+    #[serde(rename = "error")]
+    Err(E),
 }
 
-impl<T, E> Into<anyhow::Result<T>> for SerializableResult<T, E> where E: Debug
-{
-    fn into(self) -> anyhow::Result<T> {
+impl<T, E> SerializableResult<T, E> {
+    pub fn into_result(self) -> Result<T, E> {
+        match self {
+            SerializableResult::Ok(t) => Ok(t),
+            SerializableResult::Err(err) => Err(err),
+        }
+    }
+
+    pub fn as_result(&self) -> Result<&T, &E> {
         match self {
             SerializableResult::Ok(t) => Ok(t),
-            SerializableResult::Error(err) => Err(anyhow!("Error {err:?}")),
+            SerializableResult::Err(err) => Err(err),
         }
     }
+
+    pub fn is_ok(&self) -> bool {
+        matches!(self, SerializableResult::Ok(_))
+    }
+
+    pub fn is_err(&self) -> bool {
+        matches!(self, SerializableResult::Err(_))
+    }
 }
 
-impl<T, E> From<anyhow::Result<T>> for SerializableResult<T, E> where E: From<anyhow::Error>
+impl<T, E> Into<anyhow::Result<T>> for SerializableResult<T, E> where E: Debug
 {
-    fn from(res: anyhow::Result<T>) -> Self {
+    fn into(self) -> anyhow::Result<T> {
+        self.into_result().map_err(|err| anyhow!("Error {err:?}"))
+    }
+}
+
+impl<T, E> From<Result<T, E>> for SerializableResult<T, E> {
+    fn from(res: Result<T, E>) -> Self {
         match res {
             Ok(ok) => Self::Ok(ok),
-            Err(err) => Self::Error(err.into()),
+            Err(err) => Self::Err(err),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Error, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum CloudError {
+    #[error("API Key not found")]
+    ApiKeyNotFound,
+
+    #[error("App file {id} not found")]
+    AppFileNotFound { id: AppMediaObjectId },
+
+    #[error("App {id} not found")]
+    AppNotFound { id: AppId },
+
+    #[error("{task_id} is an invalid app task ID")]
+    InvalidAppTaskId { task_id: String },
+
+    #[error("{object_id} is an invalid app media object ID")]
+    InvalidAppMediaObjectId { object_id: String },
+
+    #[error("{request_id} is an invalid request ID")]
+    InvalidRequestId { request_id: String },
+
+    #[error("{socket_id} is an invalid socket ID")]
+    InvalidSocketId { socket_id: String },
+
+    #[error("At least a segment of a reservation needs to be in the future")]
+    OnlyFutureReservations,
+
+    #[error("Task time must be well-formed")]
+    TimeMalformed,
+
+    #[error("Task requested duration {requested} is smaller than domain minimum task duration time {minimum} ms")]
+    DurationTooShort { minimum: f64, requested: f64 },
+
+    #[error("Too many overlapping tasks reserved on domain, maximum is {max}")]
+    TooManyTasks { max: usize },
+
+    #[error("Detected internal inconsistency: {message}")]
+    InternalInconsistency { message: String },
+
+    #[error("Instances overlapping: {instance_ids:?}")]
+    OverlappingFixedInstances { instance_ids: HashSet<FixedInstanceId> },
+
+    #[error("Connection error: {connection_id}: {error}")]
+    ConnectionError {
+        connection_id: NodeConnectionId,
+        error:         Box<CloudError>,
+    },
+
+    #[error("Channel mask {mask:?} is invalid for channel count {channels}")]
+    ChannelMaskIncompatible { mask: ChannelMask, channels: usize },
+
+    #[error("Node {node_id:?} does not expose {kind:?} pads")]
+    ConnectionKindNotSupported { node_id: TaskNodeId, kind: ConnectionKind },
+
+    #[error("Volume {volume} is out of range, must be between 0 and {max}")]
+    VolumeOutOfRange { volume: f64, max: f64 },
+
+    #[error("Pan {pan} is out of range, must be between -1 and 1")]
+    PanOutOfRange { pan: f64 },
+
+    #[error("Delay {delay_ms} ms is out of range, must be between 0 and {max} ms")]
+    DelayOutOfRange { delay_ms: f64, max: f64 },
+
+    #[error("Track {track_node_id} gain {gain} is out of range, must be between 0 and {max}")]
+    TrackGainOutOfRange {
+        track_node_id: TrackNodeId,
+        gain:          f64,
+        max:           f64,
+    },
+
+    #[error("Automation lanes must have points at increasing, non-negative times")]
+    AutomationMalformed,
+
+    #[error("Tempo map is invalid: {error}")]
+    InvalidTempoMap { error: TempoMapError },
+
+    #[error("Click can not be enabled on a task without a tempo map")]
+    ClickWithoutTempoMap,
+
+    #[error("Mixer instance node not found: {mixer_node_id}")]
+    MixerNodeNotFound { mixer_node_id: MixerNodeId },
+
+    #[error("At least one mixer must be monitored")]
+    NoMonitoredMixers,
+
+    #[error("Mixer instance node not found: {track_node_id}")]
+    TrackNodeNotFound { track_node_id: TrackNodeId },
+
+    #[error("Fixd instance node not found: {fixed_node_id}")]
+    FixedInstanceNodeNotFound { fixed_node_id: FixedInstanceNodeId },
+
+    #[error("Dynamic instance node not found: {dynamic_node_id}")]
+    DynamicInstanceNodeNotFound { dynamic_node_id: DynamicInstanceNodeId },
+
+    #[error("Recorder node not found: {recorder_node_id}")]
+    RecorderNodeNotFound { recorder_node_id: RecorderNodeId },
+
+    #[error("Domain {domain_id} unknown")]
+    DomainNotFound { domain_id: DomainId },
+
+    #[error("Instance {instance_id} unknown")]
+    InstanceNotFound { instance_id: FixedInstanceId },
+
+    #[error("No available instance matches the selector {selector:?} of fixed instance node {fixed_node_id}")]
+    NoInstanceMatchesSelector {
+        fixed_node_id: FixedInstanceNodeId,
+        selector:      InstanceSelector,
+    },
+
+    #[error("Model {model_id} unknown")]
+    ModelNotFound { model_id: ModelId },
+
+    #[error("Preset {preset_id} of model {model_id} not found")]
+    PresetNotFound { model_id: ModelId, preset_id: PresetId },
+
+    #[error("Preset parameters are invalid for model {model_id}: {error}")]
+    InvalidPresetParameters { model_id: ModelId, error: ModelValidationError },
+
+    #[error("Parameters of node {node_id:?} are invalid: {error}")]
+    InvalidInstanceParameters {
+        node_id: TaskNodeId,
+        error:   ModelValidationError,
+    },
+
+    #[error("Model {model_id} of a dynamic instance required by node {node_id} is not supported on domain {domain_id}")]
+    DynamicInstanceNotSupported {
+        node_id:   DynamicInstanceNodeId,
+        domain_id: DomainId,
+        model_id:  ModelId,
+    },
+
+    #[error("Fixed instance {instance_id} required by fixed instance node {node_id} is not supported on domain {domain_id}")]
+    FixedInstanceNotSupported {
+        node_id:     FixedInstanceNodeId,
+        domain_id:   DomainId,
+        instance_id: FixedInstanceId,
+    },
+
+    #[error("Fixed instance {instance_id} required by fixed instance node {node_id} is not avaialble to app {app_id} on domain {domain_id}")]
+    FixedInstanceAccessDenied {
+        node_id:     FixedInstanceNodeId,
+        domain_id:   DomainId,
+        instance_id: FixedInstanceId,
+        app_id:      AppId,
+    },
+
+    #[error("Out of {resource} resource. Requested {requested} available {available}")]
+    OutOfResource {
+        resource:  ResourceId,
+        available: f64,
+        requested: f64,
+    },
+
+    #[error("Out of licenses for model {model_id}. Requested {requested} available {available}")]
+    OutOfLicenses {
+        model_id:  ModelId,
+        requested: usize,
+        available: usize,
+    },
+
+    #[error("Task {task_id} was not found")]
+    TaskNotFound { task_id: AppTaskId },
+
+    #[error("Task could not be modified: {error}")]
+    TaskModification {
+        #[from]
+        error: ModifyTaskError,
+    },
+
+    #[error("Database error: {message}")]
+    Database { message: String },
+
+    #[error("Authentication failed: {message}")]
+    Authentication { message: String },
+
+    #[error("Authorization failed: {message}")]
+    Authorization { message: String },
+
+    #[error("All retries exhausted while trying to obtain a lock")]
+    BlockingLock,
+
+    #[error("Maintenance conflicts with reserved tasks: {task_ids:?}")]
+    MaintenanceConflict { task_ids: Vec<AppTaskId> },
+
+    #[error("Maintenance starting at {starts_at} does not give {notice_ms} ms notice to reserved tasks: {task_ids:?}")]
+    MaintenanceNoticeTooShort {
+        starts_at: Timestamp,
+        notice_ms: u64,
+        task_ids:  Vec<AppTaskId>,
+    },
+
+    #[error("Scheduled start {scheduled_at} is outside of the task reservation from {from} to {to}")]
+    ScheduledOutsideReservation {
+        scheduled_at: Timestamp,
+        from:         Timestamp,
+        to:           Timestamp,
+    },
+
+    #[error("Play region from {start} to {end} is empty or outside of the task timeline of {timeline_length} seconds")]
+    PlayRegionOutsideTimeline {
+        start:           f64,
+        end:             f64,
+        timeline_length: f64,
+    },
+
+    #[error("Loop region from {start} to {end} is outside of the played segment from {segment_start} to {segment_end}")]
+    LoopRegionOutsideSegment {
+        start:         f64,
+        end:           f64,
+        segment_start: f64,
+        segment_end:   f64,
+    },
+
+    #[error("Play {play_id} is not active")]
+    PlayNotActive { play_id: PlayId },
+
+    #[error("Render output format {format:?} is not supported")]
+    UnsupportedRenderOutputFormat { format: RenderOutputFormat },
+
+    #[error("Storage quota of app {app_id} exceeded: {usage:?} is over {quota:?}")]
+    QuotaExceeded {
+        app_id: AppId,
+        quota:  StorageQuota,
+        usage:  StorageUsage,
+    },
+}
+
+impl ErrorCode for CloudError {
+    fn error_code(&self) -> &'static str {
+        use CloudError::*;
+
+        match self {
+            ApiKeyNotFound => "api_key_not_found",
+            AppFileNotFound { .. } => "app_file_not_found",
+            AppNotFound { .. } => "app_not_found",
+            InvalidAppTaskId { .. } => "invalid_app_task_id",
+            InvalidAppMediaObjectId { .. } => "invalid_app_media_object_id",
+            InvalidRequestId { .. } => "invalid_request_id",
+            InvalidSocketId { .. } => "invalid_socket_id",
+            OnlyFutureReservations => "only_future_reservations",
+            TimeMalformed => "time_malformed",
+            DurationTooShort { .. } => "duration_too_short",
+            TooManyTasks { .. } => "too_many_tasks",
+            InternalInconsistency { .. } => "internal_inconsistency",
+            OverlappingFixedInstances { .. } => "overlapping_fixed_instances",
+            ConnectionError { .. } => "connection_error",
+            ChannelMaskIncompatible { .. } => "channel_mask_incompatible",
+            ConnectionKindNotSupported { .. } => "connection_kind_not_supported",
+            VolumeOutOfRange { .. } => "volume_out_of_range",
+            PanOutOfRange { .. } => "pan_out_of_range",
+            DelayOutOfRange { .. } => "delay_out_of_range",
+            TrackGainOutOfRange { .. } => "track_gain_out_of_range",
+            AutomationMalformed => "automation_malformed",
+            InvalidTempoMap { .. } => "invalid_tempo_map",
+            ClickWithoutTempoMap => "click_without_tempo_map",
+            MixerNodeNotFound { .. } => "mixer_node_not_found",
+            NoMonitoredMixers => "no_monitored_mixers",
+            TrackNodeNotFound { .. } => "track_node_not_found",
+            FixedInstanceNodeNotFound { .. } => "fixed_instance_node_not_found",
+            DynamicInstanceNodeNotFound { .. } => "dynamic_instance_node_not_found",
+            RecorderNodeNotFound { .. } => "recorder_node_not_found",
+            DomainNotFound { .. } => "domain_not_found",
+            InstanceNotFound { .. } => "instance_not_found",
+            NoInstanceMatchesSelector { .. } => "no_instance_matches_selector",
+            ModelNotFound { .. } => "model_not_found",
+            PresetNotFound { .. } => "preset_not_found",
+            InvalidPresetParameters { .. } => "invalid_preset_parameters",
+            InvalidInstanceParameters { .. } => "invalid_instance_parameters",
+            DynamicInstanceNotSupported { .. } => "dynamic_instance_not_supported",
+            FixedInstanceNotSupported { .. } => "fixed_instance_not_supported",
+            FixedInstanceAccessDenied { .. } => "fixed_instance_access_denied",
+            OutOfResource { .. } => "out_of_resource",
+            OutOfLicenses { .. } => "out_of_licenses",
+            TaskNotFound { .. } => "task_not_found",
+            TaskModification { .. } => "task_modification",
+            Database { .. } => "database",
+            Authentication { .. } => "authentication",
+            Authorization { .. } => "authorization",
+            BlockingLock => "blocking_lock",
+            MaintenanceConflict { .. } => "maintenance_conflict",
+            MaintenanceNoticeTooShort { .. } => "maintenance_notice_too_short",
+            ScheduledOutsideReservation { .. } => "scheduled_outside_reservation",
+            PlayRegionOutsideTimeline { .. } => "play_region_outside_timeline",
+            LoopRegionOutsideSegment { .. } => "loop_region_outside_segment",
+            PlayNotActive { .. } => "play_not_active",
+            UnsupportedRenderOutputFormat { .. } => "unsupported_render_output_format",
+            QuotaExceeded { .. } => "quota_exceeded",
         }
     }
 }
@@ -1,9 +1,14 @@
+use std::collections::HashMap;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::common::media::{PlayId, RenderId};
 use crate::common::time::Timestamped;
-use crate::instance_driver::InstanceDriverCommand;
+use crate::newtypes::FixedInstanceId;
+
+/// Calibration age after which an instance is considered fully out of calibration
+pub const INSTANCE_CALIBRATION_MAX_AGE_MS: u64 = 90 * 24 * 60 * 60 * 1000;
 
 #[derive(PartialEq, Serialize, Deserialize, Copy, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -25,16 +30,6 @@ pub enum DesiredInstancePlayState {
     Stopped,
 }
 
-impl Into<InstanceDriverCommand> for DesiredInstancePlayState {
-    fn into(self) -> InstanceDriverCommand {
-        match self {
-            DesiredInstancePlayState::Playing { play_id } => InstanceDriverCommand::Play { play_id },
-            DesiredInstancePlayState::Rendering { length, render_id } => InstanceDriverCommand::Render { render_id, length },
-            DesiredInstancePlayState::Stopped => InstanceDriverCommand::Stop,
-        }
-    }
-}
-
 impl InstancePlayState {
     pub fn satisfies(&self, required: &DesiredInstancePlayState) -> bool {
         match (self, required) {
@@ -120,6 +115,18 @@ pub enum InstanceEvent {
     },
 }
 
+/// Health of the connection between an instance driver and its hardware
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceConnectionState {
+    /// Hardware responds normally
+    Connected,
+    /// Hardware responds, but with errors, retries or delays
+    Degraded,
+    /// Hardware stopped responding
+    Lost,
+}
+
 pub mod power {
     pub mod params {
         use crate::common::ParameterId;
@@ -140,3 +147,66 @@ pub mod power {
         }
     }
 }
+
+/// Health of a fixed instance, used to choose between interchangeable instances of the same model
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstanceScore {
+    /// True if the instance driver is connected to the instance
+    pub connected:          bool,
+    /// Number of faults reported by the instance driver in the recent past
+    #[serde(default)]
+    pub recent_faults:      u32,
+    /// Milliseconds since the instance was last calibrated, or null if it was never calibrated
+    #[serde(default)]
+    pub calibration_age_ms: Option<u64>,
+    /// Fraction of recent time the instance was in use by tasks, between 0 and 1
+    #[serde(default)]
+    pub utilization:        f64,
+}
+
+impl InstanceScore {
+    /// Score between 0 and 1 where higher is better, or `None` if the instance is not connected
+    ///
+    /// Every recent fault halves the score, while stale calibration and high utilization may each
+    /// reduce it by up to a half.
+    pub fn score(&self) -> Option<f64> {
+        if !self.connected {
+            return None;
+        }
+
+        let faults = 0.5_f64.powi(i32::try_from(self.recent_faults).unwrap_or(i32::MAX));
+        let calibration = self.calibration_age_ms
+                              .map(|age| age.min(INSTANCE_CALIBRATION_MAX_AGE_MS) as f64 / INSTANCE_CALIBRATION_MAX_AGE_MS as f64)
+                              .unwrap_or(1.0);
+        let utilization = if self.utilization.is_finite() {
+            self.utilization.clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        Some(faults * (1.0 - calibration / 2.0) * (1.0 - utilization / 2.0))
+    }
+}
+
+/// Select the instance with the best score from a pool of interchangeable instances
+///
+/// Instances without a score or that are not connected are never selected. Ties are broken by the
+/// instance id, so the same pool and scores always yield the same instance.
+pub fn select_best<'a>(pool: impl IntoIterator<Item = &'a FixedInstanceId>,
+                       scores: &HashMap<FixedInstanceId, InstanceScore>)
+                       -> Option<&'a FixedInstanceId> {
+    pool.into_iter()
+        .filter_map(|instance_id| Some((instance_id, scores.get(instance_id)?.score()?)))
+        .min_by(|(a_id, a_score), (b_id, b_score)| b_score.total_cmp(a_score).then_with(|| a_id.to_string().cmp(&b_id.to_string())))
+        .map(|(instance_id, _)| instance_id)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct FixedInstanceRouting {
+    pub send_count:     usize,
+    pub send_channel:   usize,
+    pub return_count:   usize,
+    pub return_channel: usize,
+}
+
+pub type FixedInstanceRoutingMap = HashMap<FixedInstanceId, FixedInstanceRouting>;
@@ -1,40 +1,259 @@
 //! Communication with the on-site media library
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use derive_more::{Constructor, Display, From, Into};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 use crate::common::task::{MediaChannels, TrackMediaFormat};
+use crate::common::tempo::PlayClick;
 use crate::common::time::{now, Timestamp};
+use crate::common::timecode::{Timecode, TimecodeFormat};
 use crate::newtypes::{AppMediaObjectId, AppTaskId};
-use crate::{MixerNodeId, TimeSegment};
+use crate::{AppId, CloudError, MixerNodeId, TimeSegment};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct MediaJobState {
-    pub progress:    f64,
-    pub retry:       usize,
-    pub error:       Option<String>,
-    pub in_progress: bool,
-    pub updated_at:  Timestamp,
+    pub progress:          f64,
+    pub retry:             usize,
+    pub error:             Option<String>,
+    /// Classification of the error, if known
+    #[serde(default)]
+    pub error_kind:        Option<MediaJobErrorKind>,
+    pub in_progress:       bool,
+    pub updated_at:        Timestamp,
+    /// Bytes transferred so far
+    #[serde(default)]
+    pub transferred_bytes: u64,
+    /// Total bytes to transfer, if known
+    #[serde(default)]
+    pub total_bytes:       Option<u64>,
+    /// Estimated transfer throughput, in bytes per second
+    #[serde(default)]
+    pub bytes_per_second:  Option<f64>,
+    /// Estimated time until the transfer completes, in milliseconds
+    #[serde(default)]
+    pub eta_ms:            Option<u64>,
 }
 
 impl Default for MediaJobState {
     fn default() -> Self {
-        Self { progress:    0.0,
-               retry:       0,
-               error:       None,
-               in_progress: false,
-               updated_at:  now(), }
+        Self { progress:          0.0,
+               retry:             0,
+               error:             None,
+               error_kind:        None,
+               in_progress:       false,
+               updated_at:        now(),
+               transferred_bytes: 0,
+               total_bytes:       None,
+               bytes_per_second:  None,
+               eta_ms:            None, }
     }
 }
 
+/// Kind of a media job
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaJobKind {
+    /// Media is transferred to the domain
+    Upload,
+    /// Media is transferred from the domain
+    Download,
+    /// Waveform peaks are generated from the media
+    GeneratePeaks,
+}
+
+/// Change of a media job, used to display progress
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum MediaJobEvent {
+    /// The job is waiting for other jobs to finish
+    Queued,
+    /// The transfer started
+    Started {
+        /// Total bytes to transfer, if known
+        #[serde(default)]
+        total_bytes: Option<u64>,
+    },
+    /// The transfer progressed
+    Progress {
+        /// Bytes transferred so far
+        transferred_bytes: u64,
+        /// Total bytes to transfer, if known
+        #[serde(default)]
+        total_bytes:       Option<u64>,
+        /// Estimated transfer throughput, in bytes per second
+        #[serde(default)]
+        bytes_per_second:  Option<f64>,
+        /// Estimated time until the transfer completes, in milliseconds
+        #[serde(default)]
+        eta_ms:            Option<u64>,
+    },
+    /// The job failed
+    Failed {
+        /// Error details
+        error:       String,
+        /// Classification of the error, if known
+        #[serde(default)]
+        error_kind:  Option<MediaJobErrorKind>,
+        /// Time until the job is retried, in milliseconds, or null if it will not be retried
+        #[serde(default)]
+        retry_in_ms: Option<u64>,
+    },
+    /// The job finished successfully
+    Done,
+}
+
+/// Classification of a media job error
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum MediaJobErrorKind {
+    /// The media could not be transferred
+    Transfer,
+    /// The media was transferred, but could not be decoded
+    Decode {
+        /// Details reported by the decoder
+        diagnostics: MediaDiagnostics,
+    },
+    /// The media was transferred, but its checksum does not match the expected one
+    ChecksumMismatch {
+        /// Checksum the media was expected to have
+        expected: MediaChecksum,
+        /// Checksum of the transferred media
+        actual:   MediaChecksum,
+    },
+}
+
+/// Algorithm of a media checksum
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Crc32,
+}
+
+/// Checksum of media content, used to verify transfers end to end
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+pub struct MediaChecksum {
+    pub algorithm: ChecksumAlgorithm,
+    /// Digest as a hexadecimal string, big endian for CRC-32
+    pub digest:    String,
+}
+
+impl MediaChecksum {
+    /// Compute the checksum of media content
+    pub fn compute(algorithm: ChecksumAlgorithm, content: &[u8]) -> Self {
+        let digest = match algorithm {
+            ChecksumAlgorithm::Sha256 => hex::encode(Sha256::digest(content)),
+            ChecksumAlgorithm::Crc32 => format!("{:08x}", crc32fast::hash(content)),
+        };
+
+        Self { algorithm, digest }
+    }
+
+    /// Check that `actual` was computed with the same algorithm and has the same digest, ignoring case
+    pub fn verify(&self, actual: &MediaChecksum) -> Result<(), MediaJobErrorKind> {
+        if self.algorithm == actual.algorithm && self.digest.eq_ignore_ascii_case(&actual.digest) {
+            Ok(())
+        } else {
+            Err(MediaJobErrorKind::ChecksumMismatch { expected: self.clone(),
+                                                      actual:   actual.clone(), })
+        }
+    }
+
+    /// Check that `content` has this checksum
+    pub fn verify_content(&self, content: &[u8]) -> Result<(), MediaJobErrorKind> {
+        self.verify(&Self::compute(self.algorithm, content))
+    }
+}
+
+/// Details about media that could not be decoded cleanly
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct MediaDiagnostics {
+    /// Media object being decoded
+    pub object_id:   AppMediaObjectId,
+    /// Decoder used for the media (for example `flac` or `mp3`)
+    pub decoder:     String,
+    /// Byte offset in the media file where the error was detected, if known
+    #[serde(default)]
+    pub byte_offset: Option<u64>,
+    /// Sample position in the media where the error was detected, if known
+    #[serde(default)]
+    pub sample_pos:  Option<u64>,
+    /// Error reported by the decoder
+    pub message:     String,
+    /// If true, the decoder skipped the corrupt data and continued decoding
+    pub recoverable: bool,
+}
+
 impl MediaJobState {
     pub fn is_finished_ok(&self) -> bool {
         !self.in_progress && self.error.is_none()
     }
+
+    /// Update the state with a job event
+    pub fn apply(&mut self, event: &MediaJobEvent) {
+        match event {
+            MediaJobEvent::Queued => {
+                self.in_progress = true;
+            }
+            MediaJobEvent::Started { total_bytes } => {
+                self.in_progress = true;
+                self.error = None;
+                self.error_kind = None;
+                self.transferred_bytes = 0;
+                self.total_bytes = *total_bytes;
+                self.progress = 0.0;
+            }
+            MediaJobEvent::Progress { transferred_bytes,
+                                      total_bytes,
+                                      bytes_per_second,
+                                      eta_ms, } => {
+                self.in_progress = true;
+                self.transferred_bytes = *transferred_bytes;
+                self.total_bytes = total_bytes.or(self.total_bytes);
+                self.bytes_per_second = *bytes_per_second;
+                self.eta_ms = eta_ms.or_else(|| self.estimate_eta_ms());
+                if let Some(total_bytes) = self.total_bytes.filter(|total_bytes| *total_bytes > 0) {
+                    self.progress = (self.transferred_bytes as f64 / total_bytes as f64).min(1.0);
+                }
+            }
+            MediaJobEvent::Failed { error,
+                                    error_kind,
+                                    retry_in_ms, } => {
+                self.error = Some(error.clone());
+                self.error_kind = error_kind.clone();
+                self.in_progress = retry_in_ms.is_some();
+                self.eta_ms = None;
+                if retry_in_ms.is_some() {
+                    self.retry += 1;
+                }
+            }
+            MediaJobEvent::Done => {
+                self.in_progress = false;
+                self.error = None;
+                self.error_kind = None;
+                self.progress = 1.0;
+                self.eta_ms = None;
+                if let Some(total_bytes) = self.total_bytes {
+                    self.transferred_bytes = total_bytes;
+                }
+            }
+        }
+
+        self.updated_at = now();
+    }
+
+    /// Time until the transfer completes at the current throughput, in milliseconds
+    pub fn estimate_eta_ms(&self) -> Option<u64> {
+        let remaining = self.total_bytes?.saturating_sub(self.transferred_bytes);
+        let bytes_per_second = self.bytes_per_second.filter(|bytes_per_second| *bytes_per_second > 0.0)?;
+
+        Some((remaining as f64 / bytes_per_second * 1000.0).round() as u64)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -44,6 +263,9 @@ pub struct MediaMetadata {
     pub seconds:     f64,
     pub sample_rate: usize,
     pub bytes:       u64,
+    /// Checksum of the media content, if known
+    #[serde(default)]
+    pub checksum:    Option<MediaChecksum>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -57,6 +279,9 @@ pub struct UploadToDomain {
     pub notify_url:  Option<String>,
     // typescript: any
     pub context:     Option<Value>,
+    /// If not null, the domain verifies the transferred media against the checksum
+    #[serde(default)]
+    pub checksum:    Option<MediaChecksum>,
 }
 
 impl UploadToDomain {
@@ -65,7 +290,8 @@ impl UploadToDomain {
                         format:      self.format,
                         seconds:     self.seconds,
                         sample_rate: self.sample_rate,
-                        bytes:       self.bytes, }
+                        bytes:       self.bytes,
+                        checksum:    self.checksum.clone(), }
     }
 }
 
@@ -85,6 +311,9 @@ pub struct ImportToDomain {
     pub seconds:     f64,
     pub sample_rate: usize,
     pub bytes:       u64,
+    /// If not null, the domain verifies the imported media against the checksum
+    #[serde(default)]
+    pub checksum:    Option<MediaChecksum>,
 }
 
 impl ImportToDomain {
@@ -93,7 +322,8 @@ impl ImportToDomain {
                         format:      self.format,
                         seconds:     self.seconds,
                         sample_rate: self.sample_rate,
-                        bytes:       self.bytes, }
+                        bytes:       self.bytes,
+                        checksum:    self.checksum.clone(), }
     }
 }
 
@@ -117,6 +347,58 @@ pub struct MediaUpload {
     pub state:    MediaJobState,
 }
 
+/// Encoding of generated waveform peaks
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PeaksFormat {
+    /// JSON array of interleaved minimum and maximum values per channel
+    Json,
+    /// Binary interleaved minimum and maximum values, as signed 8 bit integers
+    Binary8,
+    /// Binary interleaved minimum and maximum values, as signed 16 bit little endian integers
+    Binary16,
+}
+
+/// Request to generate waveform peaks of a media object, for displaying waveform overviews
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GeneratePeaks {
+    /// Number of samples summarized by each peak
+    pub resolution: u32,
+    /// Encoding of the peaks
+    pub format:     PeaksFormat,
+}
+
+/// Description of generated waveform peaks
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PeaksMetadata {
+    /// Number of channels
+    pub channels:         usize,
+    /// Number of samples summarized by each peak
+    pub samples_per_peak: u32,
+    /// Number of peaks per channel
+    pub length:           usize,
+    /// Sample rate of the media the peaks were generated from
+    pub sample_rate:      usize,
+}
+
+/// Waveform peaks of a media object, and the job generating them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MediaPeaks {
+    pub media_id: AppMediaObjectId,
+    pub generate: GeneratePeaks,
+    /// Progress of generating, in bytes of media read
+    pub state:    MediaJobState,
+    /// Path to the peaks file on the domain, once generated
+    #[serde(default)]
+    pub path:     Option<String>,
+    /// URL the peaks can be downloaded from, once generated
+    #[serde(default)]
+    pub url:      Option<String>,
+    /// Description of the peaks, once generated
+    #[serde(default)]
+    pub metadata: Option<PeaksMetadata>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MediaObject {
     pub id:       AppMediaObjectId,
@@ -124,6 +406,9 @@ pub struct MediaObject {
     pub path:     Option<String>,
     pub download: Option<MediaDownload>,
     pub upload:   Option<MediaUpload>,
+    /// Waveform peaks of the media, if requested
+    #[serde(default)]
+    pub peaks:    Option<MediaPeaks>,
     pub revision: u64,
 }
 
@@ -134,10 +419,56 @@ impl MediaObject {
                path:     None,
                download: None,
                upload:   None,
+               peaks:    None,
                revision: 0, }
     }
 }
 
+/// Limits on media storage used by an app
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StorageQuota {
+    /// Maximum total size of all media objects, in bytes
+    pub max_bytes:   u64,
+    /// Maximum number of media objects
+    pub max_objects: u64,
+}
+
+impl StorageQuota {
+    /// Check that the usage, usually including a pending upload, is within the quota
+    pub fn check(&self, app_id: &AppId, usage: &StorageUsage) -> Result<(), CloudError> {
+        if usage.bytes > self.max_bytes || usage.objects > self.max_objects {
+            Err(CloudError::QuotaExceeded { app_id: app_id.clone(),
+                                            quota:  *self,
+                                            usage:  *usage, })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Media storage used by an app
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct StorageUsage {
+    /// Total size of all media objects, in bytes
+    pub bytes:   u64,
+    /// Number of media objects
+    pub objects: u64,
+}
+
+impl StorageUsage {
+    /// Usage after uploading `bytes`, counting a new object unless an existing one is replaced
+    ///
+    /// Usage saturates instead of overflowing, so a quota check on the result still rejects the upload.
+    pub fn with_upload(self, bytes: u64, replaces: Option<&MediaMetadata>) -> Self {
+        match replaces {
+            Some(existing) => Self { bytes:   self.bytes.saturating_sub(existing.bytes).saturating_add(bytes),
+                                     objects: self.objects, },
+            None => Self { bytes:   self.bytes.saturating_add(bytes),
+                           objects: self.objects.saturating_add(1), },
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct UpdateMediaSession {
     pub media_objects: HashSet<AppMediaObjectId>,
@@ -200,21 +531,139 @@ impl Into<usize> for SampleRate {
     }
 }
 
+/// Mixers monitored during playback, with the gain applied to each as a factor
+pub type MonitoredMixers = HashMap<MixerNodeId, f64>;
+
+/// Monitored mixers as sent by clients, which may still send the single `mixer_id` of older requests
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MonitoredMixersOrId {
+    Mixers(MonitoredMixers),
+    MixerId(MixerNodeId),
+}
+
+impl From<MonitoredMixersOrId> for MonitoredMixers {
+    fn from(mixers: MonitoredMixersOrId) -> Self {
+        match mixers {
+            MonitoredMixersOrId::Mixers(mixers) => mixers,
+            MonitoredMixersOrId::MixerId(mixer_id) => HashMap::from([(mixer_id, 1.0)]),
+        }
+    }
+}
+
+/// Deserialize monitored mixers, mapping a single mixer id to that mixer monitored at unity gain
+pub(crate) fn deserialize_monitored_mixers<'de, D>(deserializer: D) -> Result<MonitoredMixers, D::Error>
+    where D: serde::Deserializer<'de>
+{
+    MonitoredMixersOrId::deserialize(deserializer).map(MonitoredMixers::from)
+}
+
+/// Like [`deserialize_monitored_mixers`], for optional monitored mixers
+pub(crate) fn deserialize_optional_monitored_mixers<'de, D>(deserializer: D) -> Result<Option<MonitoredMixers>, D::Error>
+    where D: serde::Deserializer<'de>
+{
+    Option::<MonitoredMixersOrId>::deserialize(deserializer).map(|mixers| mixers.map(MonitoredMixers::from))
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RequestPlay {
-    pub play_id:     PlayId,
-    pub mixer_id:    MixerNodeId,
+    pub play_id:            PlayId,
+    /// Mixers to monitor, each streamed separately
+    ///
+    /// The single `mixer_id` of older requests is accepted and monitored at unity gain.
+    #[serde(alias = "mixer_id", deserialize_with = "deserialize_monitored_mixers")]
+    pub mixers:             MonitoredMixers,
+    pub segment:            TimeSegment,
+    pub start_at:           f64,
+    pub looping:            bool,
+    /// Region looped while playing, or null to loop the whole segment when `looping` is set
+    #[serde(default)]
+    pub loop_region:        Option<LoopRegion>,
+    /// Range where processing is punched in, or null to process throughout playback
+    #[serde(default)]
+    pub punch:              Option<PunchRange>,
+    pub sample_rate:        SampleRate,
+    pub bit_depth:          PlayBitDepth,
+    /// Rate of playhead position messages sent to attached sockets, or null to report the position in streaming packets only
+    #[serde(default)]
+    pub position_update_hz: Option<f64>,
+    /// Time at which playback should start, used to start multiple tasks in sync, or null to start as soon as possible
+    #[serde(default)]
+    pub scheduled_at:       Option<Timestamp>,
+    /// Click following the tempo map of the task, or null for no click
+    #[serde(default)]
+    pub click:              Option<PlayClick>,
+    /// Timecode reported with the playhead position, or null to not report timecode
+    #[serde(default)]
+    pub timecode:           Option<PlayTimecode>,
+}
+
+impl RequestPlay {
+    /// Milliseconds between playhead position messages, if they are requested at a positive rate
+    pub fn position_update_interval_ms(&self) -> Option<f64> {
+        self.position_update_hz
+            .filter(|hz| hz.is_finite() && *hz > 0.0)
+            .map(|hz| 1000.0 / hz)
+    }
+}
+
+/// Timecode reported while playing, so clients can synchronize other media sources
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub struct PlayTimecode {
+    /// Frame rate of the reported timecode
+    pub format: TimecodeFormat,
+    /// Timecode at the start of the task timeline, or null to use the timecode start of the task (or zero if it has none)
+    #[serde(default)]
+    pub origin: Option<Timecode>,
+}
+
+impl PlayTimecode {
+    /// Timecode at a position on the task timeline (in seconds), given the timecode start of the task
+    pub fn timecode_at(&self, timecode_start: Option<Timecode>, timeline_pos: f64) -> Timecode {
+        let origin = self.origin.or(timecode_start).map(|origin| origin.to_seconds()).unwrap_or_default();
+
+        Timecode::from_seconds(self.format, origin + timeline_pos)
+    }
+}
+
+/// A region of the task timeline played repeatedly
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub struct LoopRegion {
+    /// Looped region of the task timeline
     pub segment:     TimeSegment,
-    pub start_at:    f64,
-    pub looping:     bool,
-    pub sample_rate: SampleRate,
-    pub bit_depth:   PlayBitDepth,
+    /// Number of times the region is played before playback continues past it, or null to loop until stopped
+    #[serde(default)]
+    pub repetitions: Option<u32>,
+}
+
+/// Range of the task timeline where instance processing is heard, outside of it the task plays bypassed
+///
+/// A range with neither bound set punches in for the whole playback.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+pub struct PunchRange {
+    /// Punch-in position in seconds, or null to punch in from the start of playback
+    #[serde(default)]
+    pub punch_in:  Option<f64>,
+    /// Punch-out position in seconds, or null to stay punched in until playback stops
+    #[serde(default)]
+    pub punch_out: Option<f64>,
+}
+
+impl PunchRange {
+    /// True if processing is heard at the position on the task timeline
+    pub fn contains(&self, position: f64) -> bool {
+        self.punch_in.is_none_or(|punch_in| position >= punch_in) && self.punch_out.is_none_or(|punch_out| position < punch_out)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RequestChangeMixer {
-    pub play_id:  PlayId,
-    pub mixer_id: MixerNodeId,
+    pub play_id: PlayId,
+    /// Mixers to monitor, replacing the currently monitored mixers
+    ///
+    /// The single `mixer_id` of older requests is accepted and monitored at unity gain.
+    #[serde(alias = "mixer_id", deserialize_with = "deserialize_monitored_mixers")]
+    pub mixers:  MonitoredMixers,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -228,19 +677,150 @@ pub struct RequestSeek {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RequestStopPlay {
     pub play_id: PlayId,
+    /// Why playback is being stopped
+    #[serde(default)]
+    pub reason:  StopReason,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RequestCancelRender {
     pub render_id: RenderId,
+    /// Why the render is being cancelled
+    #[serde(default)]
+    pub reason:    StopReason,
+}
+
+/// Why a task was stopped (or a render cancelled), including who initiated it
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// Requested by the user of the app
+    #[default]
+    UserRequest,
+    /// The task reservation ended, as scheduled by the cloud
+    ReservationEnded,
+    /// The domain or an instance used by the task entered maintenance
+    Maintenance,
+    /// An error prevented the task from continuing
+    Error {
+        /// Error details
+        message: String,
+    },
+    /// Requested by an administrator
+    Admin,
+    /// Not known, for example when reported by an older peer that did not send a reason
+    Unknown,
+}
+
+/// Why a task was deleted, including who initiated it
+pub type DeleteReason = StopReason;
+
+/// Container of rendered media
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderContainer {
+    #[default]
+    Wav,
+    Flac,
+    #[serde(rename = "wavpack")]
+    WavPack,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, Eq, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderBitDepth {
+    #[serde(rename = "16")]
+    RD16,
+    #[serde(rename = "24")]
+    RD24,
+    /// 32 bit floating point samples
+    #[default]
+    #[serde(rename = "32f")]
+    RD32F,
+}
+
+/// Format of the media file produced by a render
+///
+/// The default renders 32 bit floating point WAV files at the engine sample rate.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, Eq, PartialEq, JsonSchema)]
+pub struct RenderOutputFormat {
+    #[serde(default)]
+    pub container:   RenderContainer,
+    #[serde(default)]
+    pub bit_depth:   RenderBitDepth,
+    /// Sample rate to convert the render to, or null to keep the engine sample rate
+    #[serde(default)]
+    pub sample_rate: Option<SampleRate>,
+    /// Dither when reducing to an integer bit depth
+    #[serde(default)]
+    pub dither:      bool,
+}
+
+impl RenderOutputFormat {
+    /// Check that the container supports the bit depth, FLAC does not store floating point samples
+    pub fn validate(&self) -> Result<(), CloudError> {
+        if matches!((self.container, self.bit_depth), (RenderContainer::Flac, RenderBitDepth::RD32F)) {
+            return Err(CloudError::UnsupportedRenderOutputFormat { format: *self });
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RequestRender {
-    pub render_id: RenderId,
-    pub mixer_id:  MixerNodeId,
-    pub segment:   TimeSegment,
-    pub object_id: AppMediaObjectId,
+    pub render_id:       RenderId,
+    pub mixer_id:        MixerNodeId,
+    pub segment:         TimeSegment,
+    pub object_id:       AppMediaObjectId,
+    /// Maximum time the render may take, in milliseconds
+    ///
+    /// When exceeded, the domain cancels the render, emits a `RenderTimedOut` task event and
+    /// transitions the task to stopped, releasing the instances held by the render.
+    #[serde(default)]
+    pub max_duration_ms: Option<u64>,
+    /// Time at which rendering should start, or null to start as soon as possible
+    #[serde(default)]
+    pub scheduled_at:    Option<Timestamp>,
+    /// Format of the rendered media file
+    #[serde(default)]
+    pub output_format:   RenderOutputFormat,
+}
+
+impl RequestRender {
+    /// Time by which a render started at `started_at` must finish, if the render is limited
+    ///
+    /// Maximum durations reaching past the latest representable time do not limit the render.
+    pub fn deadline(&self, started_at: Timestamp) -> Option<Timestamp> {
+        let max_duration_ms = i64::try_from(self.max_duration_ms?).ok()?;
+
+        started_at.checked_add_signed(chrono::Duration::milliseconds(max_duration_ms))
+    }
+
+    /// True if a render started at `started_at` has exceeded its maximum duration
+    pub fn is_timed_out(&self, started_at: Timestamp) -> bool {
+        self.deadline(started_at).is_some_and(|deadline| now() > deadline)
+    }
+}
+
+/// Loudness and level analysis of a rendered media file
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+pub struct RenderAnalysis {
+    /// Integrated loudness in LUFS, as defined by ITU-R BS.1770
+    pub integrated_lufs: f64,
+    /// Maximum true peak level in dBTP
+    pub true_peak_dbtp:  f64,
+    /// RMS level in dBFS
+    pub rms_dbfs:        f64,
+    /// Duration of the rendered media, in seconds
+    pub duration:        f64,
+}
+
+impl RenderAnalysis {
+    /// True if the integrated loudness is within `tolerance` LU of `target_lufs` and the true peak does not exceed `max_true_peak_dbtp`
+    pub fn complies_with(&self, target_lufs: f64, tolerance: f64, max_true_peak_dbtp: f64) -> bool {
+        (self.integrated_lufs - target_lufs).abs() <= tolerance && self.true_peak_dbtp <= max_true_peak_dbtp
+    }
 }
 
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug, From, Into, Hash, Display, Constructor)]
@@ -250,3 +830,34 @@ pub struct PlayId(u64);
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug, From, Into, Hash, Display, Constructor)]
 #[repr(transparent)]
 pub struct RenderId(u64);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CompressedAudio {
+    pub play_id:      PlayId,
+    /// Monitored mixer the audio was taken from
+    pub mixer_id:     MixerNodeId,
+    pub timeline_pos: f64,
+    /// Timecode at `timeline_pos`, if the play request asked for timecode
+    #[serde(default)]
+    pub timecode:     Option<Timecode>,
+    pub stream_pos:   u64,
+    pub buffer:       bytes::Bytes,
+    pub num_samples:  usize,
+    pub last:         bool,
+    /// CRC-32 checksum of the buffer, if computed by the sender
+    #[serde(default)]
+    pub crc32:        Option<u32>,
+}
+
+impl CompressedAudio {
+    /// Compute and store the checksum of the buffer
+    pub fn with_checksum(mut self) -> Self {
+        self.crc32 = Some(crc32fast::hash(&self.buffer));
+        self
+    }
+
+    /// True if the buffer matches the checksum, or if there is no checksum
+    pub fn is_checksum_valid(&self) -> bool {
+        self.crc32.is_none_or(|crc32| crc32 == crc32fast::hash(&self.buffer))
+    }
+}
@@ -1,24 +1,31 @@
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-pub use change::*;
+pub use audit::*;
 pub use change::*;
 pub use error::*;
 pub use instance::*;
 pub use media::*;
 pub use model::*;
 pub use newtypes::*;
+pub use payload::*;
 pub use task::*;
+pub use tempo::*;
 pub use time::*;
+pub use timecode::*;
 
+pub mod audit;
 pub mod change;
 pub mod error;
 pub mod instance;
 pub mod media;
 pub mod model;
 pub mod newtypes;
+pub mod payload;
 pub mod task;
+pub mod tempo;
 pub mod time;
+pub mod timecode;
 
 /// A request that has an associated response type
 pub trait Request: Serialize {
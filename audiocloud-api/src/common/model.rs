@@ -4,7 +4,9 @@ use anyhow::anyhow;
 use derive_more::{Display, IsVariant, Unwrap};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+use crate::common::task::{AutomationInterpolation, AutomationLane, ConnectionKind};
 use crate::common::{FilterId, ParameterId, ReportId};
 
 #[derive(Serialize, Deserialize, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Debug, IsVariant, JsonSchema)]
@@ -19,6 +21,8 @@ pub enum ModelValueUnit {
     Hertz,
     #[serde(rename = "oct")]
     Octaves,
+    #[serde(rename = "ms")]
+    Milliseconds,
     #[serde(rename = "toggle")]
     Toggle,
     #[serde(rename = "amps")]
@@ -33,6 +37,65 @@ impl Default for ModelValueUnit {
     }
 }
 
+impl ModelValueUnit {
+    /// Taper of a control for values of this unit: logarithmic for frequencies, linear otherwise (decibels are already logarithmic)
+    pub fn taper(self) -> ModelValueTaper {
+        match self {
+            ModelValueUnit::Hertz => ModelValueTaper::Logarithmic,
+            _ => ModelValueTaper::Linear,
+        }
+    }
+
+    /// Smallest and largest numeric value allowed by the options
+    ///
+    /// Without numeric options, percentages range from 0 to 100 and toggles from 0 to 1, other units have no bounds.
+    pub fn bounds(self, options: &[ModelValueOption]) -> Option<(f64, f64)> {
+        let bounds = options.iter()
+                            .flat_map(|option| match option {
+                                ModelValueOption::Single(value) => [value.to_f64(), None],
+                                ModelValueOption::Range(min, max) if min.is_number() && max.is_number() => [min.to_f64(), max.to_f64()],
+                                ModelValueOption::Range(..) => [None, None],
+                            })
+                            .flatten()
+                            .fold(None, |bounds: Option<(f64, f64)>, value| match bounds {
+                                Some((min, max)) => Some((min.min(value), max.max(value))),
+                                None => Some((value, value)),
+                            });
+
+        bounds.or(match self {
+                  ModelValueUnit::Percent => Some((0.0, 100.0)),
+                  ModelValueUnit::Toggle => Some((0.0, 1.0)),
+                  _ => None,
+              })
+    }
+
+    /// Position between 0 and 1 of a value within the [bounds](Self::bounds) of the options, following the [taper](Self::taper)
+    ///
+    /// Returns null if the options have no numeric bounds.
+    pub fn to_normalized(self, value: f64, options: &[ModelValueOption]) -> Option<f64> {
+        let (min, max) = self.bounds(options)?;
+
+        Some(self.taper().position_of(value, min, max))
+    }
+
+    /// Value at a position between 0 and 1 within the [bounds](Self::bounds) of the options, the inverse of [`Self::to_normalized`]
+    pub fn from_normalized(self, normalized: f64, options: &[ModelValueOption]) -> Option<f64> {
+        let (min, max) = self.bounds(options)?;
+
+        Some(self.taper().value_at(normalized, min, max))
+    }
+}
+
+/// Linear gain factor of a level in decibels
+pub fn db_to_gain(db: f64) -> f64 {
+    10f64.powf(db / 20.0)
+}
+
+/// Level in decibels of a linear gain factor, negative infinity for silence
+pub fn gain_to_db(gain: f64) -> f64 {
+    20.0 * gain.log10()
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, PartialOrd, IsVariant, Unwrap, JsonSchema)]
 #[serde(untagged)]
 pub enum ModelValueOption {
@@ -53,6 +116,39 @@ impl ModelValueOption {
         Self::num_range(min, 0f64)
     }
 
+    /// True if the value is of the same type as this option (numbers for ranges)
+    pub fn is_same_type(&self, value: &ModelValue) -> bool {
+        match self {
+            ModelValueOption::Single(option) => std::mem::discriminant(option) == std::mem::discriminant(value),
+            ModelValueOption::Range(..) => value.is_number(),
+        }
+    }
+
+    /// True if the value is this option, or within this (numeric) range
+    pub fn contains(&self, value: &ModelValue) -> bool {
+        match (self, value) {
+            (ModelValueOption::Single(option), value) => option == value,
+            (ModelValueOption::Range(ModelValue::Number(min), ModelValue::Number(max)), ModelValue::Number(value)) => {
+                value >= min && value <= max
+            }
+            _ => false,
+        }
+    }
+
+    /// Value of this option closest to a number, with its distance, or null for string options
+    ///
+    /// Ranges clamp the number, toggles are compared as 0 and 1.
+    pub fn nearest(&self, input: f64) -> Option<(ModelValue, f64)> {
+        match self {
+            ModelValueOption::Single(option) => option.to_f64().map(|value| (option.clone(), (value - input).abs())),
+            ModelValueOption::Range(ModelValue::Number(min), ModelValue::Number(max)) => {
+                let value = input.clamp(min.min(*max), max.max(*min));
+                Some((ModelValue::Number(value), (value - input).abs()))
+            }
+            ModelValueOption::Range(..) => None,
+        }
+    }
+
     pub fn get_simple_type(&self) -> anyhow::Result<SimpleModelValueType> {
         match self {
             ModelValueOption::Single(value) => Ok(value.get_simple_type()),
@@ -194,14 +290,17 @@ pub type ModelReports = HashMap<ReportId, ModelReport>;
 #[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, JsonSchema)]
 pub struct Model {
     #[serde(default)]
-    pub resources:    HashMap<ResourceId, f64>,
-    pub inputs:       ModelInputs,
-    pub outputs:      ModelOutputs,
-    pub parameters:   ModelParameters,
-    pub reports:      ModelReports,
-    pub media:        bool,
+    pub resources:          HashMap<ResourceId, f64>,
+    pub inputs:             ModelInputs,
+    pub outputs:            ModelOutputs,
+    pub parameters:         ModelParameters,
+    pub reports:            ModelReports,
+    pub media:              bool,
     #[serde(default)]
-    pub capabilities: HashSet<ModelCapability>,
+    pub capabilities:       HashSet<ModelCapability>,
+    /// Nominal processing latency of fixed instances of the model in milliseconds, including converters, or null if unknown
+    #[serde(default)]
+    pub nominal_latency_ms: Option<f64>,
 }
 
 impl Model {
@@ -215,6 +314,219 @@ impl Model {
             .sum()
     }
 
+    /// Default values of all parameters that declare one
+    pub fn default_parameters(&self) -> ParameterValues {
+        self.parameters
+            .iter()
+            .filter_map(|(parameter_id, parameter)| {
+                let default = parameter.default.as_ref()?;
+                let channels = parameter.scope.len(self);
+
+                Some((parameter_id.clone(), MultiChannelValue(vec![Some(default.clone()); channels])))
+            })
+            .collect()
+    }
+
+    /// Check a parameter value against the scope and value options of the parameter
+    ///
+    /// Channels without a value are not checked, parameters without options accept any value. Channels of linked
+    /// parameters that have a value must all have the same value, a partial write is expanded to every channel in scope
+    /// with [`Model::expand_linked_value`].
+    pub fn validate_parameter(&self, id: &ParameterId, value: &MultiChannelValue) -> Result<(), ModelValidationError> {
+        let parameter = self.parameters
+                            .get(id)
+                            .ok_or_else(|| ModelValidationError::UnknownParameter { parameter_id: id.clone() })?;
+
+        let max = parameter.scope.len(self);
+        if value.0.len() > max {
+            return Err(ModelValidationError::TooManyChannels { parameter_id: id.clone(),
+                                                               channels: value.0.len(),
+                                                               max });
+        }
+
+        if parameter.linked {
+            if let Some(first) = value.0.iter().flatten().next() {
+                if let Some(channel) = value.0.iter().position(|value| matches!(value, Some(value) if value != first)) {
+                    return Err(ModelValidationError::LinkedChannelsDiffer { parameter_id: id.clone(),
+                                                                            channel });
+                }
+            }
+        }
+
+        if parameter.values.is_empty() {
+            return Ok(());
+        }
+
+        for (channel, value) in value.0
+                                     .iter()
+                                     .enumerate()
+                                     .filter_map(|(channel, value)| Some((channel, value.as_ref()?)))
+        {
+            if !parameter.values.iter().any(|option| option.is_same_type(value)) {
+                return Err(ModelValidationError::WrongType { parameter_id: id.clone(),
+                                                             channel,
+                                                             value: value.clone() });
+            }
+
+            if !parameter.values.iter().any(|option| option.contains(value)) {
+                return Err(ModelValidationError::OutOfRange { parameter_id: id.clone(),
+                                                              channel,
+                                                              value: value.clone() });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Expand a write to some channels of a linked parameter to every channel in scope
+    ///
+    /// Linked channels are always written together, so a value written to any channel is written to all of them. Values
+    /// of parameters that are not linked are returned unchanged.
+    pub fn expand_linked_value(&self, id: &ParameterId, value: &MultiChannelValue) -> MultiChannelValue {
+        match (self.parameters.get(id), value.0.iter().flatten().next()) {
+            (Some(parameter), Some(first)) if parameter.linked => MultiChannelValue(vec![Some(first.clone()); parameter.scope.len(self)]),
+            _ => value.clone(),
+        }
+    }
+
+    /// Check automation of a parameter, with values applying to all channels of the parameter
+    ///
+    /// Points must be well formed and their values valid for the parameter. Linear interpolation is only
+    /// supported for parameters accepting a range of values, and never for toggles. Parameters with bool options are
+    /// automated with 0 for false and 1 for true.
+    pub fn validate_automation(&self, id: &ParameterId, lane: &AutomationLane) -> Result<(), ModelValidationError> {
+        let parameter = self.parameters
+                            .get(id)
+                            .ok_or_else(|| ModelValidationError::UnknownParameter { parameter_id: id.clone() })?;
+
+        if !lane.is_well_formed() {
+            return Err(ModelValidationError::AutomationMalformed { parameter_id: id.clone() });
+        }
+
+        let continuous = parameter.values.is_empty() || parameter.values.iter().any(|option| matches!(option, ModelValueOption::Range(..)));
+        if lane.interpolation == AutomationInterpolation::Linear && (parameter.unit == ModelValueUnit::Toggle || !continuous) {
+            return Err(ModelValidationError::InterpolationNotSupported { parameter_id:  id.clone(),
+                                                                         unit:          parameter.unit,
+                                                                         interpolation: lane.interpolation, });
+        }
+
+        let bools = !parameter.values.is_empty()
+                    && parameter.values
+                                .iter()
+                                .all(|option| matches!(option, ModelValueOption::Single(ModelValue::Bool(_))));
+
+        lane.values().try_for_each(|value| {
+                         let value = if !bools {
+                             ModelValue::Number(value)
+                         } else if value == 0.0 || value == 1.0 {
+                             ModelValue::Bool(value == 1.0)
+                         } else {
+                             return Err(ModelValidationError::OutOfRange { parameter_id: id.clone(),
+                                                                           channel:      0,
+                                                                           value:        ModelValue::Number(value), });
+                         };
+
+                         self.validate_parameter(id, &MultiChannelValue::all_channels(self, id, value)?)
+                     })
+    }
+
+    /// Parameters in the order a panel shows them: by group (ungrouped first), order within the group and id
+    pub fn parameters_in_ui_order(&self) -> Vec<(&ParameterId, &ModelParameter)> {
+        let mut parameters = self.parameters.iter().collect::<Vec<_>>();
+        parameters.sort_by(|(a_id, a), (b_id, b)| ui_sort_key(*a_id, a.ui.as_ref()).cmp(&ui_sort_key(*b_id, b.ui.as_ref())));
+        parameters
+    }
+
+    /// Reports in the order a panel shows them, see [`Model::parameters_in_ui_order`]
+    pub fn reports_in_ui_order(&self) -> Vec<(&ReportId, &ModelReport)> {
+        let mut reports = self.reports.iter().collect::<Vec<_>>();
+        reports.sort_by(|(a_id, a), (b_id, b)| ui_sort_key(*a_id, a.ui.as_ref()).cmp(&ui_sort_key(*b_id, b.ui.as_ref())));
+        reports
+    }
+
+    /// Check a report value against the scope and value options of the report, like [`Model::validate_parameter`]
+    pub fn validate_report(&self, id: &ReportId, value: &MultiChannelValue) -> Result<(), ModelValidationError> {
+        let report = self.reports
+                         .get(id)
+                         .ok_or_else(|| ModelValidationError::UnknownReport { report_id: id.clone() })?;
+
+        let max = report.scope.len(self);
+        if value.0.len() > max {
+            return Err(ModelValidationError::ReportTooManyChannels { report_id: id.clone(),
+                                                                     channels: value.0.len(),
+                                                                     max });
+        }
+
+        if report.values.is_empty() {
+            return Ok(());
+        }
+
+        for (channel, value) in value.0
+                                     .iter()
+                                     .enumerate()
+                                     .filter_map(|(channel, value)| Some((channel, value.as_ref()?)))
+        {
+            if !report.values.iter().any(|option| option.is_same_type(value)) {
+                return Err(ModelValidationError::ReportWrongType { report_id: id.clone(),
+                                                                   channel,
+                                                                   value: value.clone() });
+            }
+
+            if !report.values.iter().any(|option| option.contains(value)) {
+                return Err(ModelValidationError::ReportOutOfRange { report_id: id.clone(),
+                                                                    channel,
+                                                                    value: value.clone() });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Make a report value valid, see [`ModelReport::normalize_value`]
+    ///
+    /// Channels beyond the scope of the report are dropped, values of the wrong type are cleared.
+    pub fn clamp_report(&self, id: &ReportId, value: &MultiChannelValue) -> Result<MultiChannelValue, ModelValidationError> {
+        let report = self.reports
+                         .get(id)
+                         .ok_or_else(|| ModelValidationError::UnknownReport { report_id: id.clone() })?;
+
+        Ok(MultiChannelValue(value.0
+                                  .iter()
+                                  .take(report.scope.len(self))
+                                  .map(|value| value.as_ref().and_then(|value| report.normalize_value(value)))
+                                  .collect()))
+    }
+
+    /// Snap numeric and toggle values to the nearest allowed value of their parameter, see [`ModelParameter::nearest_value`]
+    ///
+    /// String values and channels without a value are kept. All parameters must be declared by the model.
+    pub fn snap_parameters(&self, values: &ParameterValues) -> Result<ParameterValues, ModelValidationError> {
+        values.iter()
+              .map(|(id, value)| {
+                  let parameter = self.parameters
+                                      .get(id)
+                                      .ok_or_else(|| ModelValidationError::UnknownParameter { parameter_id: id.clone() })?;
+
+                  let snapped = value.0
+                                     .iter()
+                                     .map(|value| {
+                                         value.as_ref().map(|value| match value.to_f64() {
+                                                           Some(input) => parameter.nearest_value(input),
+                                                           None => value.clone(),
+                                                       })
+                                     })
+                                     .collect();
+
+                  Ok((id.clone(), MultiChannelValue(snapped)))
+              })
+              .collect()
+    }
+
+    /// Check all parameter values, see [`Model::validate_parameter`]
+    pub fn validate_parameters(&self, values: &ParameterValues) -> Result<(), ModelValidationError> {
+        values.iter().try_for_each(|(id, value)| self.validate_parameter(id, value))
+    }
+
     pub fn get_audio_output_channel_count(&self) -> usize {
         self.outputs
             .iter()
@@ -224,6 +536,42 @@ impl Model {
             })
             .sum()
     }
+
+    pub fn get_midi_input_count(&self) -> usize {
+        self.inputs.iter().filter(|input| input.is_midi()).count()
+    }
+
+    pub fn get_midi_output_count(&self) -> usize {
+        self.outputs.iter().filter(|output| output.is_midi()).count()
+    }
+
+    pub fn get_sidechain_input_count(&self) -> usize {
+        self.inputs.iter().filter(|input| input.is_sidechain()).count()
+    }
+
+    /// Number of power channels, taken from the widest scope of the parameters with the power role
+    pub fn get_power_channel_count(&self) -> usize {
+        self.parameters
+            .values()
+            .filter(|parameter| parameter.role.is_power())
+            .map(|parameter| parameter.scope.len(self))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Number of pads the model exposes for connections of the kind, on the output or input side
+    ///
+    /// Sidechain connections are only received, so models never expose sidechain outputs.
+    pub fn get_connection_pad_count(&self, kind: ConnectionKind, output: bool) -> usize {
+        match kind {
+            ConnectionKind::Audio if output => self.get_audio_output_channel_count(),
+            ConnectionKind::Audio => self.get_audio_input_channel_count(),
+            ConnectionKind::Midi if output => self.get_midi_output_count(),
+            ConnectionKind::Midi => self.get_midi_input_count(),
+            ConnectionKind::Sidechain if output => 0,
+            ConnectionKind::Sidechain => self.get_sidechain_input_count(),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, JsonSchema)]
@@ -233,6 +581,12 @@ pub enum ModelCapability {
     AudioRouter,
     AudioMixer,
     DigitalInputOutput,
+    /// Can identify itself on request, for example by blinking the front panel
+    Identify,
+    /// Can run a self-test and report the outcome per subsystem
+    SelfTest,
+    /// Can run a calibration routine, for example to trim channel levels or relay offsets
+    Calibration,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash, JsonSchema)]
@@ -342,11 +696,37 @@ pub enum DynamicsReportRole {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, PartialOrd, JsonSchema)]
 pub struct ModelParameter {
-    pub scope:  ModelElementScope,
+    pub scope:   ModelElementScope,
     #[serde(default)]
-    pub unit:   ModelValueUnit,
-    pub role:   ModelParameterRole,
-    pub values: Vec<ModelValueOption>,
+    pub unit:    ModelValueUnit,
+    pub role:    ModelParameterRole,
+    pub values:  Vec<ModelValueOption>,
+    /// Default value, applied to every channel in the scope of the parameter
+    #[serde(default)]
+    pub default: Option<ModelValue>,
+    /// If true, the channels are linked (for example a stereo linked control) and are always written together
+    #[serde(default)]
+    pub linked:  bool,
+    /// Hints for rendering the parameter as a control
+    #[serde(default)]
+    pub ui:      Option<ModelUiHints>,
+}
+
+impl ModelParameter {
+    /// Allowed value closest to a number, for example the nearest notch of a stepped selector
+    ///
+    /// Ties go to the option listed first. Parameters without numeric options accept the number as is.
+    pub fn nearest_value(&self, input: f64) -> ModelValue {
+        nearest_option_value(&self.values, input)
+    }
+}
+
+fn nearest_option_value(options: &[ModelValueOption], input: f64) -> ModelValue {
+    options.iter()
+           .filter_map(|option| option.nearest(input))
+           .min_by(|(_, a), (_, b)| a.total_cmp(b))
+           .map(|(value, _)| value)
+           .unwrap_or(ModelValue::Number(input))
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, PartialOrd, JsonSchema)]
@@ -371,15 +751,110 @@ impl ModelElementScope {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ModelReport {
-    pub scope:    ModelElementScope,
+    pub scope:     ModelElementScope,
+    #[serde(default)]
+    pub unit:      ModelValueUnit,
+    pub role:      ModelReportRole,
+    pub values:    Vec<ModelValueOption>,
+    #[serde(default)]
+    pub public:    bool,
+    #[serde(default)]
+    pub volatile:  bool,
+    /// Hints for rendering the report as a meter or indicator
+    #[serde(default)]
+    pub ui:        Option<ModelUiHints>,
+    /// Number of decimal places numeric values are rounded to before broadcasting, or null to keep full precision
+    ///
+    /// At most [`MAX_REPORT_PRECISION`] decimal places are used.
+    #[serde(default)]
+    pub precision: Option<u32>,
+}
+
+/// Largest number of decimal places report values are rounded to, beyond it `f64` has no precision left
+pub const MAX_REPORT_PRECISION: u32 = 15;
+
+impl ModelReport {
+    /// Value rounded to the precision and moved to the nearest allowed value, or null if it is of the wrong type
+    pub fn normalize_value(&self, value: &ModelValue) -> Option<ModelValue> {
+        let value = match (value, self.precision) {
+            (ModelValue::Number(number), Some(precision)) => {
+                let scale = 10f64.powi(precision.min(MAX_REPORT_PRECISION) as i32);
+                let rounded = (number * scale).round() / scale;
+                ModelValue::Number(if rounded.is_finite() { rounded } else { *number })
+            }
+            (value, _) => value.clone(),
+        };
+
+        if self.values.is_empty() || self.values.iter().any(|option| option.contains(&value)) {
+            return Some(value);
+        }
+
+        if !self.values.iter().any(|option| option.is_same_type(&value)) {
+            return None;
+        }
+
+        value.to_f64().map(|input| nearest_option_value(&self.values, input))
+    }
+}
+
+/// Hints for front-ends rendering a panel of parameters and reports from the model alone
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, PartialOrd, JsonSchema)]
+pub struct ModelUiHints {
+    /// Name shown instead of the parameter or report id
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Section of the panel, for example "Low band"
+    #[serde(default)]
+    pub group:        Option<String>,
+    /// Position within the group, lower first
     #[serde(default)]
-    pub unit:     ModelValueUnit,
-    pub role:     ModelReportRole,
-    pub values:   Vec<ModelValueOption>,
+    pub order:        i32,
+    /// Suffix shown after values, if not the one implied by the unit
     #[serde(default)]
-    pub public:   bool,
+    pub unit_suffix:  Option<String>,
+    /// How the position of a knob or fader maps to a value
     #[serde(default)]
-    pub volatile: bool,
+    pub taper:        ModelValueTaper,
+}
+
+/// Mapping of a control position (from 0 to 1) to a value within a range
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelValueTaper {
+    /// Values change evenly with the position
+    #[default]
+    Linear,
+    /// Values change by equal ratios, for example frequencies; linear if the range is not positive
+    Logarithmic,
+}
+
+impl ModelValueTaper {
+    /// Value at a position between 0 and 1 of a control ranging from `min` to `max`
+    pub fn value_at(self, position: f64, min: f64, max: f64) -> f64 {
+        let position = position.clamp(0.0, 1.0);
+        match self {
+            Self::Logarithmic if min > 0.0 && max > 0.0 => min * (max / min).powf(position),
+            _ => min + (max - min) * position,
+        }
+    }
+
+    /// Position between 0 and 1 of a value on a control ranging from `min` to `max`
+    pub fn position_of(self, value: f64, min: f64, max: f64) -> f64 {
+        let position = match self {
+            Self::Logarithmic if min > 0.0 && max > 0.0 && value > 0.0 => (value / min).ln() / (max / min).ln(),
+            _ => (value - min) / (max - min),
+        };
+
+        if position.is_finite() {
+            position.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+fn ui_sort_key<'a, K>(id: &'a K, ui: Option<&'a ModelUiHints>) -> (Option<&'a str>, i32, &'a K) {
+    (ui.and_then(|ui| ui.group.as_deref()), ui.map(|ui| ui.order).unwrap_or_default(), id)
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, IsVariant, JsonSchema)]
@@ -391,7 +866,7 @@ pub enum ControlChannels {
     Generic,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Hash, Display, JsonSchema)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash, Display, JsonSchema)]
 pub enum ResourceId {
     // in GiB
     #[serde(rename = "ram")]
@@ -507,3 +982,298 @@ pub fn toggle_off<T>() -> ToggleOr<T> {
 pub fn toggle_value<T>(value: T) -> ToggleOr<T> {
     ToggleOr::Value(value)
 }
+
+/// Values of a parameter, one per channel (null leaves the value on the channel unchanged)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(transparent)]
+pub struct MultiChannelValue(pub Vec<Option<ModelValue>>);
+
+impl MultiChannelValue {
+    /// The same value on the left and right channel, for stereo linked parameters
+    pub fn stereo(value: ModelValue) -> Self {
+        Self(vec![Some(value.clone()), Some(value)])
+    }
+
+    /// The same value on every channel in the scope of a parameter of the model
+    pub fn all_channels(model: &Model, parameter_id: &ParameterId, value: ModelValue) -> Result<Self, ModelValidationError> {
+        let parameter = model.parameters
+                             .get(parameter_id)
+                             .ok_or_else(|| ModelValidationError::UnknownParameter { parameter_id: parameter_id.clone(), })?;
+
+        Ok(Self(vec![Some(value); parameter.scope.len(model)]))
+    }
+}
+
+/// Parameter values of an instance, keyed by parameter id
+pub type ParameterValues = HashMap<ParameterId, MultiChannelValue>;
+
+/// Conversion between a typed single channel value and a [`ModelValue`]
+pub trait ModelValueConvert: Sized {
+    fn to_model_value(&self) -> ModelValue;
+
+    fn from_model_value(value: &ModelValue) -> Option<Self>;
+}
+
+impl ModelValueConvert for f64 {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Number(*self)
+    }
+
+    fn from_model_value(value: &ModelValue) -> Option<Self> {
+        value.to_f64()
+    }
+}
+
+impl ModelValueConvert for i64 {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Number(*self as f64)
+    }
+
+    fn from_model_value(value: &ModelValue) -> Option<Self> {
+        match value {
+            ModelValue::Number(number) if number.fract() == 0.0 => Some(*number as i64),
+            _ => None,
+        }
+    }
+}
+
+impl ModelValueConvert for u64 {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Number(*self as f64)
+    }
+
+    fn from_model_value(value: &ModelValue) -> Option<Self> {
+        match value {
+            ModelValue::Number(number) if number.fract() == 0.0 && *number >= 0.0 => Some(*number as u64),
+            _ => None,
+        }
+    }
+}
+
+impl ModelValueConvert for bool {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::Bool(*self)
+    }
+
+    fn from_model_value(value: &ModelValue) -> Option<Self> {
+        value.to_bool()
+    }
+}
+
+impl ModelValueConvert for String {
+    fn to_model_value(&self) -> ModelValue {
+        ModelValue::String(self.clone())
+    }
+
+    fn from_model_value(value: &ModelValue) -> Option<Self> {
+        match value {
+            ModelValue::String(string) => Some(string.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl ModelValueConvert for serde_json::Value {
+    fn to_model_value(&self) -> ModelValue {
+        serde_json::from_value(self.clone()).unwrap_or_else(|_| ModelValue::String(self.to_string()))
+    }
+
+    fn from_model_value(value: &ModelValue) -> Option<Self> {
+        serde_json::to_value(value).ok()
+    }
+}
+
+impl<T> ModelValueConvert for ToggleOr<T> where T: ModelValueConvert
+{
+    fn to_model_value(&self) -> ModelValue {
+        match self {
+            Self::Toggle(toggle) => ModelValue::Bool(*toggle),
+            Self::Value(value) => value.to_model_value(),
+        }
+    }
+
+    fn from_model_value(value: &ModelValue) -> Option<Self> {
+        match value {
+            ModelValue::Bool(toggle) => Some(Self::Toggle(*toggle)),
+            value => T::from_model_value(value).map(Self::Value),
+        }
+    }
+}
+
+/// Conversion between a typed parameter value and its values per channel
+pub trait MultiChannelConvert: Sized {
+    fn to_multi_channel(&self) -> MultiChannelValue;
+
+    fn from_multi_channel(value: &MultiChannelValue) -> Option<Self>;
+}
+
+impl<T> MultiChannelConvert for T where T: ModelValueConvert
+{
+    fn to_multi_channel(&self) -> MultiChannelValue {
+        MultiChannelValue(vec![Some(self.to_model_value())])
+    }
+
+    fn from_multi_channel(value: &MultiChannelValue) -> Option<Self> {
+        match value.0.as_slice() {
+            [Some(value)] => T::from_model_value(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T> MultiChannelConvert for Stereo<T> where T: ModelValueConvert
+{
+    fn to_multi_channel(&self) -> MultiChannelValue {
+        MultiChannelValue(vec![Some(self.left.to_model_value()), Some(self.right.to_model_value())])
+    }
+
+    fn from_multi_channel(value: &MultiChannelValue) -> Option<Self> {
+        match value.0.as_slice() {
+            [Some(left), Some(right)] => Some(Self { left:  T::from_model_value(left)?,
+                                                     right: T::from_model_value(right)?, }),
+            _ => None,
+        }
+    }
+}
+
+impl<T> MultiChannelConvert for Vec<T> where T: ModelValueConvert
+{
+    fn to_multi_channel(&self) -> MultiChannelValue {
+        MultiChannelValue(self.iter().map(|value| Some(value.to_model_value())).collect())
+    }
+
+    fn from_multi_channel(value: &MultiChannelValue) -> Option<Self> {
+        value.0.iter().map(|value| value.as_ref().and_then(T::from_model_value)).collect()
+    }
+}
+
+impl MultiChannelConvert for () {
+    fn to_multi_channel(&self) -> MultiChannelValue {
+        MultiChannelValue::default()
+    }
+
+    fn from_multi_channel(value: &MultiChannelValue) -> Option<Self> {
+        value.0.is_empty().then_some(())
+    }
+}
+
+/// Conversion of typed (generated) model parameters to parameter values
+pub trait ToParameterMap {
+    fn to_parameter_map(&self) -> ParameterValues;
+}
+
+/// Conversion of parameter values to typed (generated) model parameters
+pub trait FromParameterMap: Sized {
+    fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError>;
+}
+
+/// Partial parameter values that compose, implemented by typed (generated) model parameters
+pub trait Patch: Sized {
+    /// Combine with a later patch, values set in `other` take precedence
+    fn merge(self, other: Self) -> Self;
+
+    /// True if no parameter value is set
+    fn is_empty(&self) -> bool;
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Error, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ParameterValuesError {
+    #[error("Parameter {parameter_id} is missing")]
+    Missing { parameter_id: ParameterId },
+
+    #[error("Parameter {parameter_id} has values of the wrong type or channel count")]
+    Malformed { parameter_id: ParameterId },
+}
+
+/// Parameter values that do not conform to the parameter definitions of a model
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Error, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ModelValidationError {
+    #[error("Parameter {parameter_id} is not declared by the model")]
+    UnknownParameter { parameter_id: ParameterId },
+
+    #[error("Parameter {parameter_id} has {channels} channels, but its scope has only {max}")]
+    TooManyChannels {
+        parameter_id: ParameterId,
+        channels:     usize,
+        max:          usize,
+    },
+
+    #[error("Parameter {parameter_id} value {value:?} on channel {channel} is of the wrong type")]
+    WrongType {
+        parameter_id: ParameterId,
+        channel:      usize,
+        value:        ModelValue,
+    },
+
+    #[error("Parameter {parameter_id} value {value:?} on channel {channel} is not one of the allowed values")]
+    OutOfRange {
+        parameter_id: ParameterId,
+        channel:      usize,
+        value:        ModelValue,
+    },
+
+    #[error("Parameter {parameter_id} is linked, but channel {channel} does not have the same value as the first written channel")]
+    LinkedChannelsDiffer { parameter_id: ParameterId, channel: usize },
+
+    #[error("Report {report_id} is not declared by the model")]
+    UnknownReport { report_id: ReportId },
+
+    #[error("Report {report_id} has {channels} channels, but its scope has only {max}")]
+    ReportTooManyChannels {
+        report_id: ReportId,
+        channels:  usize,
+        max:       usize,
+    },
+
+    #[error("Report {report_id} value {value:?} on channel {channel} is of the wrong type")]
+    ReportWrongType {
+        report_id: ReportId,
+        channel:   usize,
+        value:     ModelValue,
+    },
+
+    #[error("Report {report_id} value {value:?} on channel {channel} is not one of the allowed values")]
+    ReportOutOfRange {
+        report_id: ReportId,
+        channel:   usize,
+        value:     ModelValue,
+    },
+
+    #[error("Parameter {parameter_id} automation must have points at increasing, non-negative times")]
+    AutomationMalformed { parameter_id: ParameterId },
+
+    #[error("Parameter {parameter_id} with unit {unit:?} does not support {interpolation:?} automation")]
+    InterpolationNotSupported {
+        parameter_id:  ParameterId,
+        unit:          ModelValueUnit,
+        interpolation: AutomationInterpolation,
+    },
+}
+
+/// Insert a typed parameter value into parameter values
+pub fn insert_parameter_value<T>(values: &mut ParameterValues, parameter_id: &str, value: &T)
+    where T: MultiChannelConvert
+{
+    values.insert(ParameterId::from(parameter_id), value.to_multi_channel());
+}
+
+/// Get a typed parameter value from parameter values, if present
+pub fn get_parameter_value<T>(values: &ParameterValues, parameter_id: &str) -> Result<Option<T>, ParameterValuesError>
+    where T: MultiChannelConvert
+{
+    match values.get(&ParameterId::from(parameter_id)) {
+        Some(value) => T::from_multi_channel(value).map(Some)
+                                                   .ok_or_else(|| ParameterValuesError::Malformed { parameter_id:
+                                                                                                        ParameterId::from(parameter_id), }),
+        None => Ok(None),
+    }
+}
+
+/// Get a typed parameter value from parameter values, failing if not present
+pub fn require_parameter_value<T>(values: &ParameterValues, parameter_id: &str) -> Result<T, ParameterValuesError>
+    where T: MultiChannelConvert
+{
+    get_parameter_value(values, parameter_id)?.ok_or_else(|| ParameterValuesError::Missing { parameter_id: ParameterId::from(parameter_id), })
+}
@@ -9,9 +9,9 @@ use once_cell::sync::OnceCell;
 use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use utoipa::openapi::SchemaFormat::Uuid;
 
-use crate::cloud::CloudError;
+use crate::common::error::CloudError;
+use crate::routing::Subject;
 use crate::{InputPadId, OutputPadId};
 
 /// Id of a fixed instance
@@ -28,11 +28,11 @@ pub struct FixedInstanceId {
 
 impl FixedInstanceId {
     pub fn driver_command_subject(&self) -> String {
-        format!("ac.inst.{}.{}.{}.cmds", &self.manufacturer, &self.name, &self.instance)
+        Subject::InstanceDriverCommands(self.clone()).to_string()
     }
 
     pub fn driver_event_subject(&self) -> String {
-        format!("ac.inst.{}.{}.{}.evts", &self.manufacturer, &self.name, &self.instance)
+        Subject::InstanceDriverEvents(self.clone()).to_string()
     }
 }
 
@@ -216,10 +216,81 @@ impl FixedInstanceNodeId {
     }
 }
 
+/// Id of a recorder node in a task
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
+#[repr(transparent)]
+pub struct RecorderNodeId(String);
+
+impl RecorderNodeId {
+    pub fn input_flow(self) -> InputPadId {
+        InputPadId::RecorderInput(self)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
 #[repr(transparent)]
 pub struct NodeConnectionId(String);
 
+/// Id of a composite node, prefixing the ids of the nodes and connections it expands into
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
+#[repr(transparent)]
+pub struct CompositeNodeId(String);
+
+impl CompositeNodeId {
+    /// Id in the task of a node or connection with a local id in the composite
+    pub fn mangle(&self, local_id: &str) -> String {
+        format!("{}/{local_id}", self.0)
+    }
+
+    /// Local id in the composite, if the id in the task belongs to the composite
+    ///
+    /// Composite ids and local ids do not contain '/', so an id belongs to at most one composite.
+    pub fn unmangle<'a>(&self, id: &'a str) -> Option<&'a str> {
+        id.strip_prefix(self.0.as_str())?
+          .strip_prefix('/')
+          .filter(|local_id| !local_id.contains('/'))
+    }
+
+    /// True if the id can be used as a composite id or a local id in a composite
+    pub fn is_valid_part(id: &str) -> bool {
+        !id.contains('/')
+    }
+
+    pub fn fixed(&self, fixed_id: &FixedInstanceNodeId) -> FixedInstanceNodeId {
+        FixedInstanceNodeId(self.mangle(fixed_id))
+    }
+
+    pub fn dynamic(&self, dynamic_id: &DynamicInstanceNodeId) -> DynamicInstanceNodeId {
+        DynamicInstanceNodeId(self.mangle(dynamic_id))
+    }
+
+    pub fn connection(&self, connection_id: &NodeConnectionId) -> NodeConnectionId {
+        NodeConnectionId(self.mangle(connection_id))
+    }
+
+    /// Output pad in the task of an instance node output pad in the composite
+    ///
+    /// Composites only contain instance nodes, pads of other nodes are returned unchanged.
+    pub fn output_pad(&self, pad: &OutputPadId) -> OutputPadId {
+        match pad {
+            OutputPadId::FixedInstanceOutput(id) => OutputPadId::FixedInstanceOutput(self.fixed(id)),
+            OutputPadId::DynamicInstanceOutput(id) => OutputPadId::DynamicInstanceOutput(self.dynamic(id)),
+            pad => pad.clone(),
+        }
+    }
+
+    /// Input pad in the task of an instance node input pad in the composite
+    ///
+    /// Composites only contain instance nodes, pads of other nodes are returned unchanged.
+    pub fn input_pad(&self, pad: &InputPadId) -> InputPadId {
+        match pad {
+            InputPadId::FixedInstanceInput(id) => InputPadId::FixedInstanceInput(self.fixed(id)),
+            InputPadId::DynamicInstanceInput(id) => InputPadId::DynamicInstanceInput(self.dynamic(id)),
+            pad => pad.clone(),
+        }
+    }
+}
+
 /// Id of an app registered with the cloud
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From)]
 #[repr(transparent)]
@@ -244,7 +315,18 @@ impl AppId {
 #[repr(transparent)]
 pub struct TaskId(String);
 
+/// Maximum length of a [`RequestId`] or [`SocketId`]
+pub const STREAMING_ID_MAX_LEN: usize = 64;
+
+/// Pattern that [`RequestId`] and [`SocketId`] values must match
+pub const STREAMING_ID_PATTERN: &str = r"^[a-zA-Z0-9_\-]{1,64}$";
+
 /// Id of a request
+///
+/// Used to correlate responses with their request, by [`Envelope`](crate::Envelope) and the streaming
+/// messages of domains, engines and instance drivers. Ids are opaque: responses may arrive in a different
+/// order than requests were sent, and only ids from [`RequestId::generate`] with the `sortable-ids` feature
+/// sort by creation time. Ids must match [`STREAMING_ID_PATTERN`].
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
 #[repr(transparent)]
 pub struct RequestId(String);
@@ -256,15 +338,17 @@ pub struct EngineId(String);
 
 impl EngineId {
     pub fn engine_command_subject(&self) -> String {
-        format!("ac.engn.{}.cmds", self)
+        Subject::EngineCommands(self.clone()).to_string()
     }
 
     pub fn engine_event_subject(&self) -> String {
-        format!("ac.engn.{}.evts", self)
+        Subject::EngineEvents(self.clone()).to_string()
     }
 }
 
 /// Id of a socket (there may be more than one streaming connection per task in a domain)
+///
+/// Like [`RequestId`], socket ids are opaque, carry no ordering and must match [`STREAMING_ID_PATTERN`].
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
 #[repr(transparent)]
 pub struct SocketId(String);
@@ -274,6 +358,26 @@ pub struct SocketId(String);
 #[repr(transparent)]
 pub struct ClientId(String);
 
+/// Id of a multipart media upload, assigned by the cloud
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
+#[repr(transparent)]
+pub struct MultipartUploadId(String);
+
+/// Id of an audit log entry
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
+#[repr(transparent)]
+pub struct AuditEntryId(String);
+
+/// Id of a snapshot of task parameters
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
+#[repr(transparent)]
+pub struct TaskSnapshotId(String);
+
+/// Id of a preset in the model preset catalog
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Deref, Constructor, Hash, From, FromStr)]
+#[repr(transparent)]
+pub struct PresetId(String);
+
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Display, Constructor, Hash, JsonSchema)]
 #[display(fmt = "{client_id}.{socket_id}")]
 pub struct ClientSocketId {
@@ -293,6 +397,117 @@ impl TaskId {
     }
 }
 
+impl RequestId {
+    /// New random request id, unique for all practical purposes
+    pub fn random() -> Self {
+        Self(random_streaming_id())
+    }
+
+    /// New request id, unique for all practical purposes
+    ///
+    /// With the `sortable-ids` feature the id is a ULID of 26 uppercase characters, and ids generated by the
+    /// same process sort in the order they were generated. Otherwise it is a random id like
+    /// [`RequestId::random`], 32 lowercase hexadecimal digits.
+    pub fn generate() -> Self {
+        Self(generate_request_id())
+    }
+
+    pub fn validate(self) -> Result<Self, CloudError> {
+        if is_valid_streaming_id(&self.0) {
+            Ok(self)
+        } else {
+            Err(CloudError::InvalidRequestId { request_id: self.0 })
+        }
+    }
+}
+
+impl SocketId {
+    /// New random socket id, unique for all practical purposes
+    pub fn random() -> Self {
+        Self(random_streaming_id())
+    }
+
+    pub fn validate(self) -> Result<Self, CloudError> {
+        if is_valid_streaming_id(&self.0) {
+            Ok(self)
+        } else {
+            Err(CloudError::InvalidSocketId { socket_id: self.0 })
+        }
+    }
+}
+
+impl AuditEntryId {
+    /// New random audit entry id, unique for all practical purposes
+    pub fn random() -> Self {
+        Self(random_streaming_id())
+    }
+}
+
+impl TaskSnapshotId {
+    /// New random snapshot id, unique for all practical purposes
+    pub fn random() -> Self {
+        Self(random_streaming_id())
+    }
+}
+
+impl PresetId {
+    /// New random preset id, unique for all practical purposes
+    pub fn random() -> Self {
+        Self(random_streaming_id())
+    }
+}
+
+fn random_streaming_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+#[cfg(feature = "sortable-ids")]
+fn generate_request_id() -> String {
+    static GENERATOR: std::sync::Mutex<ulid::Generator> = std::sync::Mutex::new(ulid::Generator::new());
+
+    let mut generator = GENERATOR.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    generator.generate().unwrap_or_else(|_| ulid::Ulid::new()).to_string()
+}
+
+#[cfg(not(feature = "sortable-ids"))]
+fn generate_request_id() -> String {
+    random_streaming_id()
+}
+
+fn is_valid_streaming_id(id: &str) -> bool {
+    static VALIDATION: OnceCell<Regex> = OnceCell::new();
+
+    VALIDATION.get_or_init(|| Regex::new(STREAMING_ID_PATTERN).unwrap()).is_match(id)
+}
+
+fn streaming_id_schema() -> schemars::schema::Schema {
+    schemars::schema::SchemaObject { instance_type: Some(schemars::schema::InstanceType::String.into()),
+                                     string: Some(Box::new(schemars::schema::StringValidation { max_length: Some(STREAMING_ID_MAX_LEN as u32),
+                                                                                                min_length: Some(1),
+                                                                                                pattern:    Some(STREAMING_ID_PATTERN.to_owned()), })),
+                                     ..Default::default() }.into()
+}
+
+impl JsonSchema for RequestId {
+    fn schema_name() -> String {
+        "RequestId".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        streaming_id_schema()
+    }
+}
+
+impl JsonSchema for SocketId {
+    fn schema_name() -> String {
+        "SocketId".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        streaming_id_schema()
+    }
+}
+
 /// A task by an app
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Display, Constructor, Hash, From)]
 #[display(fmt = "{app_id}:{task_id}")]
@@ -448,6 +663,7 @@ json_schema_new_type!(AppId,
                       MixerNodeId,
                       DynamicInstanceNodeId,
                       FixedInstanceNodeId,
+                      RecorderNodeId,
                       SecureKey,
                       DomainId,
                       ParameterId,
@@ -455,6 +671,9 @@ json_schema_new_type!(AppId,
                       ModelId,
                       TaskId,
                       ClientId,
-                      SocketId,
-                      RequestId,
+                      MultipartUploadId,
+                      AuditEntryId,
+                      TaskSnapshotId,
+                      PresetId,
+                      CompositeNodeId,
                       EngineId);
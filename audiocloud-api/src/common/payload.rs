@@ -0,0 +1,204 @@
+//! Signing and encryption of command and event payloads exchanged between the cloud and domains
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length of the AES-GCM nonce in bytes
+pub const PAYLOAD_NONCE_LEN: usize = 12;
+
+/// Protection applied to payloads of a command or event stream
+///
+/// Keys are hex encoded. The key id is carried in every envelope so that both sides can detect
+/// payloads protected with a different (for example rotated) key.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadSecurity {
+    /// Payloads are sent as they are
+    #[default]
+    None,
+    /// Payloads are signed with HMAC-SHA256, so their integrity can be verified
+    HmacSha256 {
+        /// Id of the signing key
+        key_id: String,
+        /// Hex encoded signing key
+        key:    String,
+    },
+    /// Payloads are encrypted and authenticated with AES-256-GCM
+    Aes256Gcm {
+        /// Id of the encryption key
+        key_id: String,
+        /// Hex encoded 256-bit encryption key
+        key:    String,
+    },
+}
+
+/// A payload protected according to a [`PayloadSecurity`]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum PayloadEnvelope {
+    /// Unprotected payload
+    Plain {
+        /// Payload
+        payload: Bytes,
+    },
+    /// Payload with a HMAC-SHA256 signature
+    Signed {
+        /// Id of the signing key
+        key_id:    String,
+        /// Signature of the payload
+        signature: Bytes,
+        /// Payload
+        payload:   Bytes,
+    },
+    /// Payload encrypted with AES-256-GCM, authenticating the key id as additional data
+    Encrypted {
+        /// Id of the encryption key
+        key_id:     String,
+        /// Nonce used to encrypt the payload, unique per envelope
+        nonce:      Bytes,
+        /// Encrypted payload, including the authentication tag
+        ciphertext: Bytes,
+    },
+}
+
+impl PayloadEnvelope {
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            PayloadEnvelope::Plain { .. } => "none",
+            PayloadEnvelope::Signed { .. } => "hmac_sha256",
+            PayloadEnvelope::Encrypted { .. } => "aes256_gcm",
+        }
+    }
+
+    pub fn key_id(&self) -> Option<&str> {
+        match self {
+            PayloadEnvelope::Plain { .. } => None,
+            PayloadEnvelope::Signed { key_id, .. } | PayloadEnvelope::Encrypted { key_id, .. } => Some(key_id),
+        }
+    }
+}
+
+impl PayloadSecurity {
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            PayloadSecurity::None => "none",
+            PayloadSecurity::HmacSha256 { .. } => "hmac_sha256",
+            PayloadSecurity::Aes256Gcm { .. } => "aes256_gcm",
+        }
+    }
+
+    /// Sign or encrypt a payload
+    pub fn seal(&self, payload: &[u8]) -> Result<PayloadEnvelope, PayloadSecurityError> {
+        match self {
+            PayloadSecurity::None => Ok(PayloadEnvelope::Plain { payload: Bytes::copy_from_slice(payload), }),
+            PayloadSecurity::HmacSha256 { key_id, key } => {
+                let mut mac = hmac(key_id, key)?;
+                mac.update(payload);
+
+                Ok(PayloadEnvelope::Signed { key_id:    key_id.clone(),
+                                             signature: Bytes::copy_from_slice(&mac.finalize().into_bytes()),
+                                             payload:   Bytes::copy_from_slice(payload), })
+            }
+            PayloadSecurity::Aes256Gcm { key_id, key } => {
+                let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+                let ciphertext = cipher(key_id, key)?.encrypt(&nonce,
+                                                              Payload { msg: payload,
+                                                                        aad: key_id.as_bytes(), })
+                                                     .map_err(|_| PayloadSecurityError::EncryptionFailed { key_id: key_id.clone() })?;
+
+                Ok(PayloadEnvelope::Encrypted { key_id:     key_id.clone(),
+                                                nonce:      Bytes::copy_from_slice(&nonce),
+                                                ciphertext: ciphertext.into(), })
+            }
+        }
+    }
+
+    /// Verify or decrypt a payload, returning the original payload
+    pub fn open(&self, envelope: &PayloadEnvelope) -> Result<Bytes, PayloadSecurityError> {
+        match (self, envelope) {
+            (PayloadSecurity::None, PayloadEnvelope::Plain { payload }) => Ok(payload.clone()),
+            (PayloadSecurity::HmacSha256 { key_id, key },
+             PayloadEnvelope::Signed { key_id: received_key_id,
+                                       signature,
+                                       payload, }) => {
+                check_key_id(key_id, received_key_id)?;
+
+                let mut mac = hmac(key_id, key)?;
+                mac.update(payload);
+                mac.verify_slice(signature)
+                   .map_err(|_| PayloadSecurityError::InvalidSignature { key_id: key_id.clone() })?;
+
+                Ok(payload.clone())
+            }
+            (PayloadSecurity::Aes256Gcm { key_id, key },
+             PayloadEnvelope::Encrypted { key_id: received_key_id,
+                                          nonce,
+                                          ciphertext, }) => {
+                check_key_id(key_id, received_key_id)?;
+
+                if nonce.len() != PAYLOAD_NONCE_LEN {
+                    return Err(PayloadSecurityError::DecryptionFailed { key_id: key_id.clone() });
+                }
+
+                let payload = cipher(key_id, key)?.decrypt(Nonce::from_slice(nonce),
+                                                           Payload { msg: ciphertext,
+                                                                     aad: key_id.as_bytes(), })
+                                                  .map_err(|_| PayloadSecurityError::DecryptionFailed { key_id: key_id.clone() })?;
+
+                Ok(payload.into())
+            }
+            _ => Err(PayloadSecurityError::UnexpectedScheme { expected: self.scheme().to_owned(),
+                                                              received: envelope.scheme().to_owned(), }),
+        }
+    }
+}
+
+fn check_key_id(expected: &str, received: &str) -> Result<(), PayloadSecurityError> {
+    if expected == received {
+        Ok(())
+    } else {
+        Err(PayloadSecurityError::UnknownKey { key_id: received.to_owned(), })
+    }
+}
+
+fn decode_key(key_id: &str, key: &str) -> Result<Vec<u8>, PayloadSecurityError> {
+    hex::decode(key).map_err(|_| PayloadSecurityError::InvalidKey { key_id: key_id.to_owned() })
+}
+
+fn hmac(key_id: &str, key: &str) -> Result<HmacSha256, PayloadSecurityError> {
+    <HmacSha256 as Mac>::new_from_slice(&decode_key(key_id, key)?).map_err(|_| PayloadSecurityError::InvalidKey { key_id: key_id.to_owned() })
+}
+
+fn cipher(key_id: &str, key: &str) -> Result<Aes256Gcm, PayloadSecurityError> {
+    Aes256Gcm::new_from_slice(&decode_key(key_id, key)?).map_err(|_| PayloadSecurityError::InvalidKey { key_id: key_id.to_owned() })
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Error, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum PayloadSecurityError {
+    #[error("Expected payload protected with {expected}, received {received}")]
+    UnexpectedScheme { expected: String, received: String },
+
+    #[error("Payload protected with unknown key {key_id}")]
+    UnknownKey { key_id: String },
+
+    #[error("Key {key_id} is not a valid hex encoded key of the required length")]
+    InvalidKey { key_id: String },
+
+    #[error("Payload signature does not match key {key_id}")]
+    InvalidSignature { key_id: String },
+
+    #[error("Payload could not be encrypted with key {key_id}")]
+    EncryptionFailed { key_id: String },
+
+    #[error("Payload could not be decrypted or authenticated with key {key_id}")]
+    DecryptionFailed { key_id: String },
+}
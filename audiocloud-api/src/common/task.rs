@@ -1,21 +1,29 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
 use std::ops::Range;
 
 use derive_more::{From, IsVariant, Unwrap};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-
-use crate::audio_engine::CompressedAudio;
-use crate::cloud::tasks::CreateTask;
-use crate::cloud::CloudError;
-use crate::cloud::CloudError::*;
-use crate::domain::streaming::DiffStamped;
+use thiserror::Error;
+
+use crate::common::change::ModifyTaskError;
+use crate::common::change::UpdateTaskPlay;
+use crate::common::error::CloudError;
+use crate::common::error::CloudError::*;
+use crate::common::media::{
+    CompressedAudio, DeleteReason, LoopRegion, MediaDiagnostics, MonitoredMixers, PunchRange, RenderAnalysis, RequestPlay, StopReason,
+};
+use crate::common::model::{ModelCapability, ModelValidationError, MultiChannelValue, ParameterValues, ResourceId};
+use crate::common::tempo::{PlayClick, TempoMap};
+use crate::common::time::DiffStamped;
+use crate::common::ParameterId;
 use crate::{
-    now, AppMediaObjectId, DesiredTaskPlayState, DomainId, DynamicInstanceNodeId, FixedInstanceId, FixedInstanceNodeId, MediaObjectId,
-    MixerNodeId, Model, ModelId, NodeConnectionId, PlayId, SecureKey, TaskPlayState, TimeRange, Timestamp, Timestamped, TrackMediaId,
-    TrackNodeId,
+    now, AppMediaObjectId, AppTaskId, CompositeNodeId, DesiredTaskPlayState, DomainId, DynamicInstanceNodeId, FixedInstanceId,
+    FixedInstanceNodeId, MediaObjectId, MixerNodeId, Model, ModelId, NodeConnectionId, PlayId, RecorderNodeId, RenderId, SecureKey,
+    TaskPlayState, TimeRange, Timecode, Timestamp, Timestamped, TrackMediaId, TrackNodeId,
 };
 
 /// Task specification
@@ -23,22 +31,34 @@ use crate::{
 pub struct TaskSpec {
     /// Track nodes of the task
     #[serde(default)]
-    pub tracks:      HashMap<TrackNodeId, TrackNode>,
+    pub tracks:         HashMap<TrackNodeId, TrackNode>,
     /// Mixer nodes of the task
     #[serde(default)]
-    pub mixers:      HashMap<MixerNodeId, MixerNode>,
+    pub mixers:         HashMap<MixerNodeId, MixerNode>,
     /// Dynamic instance nodes of the task
     #[serde(default)]
-    pub dynamic:     HashMap<DynamicInstanceNodeId, DynamicInstanceNode>,
+    pub dynamic:        HashMap<DynamicInstanceNodeId, DynamicInstanceNode>,
     /// Fixed instance nodes of the task
     #[serde(default)]
-    pub fixed:       HashMap<FixedInstanceNodeId, FixedInstanceNode>,
+    pub fixed:          HashMap<FixedInstanceNodeId, FixedInstanceNode>,
+    /// Recorder nodes of the task
+    #[serde(default)]
+    pub recorders:      HashMap<RecorderNodeId, RecorderNode>,
     /// Connections between nodes
     #[serde(default)]
-    pub connections: HashMap<NodeConnectionId, NodeConnection>,
+    pub connections:    HashMap<NodeConnectionId, NodeConnection>,
+    /// Pan law used by connections that do not override it
+    #[serde(default)]
+    pub pan_law:        PanLaw,
+    /// Timecode at the start of the task timeline, if the task is aligned to external material
+    #[serde(default)]
+    pub timecode_start: Option<Timecode>,
+    /// Tempo and time signature changes, used to generate the click
+    #[serde(default)]
+    pub tempo_map:      TempoMap,
     /// The revision number of the specification (starts at zero, increments for every change)
     #[serde(default)]
-    pub revision:    u64,
+    pub revision:       u64,
 }
 
 /// Create task spec
@@ -46,19 +66,31 @@ pub struct TaskSpec {
 pub struct CreateTaskSpec {
     /// Track nodes of the task
     #[serde(default)]
-    pub tracks:      HashMap<TrackNodeId, TrackNode>,
+    pub tracks:         HashMap<TrackNodeId, TrackNode>,
     /// Mixer nodes of the task
     #[serde(default)]
-    pub mixers:      HashMap<MixerNodeId, MixerNode>,
+    pub mixers:         HashMap<MixerNodeId, MixerNode>,
     /// Dynamic instance nodes of the task
     #[serde(default)]
-    pub dynamic:     HashMap<DynamicInstanceNodeId, DynamicInstanceNode>,
+    pub dynamic:        HashMap<DynamicInstanceNodeId, DynamicInstanceNode>,
     /// Fixed instance nodes of the task
     #[serde(default)]
-    pub fixed:       HashMap<FixedInstanceNodeId, FixedInstanceNode>,
+    pub fixed:          HashMap<FixedInstanceNodeId, FixedInstanceNode>,
+    /// Recorder nodes of the task
+    #[serde(default)]
+    pub recorders:      HashMap<RecorderNodeId, RecorderNode>,
     /// Connections between nodes
     #[serde(default)]
-    pub connections: HashMap<NodeConnectionId, NodeConnection>,
+    pub connections:    HashMap<NodeConnectionId, NodeConnection>,
+    /// Pan law used by connections that do not override it
+    #[serde(default)]
+    pub pan_law:        PanLaw,
+    /// Timecode at the start of the task timeline, if the task is aligned to external material
+    #[serde(default)]
+    pub timecode_start: Option<Timecode>,
+    /// Tempo and time signature changes, used to generate the click
+    #[serde(default)]
+    pub tempo_map:      TempoMap,
 }
 
 impl Into<TaskSpec> for CreateTaskSpec {
@@ -67,30 +99,337 @@ impl Into<TaskSpec> for CreateTaskSpec {
                    mixers,
                    dynamic,
                    fixed,
-                   connections, } = self;
+                   recorders,
+                   connections,
+                   pan_law,
+                   timecode_start,
+                   tempo_map, } = self;
         TaskSpec { tracks,
                    mixers,
                    dynamic,
                    fixed,
+                   recorders,
                    connections,
+                   pan_law,
+                   timecode_start,
+                   tempo_map,
                    revision: 0 }
     }
 }
 
 impl TaskSpec {
     pub fn validate(&self, models: &HashMap<ModelId, Model>) -> Result<(), CloudError> {
+        self.validate_with_max_volume(models, DEFAULT_MAX_CONNECTION_VOLUME)
+    }
+
+    /// Validate the task spec, allowing connection volumes up to `max_volume`
+    pub fn validate_with_max_volume(&self, models: &HashMap<ModelId, Model>, max_volume: f64) -> Result<(), CloudError> {
+        match self.validation_errors(models, max_volume).into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// All problems found validating the task spec, allowing connection volumes up to `max_volume`
+    ///
+    /// Unlike [`TaskSpec::validate_with_max_volume`], validation continues after the first error, so
+    /// every invalid connection is reported.
+    pub fn validation_errors(&self, models: &HashMap<ModelId, Model>, max_volume: f64) -> Vec<CloudError> {
+        let mut errors = vec![];
+
         if self.fixed.is_empty() && self.dynamic.is_empty() && self.mixers.is_empty() && self.tracks.is_empty() {
-            return Err(InternalInconsistency { message:
-                                                   format!("No tracks, mixers, dynamic instances, or fixed instances declared in task spec"), });
+            errors.push(InternalInconsistency { message:
+                                                    "No tracks, mixers, dynamic instances, or fixed instances declared in task spec".to_owned(), });
+        }
+
+        for (node_id, fixed) in &self.fixed {
+            if let Err(error) = Self::validate_parameters(node_id.clone().into(),
+                                                          &fixed.parameters,
+                                                          &fixed.parameter_automation,
+                                                          &fixed.instance_id.model_id(),
+                                                          models)
+            {
+                errors.push(error);
+            }
+        }
+
+        for (node_id, dynamic) in &self.dynamic {
+            if let Err(error) = Self::validate_parameters(node_id.clone().into(),
+                                                          &dynamic.parameters,
+                                                          &dynamic.parameter_automation,
+                                                          &dynamic.model_id,
+                                                          models)
+            {
+                errors.push(error);
+            }
+        }
+
+        for (track_id, track) in &self.tracks {
+            if !is_volume_valid(track.gain, max_volume) {
+                errors.push(TrackGainOutOfRange { track_node_id: track_id.clone(),
+                                                  gain:          track.gain,
+                                                  max:           max_volume, });
+            }
+        }
+
+        if let Err(error) = self.tempo_map.validate() {
+            errors.push(InvalidTempoMap { error });
         }
 
         for (connection_id, connection) in self.connections.iter() {
-            self.validate_connection(connection_id, connection, models)?;
+            if let Err(error) = self.validate_connection(connection_id, connection, models) {
+                errors.push(error);
+            } else if let Err(error) = connection.validate_values(max_volume) {
+                errors.push(ConnectionError { connection_id: connection_id.clone(),
+                                              error:         Box::new(error), });
+            }
+        }
+
+        errors
+    }
+
+    /// Sum of resources required by the dynamic instances of the task
+    ///
+    /// Dynamic instances with models not present in `models` do not contribute to the estimate.
+    pub fn estimate_resources(&self, models: &HashMap<ModelId, Model>) -> HashMap<ResourceId, f64> {
+        let mut resources = HashMap::new();
+
+        for dynamic in self.dynamic.values() {
+            if let Some(model) = models.get(&dynamic.model_id) {
+                for (resource, amount) in &model.resources {
+                    *resources.entry(*resource).or_default() += *amount;
+                }
+            }
+        }
+
+        resources
+    }
+
+    /// Timecode of a position on the task timeline (in seconds), if the task has a timecode start
+    pub fn timecode_at(&self, timeline_pos: f64) -> Option<Timecode> {
+        self.timecode_start.map(|start| start.offset_by_seconds(timeline_pos))
+    }
+
+    /// Length of the task timeline in seconds, up to the end of the last media placed on it
+    pub fn timeline_length(&self) -> f64 {
+        self.tracks
+            .values()
+            .flat_map(|track| track.media.values())
+            .map(|media| media.timeline_segment.end())
+            .fold(0.0, f64::max)
+    }
+
+    /// Check a play request against the task
+    ///
+    /// Monitored mixers must be valid as checked by [`TaskSpec::validate_monitored_mixers`], the loop region and punch
+    /// range must be within the task timeline, the loop region must be within the played segment, and an enabled click
+    /// must have a tempo map to follow and be routed to an existing mixer. Gains are limited to `0..=max_volume`.
+    pub fn validate_play(&self, play: &RequestPlay, max_volume: f64) -> Result<(), CloudError> {
+        self.validate_monitored_mixers(&play.mixers, max_volume)?;
+        self.validate_play_regions(&play.segment, play.loop_region.as_ref(), play.punch.as_ref())?;
+
+        if let Some(click) = play.click.as_ref().filter(|click| click.enabled) {
+            self.validate_click(click, max_volume)?;
         }
 
         Ok(())
     }
 
+    fn validate_click(&self, click: &PlayClick, max_volume: f64) -> Result<(), CloudError> {
+        if self.tempo_map.is_empty() {
+            return Err(ClickWithoutTempoMap);
+        }
+
+        if !self.mixers.contains_key(&click.mixer_id) {
+            return Err(MixerNodeNotFound { mixer_node_id: click.mixer_id.clone(), });
+        }
+
+        if !is_volume_valid(click.gain, max_volume) {
+            return Err(VolumeOutOfRange { volume: click.gain,
+                                          max:    max_volume, });
+        }
+
+        Ok(())
+    }
+
+    /// Check a play update against the task, as it would apply to the active `play`
+    ///
+    /// Replaced monitored mixers are checked like in [`TaskSpec::validate_play`], and the resulting segment, loop region
+    /// and punch range must be valid together.
+    pub fn validate_play_update(&self, play: &RequestPlay, update: &UpdateTaskPlay, max_volume: f64) -> Result<(), CloudError> {
+        if let Some(mixers) = &update.mixers {
+            self.validate_monitored_mixers(mixers, max_volume)?;
+        }
+
+        self.validate_play_regions(&update.segment.unwrap_or(play.segment),
+                                   update.loop_region.or(play.loop_region).as_ref(),
+                                   update.punch.or(play.punch).as_ref())
+    }
+
+    fn validate_play_regions(&self,
+                             segment: &TimeSegment,
+                             loop_region: Option<&LoopRegion>,
+                             punch: Option<&PunchRange>)
+                             -> Result<(), CloudError> {
+        let timeline_length = self.timeline_length();
+        let check = |start: f64, end: f64| {
+            if !(start.is_finite() && end.is_finite() && 0.0 <= start && start < end && end <= timeline_length) {
+                return Err(PlayRegionOutsideTimeline { start,
+                                                       end,
+                                                       timeline_length });
+            }
+
+            Ok(())
+        };
+
+        if let Some(loop_region) = loop_region {
+            let (start, end) = (loop_region.segment.start, loop_region.segment.end());
+            check(start, end)?;
+
+            if start < segment.start || end > segment.end() {
+                return Err(LoopRegionOutsideSegment { start,
+                                                      end,
+                                                      segment_start: segment.start,
+                                                      segment_end: segment.end() });
+            }
+        }
+
+        if let Some(punch) = punch.filter(|punch| punch.punch_in.is_some() || punch.punch_out.is_some()) {
+            check(punch.punch_in.unwrap_or(0.0), punch.punch_out.unwrap_or(timeline_length))?;
+        }
+
+        Ok(())
+    }
+
+    /// Gain of a track output after mute and solo, as a factor, or None if the track does not exist
+    ///
+    /// Muted tracks, and tracks that are not soloed while another track is, are silent.
+    pub fn track_output_gain(&self, track_id: &TrackNodeId) -> Option<f64> {
+        let track = self.tracks.get(track_id)?;
+        let soloing = self.tracks.values().any(|track| track.solo);
+
+        Some(if track.mute || (soloing && !track.solo) { 0.0 } else { track.gain })
+    }
+
+    /// Pan law in effect on a connection
+    pub fn connection_pan_law(&self, connection: &NodeConnection) -> PanLaw {
+        connection.pan_law.unwrap_or(self.pan_law)
+    }
+
+    /// Ids of all nodes of the task
+    pub fn node_ids(&self) -> impl Iterator<Item = TaskNodeId> + '_ {
+        let tracks = self.tracks.keys().cloned().map(TaskNodeId::Track);
+        let mixers = self.mixers.keys().cloned().map(TaskNodeId::Mixer);
+        let fixed = self.fixed.keys().cloned().map(TaskNodeId::FixedInstance);
+        let dynamic = self.dynamic.keys().cloned().map(TaskNodeId::DynamicInstance);
+        let recorders = self.recorders.keys().cloned().map(TaskNodeId::Recorder);
+
+        tracks.chain(mixers).chain(fixed).chain(dynamic).chain(recorders)
+    }
+
+    /// True if audio flows from node `from` to node `to` through one or more connections
+    pub fn is_reachable(&self, from: &TaskNodeId, to: &TaskNodeId) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = vec![from.clone()];
+
+        while let Some(node_id) = pending.pop() {
+            for connection in self.connections.values().filter(|connection| connection.from.references(&node_id)) {
+                let next = connection.to.node_id();
+                if &next == to {
+                    return true;
+                }
+
+                if visited.insert(next.clone()) {
+                    pending.push(next);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Connections through which audio flows into the node of `pad`, directly or through other nodes
+    ///
+    /// Each connection is yielded once, with the node on its source end, nearest connections first.
+    pub fn upstream_of(&self, pad: &NodePadId) -> impl Iterator<Item = (TaskNodeId, &NodeConnectionId)> + '_ {
+        self.traverse(pad.node_id(), |connection| (connection.to.node_id(), connection.from.node_id()))
+    }
+
+    /// Connections through which audio flows out of the node of `pad`, directly or through other nodes
+    ///
+    /// Each connection is yielded once, with the node on its destination end, nearest connections first.
+    pub fn downstream_of(&self, pad: &NodePadId) -> impl Iterator<Item = (TaskNodeId, &NodeConnectionId)> + '_ {
+        self.traverse(pad.node_id(), |connection| (connection.from.node_id(), connection.to.node_id()))
+    }
+
+    fn traverse(&self,
+                start: TaskNodeId,
+                ends: impl Fn(&NodeConnection) -> (TaskNodeId, TaskNodeId))
+                -> impl Iterator<Item = (TaskNodeId, &NodeConnectionId)> + '_ {
+        let mut connections = self.connections.iter().collect::<Vec<_>>();
+        connections.sort_by_key(|(connection_id, _)| *connection_id);
+
+        let mut visited = HashSet::from([start.clone()]);
+        let mut pending = VecDeque::from([start]);
+        let mut found = vec![];
+
+        while let Some(node_id) = pending.pop_front() {
+            for (connection_id, connection) in &connections {
+                let (near, far) = ends(connection);
+                if near == node_id {
+                    found.push((far.clone(), *connection_id));
+                    if visited.insert(far.clone()) {
+                        pending.push_back(far);
+                    }
+                }
+            }
+        }
+
+        found.into_iter()
+    }
+
+    /// Nodes without any connections
+    pub fn orphan_nodes(&self) -> impl Iterator<Item = TaskNodeId> + '_ {
+        self.node_ids().filter(|node_id| {
+                           !self.connections
+                                .values()
+                                .any(|connection| connection.from.references(node_id) || connection.to.references(node_id))
+                       })
+    }
+
+    /// All nodes ordered so that every node comes after the nodes connected to its inputs
+    ///
+    /// Nodes that do not depend on each other are ordered by their ids, so the order is stable. Returns
+    /// `None` if the connections contain a cycle.
+    pub fn topological_order(&self) -> Option<Vec<TaskNodeId>> {
+        let mut inputs = self.node_ids().map(|node_id| (node_id, 0usize)).collect::<BTreeMap<_, _>>();
+        for connection in self.connections.values() {
+            inputs.entry(connection.from.node_id()).or_default();
+            *inputs.entry(connection.to.node_id()).or_default() += 1;
+        }
+
+        let mut ready = inputs.iter()
+                              .filter(|(_, count)| **count == 0)
+                              .map(|(node_id, _)| node_id.clone())
+                              .collect::<BTreeSet<_>>();
+        let mut order = Vec::with_capacity(inputs.len());
+
+        while let Some(node_id) = ready.pop_first() {
+            for connection in self.connections.values().filter(|connection| connection.from.references(&node_id)) {
+                let next = connection.to.node_id();
+                let count = inputs.get_mut(&next).expect("all connected nodes counted");
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(next);
+                }
+            }
+
+            order.push(node_id);
+        }
+
+        (order.len() == inputs.len()).then_some(order)
+    }
+
     pub fn fixed_instance_to_fixed_id(&self, instance_id: &FixedInstanceId) -> Option<&FixedInstanceNodeId> {
         for (fixed_id, fixed) in &self.fixed {
             if &fixed.instance_id == instance_id {
@@ -100,13 +439,119 @@ impl TaskSpec {
         None
     }
 
+    /// Check that at least one mixer is monitored, that monitored mixers exist and their gains are within `0..=max_volume`
+    pub fn validate_monitored_mixers(&self, mixers: &MonitoredMixers, max_volume: f64) -> Result<(), CloudError> {
+        if mixers.is_empty() {
+            return Err(NoMonitoredMixers);
+        }
+
+        for (mixer_id, gain) in mixers {
+            if !self.mixers.contains_key(mixer_id) {
+                return Err(MixerNodeNotFound { mixer_node_id: mixer_id.clone(), });
+            }
+
+            if !is_volume_valid(*gain, max_volume) {
+                return Err(VolumeOutOfRange { volume: *gain,
+                                              max:    max_volume, });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_parameters(node_id: TaskNodeId,
+                           parameters: &InstanceParameters,
+                           automation: &ParameterAutomation,
+                           model_id: &ModelId,
+                           models: &HashMap<ModelId, Model>)
+                           -> Result<(), CloudError> {
+        if parameters.0.is_empty() && automation.is_empty() {
+            return Ok(());
+        }
+
+        let model = models.get(model_id).ok_or_else(|| ModelNotFound { model_id: model_id.clone(), })?;
+
+        parameters.validate(model)
+                  .and_then(|_| {
+                      automation.iter()
+                                .try_for_each(|(parameter_id, lane)| model.validate_automation(parameter_id, lane))
+                  })
+                  .map_err(|error| InvalidInstanceParameters { node_id, error })
+    }
+
     fn validate_connection(&self,
                            id: &NodeConnectionId,
                            connection: &NodeConnection,
                            models: &HashMap<ModelId, Model>)
                            -> Result<(), CloudError> {
-        self.check_source_channel_exists(id, &connection.from, connection.from_channels, models)?;
-        self.check_destination_channel_exists(id, &connection.to, connection.to_channels, models)?;
+        match connection.kind {
+            ConnectionKind::Audio => {
+                self.check_source_channel_exists(id, &connection.from, connection.from_channels, models)?;
+                self.check_destination_channel_exists(id, &connection.to, connection.to_channels, models)?;
+            }
+            ConnectionKind::Midi => {
+                self.check_instance_pads_exist(id,
+                                               connection.from.node_id(),
+                                               connection.kind,
+                                               connection.from_channels,
+                                               true,
+                                               models)?;
+                self.check_instance_pads_exist(id, connection.to.node_id(), connection.kind, connection.to_channels, false, models)?;
+            }
+            ConnectionKind::Sidechain => {
+                self.check_source_channel_exists(id, &connection.from, connection.from_channels, models)?;
+                self.check_instance_pads_exist(id, connection.to.node_id(), connection.kind, connection.to_channels, false, models)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that an instance node exposes enough MIDI or sidechain pads for the mask
+    ///
+    /// Sources are checked against MIDI outputs, destinations against MIDI or sidechain inputs of the model.
+    /// Mixers and tracks only expose audio pads. Audio pads are checked by the source and destination channel
+    /// checks instead.
+    fn check_instance_pads_exist(&self,
+                                 connection_id: &NodeConnectionId,
+                                 node_id: TaskNodeId,
+                                 kind: ConnectionKind,
+                                 mask: ChannelMask,
+                                 output: bool,
+                                 models: &HashMap<ModelId, Model>)
+                                 -> Result<(), CloudError> {
+        let complete_error = |error| ConnectionError { connection_id: connection_id.clone(),
+                                                       error:         Box::new(error), };
+
+        let model_id = match &node_id {
+            TaskNodeId::FixedInstance(id) => self.fixed
+                                                 .get(id)
+                                                 .ok_or_else(|| FixedInstanceNodeNotFound { fixed_node_id: id.clone() })
+                                                 .map_err(complete_error)?
+                                                 .instance_id
+                                                 .model_id(),
+            TaskNodeId::DynamicInstance(id) => self.dynamic
+                                                   .get(id)
+                                                   .ok_or_else(|| DynamicInstanceNodeNotFound { dynamic_node_id: id.clone(), })
+                                                   .map_err(complete_error)?
+                                                   .model_id
+                                                   .clone(),
+            _ => return Err(complete_error(ConnectionKindNotSupported { node_id, kind })),
+        };
+
+        let model = models.get(&model_id)
+                          .ok_or_else(|| ModelNotFound { model_id: model_id.clone(), })
+                          .map_err(complete_error)?;
+
+        let pads = model.get_connection_pad_count(kind, output);
+
+        if pads == 0 {
+            return Err(complete_error(ConnectionKindNotSupported { node_id, kind }));
+        }
+
+        if !mask.is_subset_of(0..pads) {
+            return Err(complete_error(ChannelMaskIncompatible { mask, channels: pads }));
+        }
 
         Ok(())
     }
@@ -197,6 +642,11 @@ impl TaskSpec {
 
                 dynamic.validate_destination_channels(channels, model).map_err(complete_error)
             }
+            InputPadId::RecorderInput(id) => self.recorders
+                                                 .get(id)
+                                                 .ok_or_else(|| RecorderNodeNotFound { recorder_node_id: id.clone(), })
+                                                 .and_then(|node| node.validate_destination_channels(channels))
+                                                 .map_err(complete_error),
         }
     }
 
@@ -283,13 +733,32 @@ pub struct Task {
     pub spec:         TaskSpec,
     /// Security keys and associateds permissions
     pub security:     TaskSecurity,
+    /// Descriptive metadata, for example project, client or engineer
+    #[serde(default)]
+    pub metadata:     TaskMetadata,
+    /// Labels used to search for tasks
+    #[serde(default)]
+    pub labels:       TaskLabels,
+}
+
+/// Descriptive metadata of a task, as key-value pairs
+pub type TaskMetadata = HashMap<String, String>;
+
+/// Labels of a task, used to search for tasks
+pub type TaskLabels = HashSet<String>;
+
+impl Task {
+    /// True if the task has all of the labels
+    pub fn has_labels(&self, labels: &TaskLabels) -> bool {
+        labels.is_subset(&self.labels)
+    }
 }
 
 /// Information about access keys and permissions of a task
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskSecurity {
     /// Security settings per secure key
-    pub security: HashMap<SecureKey, TaskPermissions>,
+    pub security: HashMap<SecureKey, TaskKeyGrant>,
     /// Revision number - starts at zero and is incremented at every change of task security
     #[serde(default)]
     pub revision: u64,
@@ -303,7 +772,7 @@ impl From<CreateTaskSecurity> for TaskSecurity {
 }
 
 /// Information about access keys and permissions of a task
-pub type CreateTaskSecurity = HashMap<SecureKey, TaskPermissions>;
+pub type CreateTaskSecurity = HashMap<SecureKey, TaskKeyGrant>;
 
 /// Timed resource reservations for the task (must contain all used resources)
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -341,6 +810,18 @@ pub struct CreateTaskReservation {
     pub fixed_instances: HashSet<FixedInstanceId>,
 }
 
+impl CreateTaskReservation {
+    /// Get a time range for this reservation
+    pub fn time_range(&self) -> TimeRange {
+        TimeRange::new(self.from, self.to)
+    }
+
+    /// Check that the reservation starts before it ends
+    pub fn validate(&self) -> Result<(), CloudError> {
+        self.time_range().validate()
+    }
+}
+
 impl Into<TaskReservation> for CreateTaskReservation {
     fn into(self) -> TaskReservation {
         let Self { from, to, fixed_instances } = self;
@@ -351,21 +832,6 @@ impl Into<TaskReservation> for CreateTaskReservation {
     }
 }
 
-impl From<CreateTask> for Task {
-    fn from(source: CreateTask) -> Self {
-        let CreateTask { domain_id,
-                         reservations,
-                         spec,
-                         security,
-                         .. } = source;
-
-        Self { domain_id:    domain_id.into(),
-               reservations: reservations.into(),
-               spec:         spec.into(),
-               security:     security.into(), }
-    }
-}
-
 /// Mixer node specification
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct MixerNode {
@@ -404,13 +870,72 @@ impl MixerNode {
     }
 }
 
+/// Recorder node specification
+///
+/// Recorders capture the audio connected to their input while the task plays, creating a new media
+/// object for every take.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct RecorderNode {
+    /// Number of recorded channels
+    pub channels: MediaChannels,
+    /// Format of the recorded media
+    pub format:   TrackMediaFormat,
+    /// Naming of the media objects created by the recorder
+    #[serde(default)]
+    pub naming:   RecorderMediaNaming,
+}
+
+impl RecorderNode {
+    pub fn validate_destination_channels(&self, mask: ChannelMask) -> Result<(), CloudError> {
+        let channels = self.channels.num_channels();
+        let half_channels = channels / 2;
+
+        if matches!(mask, ChannelMask::Mono(i) if i < channels) || matches!(mask, ChannelMask::Stereo(i) if i < half_channels) {
+            Ok(())
+        } else {
+            Err(ChannelMaskIncompatible { mask, channels })
+        }
+    }
+}
+
+/// Naming of the media objects created by a recorder node
+///
+/// The template may contain the placeholders `{task_id}`, `{recorder_id}` and `{take}`, replaced with the
+/// task id, the recorder node id and the take number, counting from 1.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct RecorderMediaNaming {
+    /// Template of the media object id
+    pub template: String,
+}
+
+impl Default for RecorderMediaNaming {
+    fn default() -> Self {
+        Self { template: "{task_id}-{recorder_id}-{take}".to_owned(), }
+    }
+}
+
+impl RecorderMediaNaming {
+    /// Id of the media object created for a take, owned by the app of the task
+    pub fn media_object_id(&self, task_id: &AppTaskId, recorder_id: &RecorderNodeId, take: u32) -> Result<AppMediaObjectId, CloudError> {
+        let media_id = self.template
+                           .replace("{task_id}", &task_id.task_id)
+                           .replace("{recorder_id}", recorder_id)
+                           .replace("{take}", &take.to_string());
+
+        Ok(MediaObjectId::new(media_id).validate()?.for_app(task_id.app_id.clone()))
+    }
+}
+
 /// Dynamic node specification
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct DynamicInstanceNode {
     /// The manufacturer and name of the processing software
-    pub model_id:   ModelId,
+    pub model_id:             ModelId,
     /// Parameter values
-    pub parameters: InstanceParameters,
+    pub parameters:           InstanceParameters,
+    /// Automation of parameters over the task timeline, replacing the parameter values where set
+    #[serde(default)]
+    pub parameter_automation: ParameterAutomation,
 }
 
 impl DynamicInstanceNode {
@@ -444,14 +969,47 @@ impl DynamicInstanceNode {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct FixedInstanceNode {
     /// The manufacturer, name and instance identifier of the hardware device doing the processing
-    pub instance_id: FixedInstanceId,
+    pub instance_id:          FixedInstanceId,
     /// parameters
-    pub parameters:  InstanceParameters,
+    pub parameters:           InstanceParameters,
     /// Dry-wet percentage
     ///
     /// only applicable for instances with same number of inputs and outputs,
     /// having 1 or 2 channels.
-    pub wet:         f64,
+    pub wet:                  f64,
+    /// Automation of parameters over the task timeline, replacing the parameter values where set
+    #[serde(default)]
+    pub parameter_automation: ParameterAutomation,
+    /// If not null, the cloud may allocate any available instance matching the selector when the
+    /// task is created, replacing `instance_id` which is then only the preferred instance
+    #[serde(default)]
+    pub selector:             Option<InstanceSelector>,
+}
+
+/// Selects fixed instances that may be allocated to a fixed instance node
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceSelector {
+    /// Any instance of the model
+    Model { model_id: ModelId },
+    /// Any instance of a model having all of the capabilities
+    Capabilities { capabilities: HashSet<ModelCapability> },
+    /// Any instance tagged with all of the tags in the domain configuration
+    Tags { tags: HashSet<String> },
+    /// Any instance of the same model as the preferred instance
+    AnyAvailable,
+}
+
+impl InstanceSelector {
+    /// True if the candidate instance, with its model and tags, may replace the preferred instance
+    pub fn matches(&self, preferred: &FixedInstanceId, candidate: &FixedInstanceId, model: &Model, tags: &HashSet<String>) -> bool {
+        match self {
+            Self::Model { model_id } => &candidate.model_id() == model_id,
+            Self::Capabilities { capabilities } => capabilities.is_subset(&model.capabilities),
+            Self::Tags { tags: required } => required.is_subset(tags),
+            Self::AnyAvailable => candidate.model_id() == preferred.model_id(),
+        }
+    }
 }
 
 impl FixedInstanceNode {
@@ -459,54 +1017,391 @@ impl FixedInstanceNode {
         let input_channels = model.get_audio_input_channel_count();
         let half_input_channels = input_channels / 2;
 
-        if matches!(mask, ChannelMask::Mono(i) if i < input_channels) || matches!(mask, ChannelMask::Stereo(i) if i < half_input_channels) {
-            Ok(())
-        } else {
-            Err(ChannelMaskIncompatible { mask:     mask.clone(),
-                                          channels: input_channels, })
+        if matches!(mask, ChannelMask::Mono(i) if i < input_channels) || matches!(mask, ChannelMask::Stereo(i) if i < half_input_channels) {
+            Ok(())
+        } else {
+            Err(ChannelMaskIncompatible { mask:     mask.clone(),
+                                          channels: input_channels, })
+        }
+    }
+
+    pub fn validate_destination_channels(&self, mask: ChannelMask, model: &Model) -> Result<(), CloudError> {
+        let output_channels = model.get_audio_output_channel_count();
+        let half_output_channels = output_channels / 2;
+
+        if matches!(mask, ChannelMask::Mono(i) if i < output_channels) || matches!(mask, ChannelMask::Stereo(i) if i < half_output_channels)
+        {
+            Ok(())
+        } else {
+            Err(ChannelMaskIncompatible { mask:     mask.clone(),
+                                          channels: output_channels, })
+        }
+    }
+}
+
+/// Instance nodes and connections inserted into a task as a unit, for example an EQ, compressor and
+/// limiter channel strip
+///
+/// Ids of nodes and connections are local to the composite. When inserted into a task, they are
+/// prefixed with the composite id, see [`CompositeNodeId::mangle`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct CompositeNode {
+    /// Fixed instance nodes of the composite
+    #[serde(default)]
+    pub fixed:       HashMap<FixedInstanceNodeId, FixedInstanceNode>,
+    /// Dynamic instance nodes of the composite
+    #[serde(default)]
+    pub dynamic:     HashMap<DynamicInstanceNodeId, DynamicInstanceNode>,
+    /// Connections between nodes of the composite
+    #[serde(default)]
+    pub connections: HashMap<NodeConnectionId, NodeConnection>,
+}
+
+impl CompositeNode {
+    pub fn contains(&self, node_id: &TaskNodeId) -> bool {
+        match node_id {
+            TaskNodeId::FixedInstance(fixed_id) => self.fixed.contains_key(fixed_id),
+            TaskNodeId::DynamicInstance(dynamic_id) => self.dynamic.contains_key(dynamic_id),
+            TaskNodeId::Mixer(_) | TaskNodeId::Track(_) | TaskNodeId::Recorder(_) => false,
+        }
+    }
+
+    /// Check that the composite has nodes, that neither its id nor the ids of its nodes contain '/' and that its
+    /// connections only reference its own nodes
+    pub fn validate(&self, composite_id: &CompositeNodeId) -> Result<(), ModifyTaskError> {
+        if !CompositeNodeId::is_valid_part(composite_id) {
+            return Err(ModifyTaskError::CompositeIdMalformed { composite_id: composite_id.clone(), });
+        }
+
+        if self.fixed.is_empty() && self.dynamic.is_empty() {
+            return Err(ModifyTaskError::CompositeEmpty { composite_id: composite_id.clone(), });
+        }
+
+        let reserved = self.fixed
+                           .keys()
+                           .find(|fixed_id| !CompositeNodeId::is_valid_part(fixed_id))
+                           .map(|fixed_id| TaskNodeId::from(fixed_id.clone()))
+                           .or_else(|| {
+                               self.dynamic
+                                   .keys()
+                                   .find(|dynamic_id| !CompositeNodeId::is_valid_part(dynamic_id))
+                                   .map(|dynamic_id| TaskNodeId::from(dynamic_id.clone()))
+                           });
+        if let Some(node_id) = reserved {
+            return Err(ModifyTaskError::NodeIdReserved { node_id });
+        }
+
+        for (connection_id, connection) in &self.connections {
+            if !self.contains(&connection.from.node_id()) || !self.contains(&connection.to.node_id()) {
+                return Err(ModifyTaskError::CompositeConnectionEscapes { composite_id:  composite_id.clone(),
+                                                                         connection_id: connection_id.clone(), });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Kind of media routed by a connection
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionKind {
+    /// Audio channels, between any nodes
+    #[default]
+    Audio,
+    /// MIDI between instances, channel masks select MIDI outputs and inputs of the models
+    Midi,
+    /// Audio into a sidechain, the destination channel mask selects sidechain inputs of the model
+    Sidechain,
+}
+
+/// Connection between nodes in a task
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct NodeConnection {
+    /// Source node pad
+    pub from:          OutputPadId,
+    /// Destination node pad
+    pub to:            InputPadId,
+    /// Kind of media routed through the connection
+    #[serde(default)]
+    pub kind:          ConnectionKind,
+    /// Source channel mask
+    pub from_channels: ChannelMask,
+    /// Destination channel mask
+    pub to_channels:   ChannelMask,
+    /// Volume adjustment as a factor
+    pub volume:        f64,
+    /// Panning adjustment
+    ///
+    /// Zero is centered, -1 is fully left, 1 is fully right
+    pub pan:           f64,
+    /// Pan law overriding the one set on the task, if not null
+    #[serde(default)]
+    pub pan_law:       Option<PanLaw>,
+    /// Delay applied to audio passing through the connection, in milliseconds
+    ///
+    /// Used to time align parallel processing paths. The engine compensates latencies reported by
+    /// instances automatically, so the delay is added on top of that compensation and only needs to
+    /// express intentional offsets or latencies that instances do not report.
+    #[serde(default)]
+    pub delay_ms:      f64,
+    /// Automation of volume and pan over the task timeline, replacing the static values where set
+    #[serde(default)]
+    pub automation:    ConnectionAutomation,
+}
+
+/// Default maximum volume factor on connections (+12 dB)
+pub const DEFAULT_MAX_CONNECTION_VOLUME: f64 = 4.0;
+
+/// Maximum delay on connections, in milliseconds
+pub const MAX_CONNECTION_DELAY_MS: f64 = 1000.0;
+
+impl NodeConnection {
+    /// Validate that volume is within `0..=max_volume`, pan is within `-1..=1` and delay is within `0..=MAX_CONNECTION_DELAY_MS`
+    ///
+    /// Automated values must be within the same ranges, at well formed automation points.
+    pub fn validate_values(&self, max_volume: f64) -> Result<(), CloudError> {
+        for volume in std::iter::once(self.volume).chain(self.automation.volume.iter().flat_map(AutomationLane::values)) {
+            if !is_volume_valid(volume, max_volume) {
+                return Err(VolumeOutOfRange { volume, max: max_volume });
+            }
+        }
+
+        for pan in std::iter::once(self.pan).chain(self.automation.pan.iter().flat_map(AutomationLane::values)) {
+            if !is_pan_valid(pan) {
+                return Err(PanOutOfRange { pan });
+            }
+        }
+
+        if !self.automation.is_well_formed() {
+            return Err(AutomationMalformed);
+        }
+
+        if !is_delay_valid(self.delay_ms) {
+            return Err(DelayOutOfRange { delay_ms: self.delay_ms,
+                                         max:      MAX_CONNECTION_DELAY_MS, });
+        }
+
+        Ok(())
+    }
+}
+
+/// Automation of connection values over the task timeline
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct ConnectionAutomation {
+    /// Volume as a factor, or null to use the static volume
+    #[serde(default)]
+    pub volume: Option<AutomationLane>,
+    /// Panning, or null to use the static pan
+    #[serde(default)]
+    pub pan:    Option<AutomationLane>,
+}
+
+impl ConnectionAutomation {
+    pub fn is_empty(&self) -> bool {
+        self.volume.is_none() && self.pan.is_none()
+    }
+
+    /// True if all lanes are well formed, see [`AutomationLane::is_well_formed`]
+    pub fn is_well_formed(&self) -> bool {
+        self.volume.iter().chain(self.pan.iter()).all(AutomationLane::is_well_formed)
+    }
+}
+
+/// Automation lanes of instance parameters
+pub type ParameterAutomation = HashMap<ParameterId, AutomationLane>;
+
+/// Values changing over the task timeline
+///
+/// Before the first point the value of the first point applies, after the last point the value of the last point.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct AutomationLane {
+    /// Points sorted by time
+    pub points:        Vec<AutomationPoint>,
+    /// Interpolation between consecutive points
+    #[serde(default)]
+    pub interpolation: AutomationInterpolation,
+}
+
+impl AutomationLane {
+    /// True if the lane has points, at finite and non-negative times that strictly increase and with finite values
+    pub fn is_well_formed(&self) -> bool {
+        !self.points.is_empty()
+        && self.points
+               .iter()
+               .all(|point| point.time.is_finite() && point.time >= 0.0 && point.value.is_finite())
+        && self.points.windows(2).all(|pair| pair[0].time < pair[1].time)
+    }
+
+    /// Values of the points, in order
+    pub fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        self.points.iter().map(|point| point.value)
+    }
+
+    /// Value at a position on the task timeline (in seconds), or None if the lane has no points
+    pub fn value_at(&self, time: f64) -> Option<f64> {
+        let next = self.points.partition_point(|point| point.time <= time);
+        let (before, after) = match (next.checked_sub(1).map(|index| &self.points[index]), self.points.get(next)) {
+            (Some(before), Some(after)) => (before, after),
+            (Some(point), None) | (None, Some(point)) => return Some(point.value),
+            (None, None) => return None,
+        };
+
+        Some(match self.interpolation {
+                 AutomationInterpolation::Linear => {
+                     before.value + (after.value - before.value) * (time - before.time) / (after.time - before.time)
+                 }
+                 AutomationInterpolation::Step => before.value,
+             })
+    }
+}
+
+/// A value at a position on the task timeline
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct AutomationPoint {
+    /// Position on the task timeline in seconds
+    pub time:  f64,
+    /// Value at the position
+    pub value: f64,
+}
+
+/// Interpolation between consecutive points of an automation lane
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationInterpolation {
+    /// Values change linearly between points
+    #[default]
+    Linear,
+    /// Values hold until the next point
+    Step,
+}
+
+/// Returns true if volume is within `0..=max_volume`
+pub fn is_volume_valid(volume: f64, max_volume: f64) -> bool {
+    (0.0..=max_volume).contains(&volume)
+}
+
+/// Returns true if pan is within `-1..=1`
+pub fn is_pan_valid(pan: f64) -> bool {
+    (-1.0..=1.0).contains(&pan)
+}
+
+/// Returns true if delay is within `0..=MAX_CONNECTION_DELAY_MS`
+pub fn is_delay_valid(delay_ms: f64) -> bool {
+    (0.0..=MAX_CONNECTION_DELAY_MS).contains(&delay_ms)
+}
+
+/// Pan law, determining the attenuation of a centered signal when panning
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PanLaw {
+    /// -3 dB at center, sine/cosine taper keeping power constant
+    #[default]
+    ConstantPower,
+    /// -4.5 dB at center, a compromise between constant power and linear
+    Compromise,
+    /// -6 dB at center, linear taper keeping amplitude constant
+    Linear,
+    /// 0 dB at center, only the opposite side is attenuated (balance control)
+    Balance,
+}
+
+impl PanLaw {
+    /// Attenuation of a centered signal, in dB
+    pub fn center_attenuation_db(self) -> f64 {
+        match self {
+            PanLaw::ConstantPower => -3.0,
+            PanLaw::Compromise => -4.5,
+            PanLaw::Linear => -6.0,
+            PanLaw::Balance => 0.0,
         }
     }
 
-    pub fn validate_destination_channels(&self, mask: ChannelMask, model: &Model) -> Result<(), CloudError> {
-        let output_channels = model.get_audio_output_channel_count();
-        let half_output_channels = output_channels / 2;
+    /// Left and right gain factors for a pan position (clamped to `-1..=1`)
+    pub fn gains(self, pan: f64) -> (f64, f64) {
+        let pan = pan.clamp(-1.0, 1.0);
+        let linear = ((1.0 - pan) / 2.0, (1.0 + pan) / 2.0);
+        let angle = (pan + 1.0) * std::f64::consts::FRAC_PI_4;
+        let constant_power = (angle.cos(), angle.sin());
 
-        if matches!(mask, ChannelMask::Mono(i) if i < output_channels) || matches!(mask, ChannelMask::Stereo(i) if i < half_output_channels)
-        {
-            Ok(())
-        } else {
-            Err(ChannelMaskIncompatible { mask:     mask.clone(),
-                                          channels: output_channels, })
+        match self {
+            PanLaw::ConstantPower => constant_power,
+            PanLaw::Compromise => ((linear.0 * constant_power.0).sqrt(), (linear.1 * constant_power.1).sqrt()),
+            PanLaw::Linear => linear,
+            PanLaw::Balance => ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0)),
         }
     }
 }
 
-/// Connection between nodes in a task
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
-pub struct NodeConnection {
-    /// Source node pad
-    pub from:          OutputPadId,
-    /// Destination node pad
-    pub to:            InputPadId,
-    /// Source channel mask
-    pub from_channels: ChannelMask,
-    /// Destination channel mask
-    pub to_channels:   ChannelMask,
-    /// Volume adjustment as a factor
-    pub volume:        f64,
-    /// Panning adjustment
+/// Parameter values of an instance node
+///
+/// Values are partial: parameters or channels that are not set keep the value the instance already has,
+/// which is the model default for a newly created node.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema, From)]
+#[serde(transparent)]
+pub struct InstanceParameters(pub ParameterValues);
+
+impl InstanceParameters {
+    pub fn is_empty(&self) -> bool {
+        self.0.values().all(|value| value.0.iter().all(Option::is_none))
+    }
+
+    /// Apply an update, values set in `update` replace existing values channel by channel
+    pub fn merge(&mut self, update: InstanceParameters) {
+        for (parameter_id, update) in update.0 {
+            let values = &mut self.0.entry(parameter_id).or_default().0;
+            if values.len() < update.0.len() {
+                values.resize(update.0.len(), None);
+            }
+
+            for (value, update) in values.iter_mut().zip(update.0) {
+                if update.is_some() {
+                    *value = update;
+                }
+            }
+        }
+    }
+
+    /// The update that, merged into these parameters, sets all values of `target`
     ///
-    /// Zero is centered, -1 is fully left, 1 is fully right
-    pub pan:           f64,
+    /// Values that are not set in `target` are not cleared by the update.
+    pub fn diff(&self, target: &InstanceParameters) -> InstanceParameters {
+        let mut update = ParameterValues::new();
+
+        for (parameter_id, target) in &target.0 {
+            let current = self.0.get(parameter_id);
+            let values = target.0
+                               .iter()
+                               .enumerate()
+                               .map(|(channel, value)| {
+                                   let existing = current.and_then(|current| current.0.get(channel)).and_then(Option::as_ref);
+                                   value.as_ref().filter(|value| Some(*value) != existing).cloned()
+                               })
+                               .collect::<Vec<_>>();
+
+            if values.iter().any(Option::is_some) {
+                update.insert(parameter_id.clone(), MultiChannelValue(values));
+            }
+        }
+
+        Self(update)
+    }
+
+    /// Check that every value is a declared parameter of the model, within its channel count and options
+    pub fn validate(&self, model: &Model) -> Result<(), ModelValidationError> {
+        model.validate_parameters(&self.0)
+    }
 }
 
-pub type InstanceParameters = serde_json::Value;
 pub type InstanceReports = serde_json::Value;
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct ConnectionValues {
-    pub volume: Option<f64>,
-    pub pan:    Option<f64>,
+    pub volume:  Option<f64>,
+    pub pan:     Option<f64>,
+    /// If not null, override the pan law of the connection
+    #[serde(default)]
+    pub pan_law: Option<PanLaw>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, IsVariant, Unwrap, JsonSchema)]
@@ -569,14 +1464,28 @@ pub enum InputPadId {
     /// Dynamic instance node input
     #[serde(rename = "dynamic")]
     DynamicInstanceInput(DynamicInstanceNodeId),
+
+    /// Recorder node input
+    #[serde(rename = "recorder")]
+    RecorderInput(RecorderNodeId),
 }
 
 impl InputPadId {
+    pub fn node_id(&self) -> TaskNodeId {
+        match self {
+            Self::MixerInput(mixer_id) => TaskNodeId::Mixer(mixer_id.clone()),
+            Self::FixedInstanceInput(fixed_id) => TaskNodeId::FixedInstance(fixed_id.clone()),
+            Self::DynamicInstanceInput(dynamic_id) => TaskNodeId::DynamicInstance(dynamic_id.clone()),
+            Self::RecorderInput(recorder_id) => TaskNodeId::Recorder(recorder_id.clone()),
+        }
+    }
+
     pub fn references(&self, node_id: &TaskNodeId) -> bool {
         match (self, node_id) {
             (Self::MixerInput(mixer_id), TaskNodeId::Mixer(ref_mixer_id)) => mixer_id == ref_mixer_id,
             (Self::FixedInstanceInput(fixed_id), TaskNodeId::FixedInstance(ref_fixed_id)) => fixed_id == ref_fixed_id,
             (Self::DynamicInstanceInput(dynamic_id), TaskNodeId::DynamicInstance(ref_dynamic_id)) => dynamic_id == ref_dynamic_id,
+            (Self::RecorderInput(recorder_id), TaskNodeId::Recorder(ref_recorder_id)) => recorder_id == ref_recorder_id,
             _ => false,
         }
     }
@@ -603,6 +1512,15 @@ pub enum OutputPadId {
 }
 
 impl OutputPadId {
+    pub fn node_id(&self) -> TaskNodeId {
+        match self {
+            Self::MixerOutput(mixer_id) => TaskNodeId::Mixer(mixer_id.clone()),
+            Self::FixedInstanceOutput(fixed_id) => TaskNodeId::FixedInstance(fixed_id.clone()),
+            Self::DynamicInstanceOutput(dynamic_id) => TaskNodeId::DynamicInstance(dynamic_id.clone()),
+            Self::TrackOutput(track_id) => TaskNodeId::Track(track_id.clone()),
+        }
+    }
+
     pub fn references(&self, node_id: &TaskNodeId) -> bool {
         match (self, node_id) {
             (Self::TrackOutput(track_id), TaskNodeId::Track(ref_track_id)) => track_id == ref_track_id,
@@ -631,6 +1549,7 @@ impl std::fmt::Display for InputPadId {
             Self::MixerInput(id) => write!(f, "mixer:{}", id),
             Self::FixedInstanceInput(id) => write!(f, "fixed:{}", id),
             Self::DynamicInstanceInput(id) => write!(f, "dynamic:{}", id),
+            Self::RecorderInput(id) => write!(f, "recorder:{}", id),
         }
     }
 }
@@ -664,18 +1583,32 @@ pub enum NodePadId {
     /// Dynamic instance node input
     #[serde(rename = "in_dynamic")]
     DynamicInstanceInput(DynamicInstanceNodeId),
+
+    /// Recorder node input
+    #[serde(rename = "in_recorder")]
+    RecorderInput(RecorderNodeId),
 }
 
 impl NodePadId {
     pub fn is_input(&self) -> bool {
         matches!(self,
-                 Self::MixerInput(_) | Self::FixedInstanceInput(_) | Self::DynamicInstanceInput(_))
+                 Self::MixerInput(_) | Self::FixedInstanceInput(_) | Self::DynamicInstanceInput(_) | Self::RecorderInput(_))
     }
 
     pub fn is_output(&self) -> bool {
         !self.is_input()
     }
 
+    pub fn node_id(&self) -> TaskNodeId {
+        match self {
+            Self::MixerOutput(id) | Self::MixerInput(id) => TaskNodeId::Mixer(id.clone()),
+            Self::FixedInstanceOutput(id) | Self::FixedInstanceInput(id) => TaskNodeId::FixedInstance(id.clone()),
+            Self::DynamicInstanceOutput(id) | Self::DynamicInstanceInput(id) => TaskNodeId::DynamicInstance(id.clone()),
+            Self::TrackOutput(id) => TaskNodeId::Track(id.clone()),
+            Self::RecorderInput(id) => TaskNodeId::Recorder(id.clone()),
+        }
+    }
+
     pub fn as_ref(&self) -> &Self {
         self
     }
@@ -691,6 +1624,7 @@ impl ToString for NodePadId {
             NodePadId::MixerInput(id) => InputPadId::MixerInput(id.clone()).to_string(),
             NodePadId::FixedInstanceInput(id) => InputPadId::FixedInstanceInput(id.clone()).to_string(),
             NodePadId::DynamicInstanceInput(id) => InputPadId::DynamicInstanceInput(id.clone()).to_string(),
+            NodePadId::RecorderInput(id) => InputPadId::RecorderInput(id.clone()).to_string(),
         }
     }
 }
@@ -712,18 +1646,20 @@ impl From<InputPadId> for NodePadId {
             InputPadId::MixerInput(id) => Self::MixerInput(id),
             InputPadId::FixedInstanceInput(id) => Self::FixedInstanceInput(id),
             InputPadId::DynamicInstanceInput(id) => Self::DynamicInstanceInput(id),
+            InputPadId::RecorderInput(id) => Self::RecorderInput(id),
         }
     }
 }
 
 /// Task node identifier
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema, From)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, JsonSchema, From)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskNodeId {
     Mixer(MixerNodeId),
     FixedInstance(FixedInstanceNodeId),
     DynamicInstance(DynamicInstanceNodeId),
     Track(TrackNodeId),
+    Recorder(RecorderNodeId),
 }
 
 /// Track node specification
@@ -733,9 +1669,46 @@ pub struct TrackNode {
     pub channels: MediaChannels,
     /// Media items present on the track
     pub media:    HashMap<TrackMediaId, TrackMedia>,
+    /// Gain applied to the track output as a factor
+    #[serde(default = "default_track_gain")]
+    pub gain:     f64,
+    /// Muted tracks are silent
+    #[serde(default)]
+    pub mute:     bool,
+    /// When any track of the task is soloed, only soloed tracks are heard
+    #[serde(default)]
+    pub solo:     bool,
+}
+
+fn default_track_gain() -> f64 {
+    1.0
 }
 
 impl TrackNode {
+    /// Track node with the channels, no media and unity gain
+    pub fn new(channels: MediaChannels) -> Self {
+        Self { channels,
+               media: Default::default(),
+               gain: default_track_gain(),
+               mute: false,
+               solo: false }
+    }
+
+    /// Apply the fields set in the update
+    pub fn update(&mut self, update: UpdateTaskTrack) {
+        let UpdateTaskTrack { gain, mute, solo } = update;
+
+        if let Some(gain) = gain {
+            self.gain = gain;
+        }
+        if let Some(mute) = mute {
+            self.mute = mute;
+        }
+        if let Some(solo) = solo {
+            self.solo = solo;
+        }
+    }
+
     pub fn validate_source_channels(&self, mask: ChannelMask) -> Result<(), CloudError> {
         let Self { channels, .. } = self;
 
@@ -811,6 +1784,20 @@ impl TrackMedia {
     }
 }
 
+/// Update of the mixing controls of a track node, fields that are null are not changed
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct UpdateTaskTrack {
+    /// If not null, gain applied to the track output as a factor
+    #[serde(default)]
+    pub gain: Option<f64>,
+    /// If not null, mute or unmute the track
+    #[serde(default)]
+    pub mute: Option<bool>,
+    /// If not null, solo or unsolo the track
+    #[serde(default)]
+    pub solo: Option<bool>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct UpdateTaskTrackMedia {
     pub channels:         Option<MediaChannels>,
@@ -851,6 +1838,11 @@ impl TimeSegment {
     pub fn end(&self) -> f64 {
         self.start + self.length
     }
+
+    /// True if the segments overlap (segments that only touch do not overlap)
+    pub fn intersects(&self, other: &TimeSegment) -> bool {
+        self.start < other.end() && other.start < self.end()
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
@@ -862,6 +1854,94 @@ pub struct TaskPermissions {
     pub audio:      bool,
 }
 
+/// Permissions granted to a secure key on a task
+///
+/// Besides what the key may do, a grant may restrict when, from where and by how many sockets at once the
+/// key may be used. Restrictions default to none, so grants stored before they were introduced are unchanged.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TaskKeyGrant {
+    /// What the key may do
+    #[serde(flatten)]
+    pub permissions:          TaskPermissions,
+    /// Time after which the key no longer grants access, or null if it does not expire
+    #[serde(default)]
+    pub valid_until:          Option<Timestamp>,
+    /// Maximum number of sockets attached with the key at the same time, or null if unlimited
+    #[serde(default)]
+    pub max_attached_sockets: Option<usize>,
+    /// Clients allowed to use the key, or empty to allow any client
+    #[serde(default)]
+    pub allowed_sources:      Vec<KeySource>,
+}
+
+impl From<TaskPermissions> for TaskKeyGrant {
+    fn from(permissions: TaskPermissions) -> Self {
+        Self { permissions,
+               ..Default::default() }
+    }
+}
+
+/// A client allowed to use a secure key
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    /// Clients connecting from an address within the IP network
+    IpNetwork { address: IpAddr, prefix_len: u8 },
+    /// Browser clients sending the `Origin` header, for example `https://app.example.com`
+    Origin { origin: String },
+}
+
+impl KeySource {
+    /// True if the network prefix fits the address, and the origin is a scheme and host without a path
+    pub fn is_well_formed(&self) -> bool {
+        match self {
+            KeySource::IpNetwork { address, prefix_len } => *prefix_len <= address_bits(address),
+            KeySource::Origin { origin } => match origin.split_once("://") {
+                Some((scheme, host)) => !scheme.is_empty() && !host.is_empty() && !host.contains('/'),
+                None => false,
+            },
+        }
+    }
+
+    /// True if a client with the IP address and origin, where known, matches this source
+    pub fn matches(&self, ip: Option<IpAddr>, origin: Option<&str>) -> bool {
+        match (self, ip, origin) {
+            (KeySource::IpNetwork { address: IpAddr::V4(network),
+                                    prefix_len, },
+             Some(IpAddr::V4(ip)),
+             _) => prefix_matches(&network.octets(), &ip.octets(), *prefix_len),
+            (KeySource::IpNetwork { address: IpAddr::V6(network),
+                                    prefix_len, },
+             Some(IpAddr::V6(ip)),
+             _) => prefix_matches(&network.octets(), &ip.octets(), *prefix_len),
+            (KeySource::Origin { origin: allowed }, _, Some(origin)) => allowed.eq_ignore_ascii_case(origin),
+            _ => false,
+        }
+    }
+}
+
+fn address_bits(address: &IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// True if the first `prefix_len` bits are equal, never for a prefix longer than the addresses
+fn prefix_matches(network: &[u8], address: &[u8], prefix_len: u8) -> bool {
+    let prefix_len = prefix_len as usize;
+    if prefix_len > network.len() * 8 {
+        return false;
+    }
+
+    let (bytes, bits) = (prefix_len / 8, prefix_len % 8);
+    if network[..bytes] != address[..bytes] {
+        return false;
+    }
+
+    bits == 0 || (network[bytes] ^ address[bytes]) >> (8 - bits) == 0
+}
+
 impl TaskPermissions {
     pub const fn empty() -> Self {
         Self { structure:  false,
@@ -904,8 +1984,41 @@ impl TaskPermissions {
     }
 }
 
+impl TaskKeyGrant {
+    /// True if the grant has not expired at `time`
+    pub fn is_valid_at(&self, time: Timestamp) -> bool {
+        self.valid_until.is_none_or(|valid_until| time < valid_until)
+    }
+
+    /// True if a client with the IP address and origin, where known, may use the key
+    pub fn allows_source(&self, ip: Option<IpAddr>, origin: Option<&str>) -> bool {
+        self.allowed_sources.is_empty() || self.allowed_sources.iter().any(|source| source.matches(ip, origin))
+    }
+
+    /// True if another socket may attach with the key while `attached` sockets already are
+    pub fn allows_socket(&self, attached: usize) -> bool {
+        self.max_attached_sockets.is_none_or(|max| attached < max)
+    }
+
+    /// Check that the grant has not already expired and that its allowed sources are well formed
+    pub fn validate(&self, key: &SecureKey) -> Result<(), ModifyTaskError> {
+        if let Some(valid_until) = self.valid_until.filter(|_| !self.is_valid_at(now())) {
+            return Err(ModifyTaskError::SecurityExpired { key: key.clone(),
+                                                          valid_until });
+        }
+
+        if let Some(source) = self.allowed_sources.iter().find(|source| !source.is_well_formed()) {
+            return Err(ModifyTaskError::MalformedKeySource { key:        key.clone(),
+                                                             key_source: source.clone(), });
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
+#[serde(remote = "Self", rename_all = "snake_case")]
+#[schemars(rename = "TaskEvent")]
 pub enum TaskEvent {
     PlayState {
         current:           Timestamped<TaskPlayState>,
@@ -916,7 +2029,85 @@ pub enum TaskEvent {
     StreamingPacket {
         packet: StreamingPacket,
     },
-    Deleted,
+    StreamIntegrity {
+        error: StreamIntegrityError,
+    },
+    /// The render finished and the media object was written
+    RenderFinished {
+        render_id: RenderId,
+        /// Media object the render was written to
+        object_id: AppMediaObjectId,
+        /// Loudness analysis of the rendered media, if analyzed
+        #[serde(default)]
+        analysis:  Option<RenderAnalysis>,
+    },
+    /// The render exceeded its maximum duration and was cancelled by the domain
+    RenderTimedOut {
+        render_id:       RenderId,
+        max_duration_ms: u64,
+    },
+    /// Media used by the task could not be decoded cleanly
+    MediaDiagnostics {
+        diagnostics: MediaDiagnostics,
+    },
+    /// Playback or rendering of the task was stopped, emitted along with the play state change
+    Stopped {
+        /// Why the task was stopped
+        reason: StopReason,
+    },
+    /// The task was deleted
+    ///
+    /// A bare `deleted`, as sent by older peers, is read with an unknown reason.
+    Deleted {
+        /// Why the task was deleted
+        #[serde(default = "unknown_delete_reason")]
+        reason: DeleteReason,
+    },
+}
+
+fn unknown_delete_reason() -> DeleteReason {
+    DeleteReason::Unknown
+}
+
+impl Serialize for TaskEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: serde::Serializer
+    {
+        TaskEvent::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TaskEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: serde::Deserializer<'de>
+    {
+        struct TaskEventVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for TaskEventVisitor {
+            type Value = TaskEvent;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a task event")
+            }
+
+            fn visit_str<E>(self, variant: &str) -> Result<Self::Value, E>
+                where E: serde::de::Error
+            {
+                match variant {
+                    "deleted" => Ok(TaskEvent::Deleted { reason: unknown_delete_reason(), }),
+                    variant => TaskEvent::deserialize(serde::de::value::StrDeserializer::new(variant)),
+                }
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+                where A: serde::de::MapAccess<'de>
+            {
+                TaskEvent::deserialize(serde::de::value::MapAccessDeserializer::new(map))
+            }
+        }
+
+        deserializer.deserialize_any(TaskEventVisitor)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -927,7 +2118,13 @@ pub struct StreamingPacket {
     pub audio:             Vec<DiffStamped<CompressedAudio>>,
     pub instance_metering: HashMap<FixedInstanceId, Vec<DiffStamped<serde_json::Value>>>,
     pub pad_metering:      HashMap<NodePadId, Vec<DiffStamped<PadMetering>>>,
+    /// Processing latency in milliseconds of audio arriving at each pad, measured from the task inputs
+    #[serde(default)]
+    pub node_latencies:    HashMap<NodePadId, f64>,
     pub timeline_pos:      f64,
+    /// Timecode at `timeline_pos`, if the play request asked for timecode
+    #[serde(default)]
+    pub timecode:          Option<Timecode>,
     pub streaming_pos:     u64,
     pub serial:            u64,
 }
@@ -938,8 +2135,10 @@ impl Default for StreamingPacket {
                audio:             { Default::default() },
                instance_metering: { Default::default() },
                pad_metering:      { Default::default() },
+               node_latencies:    { Default::default() },
                created_at:        { now() },
                timeline_pos:      { 0.0 },
+               timecode:          { None },
                streaming_pos:     { 0 },
                serial:            { 0 }, }
     }
@@ -950,10 +2149,260 @@ impl StreamingPacket {
         let mut rv = Self::default();
         rv.serial = packet.serial + 1;
         rv.play_id = packet.play_id.clone();
-        rv.streaming_pos = packet.streaming_pos + packet.audio.iter().map(|audio| audio.value().num_samples).sum::<usize>() as u64;
+        rv.streaming_pos = packet.streaming_pos + packet.streamed_samples();
 
         rv
     }
+
+    /// Number of samples the packet advances the stream by
+    ///
+    /// Every monitored mixer streams the same time span, so this is the longest audio of a single mixer.
+    fn streamed_samples(&self) -> u64 {
+        let mut samples = HashMap::<&MixerNodeId, u64>::new();
+        for audio in &self.audio {
+            *samples.entry(&audio.value().mixer_id).or_default() += audio.value().num_samples as u64;
+        }
+
+        samples.into_values().max().unwrap_or_default()
+    }
+
+    /// Audio of a single monitored mixer, in stream order
+    pub fn audio_of<'a>(&'a self, mixer_id: &'a MixerNodeId) -> impl Iterator<Item = &'a CompressedAudio> + 'a {
+        self.audio
+            .iter()
+            .map(DiffStamped::value)
+            .filter(move |audio| &audio.mixer_id == mixer_id)
+    }
+
+    /// Check that all audio frames in the packet match their checksums
+    pub fn verify_checksums(&self) -> Result<(), StreamIntegrityError> {
+        match self.audio.iter().find(|audio| !audio.value().is_checksum_valid()) {
+            Some(audio) => Err(StreamIntegrityError::ChecksumMismatch { play_id:    self.play_id,
+                                                                        serial:     self.serial,
+                                                                        stream_pos: audio.value().stream_pos, }),
+            None => Ok(()),
+        }
+    }
+
+    /// Start building a packet of a play id
+    pub fn builder(play_id: PlayId) -> StreamingPacketBuilder {
+        StreamingPacketBuilder::new(play_id)
+    }
+
+    pub fn play_id(&self) -> PlayId {
+        self.play_id
+    }
+
+    pub fn created_at(&self) -> Timestamp {
+        self.created_at
+    }
+
+    pub fn audio(&self) -> &[DiffStamped<CompressedAudio>] {
+        &self.audio
+    }
+
+    pub fn instance_metering(&self) -> &HashMap<FixedInstanceId, Vec<DiffStamped<serde_json::Value>>> {
+        &self.instance_metering
+    }
+
+    pub fn pad_metering(&self) -> &HashMap<NodePadId, Vec<DiffStamped<PadMetering>>> {
+        &self.pad_metering
+    }
+
+    pub fn node_latencies(&self) -> &HashMap<NodePadId, f64> {
+        &self.node_latencies
+    }
+
+    /// Metering of track outputs, after track gain, mute and solo are applied
+    pub fn track_metering(&self) -> impl Iterator<Item = (&TrackNodeId, &[DiffStamped<PadMetering>])> {
+        self.pad_metering.iter().filter_map(|(pad_id, metering)| match pad_id {
+                                    NodePadId::TrackOutput(track_id) => Some((track_id, metering.as_slice())),
+                                    _ => None,
+                                })
+    }
+
+    pub fn timeline_pos(&self) -> f64 {
+        self.timeline_pos
+    }
+
+    pub fn timecode(&self) -> Option<Timecode> {
+        self.timecode
+    }
+
+    pub fn streaming_pos(&self) -> u64 {
+        self.streaming_pos
+    }
+
+    pub fn serial(&self) -> u64 {
+        self.serial
+    }
+}
+
+/// Maximum number of metering entries per instance or pad in a single streaming packet
+pub const MAX_PACKET_METERING_LEN: usize = 256;
+
+/// Builds streaming packets outside of this crate while enforcing their invariants
+#[derive(Debug, Clone)]
+pub struct StreamingPacketBuilder {
+    packet:          StreamingPacket,
+    previous_serial: Option<u64>,
+}
+
+impl StreamingPacketBuilder {
+    pub fn new(play_id: PlayId) -> Self {
+        Self { packet:          StreamingPacket { play_id,
+                                                  ..Default::default() },
+               previous_serial: None, }
+    }
+
+    /// Continue the stream of a previous packet, with the next serial and stream position
+    pub fn following(previous: &StreamingPacket) -> Self {
+        Self { packet:          StreamingPacket::next_of(previous),
+               previous_serial: Some(previous.serial), }
+    }
+
+    pub fn with_serial(mut self, serial: u64) -> Self {
+        self.packet.serial = serial;
+        self
+    }
+
+    pub fn with_created_at(mut self, created_at: Timestamp) -> Self {
+        self.packet.created_at = created_at;
+        self
+    }
+
+    pub fn with_timeline_pos(mut self, timeline_pos: f64) -> Self {
+        self.packet.timeline_pos = timeline_pos;
+        self
+    }
+
+    pub fn with_timecode(mut self, timecode: Timecode) -> Self {
+        self.packet.timecode = Some(timecode);
+        self
+    }
+
+    pub fn with_streaming_pos(mut self, streaming_pos: u64) -> Self {
+        self.packet.streaming_pos = streaming_pos;
+        self
+    }
+
+    pub fn with_audio(mut self, audio: DiffStamped<CompressedAudio>) -> Self {
+        self.packet.audio.push(audio);
+        self
+    }
+
+    pub fn with_instance_metering(mut self, instance_id: FixedInstanceId, metering: DiffStamped<serde_json::Value>) -> Self {
+        self.packet.instance_metering.entry(instance_id).or_default().push(metering);
+        self
+    }
+
+    pub fn with_pad_metering(mut self, pad_id: NodePadId, metering: DiffStamped<PadMetering>) -> Self {
+        self.packet.pad_metering.entry(pad_id).or_default().push(metering);
+        self
+    }
+
+    pub fn with_node_latency(mut self, pad_id: NodePadId, latency_ms: f64) -> Self {
+        self.packet.node_latencies.insert(pad_id, latency_ms);
+        self
+    }
+
+    /// Validate and return the packet
+    ///
+    /// The serial must be greater than the serial of the packet this one is following, and no
+    /// instance or pad may have more than [`MAX_PACKET_METERING_LEN`] metering entries.
+    pub fn build(self) -> Result<StreamingPacket, StreamingPacketError> {
+        let Self { packet, previous_serial } = self;
+
+        if let Some(previous) = previous_serial {
+            if packet.serial <= previous {
+                return Err(StreamingPacketError::SerialNotMonotonic { play_id: packet.play_id,
+                                                                      previous,
+                                                                      serial: packet.serial });
+            }
+        }
+
+        let metering_lens = packet.instance_metering
+                                  .values()
+                                  .map(Vec::len)
+                                  .chain(packet.pad_metering.values().map(Vec::len));
+
+        for len in metering_lens {
+            if len > MAX_PACKET_METERING_LEN {
+                return Err(StreamingPacketError::MeteringTooLarge { play_id: packet.play_id,
+                                                                    len,
+                                                                    max: MAX_PACKET_METERING_LEN });
+            }
+        }
+
+        Ok(packet)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Error, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum StreamingPacketError {
+    #[error("Stream {play_id} packet serial {serial} does not follow previous serial {previous}")]
+    SerialNotMonotonic { play_id: PlayId, previous: u64, serial: u64 },
+
+    #[error("Stream {play_id} packet has {len} metering entries for a single source, at most {max} are allowed")]
+    MeteringTooLarge { play_id: PlayId, len: usize, max: usize },
+}
+
+/// Tracks serial numbers of streaming packets of a task to detect lost or repeated packets
+#[derive(Debug, Default, Clone)]
+pub struct PacketSequenceTracker {
+    play_id:     Option<PlayId>,
+    /// None once the play reached serial `u64::MAX`, which ends the play
+    next_serial: Option<u64>,
+}
+
+impl PacketSequenceTracker {
+    /// Track a received packet
+    ///
+    /// Packets of a new play id restart the sequence. After a gap the tracker continues from the
+    /// received packet, so every gap is reported only once. Serial `u64::MAX` is the last packet of a
+    /// play, any later packet of the same play is reported as a duplicate.
+    pub fn track(&mut self, packet: &StreamingPacket) -> Result<(), StreamIntegrityError> {
+        if self.play_id != Some(packet.play_id) {
+            self.play_id = Some(packet.play_id);
+            self.next_serial = packet.serial.checked_add(1);
+            return Ok(());
+        }
+
+        let expected = match self.next_serial {
+            Some(expected) if packet.serial >= expected => expected,
+            _ => {
+                return Err(StreamIntegrityError::Duplicate { play_id: packet.play_id,
+                                                             serial:  packet.serial, });
+            }
+        };
+
+        self.next_serial = packet.serial.checked_add(1);
+        if packet.serial > expected {
+            return Err(StreamIntegrityError::Gap { play_id: packet.play_id,
+                                                   expected,
+                                                   received: packet.serial });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Error, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum StreamIntegrityError {
+    #[error("Stream {play_id} skipped packets, expected serial {expected} but received {received}")]
+    Gap { play_id: PlayId, expected: u64, received: u64 },
+
+    #[error("Stream {play_id} repeated packet with serial {serial}")]
+    Duplicate { play_id: PlayId, serial: u64 },
+
+    #[error("Stream {play_id} packet {serial} has corrupted audio at stream position {stream_pos}")]
+    ChecksumMismatch {
+        play_id:    PlayId,
+        serial:     u64,
+        stream_pos: u64,
+    },
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
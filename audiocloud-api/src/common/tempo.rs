@@ -0,0 +1,142 @@
+//! Tempo and time signature changes over the task timeline, used to generate the click
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::MixerNodeId;
+
+/// Slowest supported tempo, in beats per minute
+pub const MIN_TEMPO_BPM: f64 = 20.0;
+
+/// Fastest supported tempo, in beats per minute
+pub const MAX_TEMPO_BPM: f64 = 400.0;
+
+/// Tempo and time signature changes over the task timeline
+///
+/// The first point must be at the start of the timeline. Each point applies until the next one, tempo
+/// does not ramp between points. An empty map means the task has no tempo.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct TempoMap {
+    /// Points sorted by time
+    #[serde(default)]
+    pub points: Vec<TempoPoint>,
+}
+
+/// A tempo and time signature starting at a position on the task timeline
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct TempoPoint {
+    /// Position on the task timeline in seconds
+    pub time:           f64,
+    /// Tempo in beats per minute
+    pub bpm:            f64,
+    /// Time signature
+    #[serde(default)]
+    pub time_signature: TimeSignature,
+}
+
+/// Time signature, for example 3/4 or 6/8
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TimeSignature {
+    /// Beats per bar
+    pub numerator:   u8,
+    /// Note value of a beat, a power of two
+    pub denominator: u8,
+}
+
+impl Default for TimeSignature {
+    fn default() -> Self {
+        Self { numerator:   4,
+               denominator: 4, }
+    }
+}
+
+impl TimeSignature {
+    /// True if there is at least one beat per bar and the note value is a power of two up to 64
+    pub fn is_valid(self) -> bool {
+        self.numerator > 0 && self.denominator.is_power_of_two() && self.denominator <= 64
+    }
+}
+
+impl TempoMap {
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Check that points start at zero, strictly increase in time and have valid tempos and time signatures
+    pub fn validate(&self) -> Result<(), TempoMapError> {
+        if let Some(first) = self.points.first() {
+            if first.time != 0.0 {
+                return Err(TempoMapError::NotStartingAtZero { time: first.time });
+            }
+        }
+
+        for point in &self.points {
+            if !point.time.is_finite() {
+                return Err(TempoMapError::NotIncreasing { time: point.time });
+            }
+
+            if !(MIN_TEMPO_BPM..=MAX_TEMPO_BPM).contains(&point.bpm) {
+                return Err(TempoMapError::TempoOutOfRange { bpm: point.bpm });
+            }
+
+            if !point.time_signature.is_valid() {
+                let TimeSignature { numerator, denominator } = point.time_signature;
+                return Err(TempoMapError::InvalidTimeSignature { numerator, denominator });
+            }
+        }
+
+        if let Some(pair) = self.points.windows(2).find(|pair| pair[0].time >= pair[1].time) {
+            return Err(TempoMapError::NotIncreasing { time: pair[1].time });
+        }
+
+        Ok(())
+    }
+
+    /// Point in effect at a position on the task timeline (in seconds), or None if the map is empty
+    pub fn point_at(&self, time: f64) -> Option<&TempoPoint> {
+        let next = self.points.partition_point(|point| point.time <= time);
+
+        self.points.get(next.saturating_sub(1))
+    }
+
+    /// Number of beats from the start of the timeline to a position (in seconds), or None if the map is empty
+    pub fn beats_at(&self, time: f64) -> Option<f64> {
+        let mut beats = 0.0;
+        let mut points = self.points.iter().peekable();
+
+        while let Some(point) = points.next() {
+            let end = points.peek().map(|next| next.time.min(time)).unwrap_or(time);
+            beats += (end - point.time).max(0.0) * point.bpm / 60.0;
+        }
+
+        (!self.is_empty()).then_some(beats)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Error, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum TempoMapError {
+    #[error("Tempo map must start at the start of the timeline, first point is at {time}")]
+    NotStartingAtZero { time: f64 },
+
+    #[error("Tempo map points must be at finite, strictly increasing times, point at {time} is not")]
+    NotIncreasing { time: f64 },
+
+    #[error("Tempo {bpm} BPM is out of range, must be between {MIN_TEMPO_BPM} and {MAX_TEMPO_BPM}")]
+    TempoOutOfRange { bpm: f64 },
+
+    #[error("Time signature {numerator}/{denominator} is invalid")]
+    InvalidTimeSignature { numerator: u8, denominator: u8 },
+}
+
+/// Click (metronome) played while monitoring a task, following its tempo map
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct PlayClick {
+    /// True if the click is heard
+    pub enabled:  bool,
+    /// Gain of the click as a factor
+    pub gain:     f64,
+    /// Mixer node the click is routed into
+    pub mixer_id: MixerNodeId,
+}
@@ -1,7 +1,12 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+
 use chrono::{DateTime, Duration, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::CloudError;
+
 pub type Timestamp = DateTime<Utc>;
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq, Hash, JsonSchema)]
@@ -31,14 +36,48 @@ impl TimeRange {
         self.from < self.to
     }
 
+    /// Check that the range starts before it ends, shared by task creation and time adjustment
+    pub fn validate(&self) -> Result<(), CloudError> {
+        if self.valid() {
+            Ok(())
+        } else {
+            Err(CloudError::TimeMalformed)
+        }
+    }
+
     pub fn len(&self) -> Duration {
         self.to - self.from
     }
 
+    pub fn duration(&self) -> Duration {
+        self.len()
+    }
+
     pub fn intersects(&self, other: &TimeRange) -> bool {
         self.to > other.from && self.from < other.to
     }
 
+    /// True if the other range lies entirely within this one
+    pub fn covers(&self, other: &TimeRange) -> bool {
+        self.from <= other.from && other.to <= self.to
+    }
+
+    /// Overlapping part of both ranges, or None if they do not intersect
+    pub fn intersection(&self, other: &TimeRange) -> Option<TimeRange> {
+        self.intersects(other)
+            .then(|| TimeRange::new(self.from.max(other.from), self.to.min(other.to)))
+    }
+
+    /// Smallest range covering both ranges, or None if they neither overlap nor touch
+    pub fn union(&self, other: &TimeRange) -> Option<TimeRange> {
+        (self.to >= other.from && self.from <= other.to).then(|| TimeRange::new(self.from.min(other.from), self.to.max(other.to)))
+    }
+
+    /// Split the range in two at a timestamp strictly inside it
+    pub fn split_at(&self, ts: Timestamp) -> Option<(TimeRange, TimeRange)> {
+        (self.from < ts && ts < self.to).then(|| (TimeRange::new(self.from, ts), TimeRange::new(ts, self.to)))
+    }
+
     pub fn contains(&self, ts: Timestamp) -> bool {
         match ts {
             ts if ts > self.to => false,
@@ -102,3 +141,145 @@ impl<T> Default for Timestamped<T> where T: Default
         Timestamped::new(T::default())
     }
 }
+
+/// Difference stamped in milliseconds since a common epoch, in order to pack most efficiently
+/// The epoch in InstancePacket is the created_at field of SessionPacket
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct DiffStamped<T>(usize, T);
+
+impl<T> DiffStamped<T> {
+    pub fn new(timestamp: Timestamp, value: T) -> Self {
+        (timestamp, value).into()
+    }
+
+    pub fn value(&self) -> &T {
+        &self.1
+    }
+
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.1
+    }
+}
+
+impl<T> From<(Timestamp, T)> for DiffStamped<T> {
+    fn from(value: (Timestamp, T)) -> Self {
+        let (timestamp, value) = value;
+        let diff = Utc::now() - timestamp;
+        Self(diff.num_milliseconds() as usize, value)
+    }
+}
+
+/// An event with a sequence number and the time it was emitted, for delivery over at-least-once transports
+///
+/// Sequence numbers are assigned by a single emitter, without gaps, starting at zero.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Sequenced<T> {
+    /// Sequence number of the event
+    pub seq:   u64,
+    /// Time the event was emitted
+    pub at:    Timestamp,
+    /// The event
+    pub event: T,
+}
+
+/// Stamps emitted events with consecutive sequence numbers
+#[derive(Clone, Debug, Default)]
+pub struct EventSequencer {
+    next: u64,
+}
+
+impl EventSequencer {
+    /// Continue a sequence, for example after restarting an emitter that persisted its last sequence number
+    pub fn starting_at(next: u64) -> Self {
+        Self { next }
+    }
+
+    /// Stamp an event with the next sequence number and the current time
+    pub fn stamp<T>(&mut self, event: T) -> Sequenced<T> {
+        self.stamp_at(event, now())
+    }
+
+    /// Stamp an event with the next sequence number and the time it was emitted
+    ///
+    /// The sequence wraps to zero after `u64::MAX`, which trackers see as a [`SequenceCheck::Reset`].
+    pub fn stamp_at<T>(&mut self, event: T, at: Timestamp) -> Sequenced<T> {
+        let seq = self.next;
+        self.next = self.next.wrapping_add(1);
+
+        Sequenced { seq, at, event }
+    }
+}
+
+/// Largest number of skipped sequence numbers tracked as a gap, larger jumps reset the tracker
+pub const MAX_SEQUENCE_GAP: u64 = 4096;
+
+/// How a received sequence number relates to those received before
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SequenceCheck {
+    /// The next expected sequence number, or the first one received
+    InOrder,
+    /// Sequence numbers were skipped, they may still arrive later
+    Gap { missing: Range<u64> },
+    /// A skipped sequence number arrived after later ones
+    Late,
+    /// The sequence number was received before
+    Duplicate,
+    /// The sequence number is more than [`MAX_SEQUENCE_GAP`] away from the last one, for example because the
+    /// emitter restarted, so tracking starts over from it
+    Reset { last: u64 },
+}
+
+/// Tracks received sequence numbers, to detect gaps and duplicates
+///
+/// At most [`MAX_SEQUENCE_GAP`] missing sequence numbers are remembered, so steady loss does not grow the tracker.
+#[derive(Clone, Debug, Default)]
+pub struct SequenceTracker {
+    last:    Option<u64>,
+    missing: BTreeSet<u64>,
+}
+
+impl SequenceTracker {
+    /// Record a received sequence number
+    pub fn receive(&mut self, seq: u64) -> SequenceCheck {
+        let last = match self.last {
+            Some(last) => last,
+            None => {
+                self.last = Some(seq);
+                return SequenceCheck::InOrder;
+            }
+        };
+
+        if seq.abs_diff(last) > MAX_SEQUENCE_GAP && !self.missing.contains(&seq) {
+            self.last = Some(seq);
+            self.missing.clear();
+            return SequenceCheck::Reset { last };
+        }
+
+        if seq > last {
+            // seq is larger than last, so last + 1 does not overflow
+            let missing = last + 1..seq;
+            self.last = Some(seq);
+            self.missing.extend(missing.clone());
+            self.missing = self.missing.split_off(&seq.saturating_sub(MAX_SEQUENCE_GAP));
+
+            return if missing.is_empty() {
+                SequenceCheck::InOrder
+            } else {
+                SequenceCheck::Gap { missing }
+            };
+        }
+
+        if self.missing.remove(&seq) {
+            SequenceCheck::Late
+        } else {
+            SequenceCheck::Duplicate
+        }
+    }
+
+    /// Sequence numbers skipped and not received since
+    ///
+    /// Only sequence numbers within [`MAX_SEQUENCE_GAP`] of the last one are tracked, older ones are considered lost.
+    pub fn missing(&self) -> impl Iterator<Item = u64> + '_ {
+        self.missing.iter().copied()
+    }
+}
@@ -0,0 +1,213 @@
+//! SMPTE timecode, used to align the task timeline with external video or audio material
+
+use std::fmt::{Display, Formatter};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Timecode frame rate, including drop-frame variants
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+pub enum TimecodeFormat {
+    /// 23.976 frames per second (film pulled down to NTSC)
+    #[serde(rename = "23.976")]
+    Fps23_976,
+    /// 24 frames per second (film)
+    #[serde(rename = "24")]
+    Fps24,
+    /// 25 frames per second (PAL)
+    #[serde(rename = "25")]
+    Fps25,
+    /// 29.97 frames per second, non drop-frame (NTSC)
+    #[serde(rename = "29.97")]
+    Fps29_97,
+    /// 29.97 frames per second, drop-frame (NTSC)
+    #[serde(rename = "29.97df")]
+    Fps29_97DropFrame,
+    /// 30 frames per second
+    #[serde(rename = "30")]
+    Fps30,
+    /// 50 frames per second
+    #[serde(rename = "50")]
+    Fps50,
+    /// 59.94 frames per second, non drop-frame
+    #[serde(rename = "59.94")]
+    Fps59_94,
+    /// 59.94 frames per second, drop-frame
+    #[serde(rename = "59.94df")]
+    Fps59_94DropFrame,
+    /// 60 frames per second
+    #[serde(rename = "60")]
+    Fps60,
+}
+
+impl TimecodeFormat {
+    /// Number of frame labels per second
+    pub fn nominal_fps(self) -> u64 {
+        use TimecodeFormat::*;
+
+        match self {
+            Fps23_976 | Fps24 => 24,
+            Fps25 => 25,
+            Fps29_97 | Fps29_97DropFrame | Fps30 => 30,
+            Fps50 => 50,
+            Fps59_94 | Fps59_94DropFrame | Fps60 => 60,
+        }
+    }
+
+    /// Actual number of frames per second
+    pub fn fps(self) -> f64 {
+        use TimecodeFormat::*;
+
+        match self {
+            Fps23_976 | Fps29_97 | Fps29_97DropFrame | Fps59_94 | Fps59_94DropFrame => self.nominal_fps() as f64 * 1000.0 / 1001.0,
+            Fps24 | Fps25 | Fps30 | Fps50 | Fps60 => self.nominal_fps() as f64,
+        }
+    }
+
+    /// True if frame labels are skipped to keep timecode in sync with wall clock time
+    pub fn is_drop_frame(self) -> bool {
+        matches!(self, Self::Fps29_97DropFrame | Self::Fps59_94DropFrame)
+    }
+
+    /// Number of frame labels skipped at the start of each minute, except every tenth minute
+    pub fn dropped_frames(self) -> u64 {
+        if self.is_drop_frame() {
+            self.nominal_fps() / 15
+        } else {
+            0
+        }
+    }
+}
+
+/// A position expressed as SMPTE timecode
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+pub struct Timecode {
+    /// Number of frames since 00:00:00:00
+    pub frames: u64,
+    /// Frame rate of the timecode
+    pub format: TimecodeFormat,
+}
+
+impl Timecode {
+    pub fn new(format: TimecodeFormat, frames: u64) -> Self {
+        Self { frames, format }
+    }
+
+    /// Create timecode from hours, minutes, seconds and frames labels
+    pub fn from_hmsf(format: TimecodeFormat, hours: u64, minutes: u64, seconds: u64, frames: u64) -> Result<Self, TimecodeError> {
+        let fps = format.nominal_fps();
+        let dropped = format.dropped_frames();
+
+        if minutes >= 60 || seconds >= 60 || frames >= fps {
+            return Err(TimecodeError::OutOfRange { hours,
+                                                   minutes,
+                                                   seconds,
+                                                   frames });
+        }
+
+        if seconds == 0 && frames < dropped && !minutes.is_multiple_of(10) {
+            return Err(TimecodeError::DroppedFrame { hours,
+                                                     minutes,
+                                                     seconds,
+                                                     frames });
+        }
+
+        let out_of_range = || TimecodeError::OutOfRange { hours,
+                                                          minutes,
+                                                          seconds,
+                                                          frames };
+
+        let total_minutes = hours.checked_mul(60).and_then(|hours| hours.checked_add(minutes)).ok_or_else(out_of_range)?;
+        let frames = total_minutes.checked_mul(60)
+                                  .and_then(|total_seconds| total_seconds.checked_add(seconds))
+                                  .and_then(|total_seconds| total_seconds.checked_mul(fps))
+                                  .and_then(|total_frames| total_frames.checked_add(frames))
+                                  .ok_or_else(out_of_range)?
+                     - dropped * (total_minutes - total_minutes / 10);
+
+        Ok(Self::new(format, frames))
+    }
+
+    /// Parse timecode in `HH:MM:SS:FF` form (`;` is accepted as the frames separator)
+    pub fn parse(format: TimecodeFormat, timecode: &str) -> Result<Self, TimecodeError> {
+        let malformed = || TimecodeError::Malformed { timecode: timecode.to_owned(), };
+
+        let parts = timecode.split([':', ';'])
+                            .map(|part| part.parse::<u64>().map_err(|_| malformed()))
+                            .collect::<Result<Vec<_>, _>>()?;
+
+        match parts.as_slice() {
+            [hours, minutes, seconds, frames] => Self::from_hmsf(format, *hours, *minutes, *seconds, *frames),
+            _ => Err(malformed()),
+        }
+    }
+
+    /// Hours, minutes, seconds and frames labels of the timecode
+    pub fn to_hmsf(&self) -> (u64, u64, u64, u64) {
+        let fps = self.format.nominal_fps();
+        let dropped = self.format.dropped_frames();
+        let mut frames = self.frames;
+
+        if dropped > 0 {
+            let frames_per_minute = fps * 60 - dropped;
+            let frames_per_ten_minutes = fps * 600 - dropped * 9;
+            let tens = frames / frames_per_ten_minutes;
+            let remainder = frames % frames_per_ten_minutes;
+
+            frames += dropped * 9 * tens;
+            if remainder >= dropped {
+                frames += dropped * ((remainder - dropped) / frames_per_minute);
+            }
+        }
+
+        (frames / (fps * 3600), (frames / (fps * 60)) % 60, (frames / fps) % 60, frames % fps)
+    }
+
+    /// Create timecode from a position in seconds, rounding to the nearest frame
+    pub fn from_seconds(format: TimecodeFormat, seconds: f64) -> Self {
+        Self::new(format, (seconds.max(0.0) * format.fps()).round() as u64)
+    }
+
+    /// Position of the timecode in seconds
+    pub fn to_seconds(&self) -> f64 {
+        self.frames as f64 / self.format.fps()
+    }
+
+    /// Timecode shifted by a number of seconds (saturating at zero)
+    pub fn offset_by_seconds(&self, seconds: f64) -> Self {
+        Self::from_seconds(self.format, self.to_seconds() + seconds)
+    }
+}
+
+impl Display for Timecode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (hours, minutes, seconds, frames) = self.to_hmsf();
+        let separator = if self.format.is_drop_frame() { ';' } else { ':' };
+
+        write!(f, "{hours:02}:{minutes:02}:{seconds:02}{separator}{frames:02}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Error, JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum TimecodeError {
+    #[error("Timecode {timecode} is malformed, expected HH:MM:SS:FF")]
+    Malformed { timecode: String },
+
+    #[error("Timecode {hours:02}:{minutes:02}:{seconds:02}:{frames:02} has a component out of range")]
+    OutOfRange {
+        hours:   u64,
+        minutes: u64,
+        seconds: u64,
+        frames:  u64,
+    },
+
+    #[error("Timecode {hours:02}:{minutes:02}:{seconds:02}:{frames:02} is skipped in drop-frame timecode")]
+    DroppedFrame {
+        hours:   u64,
+        minutes: u64,
+        seconds: u64,
+        frames:  u64,
+    },
+}
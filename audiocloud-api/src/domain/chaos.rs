@@ -0,0 +1,91 @@
+//! Failure injection for testing the robustness of domains and their clients
+//!
+//! Only available with the `chaos` feature. Domains should only accept chaos commands in staging
+//! environments and only from administrators.
+
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "openapi")]
+use utoipa::OpenApi;
+
+use crate::{merge_schemas, AppTaskId};
+
+/// Failure to simulate on a domain
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChaosCommand {
+    /// Drop the next streaming packets instead of delivering them
+    DropPackets {
+        /// Task whose packets are dropped, or null for packets of any task
+        task_id: Option<AppTaskId>,
+        /// Number of packets to drop
+        count:   usize,
+    },
+    /// Delay delivery of domain events
+    DelayEvents {
+        /// Delay added to every event, in milliseconds
+        delay_ms:    u64,
+        /// For how long events are delayed, in milliseconds
+        duration_ms: u64,
+    },
+    /// Fail the next render of a task
+    FailNextRender {
+        /// Task whose next render fails
+        task_id: AppTaskId,
+    },
+}
+
+/// Response to injecting or clearing failures
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChaosUpdated {
+    /// The failure is scheduled to be simulated
+    Injected {
+        /// Number of failures currently scheduled
+        pending: usize,
+    },
+    /// All scheduled failures were cleared
+    Cleared,
+}
+
+/// Inject a failure
+///
+/// Schedule a failure to be simulated by the domain. Requires administrator credentials and is
+/// only available on domains built with failure injection support.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/chaos",
+  request_body = ChaosCommand,
+  responses(
+    (status = 200, description = "Success", body = ChaosUpdated),
+    (status = 401, description = "Not authorized", body = DomainError),
+    (status = 403, description = "Not an administrator", body = DomainError),
+  ))]
+pub(crate) fn inject_failure() {}
+
+/// Clear injected failures
+///
+/// Clear all failures scheduled to be simulated. Requires administrator credentials.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  delete,
+  path = "/v1/chaos",
+  responses(
+    (status = 200, description = "Success", body = ChaosUpdated),
+    (status = 401, description = "Not authorized", body = DomainError),
+    (status = 403, description = "Not an administrator", body = DomainError),
+  ))]
+pub(crate) fn clear_failures() {}
+
+#[cfg(feature = "openapi")]
+#[derive(OpenApi)]
+#[openapi(paths(inject_failure, clear_failures))]
+pub struct ChaosApi;
+
+pub fn schemas() -> RootSchema {
+    merge_schemas([schema_for!(ChaosCommand),
+                   schema_for!(ChaosUpdated),
+                   schema_for!(super::DomainError)].into_iter())
+}
@@ -9,17 +9,27 @@ use schemars::schema::RootSchema;
 use schemars::{schema_for, JsonSchema};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+#[cfg(feature = "openapi")]
 use utoipa::OpenApi;
 
-use crate::audio_engine::EngineError;
-use crate::common::change::{DesiredTaskPlayState, ModifyTaskSpec};
-use crate::common::task::TaskPermissions;
+use crate::audio_engine::{EngineError, EngineLoad};
+use crate::cloud::domains::{DomainConfigUpdate, DomainEventKind, EventFilter, EventSeverity, LicensePool};
+use crate::cloud::tasks::TaskRunSummary;
+use crate::common::change::{DesiredTaskPlayState, ModifyTaskSpec, TaskSpecDiff};
+use crate::common::media::{DeleteReason, MediaJobEvent, MediaJobKind};
 use crate::common::task::TaskSpec;
+use crate::common::task::{TaskKeyGrant, TaskPermissions};
 use crate::domain::DomainError::AuthenticationFailed;
 use crate::instance_driver::InstanceDriverError;
-use crate::newtypes::{AppTaskId, SecureKey};
-use crate::{merge_schemas, AppId, AppMediaObjectId, EngineId, FixedInstanceId, InstanceEvent, ModifyTaskError, PlayId, RequestId, SocketId, Task, TaskEvent, TaskId, TaskPlayState, TaskPlayStateSummary, ClientSocketId};
-
+use crate::newtypes::{AppTaskId, SecureKey, TaskSnapshotId};
+use crate::{
+    merge_schemas, AppId, AppMediaObjectId, ClientSocketId, CodedError, EngineId, ErrorCode, FixedInstanceId, InstanceConnectionState,
+    InstanceEvent, ModifyTaskError, PlayId, RenderId, RequestId, Sequenced, SocketId, Task, TaskEvent, TaskId, TaskPlayState,
+    TaskPlayStateSummary, Timestamp,
+};
+
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod streaming;
 pub mod tasks;
 
@@ -35,10 +45,15 @@ pub enum DomainCommand {
         version:        u64,
         spec:           TaskSpec,
     },
+    PatchSpec {
+        app_session_id: AppTaskId,
+        version:        u64,
+        diff:           TaskSpecDiff,
+    },
     SetSecurity {
         app_session_id: AppTaskId,
         version:        u64,
-        security:       HashMap<SecureKey, TaskPermissions>,
+        security:       HashMap<SecureKey, TaskKeyGrant>,
     },
     Modify {
         app_session_id: AppTaskId,
@@ -52,18 +67,27 @@ pub enum DomainCommand {
     },
     Delete {
         app_session_id: AppTaskId,
+        #[serde(default)]
+        reason:         DeleteReason,
+    },
+    /// Apply changes to the domain configuration without restarting
+    UpdateConfig {
+        update: DomainConfigUpdate,
     },
 }
 
 impl DomainCommand {
-    pub fn get_session_id(&self) -> &AppTaskId {
+    /// Task the command applies to, or null for commands applying to the whole domain
+    pub fn get_session_id(&self) -> Option<&AppTaskId> {
         match self {
-            DomainCommand::Create { app_session_id, .. } => app_session_id,
-            DomainCommand::SetSpec { app_session_id, .. } => app_session_id,
-            DomainCommand::SetSecurity { app_session_id, .. } => app_session_id,
-            DomainCommand::Modify { app_session_id, .. } => app_session_id,
-            DomainCommand::SetDesiredPlayState { app_session_id, .. } => app_session_id,
-            DomainCommand::Delete { app_session_id, .. } => app_session_id,
+            DomainCommand::Create { app_session_id, .. } => Some(app_session_id),
+            DomainCommand::SetSpec { app_session_id, .. } => Some(app_session_id),
+            DomainCommand::PatchSpec { app_session_id, .. } => Some(app_session_id),
+            DomainCommand::SetSecurity { app_session_id, .. } => Some(app_session_id),
+            DomainCommand::Modify { app_session_id, .. } => Some(app_session_id),
+            DomainCommand::SetDesiredPlayState { app_session_id, .. } => Some(app_session_id),
+            DomainCommand::Delete { app_session_id, .. } => Some(app_session_id),
+            DomainCommand::UpdateConfig { .. } => None,
         }
     }
 
@@ -71,10 +95,12 @@ impl DomainCommand {
         match self {
             DomainCommand::Create { .. } => "create",
             DomainCommand::SetSpec { .. } => "set_spec",
+            DomainCommand::PatchSpec { .. } => "patch_spec",
             DomainCommand::SetSecurity { .. } => "set_security",
             DomainCommand::Modify { .. } => "modify",
             DomainCommand::SetDesiredPlayState { .. } => "set_desired_play_state",
             DomainCommand::Delete { .. } => "delete",
+            DomainCommand::UpdateConfig { .. } => "update_config",
         }
     }
 }
@@ -90,13 +116,230 @@ pub enum DomainEvent {
         task_id: AppTaskId,
         event:   TaskEvent,
     },
+    /// Connection between a fixed instance driver and its hardware changed
+    FixedInstanceConnection {
+        /// Fixed instance whose connection changed
+        instance_id: FixedInstanceId,
+        /// New connection state
+        state:       InstanceConnectionState,
+        /// Last time the hardware responded
+        last_seen:   Timestamp,
+    },
+    /// All licenses of a dynamic instance model are in use
+    LicensePoolExhausted {
+        /// Engine on which the pool is exhausted, or null if the domain-wide pool is exhausted
+        engine_id: Option<EngineId>,
+        /// State of the pool at the time of exhaustion
+        pool:      LicensePool,
+    },
+    /// Task finished and was removed from the domain
+    TaskFinished {
+        /// Summary of the task run, with final statistics
+        summary: TaskRunSummary,
+    },
+    /// Upload or download of a media object changed
+    MediaJob {
+        /// Media object being transferred
+        media_id: AppMediaObjectId,
+        /// Upload or download
+        kind:     MediaJobKind,
+        /// What changed
+        event:    MediaJobEvent,
+    },
+    /// Periodic report that an engine is alive, with its current load
+    EngineHeartbeat {
+        /// Engine reporting
+        engine_id: EngineId,
+        /// Load of the engine
+        load:      EngineLoad,
+        /// Time the engine sent the heartbeat
+        at:        Timestamp,
+    },
+    /// A request to extend the reservation of a task was granted or denied
+    TaskTimeExtension {
+        /// Outcome of the request
+        outcome: tasks::TaskTimeExtensionOutcome,
+    },
+}
+
+/// A [`DomainEvent`] with a sequence number and the time it was emitted
+pub type SequencedDomainEvent = Sequenced<DomainEvent>;
+
+/// Maximum number of commands returned in one [`CommandReplay`]
+pub const MAX_REPLAYED_COMMANDS: usize = 1_000;
+
+/// Request sent by a domain after reconnecting to its command source, to receive commands it may have missed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RequestCommandReplay {
+    /// Where to start replaying from
+    pub from:  CommandReplayStart,
+    /// Maximum number of commands to return, capped at [`MAX_REPLAYED_COMMANDS`]
+    #[serde(default = "default_max_replayed_commands")]
+    pub limit: usize,
+}
+
+impl RequestCommandReplay {
+    /// Number of commands that may be returned in response to this request
+    pub fn effective_limit(&self) -> usize {
+        self.limit.min(MAX_REPLAYED_COMMANDS)
+    }
+}
+
+fn default_max_replayed_commands() -> usize {
+    MAX_REPLAYED_COMMANDS
+}
+
+/// Position in the command source to start replaying from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandReplayStart {
+    /// Replay commands after this offset (exclusive), usually the last committed offset
+    FromOffset { offset: i64 },
+    /// Replay commands issued at or after this time
+    FromTimestamp { timestamp: Timestamp },
+}
+
+/// A command returned by a replay, with its position in the command source
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReplayedDomainCommand {
+    /// Offset of the command in the command source
+    pub offset:  i64,
+    /// When the command was issued
+    pub at:      Timestamp,
+    /// The command
+    pub command: DomainCommand,
+}
+
+/// One batch of replayed commands, in offset order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CommandReplay {
+    /// Commands, at most as many as the effective limit of the request
+    pub commands:      Vec<ReplayedDomainCommand>,
+    /// Offset to request the next batch from, or null if the domain caught up
+    pub next_offset:   Option<i64>,
+    /// Latest offset in the command source at the time of the replay
+    pub latest_offset: Option<i64>,
+}
+
+impl CommandReplay {
+    /// True if no more commands are waiting to be replayed
+    pub fn is_caught_up(&self) -> bool {
+        self.next_offset.is_none()
+    }
 }
 
 impl DomainEvent {
     pub fn key(&self) -> String {
         match self {
-            DomainEvent::FixedInstance { instance_id, .. } => instance_id.to_string(),
+            DomainEvent::FixedInstance { instance_id, .. } | DomainEvent::FixedInstanceConnection { instance_id, .. } => {
+                instance_id.to_string()
+            }
             DomainEvent::Task { task_id, .. } => task_id.to_string(),
+            DomainEvent::LicensePoolExhausted { pool, .. } => pool.model_id.to_string(),
+            DomainEvent::TaskFinished { summary } => summary.id.to_string(),
+            DomainEvent::MediaJob { media_id, .. } => media_id.to_string(),
+            DomainEvent::EngineHeartbeat { engine_id, .. } => engine_id.to_string(),
+            DomainEvent::TaskTimeExtension { outcome } => outcome.task_id().to_string(),
+        }
+    }
+
+    pub fn kind(&self) -> DomainEventKind {
+        match self {
+            DomainEvent::FixedInstance { event, .. } => match event {
+                InstanceEvent::State { .. } => DomainEventKind::InstanceState,
+                InstanceEvent::Error { .. } => DomainEventKind::InstanceError,
+            },
+            DomainEvent::Task { event, .. } => match event {
+                TaskEvent::PlayState { .. } | TaskEvent::Stopped { .. } | TaskEvent::RenderFinished { .. } => {
+                    DomainEventKind::TaskPlayState
+                }
+                TaskEvent::StreamingPacket { .. } => DomainEventKind::TaskMetering,
+                TaskEvent::StreamIntegrity { .. } => DomainEventKind::TaskStreamIntegrity,
+                TaskEvent::RenderTimedOut { .. } => DomainEventKind::TaskRenderTimedOut,
+                TaskEvent::MediaDiagnostics { .. } => DomainEventKind::TaskMediaDiagnostics,
+                TaskEvent::Deleted { .. } => DomainEventKind::TaskDeleted,
+            },
+            DomainEvent::FixedInstanceConnection { .. } => DomainEventKind::InstanceConnection,
+            DomainEvent::LicensePoolExhausted { .. } => DomainEventKind::LicensePoolExhausted,
+            DomainEvent::TaskFinished { .. } => DomainEventKind::TaskFinished,
+            DomainEvent::MediaJob { .. } => DomainEventKind::MediaJob,
+            DomainEvent::EngineHeartbeat { .. } => DomainEventKind::EngineHeartbeat,
+            DomainEvent::TaskTimeExtension { .. } => DomainEventKind::TaskTimeExtension,
+        }
+    }
+
+    pub fn severity(&self) -> EventSeverity {
+        if let DomainEvent::Task { event: TaskEvent::MediaDiagnostics { diagnostics },
+                                   .. } = self
+        {
+            if !diagnostics.recoverable {
+                return EventSeverity::Error;
+            }
+        }
+
+        if let DomainEvent::FixedInstanceConnection { state, .. } = self {
+            return match state {
+                InstanceConnectionState::Connected => EventSeverity::Info,
+                InstanceConnectionState::Degraded => EventSeverity::Warning,
+                InstanceConnectionState::Lost => EventSeverity::Error,
+            };
+        }
+
+        if let DomainEvent::TaskTimeExtension { outcome: tasks::TaskTimeExtensionOutcome::Denied(_), } = self {
+            return EventSeverity::Warning;
+        }
+
+        if let DomainEvent::MediaJob { event, .. } = self {
+            return match event {
+                MediaJobEvent::Progress { .. } => EventSeverity::Debug,
+                MediaJobEvent::Failed { retry_in_ms: Some(_), .. } => EventSeverity::Warning,
+                MediaJobEvent::Failed { retry_in_ms: None, .. } => EventSeverity::Error,
+                MediaJobEvent::Queued | MediaJobEvent::Started { .. } | MediaJobEvent::Done => EventSeverity::Info,
+            };
+        }
+
+        match self.kind() {
+            DomainEventKind::TaskMetering | DomainEventKind::EngineHeartbeat => EventSeverity::Debug,
+            DomainEventKind::InstanceState
+            | DomainEventKind::TaskPlayState
+            | DomainEventKind::TaskDeleted
+            | DomainEventKind::TaskFinished
+            | DomainEventKind::TaskTimeExtension
+            | DomainEventKind::MediaJob => EventSeverity::Info,
+            DomainEventKind::LicensePoolExhausted
+            | DomainEventKind::TaskStreamIntegrity
+            | DomainEventKind::TaskRenderTimedOut
+            | DomainEventKind::TaskMediaDiagnostics => EventSeverity::Warning,
+            DomainEventKind::InstanceError | DomainEventKind::InstanceConnection => EventSeverity::Error,
+        }
+    }
+}
+
+impl EventFilter {
+    /// True if the event should be sent to the sink
+    ///
+    /// Metering is sampled by streaming packet serial number, so every task keeps an even spread
+    /// of packets regardless of how many tasks are streaming.
+    pub fn matches(&self, event: &DomainEvent) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.contains(&event.kind()) {
+            return false;
+        }
+
+        if event.severity() < self.min_severity {
+            return false;
+        }
+
+        match event {
+            DomainEvent::Task { event: TaskEvent::StreamingPacket { packet },
+                                .. } => {
+                if self.metering_sample_rate <= 0.0 {
+                    false
+                } else {
+                    let interval = (1.0 / self.metering_sample_rate.min(1.0)).round() as u64;
+                    packet.serial.is_multiple_of(interval)
+                }
+            }
+            _ => true,
         }
     }
 }
@@ -137,6 +380,12 @@ pub enum DomainError {
     #[error("Task {task_id} revision {revision} cannot safely apply the update")]
     TaskModificationRevisionOutOfDate { task_id: AppTaskId, revision: u64 },
 
+    #[error("Task {task_id} snapshot {snapshot_id} not found")]
+    TaskSnapshotNotFound {
+        task_id:     AppTaskId,
+        snapshot_id: TaskSnapshotId,
+    },
+
     #[error("Task {task_id} failed to modify: {error}")]
     TaskModification { task_id: AppTaskId, error: ModifyTaskError },
 
@@ -167,11 +416,55 @@ pub enum DomainError {
     #[error("You are not authorized to access task {task_id}, required permissions {required:?}")]
     TaskAuthtorizationFailed { task_id: AppTaskId, required: TaskPermissions },
 
+    #[error("Task {task_id} render {render_id} was cancelled after exceeding maximum duration of {max_duration_ms} ms")]
+    TaskRenderTimedOut {
+        task_id:         AppTaskId,
+        render_id:       RenderId,
+        max_duration_ms: u64,
+    },
+
     #[error("Task {task_id} is in an incorrect state: state")]
     TaskIllegalPlayState { task_id: AppTaskId, state: TaskPlayStateSummary },
 
     #[error("WebRTC error: {error}")]
     WebRTCError { error: String },
+
+    #[error("Too many requests, retry after {retry_after_ms} ms")]
+    RateLimited { retry_after_ms: u64 },
+}
+
+impl ErrorCode for DomainError {
+    fn error_code(&self) -> &'static str {
+        use DomainError::*;
+
+        match self {
+            InstanceDriver { .. } => "instance_driver",
+            Engine { .. } => "engine",
+            EngineNotFound { .. } => "engine_not_found",
+            SocketNotFound { .. } => "socket_not_found",
+            SocketExists { .. } => "socket_exists",
+            TaskNotFound { .. } => "task_not_found",
+            TaskStreamNotFound { .. } => "task_stream_not_found",
+            TaskPacketNotFound { .. } => "task_packet_not_found",
+            TaskExists { .. } => "task_exists",
+            TaskModificationRevisionOutOfDate { .. } => "task_modification_revision_out_of_date",
+            TaskSnapshotNotFound { .. } => "task_snapshot_not_found",
+            TaskModification { .. } => "task_modification",
+            InstanceNotFound { .. } => "instance_not_found",
+            InstanceNotCapable { .. } => "instance_not_capable",
+            MediaNotFound { .. } => "media_not_found",
+            Serialization { .. } => "serialization",
+            NotImplemented { .. } => "not_implemented",
+            BadGateway { .. } => "bad_gateway",
+            AuthenticationFailed => "authentication_failed",
+            TaskRevisionMalformed { .. } => "task_revision_malformed",
+            TaskAuthtorizationFailed { .. } => "task_authorization_failed",
+            TaskRenderTimedOut { .. } => "task_render_timed_out",
+            TaskIllegalPlayState { .. } => "task_illegal_play_state",
+            WebRTCError { .. } => "webrtc_error",
+            RateLimited { .. } => "rate_limited",
+        }
+    }
 }
 
 impl DomainError {
@@ -181,7 +474,13 @@ impl DomainError {
         match self {
             AuthenticationFailed => 401,
             TaskAuthtorizationFailed { .. } => 403,
-            EngineNotFound { .. } | SocketNotFound { .. } | TaskNotFound { .. } | InstanceNotFound { .. } | MediaNotFound { .. } => 404,
+            EngineNotFound { .. }
+            | SocketNotFound { .. }
+            | TaskNotFound { .. }
+            | TaskSnapshotNotFound { .. }
+            | InstanceNotFound { .. }
+            | MediaNotFound { .. } => 404,
+            RateLimited { .. } => 429,
             NotImplemented { .. } => 500,
             BadGateway { .. } => 502,
             _ => 400,
@@ -189,38 +488,87 @@ impl DomainError {
     }
 }
 
+#[cfg(feature = "openapi")]
 #[derive(OpenApi)]
 #[openapi(paths(tasks::list_tasks,
                 tasks::get_task,
+                tasks::get_task_readiness,
+                tasks::validate_task_spec,
                 tasks::create_task,
                 tasks::modify_task,
+                tasks::bulk_modify_tasks,
                 tasks::delete_task,
+                tasks::create_task_snapshot,
+                tasks::list_task_snapshots,
+                tasks::apply_task_snapshot,
+                tasks::extend_task_time,
                 tasks::render_task,
                 tasks::play_task,
                 tasks::seek_task,
                 tasks::cancel_render_task,
                 tasks::stop_playing_task,
                 streaming::stream_packets,
+                streaming::stream_packets_since,
                 streaming::stream_stats))]
 pub struct DomainApi;
 
 pub fn schemas() -> RootSchema {
     merge_schemas([schema_for!(DomainError),
+                   schema_for!(CodedError<DomainError>),
                    schema_for!(DomainCommand),
+                   schema_for!(crate::cloud::domains::DomainConfigUpdate),
+                   schema_for!(TaskSpecDiff),
                    schema_for!(DomainEvent),
+                   schema_for!(SequencedDomainEvent),
+                   schema_for!(RequestCommandReplay),
+                   schema_for!(CommandReplay),
+                   schema_for!(InstanceConnectionState),
+                   schema_for!(TaskRunSummary),
+                   schema_for!(MediaJobEvent),
+                   schema_for!(EngineLoad),
                    schema_for!(AppId),
                    schema_for!(TaskId),
                    schema_for!(SocketId),
                    schema_for!(RequestId),
                    schema_for!(streaming::StreamStats),
+                   schema_for!(streaming::PlayheadPosition),
+                   schema_for!(streaming::StreamCursor),
+                   schema_for!(streaming::StreamPacketBatch),
                    schema_for!(streaming::DomainServerMessage),
+                   schema_for!(streaming::ThrottleReason),
+                   schema_for!(streaming::DetachedReason),
                    schema_for!(streaming::DomainClientMessage),
+                   schema_for!(streaming::DomainServerEnvelope),
+                   schema_for!(crate::ApiVersion),
+                   schema_for!(crate::NegotiateVersion),
+                   schema_for!(crate::VersionNegotiated),
+                   schema_for!(streaming::DomainClientEnvelope),
+                   schema_for!(streaming::SdpOffer),
+                   schema_for!(streaming::SdpAnswer),
+                   schema_for!(streaming::IceCandidate),
                    schema_for!(tasks::TaskSummaryList),
                    schema_for!(tasks::TaskWithStatusAndSpec),
+                   schema_for!(tasks::TaskReadiness),
+                   schema_for!(tasks::ReadinessBlocker),
+                   schema_for!(tasks::ValidateTaskSpec),
+                   schema_for!(tasks::TaskSpecValidation),
                    schema_for!(tasks::CreateTask),
                    schema_for!(tasks::ModifyTask),
+                   schema_for!(tasks::TaskFilter),
+                   schema_for!(tasks::BulkModifyTasks),
+                   schema_for!(tasks::BulkTasksModified),
+                   schema_for!(tasks::BulkModifyResult),
                    schema_for!(tasks::TaskCreated),
                    schema_for!(tasks::TaskDeleted),
+                   schema_for!(tasks::CreateTaskSnapshot),
+                   schema_for!(tasks::TaskSnapshotCreated),
+                   schema_for!(tasks::TaskSnapshotList),
+                   schema_for!(tasks::ApplyTaskSnapshot),
+                   schema_for!(tasks::RequestTaskTimeExtension),
+                   schema_for!(tasks::TaskTimeExtensionOutcome),
+                   schema_for!(crate::TaskSnapshot),
+                   schema_for!(crate::TaskSnapshotParameters),
+                   schema_for!(crate::AuditEntry),
                    schema_for!(tasks::TaskUpdated),
                    schema_for!(tasks::TaskPlayStopped),
                    schema_for!(tasks::TaskPlaying),
@@ -229,8 +577,13 @@ pub fn schemas() -> RootSchema {
                    schema_for!(tasks::TaskSought),
                    schema_for!(crate::StreamingPacket),
                    schema_for!(crate::RequestPlay),
+                   schema_for!(crate::PlayClick),
+                   schema_for!(crate::PlayTimecode),
                    schema_for!(crate::RequestSeek),
                    schema_for!(crate::RequestChangeMixer),
                    schema_for!(crate::RequestStopPlay),
+                   schema_for!(crate::RequestRender),
+                   schema_for!(crate::RenderOutputFormat),
+                   schema_for!(crate::RenderAnalysis),
                    schema_for!(crate::RequestCancelRender)].into_iter())
 }
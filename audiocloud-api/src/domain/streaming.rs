@@ -1,16 +1,18 @@
 //! API definitions for communicating with the apps
 use std::collections::HashMap;
 
-use chrono::Utc;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::common::change::TaskPlayState;
 use crate::common::media::{PlayId, RenderId};
-use crate::common::time::Timestamp;
+pub use crate::common::time::DiffStamped;
 use crate::domain::tasks::TaskUpdated;
 use crate::domain::DomainError;
-use crate::{AppTaskId, ClientSocketId, ModifyTaskSpec, RequestId, SecureKey, SerializableResult, SocketId, TaskEvent, TaskPermissions};
+use crate::{
+    AppTaskId, ClientSocketId, Envelope, ModifyTaskSpec, NegotiateVersion, RequestId, SecureKey, SerializableResult, SocketId,
+    StreamingPacket, TaskEvent, TaskPermissions, Timecode, Timestamp, VersionNegotiated,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct StreamStats {
@@ -29,33 +31,95 @@ pub enum SessionPacketError {
     General(String),
 }
 
-/// Difference stamped in milliseconds since a common epoch, in order to pack most efficiently
-/// The epoch in InstancePacket is the created_at field of SessionPacket
+/// Playhead position of a playing task, lighter than a full streaming packet
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
-pub struct DiffStamped<T>(usize, T);
+pub struct PlayheadPosition {
+    /// Play id of the playback
+    pub play_id:       PlayId,
+    /// Position on the task timeline in seconds
+    pub timeline_pos:  f64,
+    /// Timecode at the position, if the play request asked for timecode
+    #[serde(default)]
+    pub timecode:      Option<Timecode>,
+    /// Position in the stream in samples
+    pub streaming_pos: u64,
+    /// Time at which the playhead was at the position
+    pub created_at:    Timestamp,
+}
+
+impl From<&StreamingPacket> for PlayheadPosition {
+    fn from(packet: &StreamingPacket) -> Self {
+        Self { play_id:       packet.play_id,
+               timeline_pos:  packet.timeline_pos,
+               timecode:      packet.timecode,
+               streaming_pos: packet.streaming_pos,
+               created_at:    packet.created_at, }
+    }
+}
 
-impl<T> DiffStamped<T> {
-    pub fn new(timestamp: Timestamp, value: T) -> Self {
-        (timestamp, value).into()
+/// Maximum number of packets returned in a single batch when catching up with a stream
+pub const MAX_STREAM_PACKET_BATCH: usize = 256;
+
+/// Position in a stream, from which packets not yet received can be fetched
+///
+/// Cursors are returned by the domain with every batch of packets and should be treated as opaque by
+/// clients. A reconnecting client continues from the last cursor it received.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, JsonSchema)]
+#[repr(transparent)]
+pub struct StreamCursor(u64);
+
+impl StreamCursor {
+    /// Cursor at the start of a stream
+    pub fn start() -> Self {
+        Self(0)
     }
 
-    pub fn value(&self) -> &T {
-        &self.1
+    /// Cursor following a packet
+    ///
+    /// Serial `u64::MAX` is the last packet of a stream, the cursor after it stays at that packet.
+    pub fn after(packet: &StreamingPacket) -> Self {
+        Self(packet.serial.saturating_add(1))
     }
 
-    pub fn value_mut(&mut self) -> &mut T {
-        &mut self.1
+    /// Serial number of the first packet at or after the cursor
+    pub fn next_serial(&self) -> u64 {
+        self.0
     }
 }
 
-impl<T> From<(Timestamp, T)> for DiffStamped<T> {
-    fn from(value: (Timestamp, T)) -> Self {
-        let (timestamp, value) = value;
-        let diff = Utc::now() - timestamp;
-        Self(diff.num_milliseconds() as usize, value)
+/// Batch of stream packets following a cursor
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct StreamPacketBatch {
+    /// Packets at or after the requested cursor, by ascending serial number
+    pub packets:  Vec<StreamingPacket>,
+    /// Cursor to continue fetching from
+    pub next:     StreamCursor,
+    /// True if more packets are available after this batch
+    pub has_more: bool,
+}
+
+impl StreamPacketBatch {
+    /// Collect up to `max_packets` packets at or after the cursor from packets ordered by ascending serial
+    ///
+    /// The batch size is limited to [`MAX_STREAM_PACKET_BATCH`].
+    pub fn collect<'a>(cursor: StreamCursor, packets: impl IntoIterator<Item = &'a StreamingPacket>, max_packets: usize) -> Self {
+        let max_packets = max_packets.clamp(1, MAX_STREAM_PACKET_BATCH);
+        let mut remaining = packets.into_iter().filter(|packet| packet.serial >= cursor.next_serial());
+        let packets = remaining.by_ref().take(max_packets).cloned().collect::<Vec<_>>();
+        let next = packets.last().map(StreamCursor::after).unwrap_or(cursor);
+
+        Self { packets,
+               next,
+               has_more: remaining.next().is_some() }
     }
 }
 
+/// A [`DomainServerMessage`] with request correlation and tracing metadata
+pub type DomainServerEnvelope = Envelope<DomainServerMessage>;
+
+/// A [`DomainClientMessage`] with request correlation and tracing metadata
+pub type DomainClientEnvelope = Envelope<DomainClientMessage>;
+
 /// A mesasge received over a real-time communication channel from a streaming domain connection
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -67,6 +131,13 @@ pub enum DomainServerMessage {
         /// Event details
         event:   TaskEvent,
     },
+    /// Current playhead position of a playing task, sent at the rate requested when starting playback
+    PlayheadPosition {
+        /// Id of the playing task
+        task_id:  AppTaskId,
+        /// Playhead position
+        position: PlayheadPosition,
+    },
     /// Response to a request to change a task play state
     SetDesiredPlayStateResponse {
         /// Request id this message is responding to
@@ -119,8 +190,8 @@ pub enum DomainServerMessage {
     SubmitPeerConnectionCandidate {
         /// Socket id of the peer connection
         socket_id: SocketId,
-        /// ICE Candidate
-        candidate: Option<String>,
+        /// ICE Candidate, or null when the domain has sent all candidates
+        candidate: Option<IceCandidate>,
     },
     /// Ping message
     Ping {
@@ -130,11 +201,102 @@ pub enum DomainServerMessage {
         /// to validate that the client is running a valid version of the client code
         challenge: String,
     },
+    /// Response to a request for stream packets since a cursor
+    StreamPacketsResponse {
+        /// Request id this message is responding to
+        request_id: RequestId,
+        /// Result of the operation
+        result:     SerializableResult<StreamPacketBatch, DomainError>,
+    },
+    /// Response to a version negotiation
+    NegotiateVersionResponse {
+        /// Request id this message is responding to
+        request_id: RequestId,
+        /// Agreed version, or the versions supported by the domain
+        result:     VersionNegotiated,
+    },
     /// Notify the task permissions on this socket
     NotifyTaskPermissions {
         /// Mapping from each available task to permission information to that task
         permissions: HashMap<AppTaskId, TaskPermissions>,
     },
+    /// The client is sending requests faster than the domain is willing to process them
+    ///
+    /// Requests received before `retry_after_ms` elapses may be rejected with
+    /// [`DomainError::RateLimited`](crate::domain::DomainError::RateLimited).
+    Throttle {
+        /// Milliseconds the client should wait before sending further requests
+        retry_after_ms: u64,
+        /// Why the client is being throttled
+        reason:         ThrottleReason,
+    },
+    /// The socket was detached from a task without requesting it
+    Detached {
+        /// Id of the task the socket was detached from
+        task_id: AppTaskId,
+        /// Why the socket was detached
+        reason:  DetachedReason,
+    },
+}
+
+/// Reason a socket was detached from a task without requesting it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DetachedReason {
+    /// Another socket attached to the task with takeover
+    TakenOver {
+        /// Socket that took over the task
+        socket_id: ClientSocketId,
+    },
+    /// The task was deleted
+    TaskDeleted,
+    /// The secure key used for attachment no longer grants access to the task
+    PermissionsRevoked,
+}
+
+/// Reason a streaming domain connection is being throttled
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottleReason {
+    /// Too many requests were sent within the rate limiting window
+    RequestRate,
+    /// Too many requests are awaiting a response
+    PendingRequests,
+    /// The socket is not consuming messages fast enough and outgoing messages are queueing up
+    Backpressure,
+}
+
+/// WebRTC session description offered by the domain
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SdpOffer {
+    /// Session description protocol (SDP) text of the offer
+    pub sdp: String,
+}
+
+/// WebRTC session description the client answers an [`SdpOffer`] with
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SdpAnswer {
+    /// Session description protocol (SDP) text of the answer
+    pub sdp: String,
+}
+
+/// WebRTC ICE candidate
+///
+/// Fields are named as in the browser `RTCIceCandidateInit`, so a candidate can be passed as is.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IceCandidate {
+    /// Candidate line, as in the `a=candidate` SDP attribute
+    pub candidate:         String,
+    /// Media stream identification tag of the media component the candidate belongs to
+    #[serde(default)]
+    pub sdp_mid:           Option<String>,
+    /// Index of the media description the candidate belongs to
+    #[serde(default)]
+    pub sdp_m_line_index:  Option<u16>,
+    /// ICE username fragment of the candidate
+    #[serde(default)]
+    pub username_fragment: Option<String>,
 }
 
 /// Confirmation that the socket has been created normally from the domain client offer
@@ -147,7 +309,7 @@ pub enum PeerConnectionCreated {
         socket_id: ClientSocketId,
 
         /// The domain server's WebRTC offer
-        remote_description: String,
+        remote_description: SdpOffer,
     },
 }
 
@@ -179,7 +341,7 @@ pub enum DomainClientMessage {
         /// Request id (to reference the response to)
         request_id: RequestId,
         /// The domain server's WebRTC offer response (answer)
-        answer:     String,
+        answer:     SdpAnswer,
     },
     /// Submit a new WebRTC peer connection ICE candidate
     SubmitPeerConnectionCandidate {
@@ -187,8 +349,8 @@ pub enum DomainClientMessage {
         request_id: RequestId,
         /// Socket id of the peer connection
         socket_id:  SocketId,
-        /// ICE Candidate
-        candidate:  Option<String>,
+        /// ICE Candidate, or null when the client has sent all candidates
+        candidate:  Option<IceCandidate>,
     },
     /// Request attaching to a task
     RequestAttachToTask {
@@ -198,6 +360,10 @@ pub enum DomainClientMessage {
         task_id:    AppTaskId,
         /// Secure key to use for attachment
         secure_key: SecureKey,
+        /// If true, sockets already attached to the task are detached and notified with
+        /// [`DomainServerMessage::Detached`], for example when a reconnecting client takes over its session
+        #[serde(default)]
+        takeover:   bool,
     },
     RequestDetachFromTask {
         /// Request id (to reference the response to)
@@ -205,6 +371,27 @@ pub enum DomainClientMessage {
         /// Id of the task to attach to
         task_id:    AppTaskId,
     },
+    /// Negotiate the API version used over the connection, sent before any other request
+    NegotiateVersion {
+        /// Request id (to reference the response to)
+        request_id: RequestId,
+        /// Versions supported by the client
+        negotiate:  NegotiateVersion,
+    },
+    /// Request stream packets since a cursor, to catch up after reconnecting
+    RequestStreamPackets {
+        /// Request id (to reference the response to)
+        request_id:  RequestId,
+        /// Id of the playing or rendering task
+        task_id:     AppTaskId,
+        /// Play id of the stream
+        play_id:     PlayId,
+        /// Cursor returned with the previous batch, or null to start at the oldest available packet
+        #[serde(default)]
+        cursor:      Option<StreamCursor>,
+        /// Maximum number of packets to return, up to [`MAX_STREAM_PACKET_BATCH`]
+        max_packets: usize,
+    },
     Pong {
         challenge: String,
         response:  String,
@@ -217,6 +404,7 @@ pub enum DomainClientMessage {
 /// serial number. For a sane amount of time, the packets may be requested by the clients. If a
 /// packet is not yet models (but it is expected they will be, in the future) the request will
 /// block (wait) for `Timeout` milliseconds before giving up and returning 408.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   get,
   path = "/v1/streams/{app_id}/{task_id}/{play_id}/packet/{serial}",
@@ -238,6 +426,7 @@ pub(crate) fn stream_packets() {}
 /// Get stream statistics
 ///
 /// Get statistics about cached packets available in the stream.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   get,
   path = "/v1/streams/{app_id}/{task_id}/{play_id}",
@@ -252,3 +441,28 @@ pub(crate) fn stream_packets() {}
     ("play_id" = PlayId, Path, description = "Play id")
   ))]
 pub(crate) fn stream_stats() {}
+
+/// Load packets since a cursor
+///
+/// Returns the packets at or after the cursor, up to the batch size, with the cursor to continue from.
+/// Reconnecting clients call this repeatedly until `has_more` is false, instead of tracking serial numbers
+/// themselves. If no packet is available yet, the request waits for up to `Timeout` milliseconds and then
+/// returns an empty batch.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  get,
+  path = "/v1/streams/{app_id}/{task_id}/{play_id}/packets",
+  responses(
+    (status = 200, description = "Success", body = StreamPacketBatch),
+    (status = 401, description = "Not authorized", body = DomainError),
+    (status = 404, description = "Not found", body = DomainError),
+  ),
+  params(
+    ("app_id" = AppId, Path, description = "App id"),
+    ("task_id" = TaskId, Path, description = "Task id"),
+    ("play_id" = PlayId, Path, description = "Play id"),
+    ("cursor" = Option<u64>, Query, description = "Cursor returned with the previous batch, if not set the oldest available packets are returned"),
+    ("max_packets" = Option<usize>, Query, description = "Maximum number of packets to return, up to 256"),
+    ("Timeout" = u64, Header, description = "Milliseconds to wait for a packet to be ready")
+  ))]
+pub(crate) fn stream_packets_since() {}
@@ -4,9 +4,13 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub use crate::audio_engine::{TaskPlayStopped, TaskPlaying, TaskRenderCancelled, TaskRendering, TaskSought};
+use crate::cloud::tasks::MaintenanceConflict;
+use crate::domain::DomainError;
 use crate::{
-    AppMediaObjectId, AppTaskId, CreateTaskReservation, CreateTaskSecurity, CreateTaskSpec, FixedInstanceId, InstancePlayState,
-    MediaObject, ModifyTaskSpec, TaskPlayState, TaskSpec,
+    AppId, AppMediaObjectId, AppTaskId, AuditEntryId, ClientSocketId, CloudError, CreateTaskReservation, CreateTaskSecurity,
+    CreateTaskSpec, EngineId, FixedInstanceId, InstancePlayState, InstancePowerState, MediaJobState, MediaObject, Model, ModelId,
+    ModifyTaskSpec, SerializableResult, TaskLabels, TaskMetadata, TaskNodeId, TaskPermissions, TaskPlayState, TaskSnapshot, TaskSnapshotId,
+    TaskSpec, TaskSpecDiff, TimeRange, Timestamp,
 };
 
 /// A summary of a task
@@ -20,25 +24,125 @@ pub struct TaskSummary {
     pub waiting_for_instances: HashSet<FixedInstanceId>,
     /// List of media that are blocking or influencing completeness of play state change
     pub waiting_for_media:     HashSet<AppMediaObjectId>,
+    /// Descriptive metadata of the task
+    #[serde(default)]
+    pub metadata:              TaskMetadata,
+    /// Labels of the task
+    #[serde(default)]
+    pub labels:                TaskLabels,
 }
 
 /// A more complete information about a task
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct TaskWithStatusAndSpec {
     /// Task Id
-    pub task_id:    AppTaskId,
+    pub task_id:     AppTaskId,
     /// Current play state
-    pub play_state: TaskPlayState,
+    pub play_state:  TaskPlayState,
     /// State of attatched fixed instances
-    pub instances:  HashMap<FixedInstanceId, InstancePlayState>,
+    pub instances:   HashMap<FixedInstanceId, InstancePlayState>,
     /// State of attached media objects
-    pub media:      HashMap<AppMediaObjectId, MediaObject>,
+    pub media:       HashMap<AppMediaObjectId, MediaObject>,
     /// The current specification of the task
-    pub spec:       TaskSpec,
+    pub spec:        TaskSpec,
+    /// Sockets attached to the task
+    #[serde(default)]
+    pub attachments: Vec<TaskAttachment>,
+}
+
+/// A socket attached to a task
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaskAttachment {
+    /// Attached socket
+    pub socket_id:   ClientSocketId,
+    /// When the socket attached
+    pub attached_at: Timestamp,
+    /// Permissions granted by the secure key the socket attached with
+    pub permissions: TaskPermissions,
 }
 
 pub type TaskSummaryList = Vec<TaskSummary>;
 
+/// Pre-flight readiness report of a task
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct TaskReadiness {
+    /// True if nothing is blocking the task from playing or rendering
+    pub ready:    bool,
+    /// Everything that is currently preventing the task from being ready
+    pub blockers: Vec<ReadinessBlocker>,
+}
+
+impl TaskReadiness {
+    /// Create a readiness report from a list of blockers
+    pub fn new(blockers: Vec<ReadinessBlocker>) -> Self {
+        Self { ready: blockers.is_empty(),
+               blockers }
+    }
+}
+
+/// Validate a task specification without creating a task
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct ValidateTaskSpec {
+    /// Task specification to validate
+    pub spec:   TaskSpec,
+    /// Models to use instead of the ones known to the domain, for example while developing a model
+    #[serde(default)]
+    pub models: HashMap<ModelId, Model>,
+}
+
+/// Result of validating a task specification
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct TaskSpecValidation {
+    /// True if no problems were found
+    pub valid:  bool,
+    /// Every problem found in the task specification
+    pub errors: Vec<CloudError>,
+}
+
+impl TaskSpecValidation {
+    /// Create a validation result from a list of errors
+    pub fn new(errors: Vec<CloudError>) -> Self {
+        Self { valid: errors.is_empty(),
+               errors }
+    }
+}
+
+/// Something preventing a task from being ready
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessBlocker {
+    /// Media used by the task is not present on the domain
+    Media {
+        /// Media object id
+        media_id: AppMediaObjectId,
+        /// State of the download job, or null if the media is not being downloaded
+        download: Option<MediaJobState>,
+    },
+    /// A fixed instance used by the task is not powered up or not connected
+    Instance {
+        /// Fixed instance id
+        instance_id: FixedInstanceId,
+        /// Power state of the instance, or null if the instance has no power management
+        power:       Option<InstancePowerState>,
+        /// True if the instance driver is connected to the instance
+        connected:   bool,
+    },
+    /// No audio engine has the capacity to host the task
+    Engine {
+        /// Engine assigned to the task, if any
+        engine_id: Option<EngineId>,
+        /// Human readable reason
+        reason:    String,
+    },
+    /// The domain or an instance used by the task is in maintenance during the reservation
+    Maintenance {
+        /// Time during which maintenance is taking place
+        time:   TimeRange,
+        /// Human readable string about it, or URL to a web page detailing more information
+        reason: String,
+    },
+}
+
 /// Create a task on the domain
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct CreateTask {
@@ -50,6 +154,12 @@ pub struct CreateTask {
     pub spec:         CreateTaskSpec,
     /// Security keys and associateds permissions
     pub security:     CreateTaskSecurity,
+    /// Descriptive metadata, for example project, client or engineer
+    #[serde(default)]
+    pub metadata:     TaskMetadata,
+    /// Labels used to search for tasks
+    #[serde(default)]
+    pub labels:       TaskLabels,
 }
 
 /// Response to creating a task on the domain
@@ -59,7 +169,10 @@ pub enum TaskCreated {
     /// Created normally
     Created {
         /// Task Id
-        task_id: AppTaskId,
+        task_id:  AppTaskId,
+        /// Audit entry recording the creation, if audited
+        #[serde(default)]
+        audit_id: Option<AuditEntryId>,
     },
 }
 
@@ -68,6 +181,51 @@ pub enum TaskCreated {
 pub struct ModifyTask {
     /// A list of modifications to apply
     pub modify_spec: Vec<ModifyTaskSpec>,
+    /// When true, do not actually modify the task, just validate the modifications
+    #[serde(default)]
+    pub dry_run:     bool,
+}
+
+/// Selects tasks on the domain for a bulk operation
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TaskFilter {
+    /// Tasks to select, or empty to select all tasks
+    #[serde(default)]
+    pub task_ids: HashSet<AppTaskId>,
+    /// If set, only tasks of the app
+    #[serde(default)]
+    pub app_id:   Option<AppId>,
+}
+
+impl TaskFilter {
+    pub fn matches(&self, task_id: &AppTaskId) -> bool {
+        (self.task_ids.is_empty() || self.task_ids.contains(task_id)) && self.app_id.as_ref().is_none_or(|app_id| app_id == &task_id.app_id)
+    }
+}
+
+/// Request to apply the same modifications to all tasks on the domain selected by a filter
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct BulkModifyTasks {
+    /// Tasks to modify
+    pub filter:        TaskFilter,
+    /// Modifications to apply to every task, in order
+    pub modifications: Vec<ModifyTaskSpec>,
+}
+
+/// Response to a bulk modification, with a result per selected task
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct BulkTasksModified {
+    /// Result for every selected task, sorted by task id
+    pub results: Vec<BulkModifyResult>,
+}
+
+/// Outcome of a bulk modification of a single task
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct BulkModifyResult {
+    /// Task id
+    pub task_id: AppTaskId,
+    /// Result of modifying the task
+    pub result:  SerializableResult<TaskUpdated, DomainError>,
 }
 
 /// Response to modifying a task on the domain
@@ -80,6 +238,9 @@ pub enum TaskUpdated {
         task_id:  AppTaskId,
         /// New version to be used with `If-Matches` when submitting further modifications
         revision: u64,
+        /// Audit entry recording the update, if audited
+        #[serde(default)]
+        audit_id: Option<AuditEntryId>,
     },
     /// Did not update because a newer revision was specified and update is optional
     Ignored {
@@ -88,29 +249,144 @@ pub enum TaskUpdated {
         /// Current version to be used with `If-Matches` when submitting further modifications
         revision: u64,
     },
+    /// Validated successfully, but not updated
+    DryRun {
+        /// Task Id
+        task_id:  AppTaskId,
+        /// Version the task would have after the modifications
+        revision: u64,
+        /// Changes the modifications would make to the task spec
+        diff:     TaskSpecDiff,
+    },
+}
+
+/// Request to capture the current parameter state of a task
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct CreateTaskSnapshot {
+    /// Human readable name of the snapshot
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Response to capturing a snapshot of a task
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSnapshotCreated {
+    /// Created normally
+    Created {
+        /// Task Id
+        task_id:     AppTaskId,
+        /// Id of the new snapshot
+        snapshot_id: TaskSnapshotId,
+        /// Revision of the task spec the snapshot was taken from
+        revision:    u64,
+    },
+}
+
+pub type TaskSnapshotList = Vec<TaskSnapshot>;
+
+/// Request to restore the parameter state of a task from a snapshot
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct ApplyTaskSnapshot {
+    /// Fixed or dynamic instance nodes to restore, or empty to restore every node in the snapshot
+    #[serde(default)]
+    pub nodes: HashSet<TaskNodeId>,
+}
+
+/// Request to extend the reservation of a task, checked against the capacity of the domain
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RequestTaskTimeExtension {
+    /// New end of the reservation, must be after the current end
+    pub to: Timestamp,
+}
+
+/// The reservation of a task was extended
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaskTimeExtended {
+    /// Task Id
+    pub task_id: AppTaskId,
+    /// Start of the reservation, unchanged
+    pub from:    Timestamp,
+    /// New end of the reservation
+    pub to:      Timestamp,
+}
+
+/// The reservation of a task could not be extended
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TaskTimeExtensionDenied {
+    /// Task Id
+    pub task_id:      AppTaskId,
+    /// End of the reservation that was requested
+    pub requested_to: Timestamp,
+    /// Why the extension was denied
+    pub reason:       TaskTimeExtensionDeniedReason,
+}
+
+/// Why the reservation of a task could not be extended
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskTimeExtensionDeniedReason {
+    /// Requested end is not after the current end of the reservation
+    NotLater {
+        /// Current end of the reservation
+        current_to: Timestamp,
+    },
+    /// The engine running the task has no capacity left during the extension
+    EngineCapacity { engine_id: EngineId },
+    /// Other tasks reserve fixed instances of the task during the extension
+    ConflictingTasks { task_ids: Vec<AppTaskId> },
+    /// The domain or fixed instances of the task are under maintenance during the extension
+    Maintenance { conflicts: Vec<MaintenanceConflict> },
+}
+
+/// Outcome of a request to extend the reservation of a task
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskTimeExtensionOutcome {
+    Extended(TaskTimeExtended),
+    Denied(TaskTimeExtensionDenied),
+}
+
+impl TaskTimeExtensionOutcome {
+    pub fn task_id(&self) -> &AppTaskId {
+        match self {
+            TaskTimeExtensionOutcome::Extended(extended) => &extended.task_id,
+            TaskTimeExtensionOutcome::Denied(denied) => &denied.task_id,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskDeleted {
-    Deleted { id: AppTaskId },
+    Deleted {
+        id:       AppTaskId,
+        /// Audit entry recording the deletion, if audited
+        #[serde(default)]
+        audit_id: Option<AuditEntryId>,
+    },
 }
 
 /// List tasks
 ///
 /// Return a list of all current tasks and their status.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   get,
   path = "/v1/tasks",
   responses(
     (status = 200, description = "Success", body = TaskSummaryList),
     (status = 401, description = "Not authorized", body = DomainError),
+  ),
+  params(
+    ("label" = Option<Vec<String>>, Query, description = "If set, only tasks having all of the labels"),
   ))]
 pub(crate) fn list_tasks() {}
 
 /// Get task details
 ///
 /// Get details of a task, including dependent media and instance statuses
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   get,
   path = "/v1/tasks/{app_id}/{task_id}",
@@ -125,11 +401,47 @@ pub(crate) fn list_tasks() {}
   ))]
 pub(crate) fn get_task() {}
 
+/// Get task readiness
+///
+/// Check if the task is ready to play or render before its reservation starts: all media is
+/// present, all instances are powered and connected, an engine has capacity to host it and no
+/// maintenance is scheduled during the reservation.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  get,
+  path = "/v1/tasks/{app_id}/{task_id}/readiness",
+  responses(
+    (status = 200, description = "Success", body = TaskReadiness),
+    (status = 401, description = "Not authorized", body = DomainError),
+    (status = 404, description = "Not found", body = DomainError),
+  ),
+  params(
+    ("app_id" = AppId, Path, description = "App id"),
+    ("task_id" = TaskId, Path, description = "Task id")
+  ))]
+pub(crate) fn get_task_readiness() {}
+
+/// Validate a task specification
+///
+/// Validate a task specification without creating a task, reporting every problem found. Models
+/// submitted with the request take precedence over the models known to the domain.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/tasks/validate",
+  request_body = ValidateTaskSpec,
+  responses(
+    (status = 200, description = "Success", body = TaskSpecValidation),
+    (status = 401, description = "Not authorized", body = DomainError),
+  ))]
+pub(crate) fn validate_task_spec() {}
+
 /// Create a task
 ///
 /// In standalone mode, the task will be checked for mutual exclusivity with other tasks, otherwise
 /// it will be created. This call could also fail if the referenced resources (such as fixed
 /// instances) do not exist.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
   path = "/v1/tasks",
@@ -146,6 +458,10 @@ pub(crate) fn create_task() {}
 ///
 /// Submit modifications to the task. This generic request can be used to update most aspects of the
 /// session: adjusting parameters, creating, deleting, reconnecting nodes, changing media, etc.
+///
+/// With `dry_run` set, the modifications are validated against the current spec and the would-be
+/// revision and changes are returned, but the task is not modified.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
   path = "/v1/tasks/{app_id}/{task_id}/modify",
@@ -163,9 +479,25 @@ pub(crate) fn create_task() {}
   ))]
 pub(crate) fn modify_task() {}
 
+/// Modify multiple tasks
+///
+/// Apply the same modifications to all tasks selected by the filter. Each task is modified
+/// independently and has its own result.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/tasks/bulk-modify",
+  request_body = BulkModifyTasks,
+  responses(
+    (status = 200, description = "Success, see results of individual tasks", body = BulkTasksModified),
+    (status = 401, description = "Not authorized", body = DomainError),
+  ))]
+pub(crate) fn bulk_modify_tasks() {}
+
 /// Delete a task
 ///
 /// Delete a task and release all referenced resources.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   delete,
   path = "/v1/tasks/{app_id}/{task_id}",
@@ -180,9 +512,91 @@ pub(crate) fn modify_task() {}
   ))]
 pub(crate) fn delete_task() {}
 
+/// Create a task snapshot
+///
+/// Capture the parameter values and automation of all instance nodes of the task, to be restored
+/// later. Snapshots are kept until the task is deleted.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/tasks/{app_id}/{task_id}/snapshots",
+  request_body = CreateTaskSnapshot,
+  responses(
+    (status = 200, description = "Success", body = TaskSnapshotCreated),
+    (status = 401, description = "Not authorized", body = DomainError),
+    (status = 404, description = "Not found", body = DomainError),
+  ),
+  params(
+    ("app_id" = AppId, Path, description = "App id"),
+    ("task_id" = TaskId, Path, description = "Task id")
+  ))]
+pub(crate) fn create_task_snapshot() {}
+
+/// List task snapshots
+///
+/// Return all snapshots of the task, oldest first.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  get,
+  path = "/v1/tasks/{app_id}/{task_id}/snapshots",
+  responses(
+    (status = 200, description = "Success", body = TaskSnapshotList),
+    (status = 401, description = "Not authorized", body = DomainError),
+    (status = 404, description = "Not found", body = DomainError),
+  ),
+  params(
+    ("app_id" = AppId, Path, description = "App id"),
+    ("task_id" = TaskId, Path, description = "Task id")
+  ))]
+pub(crate) fn list_task_snapshots() {}
+
+/// Apply a task snapshot
+///
+/// Restore the parameter state of all or some instance nodes from a snapshot. This is the same as
+/// modifying the task with an `apply_snapshot` modification containing the snapshot parameters.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/tasks/{app_id}/{task_id}/snapshots/{snapshot_id}/apply",
+  request_body = ApplyTaskSnapshot,
+  responses(
+    (status = 200, description = "Success", body = TaskUpdated),
+    (status = 401, description = "Not authorized", body = DomainError),
+    (status = 404, description = "Task or snapshot not found", body = DomainError),
+    (status = 409, description = "Snapshot does not match the task nodes", body = DomainError),
+  ),
+  params(
+    ("app_id" = AppId, Path, description = "App id"),
+    ("task_id" = TaskId, Path, description = "Task id"),
+    ("snapshot_id" = TaskSnapshotId, Path, description = "Snapshot id"),
+    ("If-Match" = u64, Header, description = "The task version to be changed"),
+  ))]
+pub(crate) fn apply_task_snapshot() {}
+
+/// Extend task time
+///
+/// Request to extend the reservation of a task. The domain checks the extension against its engine capacity,
+/// reservations of other tasks and maintenance, and either extends the reservation or reports why it can't.
+#[cfg(feature = "openapi")]
+#[utoipa::path(
+  post,
+  path = "/v1/tasks/{app_id}/{task_id}/time/extend",
+  request_body = RequestTaskTimeExtension,
+  responses(
+    (status = 200, description = "Extended or denied", body = TaskTimeExtensionOutcome),
+    (status = 401, description = "Not authorized", body = DomainError),
+    (status = 404, description = "Task not found", body = DomainError),
+  ),
+  params(
+    ("app_id" = AppId, Path, description = "App id"),
+    ("task_id" = TaskId, Path, description = "Task id"),
+  ))]
+pub(crate) fn extend_task_time() {}
+
 /// Render a task to a new file
 ///
 /// The domain will check that
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
   path = "/v1/tasks/{app_id}/{task_id}/transport/render",
@@ -202,6 +616,7 @@ pub(crate) fn render_task() {}
 ///
 /// Start playing a task that is stopped. The request will return when the task has started to play
 /// or with an error.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
   path = "/v1/tasks/{app_id}/{task_id}/transport/play",
@@ -221,6 +636,7 @@ pub(crate) fn play_task() {}
 /// Seek while task is playing
 ///
 /// If the task is playing, change the playing position.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
   path = "/v1/tasks/{app_id}/{task_id}/transport/seek",
@@ -239,6 +655,7 @@ pub(crate) fn seek_task() {}
 /// Cancel rendering a task
 ///
 /// Request to stop (cancel) rendering if the task is rendering.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
   path = "/v1/tasks/{app_id}/{task_id}/transport/cancel",
@@ -258,6 +675,7 @@ pub(crate) fn cancel_render_task() {}
 /// Stop playing a task
 ///
 /// Request to stop a track if the task is playing.
+#[cfg(feature = "openapi")]
 #[utoipa::path(
   post,
   path = "/v1/tasks/{app_id}/{task_id}/transport/stop",
@@ -0,0 +1,188 @@
+//! Curated example values of major request, response and event types, for documentation
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, UNIX_EPOCH};
+
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
+use serde::Serialize;
+
+use crate::domain::streaming::{DomainServerMessage, PlayheadPosition};
+use crate::domain::tasks::{ModifyTask, TaskUpdated};
+use crate::instance_driver::{DriverCommandEnvelope, DriverCommandId, InstanceDriverCommand};
+use crate::{
+    AppId, AppTaskId, ChannelMask, CloudError, ConnectionKind, MediaChannels, MediaObjectId, MixerNode, MixerNodeId, ModifyTaskSpec,
+    NodeConnection, NodeConnectionId, PanLaw, PlayBitDepth, PlayId, RenderBitDepth, RenderContainer, RenderId, RenderOutputFormat,
+    RequestPlay, RequestRender, SampleRate, StopReason, TaskEvent, TaskId, TaskSpec, TimeSegment, Timestamp, TrackMedia, TrackMediaFormat,
+    TrackNode, TrackNodeId,
+};
+
+/// A type with a curated example value
+pub trait Example: JsonSchema + Serialize {
+    fn example() -> Self;
+}
+
+/// JSON schema and example payload of every type with a curated example, keyed by type name
+pub fn examples() -> BTreeMap<String, (RootSchema, serde_json::Value)> {
+    BTreeMap::from([entry::<TaskSpec>(),
+                    entry::<ModifyTaskSpec>(),
+                    entry::<ModifyTask>(),
+                    entry::<TaskUpdated>(),
+                    entry::<RequestPlay>(),
+                    entry::<RequestRender>(),
+                    entry::<TaskEvent>(),
+                    entry::<DomainServerMessage>(),
+                    entry::<InstanceDriverCommand>(),
+                    entry::<DriverCommandEnvelope>(),
+                    entry::<CloudError>()])
+}
+
+fn entry<T: Example>() -> (String, (RootSchema, serde_json::Value)) {
+    let example = serde_json::to_value(T::example()).expect("examples serialize to JSON");
+
+    (T::schema_name(), (schema_for!(T), example))
+}
+
+fn example_time() -> Timestamp {
+    (UNIX_EPOCH + Duration::from_secs(1_664_625_600)).into()
+}
+
+fn example_segment() -> TimeSegment {
+    TimeSegment { start:  0.0,
+                  length: 180.0, }
+}
+
+fn example_task_id() -> AppTaskId {
+    AppTaskId::new(AppId::new("example-app".to_owned()), TaskId::new("example-task".to_owned()))
+}
+
+fn example_track_id() -> TrackNodeId {
+    TrackNodeId::new("vocals".to_owned())
+}
+
+fn example_mixer_id() -> MixerNodeId {
+    MixerNodeId::new("master".to_owned())
+}
+
+impl Example for TaskSpec {
+    fn example() -> Self {
+        let media = TrackMedia { channels:         MediaChannels::Stereo,
+                                 format:           TrackMediaFormat::Wave,
+                                 media_segment:    example_segment(),
+                                 timeline_segment: example_segment(),
+                                 object_id:        MediaObjectId::new("vocals-take-3".to_owned()), };
+
+        let connection = NodeConnection { from:          example_track_id().source(),
+                                          to:            example_mixer_id().input_flow(),
+                                          kind:          ConnectionKind::Audio,
+                                          from_channels: ChannelMask::Stereo(0),
+                                          to_channels:   ChannelMask::Stereo(0),
+                                          volume:        1.0,
+                                          pan:           0.0,
+                                          pan_law:       None,
+                                          delay_ms:      0.0,
+                                          automation:    Default::default(), };
+
+        Self { tracks: HashMap::from([(example_track_id(),
+                                       TrackNode { media: HashMap::from([("take-3".to_owned().into(), media)]),
+                                                   ..TrackNode::new(MediaChannels::Stereo) })]),
+               mixers: HashMap::from([(example_mixer_id(),
+                                       MixerNode { input_channels:  2,
+                                                   output_channels: 2, })]),
+               connections: HashMap::from([(NodeConnectionId::new("vocals-to-master".to_owned()), connection)]),
+               pan_law: PanLaw::default(),
+               ..Default::default() }
+    }
+}
+
+impl Example for ModifyTaskSpec {
+    fn example() -> Self {
+        Self::SetConnectionDelay { connection_id: NodeConnectionId::new("vocals-to-master".to_owned()),
+                                   delay_ms:      2.5, }
+    }
+}
+
+impl Example for ModifyTask {
+    fn example() -> Self {
+        Self { modify_spec: vec![ModifyTaskSpec::example()],
+               dry_run:     false, }
+    }
+}
+
+impl Example for TaskUpdated {
+    fn example() -> Self {
+        Self::Updated { task_id:  example_task_id(),
+                        revision: 4,
+                        audit_id: None, }
+    }
+}
+
+impl Example for RequestPlay {
+    fn example() -> Self {
+        Self { play_id:            PlayId::new(1),
+               mixers:             HashMap::from([(example_mixer_id(), 1.0)]),
+               segment:            example_segment(),
+               start_at:           30.0,
+               looping:            false,
+               loop_region:        None,
+               punch:              None,
+               sample_rate:        SampleRate::SR48,
+               bit_depth:          PlayBitDepth::PD24,
+               position_update_hz: Some(10.0),
+               scheduled_at:       None,
+               click:              None,
+               timecode:           None, }
+    }
+}
+
+impl Example for RequestRender {
+    fn example() -> Self {
+        Self { render_id:       RenderId::new(1),
+               mixer_id:        example_mixer_id(),
+               segment:         example_segment(),
+               object_id:       (AppId::new("example-app".to_owned()), MediaObjectId::new("vocals-render".to_owned())).into(),
+               max_duration_ms: Some(600_000),
+               scheduled_at:    Some(example_time()),
+               output_format:   RenderOutputFormat { container:   RenderContainer::Flac,
+                                                     bit_depth:   RenderBitDepth::RD24,
+                                                     sample_rate: Some(SampleRate::SR48),
+                                                     dither:      false, }, }
+    }
+}
+
+impl Example for TaskEvent {
+    fn example() -> Self {
+        Self::Stopped { reason: StopReason::UserRequest, }
+    }
+}
+
+impl Example for DomainServerMessage {
+    fn example() -> Self {
+        Self::PlayheadPosition { task_id:  example_task_id(),
+                                 position: PlayheadPosition { play_id:       PlayId::new(1),
+                                                              timeline_pos:  42.5,
+                                                              timecode:      None,
+                                                              streaming_pos: 2_040_000,
+                                                              created_at:    example_time(), }, }
+    }
+}
+
+impl Example for InstanceDriverCommand {
+    fn example() -> Self {
+        Self::Play { play_id: PlayId::new(1) }
+    }
+}
+
+impl Example for DriverCommandEnvelope {
+    fn example() -> Self {
+        Self { command_id: DriverCommandId::new(17),
+               command:    InstanceDriverCommand::example(), }
+    }
+}
+
+impl Example for CloudError {
+    fn example() -> Self {
+        Self::DelayOutOfRange { delay_ms: 1500.0,
+                                max:      1000.0, }
+    }
+}
@@ -0,0 +1,316 @@
+//! An in-memory instance driver, for integration tests without hardware
+//!
+//! The mock accepts commands for any [`Model`], keeps parameters, power and play state in memory and
+//! produces the same events a real driver would. Time only advances when [`MockInstanceDriver::tick`]
+//! is called, so tests are deterministic.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::common::instance::{DesiredInstancePlayState, InstancePlayState};
+use crate::common::model::{Model, ModelValidationError, ModelValueOption, MultiChannelValue, ParameterValues, PowerDistributorReports};
+use crate::instance_driver::{
+    DriverCommandEnvelope, InstanceDriverCommand, InstanceDriverError, InstanceDriverEvent, RecentDriverCommands, ReportFilter,
+    SelfTestResult, SelfTestSubsystem,
+};
+use crate::newtypes::FixedInstanceId;
+use crate::{Timestamp, API_VERSION};
+
+/// Number of recently received commands whose outcome is remembered to answer redelivered commands
+const RECENT_COMMANDS: usize = 256;
+
+/// Instance driver simulating a device in memory
+#[derive(Debug, Clone)]
+pub struct MockInstanceDriver {
+    instance_id:   FixedInstanceId,
+    model:         Model,
+    parameters:    ParameterValues,
+    generation:    u64,
+    power:         Vec<bool>,
+    desired:       DesiredInstancePlayState,
+    play_state:    InstancePlayState,
+    media:         f64,
+    report_filter: ReportFilter,
+    last_tick:     Option<Timestamp>,
+    last_report:   Option<Timestamp>,
+    recent:        RecentDriverCommands,
+    events:        VecDeque<InstanceDriverEvent>,
+}
+
+impl MockInstanceDriver {
+    /// Create a stopped mock driver, with the default parameters of the model
+    pub fn new(instance_id: FixedInstanceId, model: Model) -> Self {
+        Self { instance_id,
+               parameters: model.default_parameters(),
+               power: vec![false; model.get_power_channel_count()],
+               model,
+               generation: 0,
+               desired: DesiredInstancePlayState::Stopped,
+               play_state: InstancePlayState::Stopped,
+               media: 0.0,
+               report_filter: ReportFilter::default(),
+               last_tick: None,
+               last_report: None,
+               recent: RecentDriverCommands::new(RECENT_COMMANDS),
+               events: VecDeque::from([InstanceDriverEvent::Started, InstanceDriverEvent::Connected]) }
+    }
+
+    /// Send only the reports included by the filter, at most once per its minimum interval
+    ///
+    /// The filter can later be replaced with [`InstanceDriverCommand::SetReportFilter`].
+    pub fn with_report_filter(mut self, report_filter: ReportFilter) -> Self {
+        self.report_filter = report_filter;
+        self
+    }
+
+    pub fn instance_id(&self) -> &FixedInstanceId {
+        &self.instance_id
+    }
+
+    pub fn parameters(&self) -> &ParameterValues {
+        &self.parameters
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn play_state(&self) -> &InstancePlayState {
+        &self.play_state
+    }
+
+    /// Media position in seconds
+    pub fn media(&self) -> f64 {
+        self.media
+    }
+
+    /// True if the power channel is switched on
+    pub fn power(&self, channel: usize) -> bool {
+        self.power.get(channel).copied().unwrap_or_default()
+    }
+
+    /// Execute a command, queueing the events it causes
+    pub fn handle(&mut self, command: InstanceDriverCommand) -> Result<(), InstanceDriverError> {
+        if let Some(capability) = command.required_capability() {
+            if !self.model.capabilities.contains(&capability) {
+                return Err(InstanceDriverError::CapabilityNotSupported { capability });
+            }
+        }
+
+        match command {
+            InstanceDriverCommand::CheckConnection => {
+                self.events.push_back(InstanceDriverEvent::Connected);
+            }
+            InstanceDriverCommand::Stop => {
+                self.set_desired(DesiredInstancePlayState::Stopped, InstancePlayState::Stopping)?;
+            }
+            InstanceDriverCommand::Play { play_id } => {
+                self.set_desired(DesiredInstancePlayState::Playing { play_id },
+                                 InstancePlayState::PreparingToPlay { play_id })?;
+            }
+            InstanceDriverCommand::Render { length, render_id } => {
+                self.set_desired(DesiredInstancePlayState::Rendering { length, render_id },
+                                 InstancePlayState::PreparingToRender { length, render_id })?;
+            }
+            InstanceDriverCommand::Rewind { to } => {
+                self.set_desired(DesiredInstancePlayState::Stopped, InstancePlayState::Rewinding { to })?;
+            }
+            InstanceDriverCommand::SetParameters(parameters) => {
+                let parameters = serde_json::from_value::<ParameterValues>(parameters).map_err(|error| {
+                                     InstanceDriverError::ParametersMalformed { error: error.to_string() }
+                                 })?;
+                self.model.validate_parameters(&parameters).map_err(parameters_error)?;
+
+                for (parameter_id, value) in parameters {
+                    let value = self.model.expand_linked_value(&parameter_id, &value);
+                    let current = self.parameters.entry(parameter_id).or_default();
+                    current.0.resize(current.0.len().max(value.0.len()), None);
+                    for (current, value) in current.0.iter_mut().zip(value.0) {
+                        if value.is_some() {
+                            *current = value;
+                        }
+                    }
+                }
+            }
+            InstanceDriverCommand::SetParametersAtomic { parameters,
+                                                         expected_generation, } => {
+                if expected_generation != self.generation {
+                    return Err(InstanceDriverError::GenerationMismatch { expected: expected_generation,
+                                                                         current:  self.generation, });
+                }
+
+                self.model.validate_parameters(&parameters).map_err(parameters_error)?;
+                self.parameters = self.model.default_parameters();
+                self.parameters.extend(parameters.into_iter().map(|(parameter_id, value)| {
+                                                                 let value = self.model.expand_linked_value(&parameter_id, &value);
+                                                                 (parameter_id, value)
+                                                             }));
+                self.generation += 1;
+                self.events
+                    .push_back(InstanceDriverEvent::ParametersApplied { generation: self.generation, });
+            }
+            InstanceDriverCommand::SetPowerChannel { channel, power } => {
+                match self.power.get_mut(channel) {
+                    Some(current) => *current = power,
+                    None => {
+                        return Err(InstanceDriverError::PowerChannelOutOfRange { channel,
+                                                                                 channels: self.power.len() })
+                    }
+                }
+
+                let reports = serde_json::to_value(PowerDistributorReports { power: Some(self.power.clone()) })
+                    .map_err(|error| InstanceDriverError::ReportsMalformed { error: error.to_string() })?;
+                self.events.push_back(InstanceDriverEvent::Reports { reports });
+            }
+            InstanceDriverCommand::Identify { .. } => {}
+            InstanceDriverCommand::RunSelfTest => {
+                let subsystems = vec![SelfTestSubsystem { name:    "mock".to_owned(),
+                                                          passed:  true,
+                                                          message: None, }];
+                self.events
+                    .push_back(InstanceDriverEvent::SelfTestResult { result: SelfTestResult { subsystems }, });
+            }
+            InstanceDriverCommand::NegotiateVersion(negotiate) => {
+                self.events
+                    .push_back(InstanceDriverEvent::VersionNegotiated { result: negotiate.respond(&[API_VERSION]), });
+            }
+            InstanceDriverCommand::RunDiagnostic { kind } => {
+                self.events.push_back(InstanceDriverEvent::DiagnosticResult { kind,
+                                                                              passed: true,
+                                                                              details: None });
+            }
+            InstanceDriverCommand::SetReportFilter(report_filter) => {
+                self.report_filter = report_filter;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Execute a command delivered in an envelope, at most once per command id
+    ///
+    /// Returns the acknowledgement to send back. Redelivered commands are not executed again, but
+    /// answered with the acknowledgement of their first delivery.
+    pub fn handle_envelope(&mut self, envelope: DriverCommandEnvelope) -> InstanceDriverEvent {
+        let DriverCommandEnvelope { command_id, command } = envelope;
+        let outcome = match self.recent.outcome(command_id) {
+            Some(outcome) => outcome.clone(),
+            None => {
+                let outcome = self.handle(command);
+                self.recent.record(command_id, outcome.clone());
+                outcome
+            }
+        };
+
+        match outcome {
+            Ok(()) => InstanceDriverEvent::Ack { command_id },
+            Err(reason) => InstanceDriverEvent::Nack { command_id, reason },
+        }
+    }
+
+    /// Advance the simulation to `now`
+    ///
+    /// Pending play state transitions complete, the media position advances while playing or rendering
+    /// and synthetic reports are queued when they are due.
+    pub fn tick(&mut self, now: Timestamp) {
+        let elapsed_ms = self.last_tick
+                             .map(|last_tick| (now - last_tick).num_milliseconds().max(0) as u64)
+                             .unwrap_or_default();
+        self.last_tick = Some(now);
+
+        let next = match self.play_state {
+            InstancePlayState::PreparingToPlay { play_id } => Some(InstancePlayState::Playing { play_id }),
+            InstancePlayState::PreparingToRender { length, render_id } => Some(InstancePlayState::Rendering { length, render_id }),
+            InstancePlayState::Rewinding { to } => {
+                self.media = to;
+                Some(InstancePlayState::Stopped)
+            }
+            InstancePlayState::Stopping => Some(InstancePlayState::Stopped),
+            InstancePlayState::Playing { .. } => {
+                self.media += elapsed_ms as f64 / 1000.0;
+                None
+            }
+            InstancePlayState::Rendering { length, .. } => {
+                self.media = (self.media + elapsed_ms as f64 / 1000.0).min(length);
+                (self.media >= length).then_some(InstancePlayState::Stopped)
+            }
+            InstancePlayState::Stopped => None,
+        };
+
+        if let Some(next) = next {
+            if next == InstancePlayState::Stopped {
+                self.desired = DesiredInstancePlayState::Stopped;
+            }
+            self.play_state = next;
+        }
+
+        let moving = matches!(self.play_state,
+                              InstancePlayState::Playing { .. } | InstancePlayState::Rendering { .. });
+        if moving || next.is_some() {
+            self.push_play_state();
+        }
+
+        let since_report_ms = self.last_report
+                                  .map(|last_report| (now - last_report).num_milliseconds().max(0) as u64);
+        if self.report_filter.is_due(since_report_ms) {
+            if let Some(reports) = self.synthetic_reports() {
+                self.last_report = Some(now);
+                self.events.push_back(InstanceDriverEvent::Reports { reports });
+            }
+        }
+    }
+
+    /// Take all events queued since the last call
+    pub fn drain_events(&mut self) -> impl Iterator<Item = InstanceDriverEvent> + '_ {
+        self.events.drain(..)
+    }
+
+    fn set_desired(&mut self, desired: DesiredInstancePlayState, transition: InstancePlayState) -> Result<(), InstanceDriverError> {
+        if !self.model.media && transition != InstancePlayState::Stopping {
+            return Err(InstanceDriverError::MediaNotPresent);
+        }
+
+        self.desired = desired;
+        self.play_state = transition;
+        self.push_play_state();
+
+        Ok(())
+    }
+
+    fn push_play_state(&mut self) {
+        self.events.push_back(InstanceDriverEvent::PlayState { desired: self.desired,
+                                                               current: self.play_state,
+                                                               media:   self.model.media.then_some(self.media), });
+    }
+
+    /// Reports at the lowest allowed value on every channel, or `None` if the filter includes none
+    fn synthetic_reports(&self) -> Option<serde_json::Value> {
+        let reports = self.model
+                          .reports
+                          .iter()
+                          .filter(|(report_id, _)| self.report_filter.includes(report_id))
+                          .map(|(report_id, report)| {
+                              let value = report.values.first().map(|option| match option {
+                                                                   ModelValueOption::Single(value) => value.clone(),
+                                                                   ModelValueOption::Range(min, _) => min.clone(),
+                                                               });
+
+                              (report_id.clone(), MultiChannelValue(vec![value; report.scope.len(&self.model)]))
+                          })
+                          .collect::<HashMap<_, _>>();
+
+        if reports.is_empty() {
+            return None;
+        }
+
+        serde_json::to_value(reports).ok()
+    }
+}
+
+fn parameters_error(error: ModelValidationError) -> InstanceDriverError {
+    match error {
+        ModelValidationError::UnknownParameter { parameter_id } => {
+            InstanceDriverError::ParameterDoesNotExist { error: parameter_id.to_string(), }
+        }
+        error => InstanceDriverError::ParametersMalformed { error: error.to_string() },
+    }
+}
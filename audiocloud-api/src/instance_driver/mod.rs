@@ -1,16 +1,25 @@
 //! Types used to communicate with the instance_driver
 
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use derive_more::{Constructor, Display, From, Into};
 use schemars::schema::RootSchema;
 use schemars::{schema_for, JsonSchema};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+#[cfg(feature = "openapi")]
 use utoipa::OpenApi;
 
 use crate::common::instance::{DesiredInstancePlayState, InstancePlayState};
 use crate::common::media::{PlayId, RenderId};
+use crate::common::model::{ModelCapability, ParameterValues};
 use crate::common::task::InstanceReports;
-use crate::newtypes::FixedInstanceId;
-use crate::{merge_schemas, Request, SerializableResult};
+use crate::newtypes::{FixedInstanceId, ReportId};
+#[cfg(feature = "openapi")]
+use crate::openapi_document;
+use crate::{merge_schemas, Envelope, NegotiateVersion, Request, SerializableResult, VersionNegotiated};
+
+pub mod mock;
 
 /// A command that can be sent to the instance driver
 #[derive(PartialEq, Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -18,17 +27,202 @@ use crate::{merge_schemas, Request, SerializableResult};
 pub enum InstanceDriverCommand {
     CheckConnection,
     Stop,
-    Play { play_id: PlayId },
-    Render { length: f64, render_id: RenderId },
-    Rewind { to: f64 },
+    Play {
+        play_id: PlayId,
+    },
+    Render {
+        length:    f64,
+        render_id: RenderId,
+    },
+    Rewind {
+        to: f64,
+    },
     SetParameters(serde_json::Value),
-    SetPowerChannel { channel: usize, power: bool },
+    /// Replace all parameter values with a snapshot, if the driver is at the expected generation
+    ///
+    /// Parameters not present in the snapshot are reset to their model defaults. The driver applies
+    /// the whole snapshot before executing any other command, increments its generation counter and
+    /// reports the new generation with [`InstanceDriverEvent::ParametersApplied`]. If the driver is at
+    /// a different generation, the command is rejected with [`InstanceDriverError::GenerationMismatch`].
+    SetParametersAtomic {
+        parameters:          ParameterValues,
+        expected_generation: u64,
+    },
+    SetPowerChannel {
+        channel: usize,
+        power:   bool,
+    },
+    Identify {
+        duration_ms: u64,
+    },
+    RunSelfTest,
+    /// Negotiate the API version used with the driver, answered with [`InstanceDriverEvent::VersionNegotiated`]
+    NegotiateVersion(NegotiateVersion),
+    /// Run a diagnostic routine, reported with [`InstanceDriverEvent::DiagnosticResult`] when it completes
+    ///
+    /// Self-tests are run with [`InstanceDriverCommand::RunSelfTest`], which reports the outcome per subsystem.
+    RunDiagnostic {
+        kind: DiagnosticKind,
+    },
+    /// Replace the filter selecting which reports the driver sends, and how often
+    SetReportFilter(ReportFilter),
+}
+
+impl Into<InstanceDriverCommand> for DesiredInstancePlayState {
+    fn into(self) -> InstanceDriverCommand {
+        match self {
+            DesiredInstancePlayState::Playing { play_id } => InstanceDriverCommand::Play { play_id },
+            DesiredInstancePlayState::Rendering { length, render_id } => InstanceDriverCommand::Render { render_id, length },
+            DesiredInstancePlayState::Stopped => InstanceDriverCommand::Stop,
+        }
+    }
+}
+
+impl InstanceDriverCommand {
+    /// Capability the instance model must advertise to accept this command
+    pub fn required_capability(&self) -> Option<ModelCapability> {
+        match self {
+            Self::SetPowerChannel { .. } => Some(ModelCapability::PowerDistributor),
+            Self::Identify { .. } => Some(ModelCapability::Identify),
+            Self::RunSelfTest => Some(ModelCapability::SelfTest),
+            Self::RunDiagnostic { kind } => Some(kind.required_capability()),
+            _ => None,
+        }
+    }
 }
 
 impl Request for InstanceDriverCommand {
     type Response = SerializableResult<(), InstanceDriverError>;
 }
 
+/// Id of a command sent to the instance driver, unique per instance
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug, From, Into, Hash, Display, Constructor, JsonSchema)]
+#[repr(transparent)]
+pub struct DriverCommandId(u64);
+
+/// A command with an id, delivered at least once over links that may lose messages
+///
+/// The driver acknowledges every received envelope with [`InstanceDriverEvent::Ack`] or
+/// [`InstanceDriverEvent::Nack`], and executes a command id only once, even if it is redelivered.
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct DriverCommandEnvelope {
+    /// Id of the command
+    pub command_id: DriverCommandId,
+    /// The command to execute
+    pub command:    InstanceDriverCommand,
+}
+
+impl Request for DriverCommandEnvelope {
+    type Response = SerializableResult<(), InstanceDriverError>;
+}
+
+/// A [`DriverCommandEnvelope`] with request correlation and tracing metadata
+pub type TracedDriverCommand = Envelope<DriverCommandEnvelope>;
+
+/// An [`InstanceDriverEvent`] with request correlation and tracing metadata
+pub type TracedDriverEvent = Envelope<InstanceDriverEvent>;
+
+/// When to resend a command that has not been acknowledged
+///
+/// A negative acknowledgement is final, only missing acknowledgements cause a resend.
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct ResendPolicy {
+    /// Milliseconds to wait for an acknowledgement of the first attempt
+    pub ack_timeout_ms: u64,
+    /// Factor by which the timeout grows with every attempt
+    pub backoff:        f64,
+    /// Upper limit of the timeout in milliseconds
+    pub max_timeout_ms: u64,
+    /// Maximum number of attempts, including the first one
+    pub max_attempts:   u32,
+}
+
+impl Default for ResendPolicy {
+    fn default() -> Self {
+        Self { ack_timeout_ms: 250,
+               backoff:        2.0,
+               max_timeout_ms: 5_000,
+               max_attempts:   5, }
+    }
+}
+
+impl ResendPolicy {
+    /// Milliseconds to wait for an acknowledgement of an attempt (starting at 1), or `None` if the
+    /// command should not be sent again
+    pub fn ack_timeout_ms(&self, attempt: u32) -> Option<u64> {
+        if attempt == 0 || attempt > self.max_attempts {
+            return None;
+        }
+
+        let timeout = self.ack_timeout_ms as f64 * self.backoff.max(1.0).powi(i32::try_from(attempt - 1).unwrap_or(i32::MAX));
+
+        Some(timeout.min(self.max_timeout_ms as f64) as u64)
+    }
+}
+
+/// Remembers the outcome of recently received commands, so redelivered commands are executed only once
+///
+/// A redelivered command is answered with the outcome of its first delivery, so a lost negative
+/// acknowledgement is not turned into an acknowledgement by a resend.
+#[derive(Debug, Clone)]
+pub struct RecentDriverCommands {
+    capacity: usize,
+    order:    VecDeque<DriverCommandId>,
+    outcomes: HashMap<DriverCommandId, Result<(), InstanceDriverError>>,
+}
+
+impl RecentDriverCommands {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1),
+               order:    VecDeque::new(),
+               outcomes: HashMap::new(), }
+    }
+
+    /// Outcome of a command received before, or `None` if it was not received recently
+    pub fn outcome(&self, command_id: DriverCommandId) -> Option<&Result<(), InstanceDriverError>> {
+        self.outcomes.get(&command_id)
+    }
+
+    /// Record the outcome of executing a received command
+    pub fn record(&mut self, command_id: DriverCommandId, outcome: Result<(), InstanceDriverError>) {
+        if self.outcomes.insert(command_id, outcome).is_some() {
+            return;
+        }
+
+        self.order.push_back(command_id);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.outcomes.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Reports an instance driver sends, and how often
+///
+/// High frequency reports such as metering are sent at the rate the hardware produces them, unless
+/// a consumer asks for less.
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct ReportFilter {
+    /// Reports to send, all reports if empty
+    #[serde(default)]
+    pub reports:         HashSet<ReportId>,
+    /// Minimum number of milliseconds between two updates of the same report, zero for no limit
+    #[serde(default)]
+    pub min_interval_ms: u64,
+}
+
+impl ReportFilter {
+    pub fn includes(&self, report_id: &ReportId) -> bool {
+        self.reports.is_empty() || self.reports.contains(report_id)
+    }
+
+    /// True if a report last sent `elapsed_ms` ago (or never) may be sent again
+    pub fn is_due(&self, elapsed_ms: Option<u64>) -> bool {
+        elapsed_ms.is_none_or(|elapsed_ms| elapsed_ms >= self.min_interval_ms)
+    }
+}
+
 #[derive(PartialEq, Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct SetInstanceParameters {
     pub parameters: serde_json::Value,
@@ -46,6 +240,9 @@ pub enum InstanceDriverError {
     #[error("Parameters are malformed: {error}")]
     ParametersMalformed { error: String },
 
+    #[error("Expected parameter generation {expected}, but the instance is at generation {current}")]
+    GenerationMismatch { expected: u64, current: u64 },
+
     #[error("Reports are malformed: {error}")]
     ReportsMalformed { error: String },
 
@@ -55,6 +252,12 @@ pub enum InstanceDriverError {
     #[error("Instance is not a power controller")]
     NotPowerController,
 
+    #[error("Power channel {channel} does not exist, the instance has {channels} power channels")]
+    PowerChannelOutOfRange { channel: usize, channels: usize },
+
+    #[error("Instance does not support {capability:?}")]
+    CapabilityNotSupported { capability: ModelCapability },
+
     #[error("Driver can't guarantee that playback won't be interrupted")]
     NotInterruptable,
 
@@ -86,6 +289,76 @@ pub enum InstanceDriverEvent {
         current: InstancePlayState,
         media:   Option<f64>,
     },
+
+    /// Self-test completed
+    SelfTestResult { result: SelfTestResult },
+
+    /// Diagnostic routine completed
+    DiagnosticResult {
+        kind:    DiagnosticKind,
+        passed:  bool,
+        /// Details reported by the device, usually only present on failure
+        #[serde(default)]
+        details: Option<String>,
+    },
+
+    /// Outcome of a version negotiation
+    VersionNegotiated { result: VersionNegotiated },
+
+    /// A parameter snapshot was applied, the instance is now at this generation
+    ParametersApplied { generation: u64 },
+
+    /// Command was received and accepted
+    Ack { command_id: DriverCommandId },
+
+    /// Command was received but rejected, and should not be resent
+    Nack {
+        command_id: DriverCommandId,
+        reason:     InstanceDriverError,
+    },
+}
+
+/// Diagnostic routine run by a device on request
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy, Debug, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// Calibration of the device, which may change its internal trims
+    Calibration,
+}
+
+impl DiagnosticKind {
+    /// Capability the instance model must advertise to run this diagnostic
+    pub fn required_capability(self) -> ModelCapability {
+        match self {
+            Self::Calibration => ModelCapability::Calibration,
+        }
+    }
+}
+
+/// Outcome of a device self-test
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct SelfTestResult {
+    /// Outcome per tested subsystem
+    pub subsystems: Vec<SelfTestSubsystem>,
+}
+
+impl SelfTestResult {
+    /// True if every subsystem passed
+    pub fn passed(&self) -> bool {
+        self.subsystems.iter().all(|subsystem| subsystem.passed)
+    }
+}
+
+/// Outcome of a self-test of a single device subsystem
+#[derive(PartialEq, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct SelfTestSubsystem {
+    /// Name of the subsystem, as reported by the device (for example `psu` or `channel_1`)
+    pub name:    String,
+    /// True if the subsystem passed the test
+    pub passed:  bool,
+    /// Details reported by the device, usually only present on failure
+    #[serde(default)]
+    pub message: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -108,6 +381,7 @@ pub enum InstanceCommandAccepted {
     Updated { id: FixedInstanceId },
 }
 
+#[cfg(feature = "openapi")]
 mod instance {
     /// Set desired play state
     ///
@@ -145,6 +419,7 @@ mod instance {
     fn set_parameters() {}
 }
 
+#[cfg(feature = "openapi")]
 mod driver {
     /// List running instances
     ///
@@ -158,13 +433,27 @@ mod driver {
     fn list_instances() {}
 }
 
+#[cfg(feature = "openapi")]
 #[derive(OpenApi)]
 #[openapi(paths(instance::accept_command, instance::set_parameters, driver::list_instances))]
 pub struct InstanceDriverApi;
 
+/// OpenAPI document of the instance driver, served on the given `(url, description)` servers
+#[cfg(feature = "openapi")]
+pub fn openapi<'a>(servers: impl IntoIterator<Item = (&'a str, &'a str)>) -> anyhow::Result<String> {
+    openapi_document(InstanceDriverApi::openapi(), schemas(), "Audio Cloud Instance Driver", servers)
+}
+
 pub fn schemas() -> RootSchema {
     merge_schemas([schema_for!(InstanceDriverError),
                    schema_for!(InstanceDriverCommand),
+                   schema_for!(DriverCommandEnvelope),
+                   schema_for!(TracedDriverCommand),
+                   schema_for!(VersionNegotiated),
+                   schema_for!(ResendPolicy),
+                   schema_for!(ReportFilter),
+                   schema_for!(SelfTestResult),
+                   schema_for!(DiagnosticKind),
                    schema_for!(InstanceCommandAccepted),
                    schema_for!(InstanceParametersUpdated),
                    schema_for!(SetInstanceParameters),
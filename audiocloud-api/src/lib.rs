@@ -5,9 +5,19 @@ pub use api::*;
 pub use common::*;
 
 pub mod api;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "engine")]
 pub mod audio_engine;
+#[cfg(feature = "cloud")]
 pub mod cloud;
 pub mod common;
+#[cfg(feature = "domain")]
 pub mod domain;
+#[cfg(feature = "domain")]
+pub mod examples;
+#[cfg(feature = "driver")]
 pub mod instance_driver;
-
+#[cfg(feature = "proto")]
+pub mod proto;
+pub mod routing;
@@ -0,0 +1,365 @@
+//! Messages for the domain API
+
+use prost_types::{Timestamp, Value};
+
+#[cfg(feature = "domain")]
+use crate::domain::{DomainCommand, DomainEvent};
+#[cfg(feature = "domain")]
+use crate::proto::{from_timestamp, from_value, missing, parse_id, to_timestamp, to_value, ProtoError};
+
+/// A [`DomainCommand`](crate::domain::DomainCommand)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DomainCommandMessage {
+    #[prost(oneof = "domain_command_message::Command", tags = "1, 2, 3, 4, 5, 6, 7, 8")]
+    pub command: Option<domain_command_message::Command>,
+}
+
+pub mod domain_command_message {
+    use super::Value;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Create {
+        #[prost(string, tag = "1")]
+        pub app_session_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub task:           Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetSpec {
+        #[prost(string, tag = "1")]
+        pub app_session_id: String,
+        #[prost(uint64, tag = "2")]
+        pub version:        u64,
+        #[prost(message, optional, tag = "3")]
+        pub spec:           Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PatchSpec {
+        #[prost(string, tag = "1")]
+        pub app_session_id: String,
+        #[prost(uint64, tag = "2")]
+        pub version:        u64,
+        #[prost(message, optional, tag = "3")]
+        pub diff:           Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetSecurity {
+        #[prost(string, tag = "1")]
+        pub app_session_id: String,
+        #[prost(uint64, tag = "2")]
+        pub version:        u64,
+        #[prost(message, optional, tag = "3")]
+        pub security:       Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Modify {
+        #[prost(string, tag = "1")]
+        pub app_session_id: String,
+        #[prost(uint64, tag = "2")]
+        pub version:        u64,
+        #[prost(message, optional, tag = "3")]
+        pub modifications:  Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetDesiredPlayState {
+        #[prost(string, tag = "1")]
+        pub app_session_id:     String,
+        #[prost(uint64, tag = "2")]
+        pub version:            u64,
+        #[prost(message, optional, tag = "3")]
+        pub desired_play_state: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Delete {
+        #[prost(string, tag = "1")]
+        pub app_session_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub reason:         Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct UpdateConfig {
+        #[prost(message, optional, tag = "1")]
+        pub update: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Command {
+        #[prost(message, tag = "1")]
+        Create(Create),
+        #[prost(message, tag = "2")]
+        SetSpec(SetSpec),
+        #[prost(message, tag = "3")]
+        PatchSpec(PatchSpec),
+        #[prost(message, tag = "4")]
+        SetSecurity(SetSecurity),
+        #[prost(message, tag = "5")]
+        Modify(Modify),
+        #[prost(message, tag = "6")]
+        SetDesiredPlayState(SetDesiredPlayState),
+        #[prost(message, tag = "7")]
+        Delete(Delete),
+        #[prost(message, tag = "8")]
+        UpdateConfig(UpdateConfig),
+    }
+}
+
+/// A [`DomainEvent`](crate::domain::DomainEvent)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DomainEventMessage {
+    #[prost(oneof = "domain_event_message::Event", tags = "1, 2, 3, 4, 5, 6, 7, 8")]
+    pub event: Option<domain_event_message::Event>,
+}
+
+pub mod domain_event_message {
+    use super::{Timestamp, Value};
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FixedInstance {
+        #[prost(string, tag = "1")]
+        pub instance_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub event:       Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Task {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub event:   Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FixedInstanceConnection {
+        #[prost(string, tag = "1")]
+        pub instance_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub state:       Option<Value>,
+        #[prost(message, optional, tag = "3")]
+        pub last_seen:   Option<Timestamp>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct LicensePoolExhausted {
+        #[prost(string, optional, tag = "1")]
+        pub engine_id: Option<String>,
+        #[prost(message, optional, tag = "2")]
+        pub pool:      Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TaskFinished {
+        #[prost(message, optional, tag = "1")]
+        pub summary: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MediaJob {
+        #[prost(string, tag = "1")]
+        pub media_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub kind:     Option<Value>,
+        #[prost(message, optional, tag = "3")]
+        pub event:    Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct EngineHeartbeat {
+        #[prost(string, tag = "1")]
+        pub engine_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub load:      Option<Value>,
+        #[prost(message, optional, tag = "3")]
+        pub at:        Option<Timestamp>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TaskTimeExtension {
+        #[prost(message, optional, tag = "1")]
+        pub outcome: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "1")]
+        FixedInstance(FixedInstance),
+        #[prost(message, tag = "2")]
+        Task(Task),
+        #[prost(message, tag = "3")]
+        FixedInstanceConnection(FixedInstanceConnection),
+        #[prost(message, tag = "4")]
+        LicensePoolExhausted(LicensePoolExhausted),
+        #[prost(message, tag = "5")]
+        TaskFinished(TaskFinished),
+        #[prost(message, tag = "6")]
+        MediaJob(MediaJob),
+        #[prost(message, tag = "7")]
+        EngineHeartbeat(EngineHeartbeat),
+        #[prost(message, tag = "8")]
+        TaskTimeExtension(TaskTimeExtension),
+    }
+}
+
+#[cfg(feature = "domain")]
+impl TryFrom<&DomainCommand> for DomainCommandMessage {
+    type Error = ProtoError;
+
+    fn try_from(command: &DomainCommand) -> Result<Self, Self::Error> {
+        use domain_command_message::*;
+
+        let command = match command {
+            DomainCommand::Create { app_session_id, task } => Command::Create(Create { app_session_id: app_session_id.to_string(),
+                                                                                       task:           to_value("task", task)?, }),
+            DomainCommand::SetSpec { app_session_id,
+                                     version,
+                                     spec, } => Command::SetSpec(SetSpec { app_session_id: app_session_id.to_string(),
+                                                                           version:        *version,
+                                                                           spec:           to_value("spec", spec)?, }),
+            DomainCommand::PatchSpec { app_session_id,
+                                       version,
+                                       diff, } => Command::PatchSpec(PatchSpec { app_session_id: app_session_id.to_string(),
+                                                                                 version:        *version,
+                                                                                 diff:           to_value("diff", diff)?, }),
+            DomainCommand::SetSecurity { app_session_id,
+                                         version,
+                                         security, } => Command::SetSecurity(SetSecurity { app_session_id: app_session_id.to_string(),
+                                                                                           version:        *version,
+                                                                                           security:       to_value("security", security)?, }),
+            DomainCommand::Modify { app_session_id,
+                                    version,
+                                    modifications, } => Command::Modify(Modify { app_session_id: app_session_id.to_string(),
+                                                                                 version:        *version,
+                                                                                 modifications:  to_value("modifications", modifications)?, }),
+            DomainCommand::SetDesiredPlayState { app_session_id,
+                                                 version,
+                                                 desired_play_state, } => {
+                Command::SetDesiredPlayState(SetDesiredPlayState { app_session_id:     app_session_id.to_string(),
+                                                                   version:            *version,
+                                                                   desired_play_state: to_value("desired_play_state", desired_play_state)?, })
+            }
+            DomainCommand::Delete { app_session_id, reason } => Command::Delete(Delete { app_session_id: app_session_id.to_string(),
+                                                                                         reason:         to_value("reason", reason)?, }),
+            DomainCommand::UpdateConfig { update } => Command::UpdateConfig(UpdateConfig { update: to_value("update", update)?, }),
+        };
+
+        Ok(Self { command: Some(command) })
+    }
+}
+
+#[cfg(feature = "domain")]
+impl TryFrom<&DomainCommandMessage> for DomainCommand {
+    type Error = ProtoError;
+
+    fn try_from(message: &DomainCommandMessage) -> Result<Self, Self::Error> {
+        use domain_command_message::*;
+
+        Ok(match message.command.as_ref().ok_or_else(|| missing("command"))? {
+            Command::Create(command) => Self::Create { app_session_id: parse_id("app_session_id", &command.app_session_id)?,
+                                                       task:           from_value("task", &command.task)?, },
+            Command::SetSpec(command) => Self::SetSpec { app_session_id: parse_id("app_session_id", &command.app_session_id)?,
+                                                         version:        command.version,
+                                                         spec:           from_value("spec", &command.spec)?, },
+            Command::PatchSpec(command) => Self::PatchSpec { app_session_id: parse_id("app_session_id", &command.app_session_id)?,
+                                                             version:        command.version,
+                                                             diff:           from_value("diff", &command.diff)?, },
+            Command::SetSecurity(command) => Self::SetSecurity { app_session_id: parse_id("app_session_id", &command.app_session_id)?,
+                                                                 version:        command.version,
+                                                                 security:       from_value("security", &command.security)?, },
+            Command::Modify(command) => Self::Modify { app_session_id: parse_id("app_session_id", &command.app_session_id)?,
+                                                       version:        command.version,
+                                                       modifications:  from_value("modifications", &command.modifications)?, },
+            Command::SetDesiredPlayState(command) => {
+                Self::SetDesiredPlayState { app_session_id:     parse_id("app_session_id", &command.app_session_id)?,
+                                            version:            command.version,
+                                            desired_play_state: from_value("desired_play_state", &command.desired_play_state)?, }
+            }
+            Command::Delete(command) => Self::Delete { app_session_id: parse_id("app_session_id", &command.app_session_id)?,
+                                                       reason:         from_value("reason", &command.reason)?, },
+            Command::UpdateConfig(command) => Self::UpdateConfig { update: from_value("update", &command.update)?, },
+        })
+    }
+}
+
+#[cfg(feature = "domain")]
+impl TryFrom<&DomainEvent> for DomainEventMessage {
+    type Error = ProtoError;
+
+    fn try_from(event: &DomainEvent) -> Result<Self, Self::Error> {
+        use domain_event_message::*;
+
+        let event = match event {
+            DomainEvent::FixedInstance { instance_id, event } => Event::FixedInstance(FixedInstance { instance_id: instance_id.to_string(),
+                                                                                                      event:       to_value("event",
+                                                                                                                            event)?, }),
+            DomainEvent::Task { task_id, event } => Event::Task(Task { task_id: task_id.to_string(),
+                                                                       event:   to_value("event", event)?, }),
+            DomainEvent::FixedInstanceConnection { instance_id,
+                                                   state,
+                                                   last_seen, } => {
+                Event::FixedInstanceConnection(FixedInstanceConnection { instance_id: instance_id.to_string(),
+                                                                         state:       to_value("state", state)?,
+                                                                         last_seen:   to_timestamp(last_seen), })
+            }
+            DomainEvent::LicensePoolExhausted { engine_id, pool } => {
+                Event::LicensePoolExhausted(LicensePoolExhausted { engine_id: engine_id.as_ref().map(ToString::to_string),
+                                                                   pool:      to_value("pool", pool)?, })
+            }
+            DomainEvent::TaskFinished { summary } => Event::TaskFinished(TaskFinished { summary: to_value("summary", summary)?, }),
+            DomainEvent::MediaJob { media_id, kind, event } => Event::MediaJob(MediaJob { media_id: media_id.to_string(),
+                                                                                          kind:     to_value("kind", kind)?,
+                                                                                          event:    to_value("event", event)?, }),
+            DomainEvent::EngineHeartbeat { engine_id, load, at } => {
+                Event::EngineHeartbeat(EngineHeartbeat { engine_id: engine_id.to_string(),
+                                                         load:      to_value("load", load)?,
+                                                         at:        to_timestamp(at), })
+            }
+            DomainEvent::TaskTimeExtension { outcome } => {
+                Event::TaskTimeExtension(TaskTimeExtension { outcome: to_value("outcome", outcome)?, })
+            }
+        };
+
+        Ok(Self { event: Some(event) })
+    }
+}
+
+#[cfg(feature = "domain")]
+impl TryFrom<&DomainEventMessage> for DomainEvent {
+    type Error = ProtoError;
+
+    fn try_from(message: &DomainEventMessage) -> Result<Self, Self::Error> {
+        use domain_event_message::*;
+
+        Ok(match message.event.as_ref().ok_or_else(|| missing("event"))? {
+            Event::FixedInstance(event) => Self::FixedInstance { instance_id: parse_id("instance_id", &event.instance_id)?,
+                                                                 event:       from_value("event", &event.event)?, },
+            Event::Task(event) => Self::Task { task_id: parse_id("task_id", &event.task_id)?,
+                                               event:   from_value("event", &event.event)?, },
+            Event::FixedInstanceConnection(event) => {
+                Self::FixedInstanceConnection { instance_id: parse_id("instance_id", &event.instance_id)?,
+                                                state:       from_value("state", &event.state)?,
+                                                last_seen:   from_timestamp("last_seen", &event.last_seen)?, }
+            }
+            Event::LicensePoolExhausted(event) => Self::LicensePoolExhausted { engine_id:
+                                                                                   event.engine_id
+                                                                                        .as_deref()
+                                                                                        .map(|engine_id| parse_id("engine_id", engine_id))
+                                                                                        .transpose()?,
+                                                                               pool:      from_value("pool", &event.pool)?, },
+            Event::TaskFinished(event) => Self::TaskFinished { summary: from_value("summary", &event.summary)?, },
+            Event::MediaJob(event) => Self::MediaJob { media_id: parse_id("media_id", &event.media_id)?,
+                                                       kind:     from_value("kind", &event.kind)?,
+                                                       event:    from_value("event", &event.event)?, },
+            Event::EngineHeartbeat(event) => Self::EngineHeartbeat { engine_id: parse_id("engine_id", &event.engine_id)?,
+                                                                     load:      from_value("load", &event.load)?,
+                                                                     at:        from_timestamp("at", &event.at)?, },
+            Event::TaskTimeExtension(event) => Self::TaskTimeExtension { outcome: from_value("outcome", &event.outcome)?, },
+        })
+    }
+}
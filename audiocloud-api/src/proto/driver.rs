@@ -0,0 +1,391 @@
+//! Messages for the instance driver API
+
+use prost_types::Value;
+
+#[cfg(feature = "driver")]
+use crate::common::media::{PlayId, RenderId};
+#[cfg(feature = "driver")]
+use crate::instance_driver::{DriverCommandEnvelope, DriverCommandId, InstanceDriverCommand, InstanceDriverEvent};
+#[cfg(feature = "driver")]
+use crate::proto::{from_value, missing, to_value, ProtoError};
+
+/// A [`DriverCommandEnvelope`](crate::instance_driver::DriverCommandEnvelope)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct InstanceDriverCommandMessage {
+    /// Id of the command, unique per instance
+    #[prost(uint64, tag = "1")]
+    pub command_id: u64,
+    #[prost(oneof = "instance_driver_command_message::Command",
+            tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14")]
+    pub command:    Option<instance_driver_command_message::Command>,
+}
+
+pub mod instance_driver_command_message {
+    use super::Value;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CheckConnection {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Stop {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Play {
+        #[prost(uint64, tag = "1")]
+        pub play_id: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Render {
+        #[prost(double, tag = "1")]
+        pub length:    f64,
+        #[prost(uint64, tag = "2")]
+        pub render_id: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Rewind {
+        #[prost(double, tag = "1")]
+        pub to: f64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetParameters {
+        #[prost(message, optional, tag = "1")]
+        pub parameters: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetParametersAtomic {
+        #[prost(message, optional, tag = "1")]
+        pub parameters:          Option<Value>,
+        #[prost(uint64, tag = "2")]
+        pub expected_generation: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetPowerChannel {
+        #[prost(uint64, tag = "1")]
+        pub channel: u64,
+        #[prost(bool, tag = "2")]
+        pub power:   bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Identify {
+        #[prost(uint64, tag = "1")]
+        pub duration_ms: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RunSelfTest {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct NegotiateVersion {
+        #[prost(message, optional, tag = "1")]
+        pub negotiate: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RunDiagnostic {
+        #[prost(message, optional, tag = "1")]
+        pub kind: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetReportFilter {
+        #[prost(message, optional, tag = "1")]
+        pub filter: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Command {
+        #[prost(message, tag = "2")]
+        CheckConnection(CheckConnection),
+        #[prost(message, tag = "3")]
+        Stop(Stop),
+        #[prost(message, tag = "4")]
+        Play(Play),
+        #[prost(message, tag = "5")]
+        Render(Render),
+        #[prost(message, tag = "6")]
+        Rewind(Rewind),
+        #[prost(message, tag = "7")]
+        SetParameters(SetParameters),
+        #[prost(message, tag = "8")]
+        SetParametersAtomic(SetParametersAtomic),
+        #[prost(message, tag = "9")]
+        SetPowerChannel(SetPowerChannel),
+        #[prost(message, tag = "10")]
+        Identify(Identify),
+        #[prost(message, tag = "11")]
+        RunSelfTest(RunSelfTest),
+        #[prost(message, tag = "12")]
+        NegotiateVersion(NegotiateVersion),
+        #[prost(message, tag = "13")]
+        RunDiagnostic(RunDiagnostic),
+        #[prost(message, tag = "14")]
+        SetReportFilter(SetReportFilter),
+    }
+}
+
+/// An [`InstanceDriverEvent`](crate::instance_driver::InstanceDriverEvent)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct InstanceDriverEventMessage {
+    #[prost(oneof = "instance_driver_event_message::Event", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12")]
+    pub event: Option<instance_driver_event_message::Event>,
+}
+
+pub mod instance_driver_event_message {
+    use super::Value;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Started {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct IoError {
+        #[prost(string, tag = "1")]
+        pub error: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ConnectionLost {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Connected {}
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Reports {
+        #[prost(message, optional, tag = "1")]
+        pub reports: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PlayState {
+        #[prost(message, optional, tag = "1")]
+        pub desired: Option<Value>,
+        #[prost(message, optional, tag = "2")]
+        pub current: Option<Value>,
+        #[prost(double, optional, tag = "3")]
+        pub media:   Option<f64>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SelfTestResult {
+        #[prost(message, optional, tag = "1")]
+        pub result: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct DiagnosticResult {
+        #[prost(message, optional, tag = "1")]
+        pub kind:    Option<Value>,
+        #[prost(bool, tag = "2")]
+        pub passed:  bool,
+        #[prost(string, optional, tag = "3")]
+        pub details: Option<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct VersionNegotiated {
+        #[prost(message, optional, tag = "1")]
+        pub result: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ParametersApplied {
+        #[prost(uint64, tag = "1")]
+        pub generation: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Ack {
+        #[prost(uint64, tag = "1")]
+        pub command_id: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Nack {
+        #[prost(uint64, tag = "1")]
+        pub command_id: u64,
+        #[prost(message, optional, tag = "2")]
+        pub reason:     Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "1")]
+        Started(Started),
+        #[prost(message, tag = "2")]
+        IoError(IoError),
+        #[prost(message, tag = "3")]
+        ConnectionLost(ConnectionLost),
+        #[prost(message, tag = "4")]
+        Connected(Connected),
+        #[prost(message, tag = "5")]
+        Reports(Reports),
+        #[prost(message, tag = "6")]
+        PlayState(PlayState),
+        #[prost(message, tag = "7")]
+        SelfTestResult(SelfTestResult),
+        #[prost(message, tag = "8")]
+        DiagnosticResult(DiagnosticResult),
+        #[prost(message, tag = "9")]
+        VersionNegotiated(VersionNegotiated),
+        #[prost(message, tag = "10")]
+        ParametersApplied(ParametersApplied),
+        #[prost(message, tag = "11")]
+        Ack(Ack),
+        #[prost(message, tag = "12")]
+        Nack(Nack),
+    }
+}
+
+#[cfg(feature = "driver")]
+impl TryFrom<&DriverCommandEnvelope> for InstanceDriverCommandMessage {
+    type Error = ProtoError;
+
+    fn try_from(envelope: &DriverCommandEnvelope) -> Result<Self, Self::Error> {
+        use instance_driver_command_message::*;
+
+        let command = match &envelope.command {
+            InstanceDriverCommand::CheckConnection => Command::CheckConnection(CheckConnection {}),
+            InstanceDriverCommand::Stop => Command::Stop(Stop {}),
+            InstanceDriverCommand::Play { play_id } => Command::Play(Play { play_id: (*play_id).into(), }),
+            InstanceDriverCommand::Render { length, render_id } => Command::Render(Render { length:    *length,
+                                                                                            render_id: (*render_id).into(), }),
+            InstanceDriverCommand::Rewind { to } => Command::Rewind(Rewind { to: *to }),
+            InstanceDriverCommand::SetParameters(parameters) => {
+                Command::SetParameters(SetParameters { parameters: to_value("parameters", parameters)?, })
+            }
+            InstanceDriverCommand::SetParametersAtomic { parameters,
+                                                         expected_generation, } => {
+                Command::SetParametersAtomic(SetParametersAtomic { parameters:          to_value("parameters", parameters)?,
+                                                                   expected_generation: *expected_generation, })
+            }
+            InstanceDriverCommand::SetPowerChannel { channel, power } => Command::SetPowerChannel(SetPowerChannel { channel: *channel
+                                                                                                                             as u64,
+                                                                                                                    power:   *power, }),
+            InstanceDriverCommand::Identify { duration_ms } => Command::Identify(Identify { duration_ms: *duration_ms }),
+            InstanceDriverCommand::RunSelfTest => Command::RunSelfTest(RunSelfTest {}),
+            InstanceDriverCommand::NegotiateVersion(negotiate) => {
+                Command::NegotiateVersion(NegotiateVersion { negotiate: to_value("negotiate", negotiate)?, })
+            }
+            InstanceDriverCommand::RunDiagnostic { kind } => Command::RunDiagnostic(RunDiagnostic { kind: to_value("kind", kind)?, }),
+            InstanceDriverCommand::SetReportFilter(filter) => {
+                Command::SetReportFilter(SetReportFilter { filter: to_value("filter", filter)?, })
+            }
+        };
+
+        Ok(Self { command_id: envelope.command_id.into(),
+                  command:    Some(command), })
+    }
+}
+
+#[cfg(feature = "driver")]
+impl TryFrom<&InstanceDriverCommandMessage> for DriverCommandEnvelope {
+    type Error = ProtoError;
+
+    fn try_from(message: &InstanceDriverCommandMessage) -> Result<Self, Self::Error> {
+        use instance_driver_command_message::*;
+
+        let command = match message.command.as_ref().ok_or_else(|| missing("command"))? {
+            Command::CheckConnection(_) => InstanceDriverCommand::CheckConnection,
+            Command::Stop(_) => InstanceDriverCommand::Stop,
+            Command::Play(command) => InstanceDriverCommand::Play { play_id: PlayId::new(command.play_id), },
+            Command::Render(command) => InstanceDriverCommand::Render { length:    command.length,
+                                                                        render_id: RenderId::new(command.render_id), },
+            Command::Rewind(command) => InstanceDriverCommand::Rewind { to: command.to },
+            Command::SetParameters(command) => InstanceDriverCommand::SetParameters(from_value("parameters", &command.parameters)?),
+            Command::SetParametersAtomic(command) => {
+                InstanceDriverCommand::SetParametersAtomic { parameters:          from_value("parameters", &command.parameters)?,
+                                                             expected_generation: command.expected_generation, }
+            }
+            Command::SetPowerChannel(command) => {
+                InstanceDriverCommand::SetPowerChannel { channel: usize::try_from(command.channel).map_err(|error| {
+                                                                                                      ProtoError::malformed("channel",
+                                                                                                                            error)
+                                                                                                  })?,
+                                                         power:   command.power, }
+            }
+            Command::Identify(command) => InstanceDriverCommand::Identify { duration_ms: command.duration_ms, },
+            Command::RunSelfTest(_) => InstanceDriverCommand::RunSelfTest,
+            Command::NegotiateVersion(command) => InstanceDriverCommand::NegotiateVersion(from_value("negotiate", &command.negotiate)?),
+            Command::RunDiagnostic(command) => InstanceDriverCommand::RunDiagnostic { kind: from_value("kind", &command.kind)?, },
+            Command::SetReportFilter(command) => InstanceDriverCommand::SetReportFilter(from_value("filter", &command.filter)?),
+        };
+
+        Ok(Self { command_id: DriverCommandId::new(message.command_id),
+                  command })
+    }
+}
+
+#[cfg(feature = "driver")]
+impl TryFrom<&InstanceDriverEvent> for InstanceDriverEventMessage {
+    type Error = ProtoError;
+
+    fn try_from(event: &InstanceDriverEvent) -> Result<Self, Self::Error> {
+        use instance_driver_event_message::*;
+
+        let event = match event {
+            InstanceDriverEvent::Started => Event::Started(Started {}),
+            InstanceDriverEvent::IOError { error } => Event::IoError(IoError { error: error.clone() }),
+            InstanceDriverEvent::ConnectionLost => Event::ConnectionLost(ConnectionLost {}),
+            InstanceDriverEvent::Connected => Event::Connected(Connected {}),
+            InstanceDriverEvent::Reports { reports } => Event::Reports(Reports { reports: to_value("reports", reports)?, }),
+            InstanceDriverEvent::PlayState { desired, current, media } => {
+                Event::PlayState(PlayState { desired: to_value("desired", desired)?,
+                                             current: to_value("current", current)?,
+                                             media:   *media, })
+            }
+            InstanceDriverEvent::SelfTestResult { result } => {
+                Event::SelfTestResult(SelfTestResult { result: to_value("result", result)?, })
+            }
+            InstanceDriverEvent::DiagnosticResult { kind, passed, details } => {
+                Event::DiagnosticResult(DiagnosticResult { kind:    to_value("kind", kind)?,
+                                                           passed:  *passed,
+                                                           details: details.clone(), })
+            }
+            InstanceDriverEvent::VersionNegotiated { result } => {
+                Event::VersionNegotiated(VersionNegotiated { result: to_value("result", result)?, })
+            }
+            InstanceDriverEvent::ParametersApplied { generation } => {
+                Event::ParametersApplied(ParametersApplied { generation: *generation })
+            }
+            InstanceDriverEvent::Ack { command_id } => Event::Ack(Ack { command_id: (*command_id).into(), }),
+            InstanceDriverEvent::Nack { command_id, reason } => Event::Nack(Nack { command_id: (*command_id).into(),
+                                                                                   reason:     to_value("reason", reason)?, }),
+        };
+
+        Ok(Self { event: Some(event) })
+    }
+}
+
+#[cfg(feature = "driver")]
+impl TryFrom<&InstanceDriverEventMessage> for InstanceDriverEvent {
+    type Error = ProtoError;
+
+    fn try_from(message: &InstanceDriverEventMessage) -> Result<Self, Self::Error> {
+        use instance_driver_event_message::*;
+
+        Ok(match message.event.as_ref().ok_or_else(|| missing("event"))? {
+            Event::Started(_) => Self::Started,
+            Event::IoError(event) => Self::IOError { error: event.error.clone(), },
+            Event::ConnectionLost(_) => Self::ConnectionLost,
+            Event::Connected(_) => Self::Connected,
+            Event::Reports(event) => Self::Reports { reports: from_value("reports", &event.reports)?, },
+            Event::PlayState(event) => Self::PlayState { desired: from_value("desired", &event.desired)?,
+                                                         current: from_value("current", &event.current)?,
+                                                         media:   event.media, },
+            Event::SelfTestResult(event) => Self::SelfTestResult { result: from_value("result", &event.result)?, },
+            Event::DiagnosticResult(event) => Self::DiagnosticResult { kind:    from_value("kind", &event.kind)?,
+                                                                       passed:  event.passed,
+                                                                       details: event.details.clone(), },
+            Event::VersionNegotiated(event) => Self::VersionNegotiated { result: from_value("result", &event.result)?, },
+            Event::ParametersApplied(event) => Self::ParametersApplied { generation: event.generation, },
+            Event::Ack(event) => Self::Ack { command_id: DriverCommandId::new(event.command_id), },
+            Event::Nack(event) => Self::Nack { command_id: DriverCommandId::new(event.command_id),
+                                               reason:     from_value("reason", &event.reason)?, },
+        })
+    }
+}
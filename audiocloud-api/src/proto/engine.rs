@@ -0,0 +1,569 @@
+//! Messages for the audio engine API
+
+use std::collections::HashMap;
+
+use prost_types::Value;
+
+#[cfg(feature = "engine")]
+use crate::audio_engine::{EngineCommand, EngineCommandEnvelope, EngineEvent, EngineEventEnvelope};
+#[cfg(feature = "engine")]
+use crate::common::media::{PlayId, RenderId};
+#[cfg(feature = "engine")]
+use crate::proto::{from_id_map, from_value, missing, parse_id, to_id_map, to_value, ProtoError};
+
+/// An [`EngineCommandEnvelope`](crate::audio_engine::EngineCommandEnvelope)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EngineCommandMessage {
+    /// Engine that should execute the command
+    #[prost(string, tag = "1")]
+    pub engine_id: String,
+    #[prost(oneof = "engine_command_message::Command", tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13")]
+    pub command:   Option<engine_command_message::Command>,
+}
+
+pub mod engine_command_message {
+    use super::{HashMap, Value};
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetSpec {
+        #[prost(string, tag = "1")]
+        pub task_id:     String,
+        #[prost(message, optional, tag = "2")]
+        pub spec:        Option<Value>,
+        #[prost(message, optional, tag = "3")]
+        pub instances:   Option<Value>,
+        #[prost(map = "string, string", tag = "4")]
+        pub media_ready: HashMap<String, String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PatchSpec {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub diff:    Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Media {
+        #[prost(string, tag = "1")]
+        pub task_id:     String,
+        #[prost(map = "string, string", tag = "2")]
+        pub media_ready: HashMap<String, String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Instances {
+        #[prost(string, tag = "1")]
+        pub task_id:   String,
+        #[prost(message, optional, tag = "2")]
+        pub instances: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ModifySpec {
+        #[prost(string, tag = "1")]
+        pub task_id:     String,
+        #[prost(message, optional, tag = "2")]
+        pub transaction: Option<Value>,
+        #[prost(message, optional, tag = "3")]
+        pub instances:   Option<Value>,
+        #[prost(map = "string, string", tag = "4")]
+        pub media_ready: HashMap<String, String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SetDynamicParameterValues {
+        #[prost(string, tag = "1")]
+        pub task_id:    String,
+        #[prost(string, tag = "2")]
+        pub dynamic_id: String,
+        #[prost(message, optional, tag = "3")]
+        pub values:     Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Render {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub render:  Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Play {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub play:    Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct UpdatePlay {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub update:  Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CancelRender {
+        #[prost(string, tag = "1")]
+        pub task_id:   String,
+        #[prost(uint64, tag = "2")]
+        pub render_id: u64,
+        #[prost(message, optional, tag = "3")]
+        pub reason:    Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct StopPlay {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(uint64, tag = "2")]
+        pub play_id: u64,
+        #[prost(message, optional, tag = "3")]
+        pub reason:  Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Close {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub reason:  Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Command {
+        #[prost(message, tag = "2")]
+        SetSpec(SetSpec),
+        #[prost(message, tag = "3")]
+        PatchSpec(PatchSpec),
+        #[prost(message, tag = "4")]
+        Media(Media),
+        #[prost(message, tag = "5")]
+        Instances(Instances),
+        #[prost(message, tag = "6")]
+        ModifySpec(ModifySpec),
+        #[prost(message, tag = "7")]
+        SetDynamicParameterValues(SetDynamicParameterValues),
+        #[prost(message, tag = "8")]
+        Render(Render),
+        #[prost(message, tag = "9")]
+        Play(Play),
+        #[prost(message, tag = "10")]
+        UpdatePlay(UpdatePlay),
+        #[prost(message, tag = "11")]
+        CancelRender(CancelRender),
+        #[prost(message, tag = "12")]
+        StopPlay(StopPlay),
+        #[prost(message, tag = "13")]
+        Close(Close),
+    }
+}
+
+/// An [`EngineEventEnvelope`](crate::audio_engine::EngineEventEnvelope)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EngineEventMessage {
+    /// Engine that emitted the event
+    #[prost(string, tag = "1")]
+    pub engine_id: String,
+    #[prost(oneof = "engine_event_message::Event", tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13")]
+    pub event:     Option<engine_event_message::Event>,
+}
+
+pub mod engine_event_message {
+    use super::Value;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Stopped {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(message, optional, tag = "2")]
+        pub reason:  Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Playing {
+        #[prost(string, tag = "1")]
+        pub task_id:         String,
+        #[prost(uint64, tag = "2")]
+        pub play_id:         u64,
+        #[prost(message, optional, tag = "3")]
+        pub audio:           Option<Value>,
+        #[prost(message, optional, tag = "4")]
+        pub peak_metering:   Option<Value>,
+        #[prost(message, optional, tag = "5")]
+        pub dynamic_reports: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Looped {
+        #[prost(string, tag = "1")]
+        pub task_id:   String,
+        #[prost(uint64, tag = "2")]
+        pub play_id:   u64,
+        #[prost(uint32, tag = "3")]
+        pub iteration: u32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Punched {
+        #[prost(string, tag = "1")]
+        pub task_id:    String,
+        #[prost(uint64, tag = "2")]
+        pub play_id:    u64,
+        #[prost(bool, tag = "3")]
+        pub punched_in: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PlayingFailed {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(uint64, tag = "2")]
+        pub play_id: u64,
+        #[prost(string, tag = "3")]
+        pub error:   String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Rendering {
+        #[prost(string, tag = "1")]
+        pub task_id:    String,
+        #[prost(uint64, tag = "2")]
+        pub render_id:  u64,
+        #[prost(double, tag = "3")]
+        pub completion: f64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RenderingFinished {
+        #[prost(string, tag = "1")]
+        pub task_id:   String,
+        #[prost(uint64, tag = "2")]
+        pub render_id: u64,
+        #[prost(string, tag = "3")]
+        pub path:      String,
+        #[prost(message, optional, tag = "4")]
+        pub analysis:  Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct RenderingFailed {
+        #[prost(string, tag = "1")]
+        pub task_id:   String,
+        #[prost(uint64, tag = "2")]
+        pub render_id: u64,
+        #[prost(string, tag = "3")]
+        pub error:     String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Recorded {
+        #[prost(string, tag = "1")]
+        pub task_id:     String,
+        #[prost(uint64, tag = "2")]
+        pub play_id:     u64,
+        #[prost(string, tag = "3")]
+        pub recorder_id: String,
+        #[prost(string, tag = "4")]
+        pub object_id:   String,
+        #[prost(uint32, tag = "5")]
+        pub take:        u32,
+        #[prost(string, tag = "6")]
+        pub path:        String,
+        #[prost(double, tag = "7")]
+        pub duration:    f64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Error {
+        #[prost(string, tag = "1")]
+        pub task_id: String,
+        #[prost(string, tag = "2")]
+        pub error:   String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct MediaDiagnostics {
+        #[prost(string, tag = "1")]
+        pub task_id:     String,
+        #[prost(message, optional, tag = "2")]
+        pub diagnostics: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ClockChanged {
+        #[prost(message, optional, tag = "1")]
+        pub status: Option<Value>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "2")]
+        Stopped(Stopped),
+        #[prost(message, tag = "3")]
+        Playing(Playing),
+        #[prost(message, tag = "4")]
+        Looped(Looped),
+        #[prost(message, tag = "5")]
+        Punched(Punched),
+        #[prost(message, tag = "6")]
+        PlayingFailed(PlayingFailed),
+        #[prost(message, tag = "7")]
+        Rendering(Rendering),
+        #[prost(message, tag = "8")]
+        RenderingFinished(RenderingFinished),
+        #[prost(message, tag = "9")]
+        RenderingFailed(RenderingFailed),
+        #[prost(message, tag = "10")]
+        Recorded(Recorded),
+        #[prost(message, tag = "11")]
+        Error(Error),
+        #[prost(message, tag = "12")]
+        MediaDiagnostics(MediaDiagnostics),
+        #[prost(message, tag = "13")]
+        ClockChanged(ClockChanged),
+    }
+}
+
+#[cfg(feature = "engine")]
+impl TryFrom<&EngineCommandEnvelope> for EngineCommandMessage {
+    type Error = ProtoError;
+
+    fn try_from(envelope: &EngineCommandEnvelope) -> Result<Self, Self::Error> {
+        use engine_command_message::*;
+
+        let command = match &envelope.command {
+            EngineCommand::SetSpec { task_id,
+                                     spec,
+                                     instances,
+                                     media_ready, } => Command::SetSpec(SetSpec { task_id:     task_id.to_string(),
+                                                                                  spec:        to_value("spec", spec)?,
+                                                                                  instances:   to_value("instances", instances)?,
+                                                                                  media_ready: to_id_map(media_ready), }),
+            EngineCommand::PatchSpec { task_id, diff } => Command::PatchSpec(PatchSpec { task_id: task_id.to_string(),
+                                                                                         diff:    to_value("diff", diff)?, }),
+            EngineCommand::Media { task_id, media_ready } => Command::Media(Media { task_id:     task_id.to_string(),
+                                                                                    media_ready: to_id_map(media_ready), }),
+            EngineCommand::Instances { task_id, instances } => Command::Instances(Instances { task_id:   task_id.to_string(),
+                                                                                              instances: to_value("instances", instances)?, }),
+            EngineCommand::ModifySpec { task_id,
+                                        transaction,
+                                        instances,
+                                        media_ready, } => Command::ModifySpec(ModifySpec { task_id:     task_id.to_string(),
+                                                                                           transaction: to_value("transaction",
+                                                                                                                 transaction)?,
+                                                                                           instances:   to_value("instances", instances)?,
+                                                                                           media_ready: to_id_map(media_ready), }),
+            EngineCommand::SetDynamicParameterValues { task_id,
+                                                       dynamic_id,
+                                                       values, } => {
+                Command::SetDynamicParameterValues(SetDynamicParameterValues { task_id:    task_id.to_string(),
+                                                                               dynamic_id: dynamic_id.to_string(),
+                                                                               values:     to_value("values", values)?, })
+            }
+            EngineCommand::Render { task_id, render } => Command::Render(Render { task_id: task_id.to_string(),
+                                                                                  render:  to_value("render", render)?, }),
+            EngineCommand::Play { task_id, play } => Command::Play(Play { task_id: task_id.to_string(),
+                                                                          play:    to_value("play", play)?, }),
+            EngineCommand::UpdatePlay { task_id, update } => Command::UpdatePlay(UpdatePlay { task_id: task_id.to_string(),
+                                                                                              update:  to_value("update", update)?, }),
+            EngineCommand::CancelRender { task_id,
+                                          render_id,
+                                          reason, } => Command::CancelRender(CancelRender { task_id:   task_id.to_string(),
+                                                                                            render_id: (*render_id).into(),
+                                                                                            reason:    to_value("reason", reason)?, }),
+            EngineCommand::StopPlay { task_id, play_id, reason } => Command::StopPlay(StopPlay { task_id: task_id.to_string(),
+                                                                                                 play_id: (*play_id).into(),
+                                                                                                 reason:  to_value("reason", reason)?, }),
+            EngineCommand::Close { task_id, reason } => Command::Close(Close { task_id: task_id.to_string(),
+                                                                               reason:  to_value("reason", reason)?, }),
+        };
+
+        Ok(Self { engine_id: envelope.engine_id.to_string(),
+                  command:   Some(command), })
+    }
+}
+
+#[cfg(feature = "engine")]
+impl TryFrom<&EngineCommandMessage> for EngineCommandEnvelope {
+    type Error = ProtoError;
+
+    fn try_from(message: &EngineCommandMessage) -> Result<Self, Self::Error> {
+        use engine_command_message::*;
+
+        let command = match message.command.as_ref().ok_or_else(|| missing("command"))? {
+            Command::SetSpec(command) => EngineCommand::SetSpec { task_id:     parse_id("task_id", &command.task_id)?,
+                                                                  spec:        from_value("spec", &command.spec)?,
+                                                                  instances:   from_value("instances", &command.instances)?,
+                                                                  media_ready: from_id_map("media_ready", &command.media_ready)?, },
+            Command::PatchSpec(command) => EngineCommand::PatchSpec { task_id: parse_id("task_id", &command.task_id)?,
+                                                                      diff:    from_value("diff", &command.diff)?, },
+            Command::Media(command) => EngineCommand::Media { task_id:     parse_id("task_id", &command.task_id)?,
+                                                              media_ready: from_id_map("media_ready", &command.media_ready)?, },
+            Command::Instances(command) => EngineCommand::Instances { task_id:   parse_id("task_id", &command.task_id)?,
+                                                                      instances: from_value("instances", &command.instances)?, },
+            Command::ModifySpec(command) => EngineCommand::ModifySpec { task_id:     parse_id("task_id", &command.task_id)?,
+                                                                        transaction: from_value("transaction", &command.transaction)?,
+                                                                        instances:   from_value("instances", &command.instances)?,
+                                                                        media_ready: from_id_map("media_ready", &command.media_ready)?, },
+            Command::SetDynamicParameterValues(command) => {
+                EngineCommand::SetDynamicParameterValues { task_id:    parse_id("task_id", &command.task_id)?,
+                                                           dynamic_id: parse_id("dynamic_id", &command.dynamic_id)?,
+                                                           values:     from_value("values", &command.values)?, }
+            }
+            Command::Render(command) => EngineCommand::Render { task_id: parse_id("task_id", &command.task_id)?,
+                                                                render:  from_value("render", &command.render)?, },
+            Command::Play(command) => EngineCommand::Play { task_id: parse_id("task_id", &command.task_id)?,
+                                                            play:    from_value("play", &command.play)?, },
+            Command::UpdatePlay(command) => EngineCommand::UpdatePlay { task_id: parse_id("task_id", &command.task_id)?,
+                                                                        update:  from_value("update", &command.update)?, },
+            Command::CancelRender(command) => EngineCommand::CancelRender { task_id:   parse_id("task_id", &command.task_id)?,
+                                                                            render_id: RenderId::new(command.render_id),
+                                                                            reason:    from_value("reason", &command.reason)?, },
+            Command::StopPlay(command) => EngineCommand::StopPlay { task_id: parse_id("task_id", &command.task_id)?,
+                                                                    play_id: PlayId::new(command.play_id),
+                                                                    reason:  from_value("reason", &command.reason)?, },
+            Command::Close(command) => EngineCommand::Close { task_id: parse_id("task_id", &command.task_id)?,
+                                                              reason:  from_value("reason", &command.reason)?, },
+        };
+
+        Ok(Self { engine_id: message.engine_id.clone().into(),
+                  command })
+    }
+}
+
+#[cfg(feature = "engine")]
+impl TryFrom<&EngineEventEnvelope> for EngineEventMessage {
+    type Error = ProtoError;
+
+    fn try_from(envelope: &EngineEventEnvelope) -> Result<Self, Self::Error> {
+        use engine_event_message::*;
+
+        let event = match &envelope.event {
+            EngineEvent::Stopped { task_id, reason } => Event::Stopped(Stopped { task_id: task_id.to_string(),
+                                                                                 reason:  to_value("reason", reason)?, }),
+            EngineEvent::Playing { task_id,
+                                   play_id,
+                                   audio,
+                                   peak_metering,
+                                   dynamic_reports, } => {
+                Event::Playing(Playing { task_id:         task_id.to_string(),
+                                         play_id:         (*play_id).into(),
+                                         audio:           to_value("audio", audio)?,
+                                         peak_metering:   to_value("peak_metering", peak_metering)?,
+                                         dynamic_reports: to_value("dynamic_reports", dynamic_reports)?, })
+            }
+            EngineEvent::Looped { task_id,
+                                  play_id,
+                                  iteration, } => Event::Looped(Looped { task_id:   task_id.to_string(),
+                                                                         play_id:   (*play_id).into(),
+                                                                         iteration: *iteration, }),
+            EngineEvent::Punched { task_id,
+                                   play_id,
+                                   punched_in, } => Event::Punched(Punched { task_id:    task_id.to_string(),
+                                                                             play_id:    (*play_id).into(),
+                                                                             punched_in: *punched_in, }),
+            EngineEvent::PlayingFailed { task_id, play_id, error } => Event::PlayingFailed(PlayingFailed { task_id: task_id.to_string(),
+                                                                                                           play_id: (*play_id).into(),
+                                                                                                           error:   error.clone(), }),
+            EngineEvent::Rendering { task_id,
+                                     render_id,
+                                     completion, } => Event::Rendering(Rendering { task_id:    task_id.to_string(),
+                                                                                   render_id:  (*render_id).into(),
+                                                                                   completion: *completion, }),
+            EngineEvent::RenderingFinished { task_id,
+                                             render_id,
+                                             path,
+                                             analysis, } => Event::RenderingFinished(RenderingFinished { task_id:   task_id.to_string(),
+                                                                                                         render_id: (*render_id).into(),
+                                                                                                         path:      path.clone(),
+                                                                                                         analysis:  to_value("analysis",
+                                                                                                                             analysis)?, }),
+            EngineEvent::RenderingFailed { task_id, render_id, error } => {
+                Event::RenderingFailed(RenderingFailed { task_id:   task_id.to_string(),
+                                                         render_id: (*render_id).into(),
+                                                         error:     error.clone(), })
+            }
+            EngineEvent::Recorded { task_id,
+                                    play_id,
+                                    recorder_id,
+                                    object_id,
+                                    take,
+                                    path,
+                                    duration, } => Event::Recorded(Recorded { task_id:     task_id.to_string(),
+                                                                              play_id:     (*play_id).into(),
+                                                                              recorder_id: recorder_id.to_string(),
+                                                                              object_id:   object_id.to_string(),
+                                                                              take:        *take,
+                                                                              path:        path.clone(),
+                                                                              duration:    *duration, }),
+            EngineEvent::Error { task_id, error } => Event::Error(Error { task_id: task_id.to_string(),
+                                                                          error:   error.clone(), }),
+            EngineEvent::MediaDiagnostics { task_id, diagnostics } => {
+                Event::MediaDiagnostics(MediaDiagnostics { task_id:     task_id.to_string(),
+                                                           diagnostics: to_value("diagnostics", diagnostics)?, })
+            }
+            EngineEvent::ClockChanged { status } => Event::ClockChanged(ClockChanged { status: to_value("status", status)?, }),
+        };
+
+        Ok(Self { engine_id: envelope.engine_id.to_string(),
+                  event:     Some(event), })
+    }
+}
+
+#[cfg(feature = "engine")]
+impl TryFrom<&EngineEventMessage> for EngineEventEnvelope {
+    type Error = ProtoError;
+
+    fn try_from(message: &EngineEventMessage) -> Result<Self, Self::Error> {
+        use engine_event_message::*;
+
+        let event = match message.event.as_ref().ok_or_else(|| missing("event"))? {
+            Event::Stopped(event) => EngineEvent::Stopped { task_id: parse_id("task_id", &event.task_id)?,
+                                                            reason:  from_value("reason", &event.reason)?, },
+            Event::Playing(event) => EngineEvent::Playing { task_id:         parse_id("task_id", &event.task_id)?,
+                                                            play_id:         PlayId::new(event.play_id),
+                                                            audio:           from_value("audio", &event.audio)?,
+                                                            peak_metering:   from_value("peak_metering", &event.peak_metering)?,
+                                                            dynamic_reports: from_value("dynamic_reports", &event.dynamic_reports)?, },
+            Event::Looped(event) => EngineEvent::Looped { task_id:   parse_id("task_id", &event.task_id)?,
+                                                          play_id:   PlayId::new(event.play_id),
+                                                          iteration: event.iteration, },
+            Event::Punched(event) => EngineEvent::Punched { task_id:    parse_id("task_id", &event.task_id)?,
+                                                            play_id:    PlayId::new(event.play_id),
+                                                            punched_in: event.punched_in, },
+            Event::PlayingFailed(event) => EngineEvent::PlayingFailed { task_id: parse_id("task_id", &event.task_id)?,
+                                                                        play_id: PlayId::new(event.play_id),
+                                                                        error:   event.error.clone(), },
+            Event::Rendering(event) => EngineEvent::Rendering { task_id:    parse_id("task_id", &event.task_id)?,
+                                                                render_id:  RenderId::new(event.render_id),
+                                                                completion: event.completion, },
+            Event::RenderingFinished(event) => EngineEvent::RenderingFinished { task_id:   parse_id("task_id", &event.task_id)?,
+                                                                                render_id: RenderId::new(event.render_id),
+                                                                                path:      event.path.clone(),
+                                                                                analysis:  from_value("analysis", &event.analysis)?, },
+            Event::RenderingFailed(event) => EngineEvent::RenderingFailed { task_id:   parse_id("task_id", &event.task_id)?,
+                                                                            render_id: RenderId::new(event.render_id),
+                                                                            error:     event.error.clone(), },
+            Event::Recorded(event) => EngineEvent::Recorded { task_id:     parse_id("task_id", &event.task_id)?,
+                                                              play_id:     PlayId::new(event.play_id),
+                                                              recorder_id: parse_id("recorder_id", &event.recorder_id)?,
+                                                              object_id:   parse_id("object_id", &event.object_id)?,
+                                                              take:        event.take,
+                                                              path:        event.path.clone(),
+                                                              duration:    event.duration, },
+            Event::Error(event) => EngineEvent::Error { task_id: parse_id("task_id", &event.task_id)?,
+                                                        error:   event.error.clone(), },
+            Event::MediaDiagnostics(event) => EngineEvent::MediaDiagnostics { task_id:     parse_id("task_id", &event.task_id)?,
+                                                                              diagnostics: from_value("diagnostics", &event.diagnostics)?, },
+            Event::ClockChanged(event) => EngineEvent::ClockChanged { status: from_value("status", &event.status)?, },
+        };
+
+        Ok(Self { engine_id: message.engine_id.clone().into(),
+                  event })
+    }
+}
@@ -0,0 +1,154 @@
+//! Protocol buffer messages for deployments using gRPC instead of REST and WebSockets
+//!
+//! The messages are defined in `proto/audiocloud.proto`, with a message per variant of the serde types. Identifiers
+//! and scalars are native fields, nested structures are `google.protobuf.Value` in the JSON form of the serde type.
+//! Integers in nested structures must be exactly representable as a double, larger ones fail to convert.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+
+use chrono::{TimeZone, Utc};
+use prost_types::value::Kind;
+use prost_types::{ListValue, Struct, Value};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::Timestamp;
+
+pub use domain::*;
+pub use driver::*;
+pub use engine::*;
+
+pub mod domain;
+pub mod driver;
+pub mod engine;
+
+/// Contents of `proto/audiocloud.proto`, for generating clients in other languages
+pub const PROTO_DEFINITIONS: &str = include_str!("../../proto/audiocloud.proto");
+
+#[derive(Debug, Error)]
+pub enum ProtoError {
+    #[error("Message has no {field}")]
+    MissingField { field: String },
+
+    #[error("Field {field} is malformed: {error}")]
+    Malformed { field: String, error: String },
+}
+
+impl ProtoError {
+    fn malformed(field: &str, error: impl Display) -> Self {
+        Self::Malformed { field: field.to_owned(),
+                          error: error.to_string(), }
+    }
+}
+
+pub(crate) fn missing(field: &str) -> ProtoError {
+    ProtoError::MissingField { field: field.to_owned() }
+}
+
+/// Largest integer a `google.protobuf.Value` number (an `f64`) holds exactly
+const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Structured value in the JSON form of the serde type
+///
+/// Integers larger than [`MAX_EXACT_INTEGER`] are rejected instead of rounded, so ids and counters are never altered.
+pub(crate) fn to_value<T: Serialize>(field: &str, value: &T) -> Result<Option<Value>, ProtoError> {
+    let json = serde_json::to_value(value).map_err(|error| ProtoError::malformed(field, error))?;
+    Ok(Some(json_to_value(field, json)?))
+}
+
+/// Read a structured value, a missing value is read as null
+pub(crate) fn from_value<T: DeserializeOwned>(field: &str, value: &Option<Value>) -> Result<T, ProtoError> {
+    let json = value.clone().map(value_to_json).unwrap_or_default();
+    serde_json::from_value(json).map_err(|error| ProtoError::malformed(field, error))
+}
+
+/// Parse an identifier from its string form
+pub(crate) fn parse_id<T: DeserializeOwned>(field: &str, id: &str) -> Result<T, ProtoError> {
+    serde_json::from_value(serde_json::Value::String(id.to_owned())).map_err(|error| ProtoError::malformed(field, error))
+}
+
+pub(crate) fn to_id_map<K: Display>(map: &HashMap<K, String>) -> HashMap<String, String> {
+    map.iter().map(|(id, value)| (id.to_string(), value.clone())).collect()
+}
+
+pub(crate) fn from_id_map<K: DeserializeOwned + Eq + Hash>(field: &str,
+                                                           map: &HashMap<String, String>)
+                                                           -> Result<HashMap<K, String>, ProtoError> {
+    map.iter().map(|(id, value)| Ok((parse_id(field, id)?, value.clone()))).collect()
+}
+
+pub(crate) fn to_timestamp(timestamp: &Timestamp) -> Option<prost_types::Timestamp> {
+    Some(prost_types::Timestamp { seconds: timestamp.timestamp(),
+                                  nanos:   timestamp.timestamp_subsec_nanos() as i32, })
+}
+
+pub(crate) fn from_timestamp(field: &str, timestamp: &Option<prost_types::Timestamp>) -> Result<Timestamp, ProtoError> {
+    let timestamp = timestamp.as_ref().ok_or_else(|| missing(field))?;
+    let nanos = u32::try_from(timestamp.nanos).map_err(|error| ProtoError::malformed(field, error))?;
+
+    Utc.timestamp_opt(timestamp.seconds, nanos)
+       .single()
+       .ok_or_else(|| ProtoError::malformed(field, "timestamp is out of range"))
+}
+
+fn json_to_value(field: &str, json: serde_json::Value) -> Result<Value, ProtoError> {
+    let kind = match json {
+        serde_json::Value::Null => Kind::NullValue(0),
+        serde_json::Value::Bool(value) => Kind::BoolValue(value),
+        serde_json::Value::Number(value) => Kind::NumberValue(number_to_f64(field, &value)?),
+        serde_json::Value::String(value) => Kind::StringValue(value),
+        serde_json::Value::Array(values) => {
+            Kind::ListValue(ListValue { values: values.into_iter()
+                                                      .map(|value| json_to_value(field, value))
+                                                      .collect::<Result<_, _>>()?, })
+        }
+        serde_json::Value::Object(fields) => {
+            Kind::StructValue(Struct { fields: fields.into_iter()
+                                                     .map(|(name, value)| Ok((name, json_to_value(field, value)?)))
+                                                     .collect::<Result<_, ProtoError>>()?, })
+        }
+    };
+
+    Ok(Value { kind: Some(kind) })
+}
+
+fn number_to_f64(field: &str, number: &serde_json::Number) -> Result<f64, ProtoError> {
+    let max = MAX_EXACT_INTEGER as u64;
+    let inexact = number.as_u64().map(|integer| integer > max).or_else(|| number.as_i64().map(|integer| integer.unsigned_abs() > max));
+
+    match number.as_f64() {
+        Some(value) if inexact != Some(true) => Ok(value),
+        _ => Err(ProtoError::malformed(field, format!("number {number} can not be represented exactly"))),
+    }
+}
+
+fn value_to_json(value: Value) -> serde_json::Value {
+    match value.kind {
+        None | Some(Kind::NullValue(_)) => serde_json::Value::Null,
+        Some(Kind::BoolValue(value)) => serde_json::Value::Bool(value),
+        Some(Kind::NumberValue(value)) => number_to_json(value),
+        Some(Kind::StringValue(value)) => serde_json::Value::String(value),
+        Some(Kind::ListValue(list)) => serde_json::Value::Array(list.values.into_iter().map(value_to_json).collect()),
+        Some(Kind::StructValue(fields)) => serde_json::Value::Object(fields.fields
+                                                                           .into_iter()
+                                                                           .map(|(name, value)| (name, value_to_json(value)))
+                                                                           .collect()),
+    }
+}
+
+/// Whole numbers are read back as integers, so they can be deserialized into integer fields
+fn number_to_json(number: f64) -> serde_json::Value {
+    if number.fract() == 0.0 && number.abs() <= MAX_EXACT_INTEGER {
+        if number < 0.0 {
+            serde_json::Value::from(number as i64)
+        } else {
+            serde_json::Value::from(number as u64)
+        }
+    } else {
+        serde_json::Number::from_f64(number).map(serde_json::Value::Number)
+                                            .unwrap_or_default()
+    }
+}
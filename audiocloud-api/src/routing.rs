@@ -0,0 +1,134 @@
+//! Names of the message bus subjects services communicate over
+//!
+//! Subjects are dot separated tokens, starting with `ac` and a short service name and ending with
+//! `cmds` for commands or `evts` for events. Id tokens may not be empty, contain dots, whitespace or the
+//! `*` and `>` wildcards.
+
+use std::fmt::{Display, Formatter};
+
+use thiserror::Error;
+
+use crate::{AppId, AppTaskId, DomainId, EngineId, FixedInstanceId, TaskId};
+
+const PREFIX: &str = "ac";
+const COMMANDS: &str = "cmds";
+const EVENTS: &str = "evts";
+
+/// A message bus subject
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Subject {
+    /// Commands to the driver of a fixed instance
+    InstanceDriverCommands(FixedInstanceId),
+    /// Events from the driver of a fixed instance
+    InstanceDriverEvents(FixedInstanceId),
+    /// Commands to an audio engine
+    EngineCommands(EngineId),
+    /// Events from an audio engine
+    EngineEvents(EngineId),
+    /// Commands to a domain
+    DomainCommands(DomainId),
+    /// Events from a domain
+    DomainEvents(DomainId),
+    /// Events of a task
+    TaskEvents(AppTaskId),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum SubjectError {
+    #[error("Subject token {token:?} is empty or contains dots, whitespace or wildcards")]
+    InvalidToken { token: String },
+
+    #[error("Subject {subject:?} is not a known audiocloud subject")]
+    UnknownSubject { subject: String },
+}
+
+impl Subject {
+    fn tokens(&self) -> Vec<&str> {
+        match self {
+            Self::InstanceDriverCommands(id) => vec![PREFIX, "inst", &id.manufacturer, &id.name, &id.instance, COMMANDS],
+            Self::InstanceDriverEvents(id) => vec![PREFIX, "inst", &id.manufacturer, &id.name, &id.instance, EVENTS],
+            Self::EngineCommands(id) => vec![PREFIX, "engn", id.as_str(), COMMANDS],
+            Self::EngineEvents(id) => vec![PREFIX, "engn", id.as_str(), EVENTS],
+            Self::DomainCommands(id) => vec![PREFIX, "domn", id.as_str(), COMMANDS],
+            Self::DomainEvents(id) => vec![PREFIX, "domn", id.as_str(), EVENTS],
+            Self::TaskEvents(id) => vec![PREFIX, "task", id.app_id.as_str(), id.task_id.as_str(), EVENTS],
+        }
+    }
+
+    /// The subject as a string, if all id tokens are valid
+    pub fn validated(&self) -> Result<String, SubjectError> {
+        let tokens = self.tokens();
+        if let Some(token) = tokens.iter().find(|token| !is_valid_token(token)) {
+            return Err(SubjectError::InvalidToken { token: token.to_string() });
+        }
+
+        Ok(tokens.join("."))
+    }
+
+    /// Parse a subject string
+    pub fn parse(subject: &str) -> Result<Self, SubjectError> {
+        let tokens = subject.split('.').collect::<Vec<_>>();
+        if let Some(token) = tokens.iter().find(|token| !is_valid_token(token)) {
+            return Err(SubjectError::InvalidToken { token: token.to_string() });
+        }
+
+        let parsed = match tokens.as_slice() {
+            [PREFIX, "inst", manufacturer, name, instance, kind] => {
+                let id = FixedInstanceId::new(manufacturer.to_string(), name.to_string(), instance.to_string());
+                match *kind {
+                    COMMANDS => Some(Self::InstanceDriverCommands(id)),
+                    EVENTS => Some(Self::InstanceDriverEvents(id)),
+                    _ => None,
+                }
+            }
+            [PREFIX, "engn", id, COMMANDS] => Some(Self::EngineCommands(EngineId::new(id.to_string()))),
+            [PREFIX, "engn", id, EVENTS] => Some(Self::EngineEvents(EngineId::new(id.to_string()))),
+            [PREFIX, "domn", id, COMMANDS] => Some(Self::DomainCommands(DomainId::new(id.to_string()))),
+            [PREFIX, "domn", id, EVENTS] => Some(Self::DomainEvents(DomainId::new(id.to_string()))),
+            [PREFIX, "task", app_id, task_id, EVENTS] => {
+                Some(Self::TaskEvents(AppTaskId::new(AppId::new(app_id.to_string()), TaskId::new(task_id.to_string()))))
+            }
+            _ => None,
+        };
+
+        parsed.ok_or_else(|| SubjectError::UnknownSubject { subject: subject.to_owned(), })
+    }
+}
+
+impl Display for Subject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tokens().join("."))
+    }
+}
+
+fn is_valid_token(token: &str) -> bool {
+    !token.is_empty() && !token.chars().any(|c| c == '.' || c == '*' || c == '>' || c.is_whitespace())
+}
+
+pub fn instance_driver_command_subject(instance_id: &FixedInstanceId) -> Result<String, SubjectError> {
+    Subject::InstanceDriverCommands(instance_id.clone()).validated()
+}
+
+pub fn instance_driver_event_subject(instance_id: &FixedInstanceId) -> Result<String, SubjectError> {
+    Subject::InstanceDriverEvents(instance_id.clone()).validated()
+}
+
+pub fn engine_command_subject(engine_id: &EngineId) -> Result<String, SubjectError> {
+    Subject::EngineCommands(engine_id.clone()).validated()
+}
+
+pub fn engine_event_subject(engine_id: &EngineId) -> Result<String, SubjectError> {
+    Subject::EngineEvents(engine_id.clone()).validated()
+}
+
+pub fn domain_command_subject(domain_id: &DomainId) -> Result<String, SubjectError> {
+    Subject::DomainCommands(domain_id.clone()).validated()
+}
+
+pub fn domain_event_subject(domain_id: &DomainId) -> Result<String, SubjectError> {
+    Subject::DomainEvents(domain_id.clone()).validated()
+}
+
+pub fn task_events_subject(task_id: &AppTaskId) -> Result<String, SubjectError> {
+    Subject::TaskEvents(task_id.clone()).validated()
+}
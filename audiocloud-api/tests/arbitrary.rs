@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use audiocloud_api::arbitrary::task_spec_with_models;
+use audiocloud_api::codec::{from_msgpack_slice, to_msgpack};
+use audiocloud_api::{
+    CloudError, ModifyTaskError, ModifyTaskSpec, PacketSequenceTracker, StreamingPacket, StreamingPacketBuilder, TaskSpec, TimeRange,
+};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn generated_task_specs_are_valid(spec in any::<TaskSpec>()) {
+        prop_assert!(spec.validate(&HashMap::new()).is_ok());
+    }
+
+    #[test]
+    fn generated_task_specs_with_models_are_valid((models, spec) in task_spec_with_models()) {
+        prop_assert!(spec.validate(&models).is_ok());
+    }
+
+    #[test]
+    fn task_specs_round_trip_through_msgpack(spec in any::<TaskSpec>()) {
+        let decoded = from_msgpack_slice::<TaskSpec>(&to_msgpack(&spec).expect("serialize")).expect("deserialize");
+        prop_assert_eq!(decoded, spec);
+    }
+
+    #[test]
+    fn modifications_are_all_or_nothing(spec in any::<TaskSpec>(), modify in prop::collection::vec(any::<ModifyTaskSpec>(), 1..16)) {
+        let mut spec = spec;
+        for modify in modify {
+            let before = spec.clone();
+            match spec.modify(modify) {
+                Ok(()) => prop_assert!(spec.revision > before.revision),
+                Err(_) => prop_assert_eq!(&spec, &before),
+            }
+        }
+    }
+
+    #[test]
+    fn transactions_are_all_or_nothing(spec in any::<TaskSpec>(), modify in prop::collection::vec(any::<ModifyTaskSpec>(), 1..16)) {
+        let mut transacted = spec.clone();
+        match transacted.apply_transaction(modify.clone()) {
+            Ok(applied) => {
+                let mut sequential = spec.clone();
+                for modify in modify {
+                    prop_assert!(sequential.modify(modify).is_ok());
+                }
+                prop_assert_eq!(&transacted, &sequential);
+                prop_assert_eq!(applied.revision, transacted.revision);
+            }
+            Err(ModifyTaskError::TransactionFailed { index, .. }) => {
+                prop_assert!(index < modify.len());
+                prop_assert_eq!(&transacted, &spec);
+            }
+            Err(error) => prop_assert!(false, "unexpected error {}", error),
+        }
+    }
+
+    #[test]
+    fn applied_diffs_reproduce_the_target_spec(spec in any::<TaskSpec>(), modify in prop::collection::vec(any::<ModifyTaskSpec>(), 1..16)) {
+        let mut target = spec.clone();
+        for modify in modify {
+            let _ = target.modify(modify);
+        }
+
+        let mut patched = spec.clone();
+        prop_assert!(patched.apply_diff(spec.diff(&target)).is_ok());
+        prop_assert_eq!(&patched, &target);
+
+        if target.revision != spec.revision {
+            let stale = patched.apply_diff(spec.diff(&target));
+            let rejected = matches!(stale, Err(ModifyTaskError::RevisionMismatch { .. }));
+            prop_assert!(rejected);
+        }
+    }
+
+    #[test]
+    fn streaming_packets_round_trip_through_msgpack(packet in any::<StreamingPacket>()) {
+        let decoded = from_msgpack_slice::<StreamingPacket>(&to_msgpack(&packet).expect("serialize")).expect("deserialize");
+        prop_assert!(decoded.verify_checksums().is_ok());
+        prop_assert_eq!(decoded, packet);
+    }
+
+    #[test]
+    fn consecutive_streaming_packets_are_in_sequence(packet in any::<StreamingPacket>(), count in 1..8usize) {
+        let mut tracker = PacketSequenceTracker::default();
+        let mut packet = packet;
+        for _ in 0..count {
+            prop_assert!(tracker.track(&packet).is_ok());
+            packet = StreamingPacket::next_of(&packet);
+        }
+    }
+
+    #[test]
+    fn built_streaming_packets_follow_their_predecessor(packet in any::<StreamingPacket>(), serial_skip in 0..4u64) {
+        let mut tracker = PacketSequenceTracker::default();
+        prop_assert!(tracker.track(&packet).is_ok());
+
+        let next = StreamingPacketBuilder::following(&packet).build().expect("next packet");
+        prop_assert!(tracker.track(&next).is_ok());
+        prop_assert_eq!(next.play_id(), packet.play_id());
+
+        let repeated = StreamingPacketBuilder::following(&next).with_serial(next.serial() - serial_skip).build();
+        prop_assert!(repeated.is_err());
+    }
+
+    #[test]
+    fn time_ranges_validate_when_they_start_before_they_end(range in any::<TimeRange>()) {
+        match range.validate() {
+            Ok(()) => prop_assert!(range.from < range.to),
+            Err(err) => {
+                prop_assert!(matches!(err, CloudError::TimeMalformed));
+                prop_assert!(range.from >= range.to);
+            }
+        }
+    }
+
+    #[test]
+    fn time_range_intersections_are_covered_by_both(a in any::<TimeRange>(), b in any::<TimeRange>()) {
+        prop_assume!(a.valid() && b.valid());
+
+        prop_assert_eq!(a.intersects(&b), b.intersects(&a));
+        match a.intersection(&b) {
+            Some(both) => {
+                prop_assert!(a.intersects(&b));
+                prop_assert!(both.valid());
+                prop_assert!(a.covers(&both) && b.covers(&both));
+            }
+            None => prop_assert!(!a.intersects(&b)),
+        }
+    }
+
+    #[test]
+    fn time_range_unions_cover_both(a in any::<TimeRange>(), b in any::<TimeRange>()) {
+        prop_assume!(a.valid() && b.valid());
+
+        prop_assert_eq!(a.union(&b), b.union(&a));
+        if let Some(union) = a.union(&b) {
+            prop_assert!(union.covers(&a) && union.covers(&b));
+            prop_assert!(union.duration() <= a.duration() + b.duration());
+        } else {
+            prop_assert!(!a.intersects(&b));
+        }
+    }
+
+    #[test]
+    fn split_time_ranges_rejoin(range in any::<TimeRange>(), at in 0..=100i32) {
+        prop_assume!(range.valid());
+
+        let ts = range.from + range.duration() * at / 100;
+        match range.split_at(ts) {
+            Some((before, after)) => {
+                prop_assert!(before.validate().is_ok() && after.validate().is_ok());
+                prop_assert!(range.contains(ts));
+                prop_assert_eq!(before.duration() + after.duration(), range.duration());
+                prop_assert_eq!(before.union(&after), Some(range));
+            }
+            None => prop_assert!(ts == range.from || ts == range.to),
+        }
+    }
+}
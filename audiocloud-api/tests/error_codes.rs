@@ -0,0 +1,28 @@
+use audiocloud_api::audio_engine::EngineError;
+use audiocloud_api::domain::DomainError;
+use audiocloud_api::{AppTaskId, CloudError, CodedError, ErrorCode};
+
+#[test]
+fn coded_errors_serialize_code_and_message_next_to_fields() {
+    let task_id = AppTaskId::new("app".to_owned().into(), "task".to_owned().into());
+    let error = CodedError::from(DomainError::TaskNotFound { task_id: task_id.clone() });
+
+    let json = serde_json::to_value(&error).expect("serialize");
+    assert_eq!(json["code"], "task_not_found");
+    assert_eq!(json["type"], "task_not_found");
+    assert_eq!(json["message"], format!("Task {task_id} not found"));
+
+    let error: CodedError<DomainError> = serde_json::from_value(json).expect("deserialize");
+    assert!(matches!(error.error, DomainError::TaskNotFound { .. }));
+}
+
+#[test]
+fn error_codes_are_stable_snake_case() {
+    assert_eq!(DomainError::WebRTCError { error: "ice".to_owned() }.error_code(), "webrtc_error");
+    assert_eq!(CloudError::BlockingLock.error_code(), "blocking_lock");
+    assert_eq!(EngineError::RPC("timeout".to_owned()).error_code(), "rpc");
+
+    let json = serde_json::to_value(CodedError::from(EngineError::TrackNotFound(3))).expect("serialize");
+    assert_eq!(json["code"], "track_not_found");
+    assert_eq!(json["track_not_found"], 3);
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use audiocloud_api::{
+    AutomationInterpolation, AutomationLane, AutomationPoint, ControlChannels, InstanceParameters, Model, ModelElementScope, ModelInput,
+    ModelParameter, ModelParameterRole, ModelReport, ModelReportRole, ModelUiHints, ModelValidationError, ModelValue, ModelValueOption,
+    ModelValueTaper, ModelValueUnit, MultiChannelValue,
+};
+
+fn parameters(values: impl IntoIterator<Item = (&'static str, Vec<Option<f64>>)>) -> InstanceParameters {
+    InstanceParameters(values.into_iter()
+                             .map(|(parameter_id, values)| {
+                                 (parameter_id.into(),
+                                  MultiChannelValue(values.into_iter().map(|value| value.map(ModelValue::Number)).collect()))
+                             })
+                             .collect())
+}
+
+fn model() -> Model {
+    let parameter = |scope| ModelParameter { scope,
+                                             unit: ModelValueUnit::Decibels,
+                                             role: ModelParameterRole::NoRole,
+                                             values: vec![ModelValueOption::num_range(-20.0, 20.0)],
+                                             default: None,
+                                             linked: false,
+                                             ui: None };
+
+    Model { inputs: vec![ModelInput::Audio(ControlChannels::Left), ModelInput::Audio(ControlChannels::Right)],
+            parameters: HashMap::from([("gain".into(), parameter(ModelElementScope::AllInputs)),
+                                       ("trim".into(), parameter(ModelElementScope::Global))]),
+            ..Default::default() }
+}
+
+#[test]
+fn merge_replaces_values_set_in_the_update() {
+    let mut current = parameters([("gain", vec![Some(1.0), Some(2.0)]), ("trim", vec![Some(0.0)])]);
+    current.merge(parameters([("gain", vec![None, Some(3.0)])]));
+
+    assert_eq!(current,
+               parameters([("gain", vec![Some(1.0), Some(3.0)]), ("trim", vec![Some(0.0)])]));
+}
+
+#[test]
+fn diff_merged_into_current_reaches_target() {
+    let current = parameters([("gain", vec![Some(1.0), Some(2.0)])]);
+    let target = parameters([("gain", vec![Some(1.0), Some(4.0)]), ("trim", vec![Some(-3.0)])]);
+
+    let update = current.diff(&target);
+    assert_eq!(update, parameters([("gain", vec![None, Some(4.0)]), ("trim", vec![Some(-3.0)])]));
+
+    let mut merged = current.clone();
+    merged.merge(update);
+    assert_eq!(merged, target);
+    assert!(merged.diff(&target).is_empty());
+}
+
+#[test]
+fn validate_checks_parameters_against_the_model() {
+    let model = model();
+
+    assert!(parameters([("gain", vec![Some(-20.0), Some(20.0)])]).validate(&model).is_ok());
+    assert!(matches!(parameters([("drive", vec![Some(1.0)])]).validate(&model),
+                     Err(ModelValidationError::UnknownParameter { .. })));
+    assert!(matches!(parameters([("trim", vec![Some(1.0), Some(1.0)])]).validate(&model),
+                     Err(ModelValidationError::TooManyChannels { channels: 2, max: 1, .. })));
+    assert!(matches!(parameters([("gain", vec![None, Some(21.0)])]).validate(&model),
+                     Err(ModelValidationError::OutOfRange { channel: 1, .. })));
+    assert!(matches!(model.validate_parameter(&"trim".into(), &MultiChannelValue(vec![Some(ModelValue::Bool(true))])),
+                     Err(ModelValidationError::WrongType { channel: 0, .. })));
+}
+
+#[test]
+fn linked_parameters_are_written_to_all_channels_together() {
+    let mut model = model();
+    model.parameters.get_mut(&"gain".into()).expect("gain").linked = true;
+
+    let linked = MultiChannelValue::all_channels(&model, &"gain".into(), ModelValue::Number(3.0)).expect("gain channels");
+    assert_eq!(linked, MultiChannelValue::stereo(ModelValue::Number(3.0)));
+    assert!(model.validate_parameter(&"gain".into(), &linked).is_ok());
+    assert!(model.validate_parameter(&"gain".into(), &MultiChannelValue(vec![None, None]))
+                 .is_ok());
+
+    assert!(matches!(parameters([("gain", vec![Some(1.0), Some(2.0)])]).validate(&model),
+                     Err(ModelValidationError::LinkedChannelsDiffer { channel: 1, .. })));
+    for partial in [vec![None, Some(2.0)], vec![Some(2.0)]] {
+        let partial = MultiChannelValue(partial.into_iter().map(|value| value.map(ModelValue::Number)).collect());
+        assert!(model.validate_parameter(&"gain".into(), &partial).is_ok());
+        assert_eq!(model.expand_linked_value(&"gain".into(), &partial),
+                   MultiChannelValue::stereo(ModelValue::Number(2.0)));
+    }
+    assert!(model.validate_automation(&"gain".into(), &lane(AutomationInterpolation::Linear, &[-20.0, 20.0]))
+                 .is_ok());
+    assert!(matches!(MultiChannelValue::all_channels(&model, &"drive".into(), ModelValue::Number(0.0)),
+                     Err(ModelValidationError::UnknownParameter { .. })));
+}
+
+#[test]
+fn parameters_are_ordered_by_ui_group_and_order() {
+    let mut model = model();
+    model.parameters.get_mut(&"gain".into()).expect("gain").ui = Some(ModelUiHints { display_name: Some("Gain".to_owned()),
+                                                                                     group: Some("Output".to_owned()),
+                                                                                     order: 1,
+                                                                                     ..Default::default() });
+    model.parameters.insert("freq".into(),
+                            serde_json::from_value(serde_json::json!({
+                                                       "scope": "global",
+                                                       "unit": "hz",
+                                                       "role": "no_role",
+                                                       "values": [[20.0, 20000.0]],
+                                                       "ui": { "group": "Output", "unit_suffix": "Hz", "taper": "logarithmic" }
+                                                   })).expect("parameter with ui hints"));
+
+    let order = model.parameters_in_ui_order()
+                     .into_iter()
+                     .map(|(id, _)| id.to_string())
+                     .collect::<Vec<_>>();
+    assert_eq!(order, vec!["trim", "freq", "gain"]);
+
+    let taper = model.parameters[&"freq".into()].ui.as_ref().expect("freq ui").taper;
+    assert_eq!(taper, ModelValueTaper::Logarithmic);
+    assert!((taper.value_at(0.5, 20.0, 20000.0) - 632.455).abs() < 0.001);
+    assert!((taper.position_of(632.455, 20.0, 20000.0) - 0.5).abs() < 0.001);
+    assert_eq!(ModelValueTaper::Linear.value_at(0.25, -20.0, 20.0), -10.0);
+    assert_eq!(ModelValueTaper::Linear.position_of(0.0, 0.0, 0.0), 0.0);
+}
+
+#[test]
+fn nearest_value_snaps_to_notches_and_clamps_to_ranges() {
+    let notches = [35.0, 60.0, 110.0, 220.0].map(|hz| ModelValueOption::Single(ModelValue::Number(hz)));
+    let selector = ModelParameter { scope:   ModelElementScope::AllInputs,
+                                    unit:    ModelValueUnit::Hertz,
+                                    role:    ModelParameterRole::NoRole,
+                                    values:  notches.to_vec(),
+                                    default: None,
+                                    linked:  true,
+                                    ui:      None, };
+
+    assert_eq!(selector.nearest_value(70.0), ModelValue::Number(60.0));
+    assert_eq!(selector.nearest_value(1000.0), ModelValue::Number(220.0));
+    assert_eq!(selector.nearest_value(85.0), ModelValue::Number(60.0));
+
+    let model = model();
+    let gain = &model.parameters[&"gain".into()];
+    assert_eq!(gain.nearest_value(3.5), ModelValue::Number(3.5));
+    assert_eq!(gain.nearest_value(-30.0), ModelValue::Number(-20.0));
+
+    let toggle = ModelParameter { values: vec![ModelValueOption::Single(ModelValue::Bool(false)),
+                                               ModelValueOption::Single(ModelValue::Bool(true))],
+                                  ..selector.clone() };
+    assert_eq!(toggle.nearest_value(0.8), ModelValue::Bool(true));
+
+    let free = ModelParameter { values: vec![],
+                                ..selector };
+    assert_eq!(free.nearest_value(12.0), ModelValue::Number(12.0));
+}
+
+#[test]
+fn snap_parameters_snaps_every_channel_against_the_model() {
+    let model = model();
+
+    assert_eq!(model.snap_parameters(&parameters([("gain", vec![Some(25.0), None]), ("trim", vec![Some(-1.5)])]).0),
+               Ok(parameters([("gain", vec![Some(20.0), None]), ("trim", vec![Some(-1.5)])]).0));
+    assert!(matches!(model.snap_parameters(&parameters([("drive", vec![Some(1.0)])]).0),
+                     Err(ModelValidationError::UnknownParameter { .. })));
+}
+
+#[test]
+fn reports_are_validated_and_clamped_against_the_model() {
+    let mut model = model();
+    model.reports.insert("gain_reduction".into(),
+                         ModelReport { scope:     ModelElementScope::AllInputs,
+                                       unit:      ModelValueUnit::Decibels,
+                                       role:      ModelReportRole::NoRole,
+                                       values:    vec![ModelValueOption::num_range(-30.0, 0.0)],
+                                       public:    true,
+                                       volatile:  true,
+                                       ui:        None,
+                                       precision: Some(1), });
+    let report = |values: Vec<Option<ModelValue>>| MultiChannelValue(values);
+    let id = "gain_reduction".into();
+
+    assert!(model.validate_report(&id, &report(vec![Some(ModelValue::Number(-3.25)), None]))
+                 .is_ok());
+    assert!(matches!(model.validate_report(&id, &report(vec![None, Some(ModelValue::Number(1.5))])),
+                     Err(ModelValidationError::ReportOutOfRange { channel: 1, .. })));
+    assert!(matches!(model.validate_report(&id, &report(vec![Some(ModelValue::Bool(true))])),
+                     Err(ModelValidationError::ReportWrongType { channel: 0, .. })));
+    assert!(matches!(model.validate_report(&id, &report(vec![None, None, None])),
+                     Err(ModelValidationError::ReportTooManyChannels { channels: 3, max: 2, .. })));
+    assert!(matches!(model.validate_report(&"level".into(), &report(vec![])),
+                     Err(ModelValidationError::UnknownReport { .. })));
+
+    let clamped = model.clamp_report(&id,
+                                     &report(vec![Some(ModelValue::Number(-3.25)),
+                                                  Some(ModelValue::Number(1.5)),
+                                                  Some(ModelValue::Number(-40.0))]))
+                       .expect("clamp report");
+    assert_eq!(clamped, report(vec![Some(ModelValue::Number(-3.3)), Some(ModelValue::Number(0.0))]));
+    assert!(model.validate_report(&id, &clamped).is_ok());
+
+    assert_eq!(model.clamp_report(&id, &report(vec![Some(ModelValue::String("over".to_owned())), None])),
+               Ok(report(vec![None, None])));
+}
+
+#[test]
+fn report_precision_is_capped() {
+    let report = ModelReport { scope:     ModelElementScope::Global,
+                               unit:      ModelValueUnit::Decibels,
+                               role:      ModelReportRole::NoRole,
+                               values:    vec![],
+                               public:    true,
+                               volatile:  true,
+                               ui:        None,
+                               precision: Some(u32::MAX), };
+
+    assert_eq!(report.normalize_value(&ModelValue::Number(-3.25)), Some(ModelValue::Number(-3.25)));
+    assert_eq!(report.normalize_value(&ModelValue::Number(f64::MAX)), Some(ModelValue::Number(f64::MAX)));
+}
+
+fn lane(interpolation: AutomationInterpolation, values: &[f64]) -> AutomationLane {
+    let points = values.iter()
+                       .enumerate()
+                       .map(|(index, &value)| AutomationPoint { time: index as f64, value })
+                       .collect();
+
+    AutomationLane { points, interpolation }
+}
+
+#[test]
+fn validate_automation_checks_values_and_interpolation() {
+    let mut model = model();
+    model.parameters.insert("bypass".into(),
+                            ModelParameter { scope:   ModelElementScope::Global,
+                                             unit:    ModelValueUnit::Toggle,
+                                             role:    ModelParameterRole::NoRole,
+                                             values:  vec![ModelValueOption::Single(ModelValue::Bool(false)),
+                                                           ModelValueOption::Single(ModelValue::Bool(true))],
+                                             default: None,
+                                             linked:  false,
+                                             ui:      None, });
+
+    assert!(model.validate_automation(&"gain".into(), &lane(AutomationInterpolation::Linear, &[-20.0, 0.0, 20.0]))
+                 .is_ok());
+    assert!(model.validate_automation(&"bypass".into(), &lane(AutomationInterpolation::Step, &[0.0, 1.0]))
+                 .is_ok());
+
+    assert!(matches!(model.validate_automation(&"gain".into(), &lane(AutomationInterpolation::Linear, &[0.0, 21.0])),
+                     Err(ModelValidationError::OutOfRange { .. })));
+    assert!(matches!(model.validate_automation(&"bypass".into(), &lane(AutomationInterpolation::Step, &[0.0, 0.5])),
+                     Err(ModelValidationError::OutOfRange { .. })));
+    assert!(matches!(model.validate_automation(&"bypass".into(), &lane(AutomationInterpolation::Linear, &[0.0, 1.0])),
+                     Err(ModelValidationError::InterpolationNotSupported { .. })));
+    assert!(matches!(model.validate_automation(&"gain".into(), &lane(AutomationInterpolation::Linear, &[])),
+                     Err(ModelValidationError::AutomationMalformed { .. })));
+}
@@ -0,0 +1,28 @@
+use audiocloud_api::cloud::domains::LicensePool;
+use audiocloud_api::{EngineId, ModelId};
+
+fn engine(id: &str) -> EngineId {
+    EngineId::new(id.to_owned())
+}
+
+fn pool(total: usize) -> LicensePool {
+    LicensePool::new(ModelId { manufacturer: "acme".to_owned(),
+                               name:         "reverb".to_owned(), },
+                     total)
+}
+
+#[test]
+fn dividing_a_pool_shares_only_the_available_seats() {
+    let mut pool = pool(10);
+    pool.borrow_seats(3).expect("borrow seats");
+
+    let shares = pool.divide([engine("a"), engine("b")]);
+    assert_eq!(shares[&engine("a")].total, 4);
+    assert_eq!(shares[&engine("b")].total, 3);
+    assert!(shares.values().all(|share| share.in_use == 0));
+    assert_eq!(shares.values().map(|share| share.total).sum::<usize>() + pool.in_use, pool.total);
+
+    pool.borrow_seats(7).expect("borrow seats");
+    assert!(pool.divide([engine("a"), engine("b")]).values().all(LicensePool::is_exhausted));
+    assert!(pool.divide([]).is_empty());
+}
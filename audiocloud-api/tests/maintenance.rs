@@ -0,0 +1,124 @@
+use std::collections::{HashMap, HashSet};
+
+use audiocloud_api::cloud::domains::{
+    AddMaintenance, DomainConfig, Maintenance, MaintenanceConflictPolicy, MaintenanceRecurrence, RecurrenceFrequency,
+};
+use audiocloud_api::{AppId, AppTaskId, CloudError, DomainId, Task, TaskId, TaskReservation, TaskSecurity, TimeRange, Timestamp};
+use chrono::Duration;
+use serde_json::json;
+
+fn at(time: &str) -> Timestamp {
+    time.parse().expect("parse timestamp")
+}
+
+fn range(from: &str, to: &str) -> TimeRange {
+    TimeRange::new(at(from), at(to))
+}
+
+fn maintenance(frequency: RecurrenceFrequency, interval: u32, until: Option<&str>) -> Maintenance {
+    Maintenance { time:       range("2024-01-31T08:00:00Z", "2024-01-31T10:00:00Z"),
+                  reason:     "calibration".to_owned(),
+                  recurrence: Some(MaintenanceRecurrence { frequency,
+                                                           interval,
+                                                           until: until.map(at) }), }
+}
+
+#[test]
+fn recurring_maintenance_expands_within_the_range() {
+    let weekly = maintenance(RecurrenceFrequency::Weekly, 2, None);
+
+    assert_eq!(weekly.occurrences_within(&range("2024-02-01T00:00:00Z", "2024-03-01T00:00:00Z")),
+               vec![range("2024-02-14T08:00:00Z", "2024-02-14T10:00:00Z"),
+                    range("2024-02-28T08:00:00Z", "2024-02-28T10:00:00Z")]);
+    assert!(weekly.intersects(&range("2024-02-14T09:00:00Z", "2024-02-14T09:30:00Z")));
+    assert!(!weekly.intersects(&range("2024-02-07T08:00:00Z", "2024-02-07T10:00:00Z")));
+}
+
+#[test]
+fn monthly_maintenance_stays_within_short_months_and_stops_at_until() {
+    let monthly = maintenance(RecurrenceFrequency::Monthly, 1, Some("2024-03-31T08:00:00Z"));
+
+    assert_eq!(monthly.occurrences_within(&range("2024-01-01T00:00:00Z", "2025-01-01T00:00:00Z")),
+               vec![range("2024-01-31T08:00:00Z", "2024-01-31T10:00:00Z"),
+                    range("2024-02-29T08:00:00Z", "2024-02-29T10:00:00Z"),
+                    range("2024-03-31T08:00:00Z", "2024-03-31T10:00:00Z")]);
+}
+
+#[test]
+fn occurrences_far_from_the_first_are_found_without_overflowing() {
+    let daily = maintenance(RecurrenceFrequency::Daily, 3, None);
+
+    assert_eq!(daily.occurrences_within(&range("2224-01-01T00:00:00Z", "2224-01-04T00:00:00Z")),
+               vec![range("2224-01-03T08:00:00Z", "2224-01-03T10:00:00Z")]);
+    assert!(maintenance(RecurrenceFrequency::Monthly, 1, None).intersects(&range("2124-05-31T09:00:00Z", "2124-05-31T09:01:00Z")));
+
+    let last = Maintenance { time: TimeRange::new(Timestamp::MAX_UTC - Duration::hours(1), Timestamp::MAX_UTC),
+                             ..daily };
+    assert!(!last.intersects(&range("2024-01-01T00:00:00Z", "2024-01-02T00:00:00Z")));
+    assert_eq!(last.occurrences_within(&TimeRange::new(Timestamp::MAX_UTC - Duration::days(1), Timestamp::MAX_UTC))
+                   .len(),
+               1);
+}
+
+fn affected() -> Vec<AppTaskId> {
+    vec![AppTaskId::new(AppId::new("app".to_owned()), TaskId::new("task".to_owned()))]
+}
+
+#[test]
+fn maintenance_without_a_conflict_policy_keeps_affected_tasks() {
+    let add = serde_json::from_str::<AddMaintenance>(
+                                                     r#"{
+            "time": {"from": "2024-01-31T08:00:00Z", "to": "2024-01-31T10:00:00Z"},
+            "reason": "calibration"
+        }"#,
+    ).expect("deserialize maintenance");
+
+    assert_eq!(add.conflict_policy, MaintenanceConflictPolicy::AllowOverlap);
+    assert_eq!(add.resolve_conflicts(affected()).expect("resolve conflicts"), vec![]);
+}
+
+#[test]
+fn huge_maintenance_notice_is_too_short_instead_of_overflowing() {
+    let add = AddMaintenance { time:            range("2024-01-31T08:00:00Z", "2024-01-31T10:00:00Z"),
+                               reason:          "calibration".to_owned(),
+                               recurrence:      None,
+                               conflict_policy: MaintenanceConflictPolicy::CancelTasks { notice_ms: u64::MAX }, };
+
+    assert!(matches!(add.resolve_conflicts(affected()), Err(CloudError::MaintenanceNoticeTooShort { .. })));
+    assert_eq!(add.resolve_conflicts(vec![]).expect("resolve without conflicts"), vec![]);
+}
+
+#[test]
+fn tasks_overlapping_a_later_occurrence_are_affected() {
+    let mut domain: DomainConfig = serde_json::from_value(json!({
+        "domain_id": "studio",
+        "models": { "inline": { "models": {} } },
+        "public_host": "localhost",
+    })).expect("parse domain config");
+
+    let task_id = affected().remove(0);
+    domain.tasks.insert(task_id.clone(),
+                        Task { domain_id:    DomainId::new("studio".to_owned()),
+                               reservations: TaskReservation { from:            at("2024-02-07T09:00:00Z"),
+                                                               to:              at("2024-02-07T11:00:00Z"),
+                                                               fixed_instances: HashSet::new(),
+                                                               revision:        0, },
+                               spec:         Default::default(),
+                               security:     TaskSecurity { security: HashMap::new(),
+                                                            revision: 0, },
+                               metadata:     Default::default(),
+                               labels:       Default::default(), });
+
+    let weekly = maintenance(RecurrenceFrequency::Weekly, 1, None);
+    assert!(!weekly.time.intersects(&domain.tasks[&task_id].reservations.time_range()));
+    assert_eq!(domain.tasks_affected_by_maintenance(&weekly, None), vec![task_id.clone()]);
+    assert_eq!(domain.tasks_affected_by_maintenance(&maintenance(RecurrenceFrequency::Weekly, 2, None), None),
+               vec![]);
+
+    let add = AddMaintenance { time:            weekly.time,
+                               reason:          weekly.reason.clone(),
+                               recurrence:      weekly.recurrence.clone(),
+                               conflict_policy: MaintenanceConflictPolicy::Reject, };
+    assert!(matches!(add.resolve_conflicts(domain.tasks_affected_by_maintenance(&add.maintenance(), None)),
+                     Err(CloudError::MaintenanceConflict { task_ids }) if task_ids == vec![task_id]));
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use audiocloud_api::instance_driver::mock::MockInstanceDriver;
+use audiocloud_api::instance_driver::{
+    DiagnosticKind, DriverCommandEnvelope, DriverCommandId, InstanceDriverCommand, InstanceDriverError, InstanceDriverEvent, ReportFilter,
+};
+use audiocloud_api::{
+    now, FixedInstanceId, InstancePlayState, Model, ModelCapability, ModelElementScope, ModelParameter, ModelParameterRole, ModelReport,
+    ModelReportRole, ModelValue, ModelValueOption, ModelValueUnit, PlayId,
+};
+use chrono::Duration;
+
+fn driver() -> MockInstanceDriver {
+    let model = Model { parameters: HashMap::from([("gain".into(),
+                                                    ModelParameter { scope:   ModelElementScope::Global,
+                                                                     unit:    ModelValueUnit::Decibels,
+                                                                     role:    ModelParameterRole::NoRole,
+                                                                     values:  vec![ModelValueOption::num_range(-20.0, 20.0)],
+                                                                     default: None,
+                                                                     linked:  false,
+                                                                     ui:      None, })]),
+                        reports: HashMap::from([("level".into(),
+                                                 ModelReport { scope:     ModelElementScope::Global,
+                                                               unit:      ModelValueUnit::Decibels,
+                                                               role:      ModelReportRole::NoRole,
+                                                               values:    vec![ModelValueOption::num_range(-100.0, 0.0)],
+                                                               public:    true,
+                                                               volatile:  true,
+                                                               ui:        None,
+                                                               precision: None, })]),
+                        media: true,
+                        capabilities: [ModelCapability::SelfTest].into(),
+                        ..Default::default() };
+
+    let instance_id = FixedInstanceId::new("acme".to_owned(), "comp".to_owned(), "1".to_owned());
+    let mut driver = MockInstanceDriver::new(instance_id, model).with_report_filter(ReportFilter { reports:         Default::default(),
+                                                                                                   min_interval_ms: 1_000, });
+    assert_eq!(driver.drain_events().collect::<Vec<_>>(),
+               vec![InstanceDriverEvent::Started, InstanceDriverEvent::Connected]);
+
+    driver
+}
+
+#[test]
+fn play_advances_media_and_reports_at_the_configured_rate() {
+    let mut driver = driver();
+    let start = now();
+
+    driver.handle(InstanceDriverCommand::Play { play_id: PlayId::new(1) })
+          .expect("play");
+    assert_eq!(driver.play_state(), &InstancePlayState::PreparingToPlay { play_id: PlayId::new(1) });
+
+    driver.tick(start);
+    assert_eq!(driver.play_state(), &InstancePlayState::Playing { play_id: PlayId::new(1) });
+    let reports = driver.drain_events()
+                        .filter(|event| matches!(event, InstanceDriverEvent::Reports { .. }))
+                        .count();
+    assert_eq!(reports, 1);
+
+    driver.tick(start + Duration::milliseconds(500));
+    assert_eq!(driver.media(), 0.5);
+    assert!(driver.drain_events()
+                  .all(|event| matches!(event, InstanceDriverEvent::PlayState { .. })));
+
+    driver.tick(start + Duration::milliseconds(1_000));
+    assert!(driver.drain_events()
+                  .any(|event| matches!(event, InstanceDriverEvent::Reports { .. })));
+
+    driver.handle(InstanceDriverCommand::Stop).expect("stop");
+    driver.tick(start + Duration::milliseconds(1_100));
+    assert_eq!(driver.play_state(), &InstancePlayState::Stopped);
+}
+
+#[test]
+fn report_filters_can_be_replaced_with_a_command() {
+    let mut driver = driver();
+    let start = now();
+    let reports = |driver: &mut MockInstanceDriver| {
+        driver.drain_events()
+              .filter_map(|event| match event {
+                  InstanceDriverEvent::Reports { reports } => Some(reports),
+                  _ => None,
+              })
+              .collect::<Vec<_>>()
+    };
+
+    driver.tick(start);
+    assert_eq!(reports(&mut driver).len(), 1);
+
+    driver.handle(InstanceDriverCommand::SetReportFilter(ReportFilter { reports:         Default::default(),
+                                                                        min_interval_ms: 100, }))
+          .expect("set report filter");
+    driver.tick(start + Duration::milliseconds(100));
+    assert_eq!(reports(&mut driver).len(), 1);
+
+    driver.handle(InstanceDriverCommand::SetReportFilter(ReportFilter { reports:         ["meter".into()].into(),
+                                                                        min_interval_ms: 0, }))
+          .expect("set report filter");
+    driver.tick(start + Duration::milliseconds(200));
+    assert!(reports(&mut driver).is_empty());
+}
+
+#[test]
+fn commands_are_checked_against_the_model() {
+    let mut driver = driver();
+
+    assert!(driver.handle(InstanceDriverCommand::SetParameters(serde_json::json!({ "gain": [3.0] })))
+                  .is_ok());
+    assert!(matches!(driver.handle(InstanceDriverCommand::SetParameters(serde_json::json!({ "drive": [1.0] }))),
+                     Err(InstanceDriverError::ParameterDoesNotExist { .. })));
+    assert!(matches!(driver.handle(InstanceDriverCommand::SetParameters(serde_json::json!({ "gain": [30.0] }))),
+                     Err(InstanceDriverError::ParametersMalformed { .. })));
+    assert!(matches!(driver.handle(InstanceDriverCommand::SetPowerChannel { channel: 0, power: true }),
+                     Err(InstanceDriverError::CapabilityNotSupported { capability: ModelCapability::PowerDistributor, })));
+    assert!(driver.handle(InstanceDriverCommand::RunSelfTest).is_ok());
+    assert!(matches!(driver.handle(InstanceDriverCommand::RunDiagnostic { kind: DiagnosticKind::Calibration, }),
+                     Err(InstanceDriverError::CapabilityNotSupported { capability: ModelCapability::Calibration, })));
+}
+
+#[test]
+fn power_channels_are_checked_against_the_model() {
+    let model = Model { parameters: HashMap::from([("power".into(),
+                                                    ModelParameter { scope:   ModelElementScope::Count(4),
+                                                                     unit:    ModelValueUnit::Toggle,
+                                                                     role:    ModelParameterRole::Power,
+                                                                     values:  vec![ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                                   ModelValueOption::Single(ModelValue::Bool(true)),],
+                                                                     default: None,
+                                                                     linked:  false,
+                                                                     ui:      None, })]),
+                        capabilities: [ModelCapability::PowerDistributor].into(),
+                        ..Default::default() };
+    let instance_id = FixedInstanceId::new("netio".to_owned(), "pdu".to_owned(), "1".to_owned());
+    let mut driver = MockInstanceDriver::new(instance_id, model);
+
+    driver.handle(InstanceDriverCommand::SetPowerChannel { channel: 3, power: true })
+          .expect("set power channel");
+    assert!(driver.power(3));
+    assert!(!driver.power(0));
+
+    assert_eq!(driver.handle(InstanceDriverCommand::SetPowerChannel { channel: 4, power: true }),
+               Err(InstanceDriverError::PowerChannelOutOfRange { channel: 4, channels: 4 }));
+    assert_eq!(driver.handle(InstanceDriverCommand::SetPowerChannel { channel: usize::MAX,
+                                                                      power:   true, }),
+               Err(InstanceDriverError::PowerChannelOutOfRange { channel:  usize::MAX,
+                                                                 channels: 4, }));
+}
+
+fn envelope(command_id: u64, expected_generation: u64) -> DriverCommandEnvelope {
+    DriverCommandEnvelope { command_id: DriverCommandId::new(command_id),
+                            command:    InstanceDriverCommand::SetParametersAtomic { parameters: Default::default(),
+                                                                                     expected_generation }, }
+}
+
+#[test]
+fn redelivered_envelopes_are_executed_once() {
+    let mut driver = driver();
+
+    assert_eq!(driver.handle_envelope(envelope(7, 0)),
+               InstanceDriverEvent::Ack { command_id: DriverCommandId::new(7), });
+    assert_eq!(driver.handle_envelope(envelope(7, 0)),
+               InstanceDriverEvent::Ack { command_id: DriverCommandId::new(7), });
+    assert_eq!(driver.generation(), 1);
+
+    let rejected = InstanceDriverEvent::Nack { command_id: DriverCommandId::new(8),
+                                               reason:     InstanceDriverError::GenerationMismatch { expected: 0, current: 1 }, };
+    assert_eq!(driver.handle_envelope(envelope(8, 0)), rejected);
+    assert_eq!(driver.handle_envelope(envelope(9, 1)),
+               InstanceDriverEvent::Ack { command_id: DriverCommandId::new(9), });
+    assert_eq!(driver.handle_envelope(envelope(8, 0)), rejected);
+    assert_eq!(driver.generation(), 2);
+}
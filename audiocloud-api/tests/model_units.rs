@@ -0,0 +1,40 @@
+use audiocloud_api::{db_to_gain, gain_to_db, ModelValue, ModelValueOption, ModelValueUnit};
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.expect("numeric options");
+    assert!((actual - expected).abs() < 1e-9, "{actual} is not {expected}");
+}
+
+#[test]
+fn decibels_convert_to_linear_gain_and_back() {
+    assert_eq!(db_to_gain(0.0), 1.0);
+    assert!((db_to_gain(-6.0) - 0.501187).abs() < 1e-6);
+    assert!((gain_to_db(2.0) - 6.0206).abs() < 1e-4);
+    assert_eq!(gain_to_db(0.0), f64::NEG_INFINITY);
+    assert!((gain_to_db(db_to_gain(-18.5)) + 18.5).abs() < 1e-9);
+}
+
+#[test]
+fn decibels_and_percentages_normalize_linearly() {
+    let gain = [ModelValueOption::num_range(-20.0, 20.0)];
+    assert_close(ModelValueUnit::Decibels.to_normalized(0.0, &gain), 0.5);
+    assert_close(ModelValueUnit::Decibels.to_normalized(-30.0, &gain), 0.0);
+    assert_close(ModelValueUnit::Decibels.from_normalized(0.75, &gain), 10.0);
+
+    assert_close(ModelValueUnit::Percent.to_normalized(25.0, &[]), 0.25);
+    assert_close(ModelValueUnit::Percent.from_normalized(1.0, &[ModelValueOption::zero_to(50.0)]),
+                 50.0);
+    assert_eq!(ModelValueUnit::Decibels.to_normalized(0.0, &[]), None);
+}
+
+#[test]
+fn frequencies_normalize_logarithmically_across_notches() {
+    let notches = [35.0, 60.0, 110.0, 220.0].map(|hz| ModelValueOption::Single(ModelValue::Number(hz)));
+
+    assert_close(ModelValueUnit::Hertz.to_normalized(35.0, &notches), 0.0);
+    assert_close(ModelValueUnit::Hertz.to_normalized(220.0, &notches), 1.0);
+
+    let sweep = [ModelValueOption::num_range(20.0, 20000.0)];
+    assert_close(ModelValueUnit::Hertz.to_normalized(200.0, &sweep), 1.0 / 3.0);
+    assert_close(ModelValueUnit::Hertz.from_normalized(2.0 / 3.0, &sweep), 2000.0);
+}
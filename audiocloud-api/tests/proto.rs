@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use chrono::{TimeZone, Utc};
+use prost::Message;
+
+use audiocloud_api::audio_engine::{EngineCommand, EngineCommandEnvelope, EngineEvent, EngineEventEnvelope, EngineLoad};
+use audiocloud_api::common::media::StopReason;
+use audiocloud_api::domain::{DomainCommand, DomainEvent};
+use audiocloud_api::instance_driver::{DriverCommandEnvelope, DriverCommandId, InstanceDriverCommand, InstanceDriverEvent};
+use audiocloud_api::proto::{
+    domain_command_message, engine_event_message, DomainCommandMessage, DomainEventMessage, EngineCommandMessage, EngineEventMessage,
+    InstanceDriverCommandMessage, InstanceDriverEventMessage, ProtoError,
+};
+use audiocloud_api::{
+    AppId, AppMediaObjectId, AppTaskId, DesiredInstancePlayState, EngineId, InstancePlayState, MediaObjectId, PlayId, TaskId,
+};
+
+fn task_id() -> AppTaskId {
+    AppTaskId::new(AppId::new("app".to_owned()), TaskId::new("task".to_owned()))
+}
+
+fn round_trip<M: Message + Default>(message: &M) -> M {
+    M::decode(message.encode_to_vec().as_slice()).expect("decode protobuf")
+}
+
+#[test]
+fn messages_round_trip_through_protobuf_encoding() {
+    let command = DomainCommand::Delete { app_session_id: task_id(),
+                                          reason:         Default::default(), };
+    let message = DomainCommandMessage::try_from(&command).expect("encode command");
+    assert!(matches!(&message.command,
+                     Some(domain_command_message::Command::Delete(delete)) if delete.app_session_id == "app:task"));
+    assert_eq!(DomainCommand::try_from(&round_trip(&message)).expect("decode command"), command);
+
+    let envelope = EngineEventEnvelope { engine_id: EngineId::new("engine".to_owned()),
+                                         event:     EngineEvent::Stopped { task_id: task_id(),
+                                                                           reason:  StopReason::default(), }, };
+    let message = EngineEventMessage::try_from(&envelope).expect("encode event");
+    assert_eq!(message.engine_id, "engine");
+    assert!(matches!(&message.event, Some(engine_event_message::Event::Stopped(stopped)) if stopped.task_id == "app:task"));
+    assert_eq!(EngineEventEnvelope::try_from(&round_trip(&message)).expect("decode event"),
+               envelope);
+}
+
+#[test]
+fn structured_values_keep_integers_floats_and_timestamps() {
+    let event = DomainEvent::EngineHeartbeat { engine_id: EngineId::new("engine".to_owned()),
+                                               load:      EngineLoad { cpu:              0.25,
+                                                                       memory:           1 << 40,
+                                                                       active_tasks:     3,
+                                                                       buffer_underruns: 0, },
+                                               at:        Utc.timestamp_opt(1_700_000_000, 123_456_789).unwrap(), };
+    let message = DomainEventMessage::try_from(&event).expect("encode event");
+    assert_eq!(DomainEvent::try_from(&round_trip(&message)).expect("decode event"), event);
+
+    let media_id = AppMediaObjectId::new(AppId::new("app".to_owned()), MediaObjectId::new("take".to_owned()));
+    let envelope = EngineCommandEnvelope { engine_id: EngineId::new("engine".to_owned()),
+                                           command:   EngineCommand::Media { task_id:     task_id(),
+                                                                             media_ready: HashMap::from([(media_id,
+                                                                                                          "/media/take.wav".to_owned())]), }, };
+    let message = EngineCommandMessage::try_from(&envelope).expect("encode command");
+    assert_eq!(EngineCommandEnvelope::try_from(&round_trip(&message)).expect("decode command"),
+               envelope);
+}
+
+#[test]
+fn driver_messages_round_trip_through_protobuf_encoding() {
+    let envelope = DriverCommandEnvelope { command_id: DriverCommandId::new(7),
+                                           command:    InstanceDriverCommand::SetParameters(serde_json::json!({ "gain": [1.5, null] })), };
+    let message = InstanceDriverCommandMessage::try_from(&envelope).expect("encode command");
+    assert_eq!(message.command_id, 7);
+    assert_eq!(DriverCommandEnvelope::try_from(&round_trip(&message)).expect("decode command"),
+               envelope);
+
+    let event = InstanceDriverEvent::PlayState { desired: DesiredInstancePlayState::Playing { play_id: PlayId::new(3) },
+                                                 current: InstancePlayState::Playing { play_id: PlayId::new(3) },
+                                                 media:   Some(12.5), };
+    let message = InstanceDriverEventMessage::try_from(&event).expect("encode event");
+    assert_eq!(InstanceDriverEvent::try_from(&round_trip(&message)).expect("decode event"), event);
+}
+
+#[test]
+fn integers_beyond_exact_float_precision_are_rejected_instead_of_rounded() {
+    let playing = |play_id| InstanceDriverEvent::PlayState { desired: DesiredInstancePlayState::Playing { play_id },
+                                                             current: InstancePlayState::Playing { play_id },
+                                                             media:   None, };
+
+    let exact = playing(PlayId::new(1 << 53));
+    let message = InstanceDriverEventMessage::try_from(&exact).expect("encode event");
+    assert_eq!(InstanceDriverEvent::try_from(&round_trip(&message)).expect("decode event"), exact);
+
+    for play_id in [(1 << 53) + 1, u64::MAX] {
+        assert!(matches!(InstanceDriverEventMessage::try_from(&playing(PlayId::new(play_id))),
+                         Err(ProtoError::Malformed { .. })));
+    }
+}
+
+#[test]
+fn messages_without_a_variant_are_rejected() {
+    assert!(matches!(DomainCommand::try_from(&DomainCommandMessage::default()),
+                     Err(ProtoError::MissingField { field }) if field == "command"));
+    assert!(matches!(EngineEventEnvelope::try_from(&EngineEventMessage { engine_id: "engine".to_owned(),
+                                                                          event:     None, }),
+                     Err(ProtoError::MissingField { field }) if field == "event"));
+}
@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+
+use audiocloud_api::{Envelope, RequestId};
+
+#[test]
+fn generated_request_ids_are_valid_and_unique() {
+    let ids = (0..1000).map(|_| RequestId::generate()).collect::<Vec<_>>();
+
+    assert!(ids.iter().all(|id| id.clone().validate().is_ok()));
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    assert!(RequestId::random().validate().is_ok());
+
+    let request = Envelope::request(());
+    let reply = request.reply(());
+    assert_eq!(reply.request_id, request.request_id);
+}
+
+#[cfg(not(feature = "sortable-ids"))]
+#[test]
+fn generated_request_ids_are_random_hexadecimal() {
+    let id = RequestId::generate();
+
+    assert_eq!(id.len(), 32);
+    assert!(id.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)));
+}
+
+#[cfg(feature = "sortable-ids")]
+#[test]
+fn generated_request_ids_sort_in_generation_order() {
+    let ids = (0..1000).map(|_| RequestId::generate()).collect::<Vec<_>>();
+
+    assert!(ids.iter()
+               .all(|id| id.len() == 26 && id.chars().all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())));
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+}
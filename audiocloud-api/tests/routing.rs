@@ -0,0 +1,29 @@
+use audiocloud_api::routing::{instance_driver_command_subject, task_events_subject, Subject, SubjectError};
+use audiocloud_api::{AppId, AppTaskId, EngineId, FixedInstanceId, TaskId};
+
+#[test]
+fn subjects_round_trip_through_parse() {
+    let instance_id = FixedInstanceId::new("distopik".to_owned(), "dual1084".to_owned(), "1".to_owned());
+    let task_id = AppTaskId::new(AppId::new("app".to_owned()), TaskId::new("task".to_owned()));
+
+    assert_eq!(instance_driver_command_subject(&instance_id).as_deref(),
+               Ok("ac.inst.distopik.dual1084.1.cmds"));
+    assert_eq!(task_events_subject(&task_id).as_deref(), Ok("ac.task.app.task.evts"));
+
+    for subject in [Subject::InstanceDriverEvents(instance_id),
+                    Subject::EngineCommands(EngineId::new("engine".to_owned())),
+                    Subject::TaskEvents(task_id)]
+    {
+        assert_eq!(Subject::parse(&subject.validated().expect("valid subject")), Ok(subject));
+    }
+}
+
+#[test]
+fn invalid_tokens_and_unknown_subjects_are_rejected() {
+    let task_id = AppTaskId::new(AppId::new("app".to_owned()), TaskId::new("my.task".to_owned()));
+
+    assert!(matches!(task_events_subject(&task_id), Err(SubjectError::InvalidToken { .. })));
+    assert!(matches!(Subject::parse("ac.engn.*.cmds"), Err(SubjectError::InvalidToken { .. })));
+    assert!(matches!(Subject::parse("ac.engn..cmds"), Err(SubjectError::InvalidToken { .. })));
+    assert!(matches!(Subject::parse("ac.task.app.task.cmds"), Err(SubjectError::UnknownSubject { .. })));
+}
@@ -0,0 +1,89 @@
+use std::collections::{HashMap, HashSet};
+
+use audiocloud_api::cloud::domains::{Maintenance, MaintenanceRecurrence, RecurrenceFrequency};
+use audiocloud_api::cloud::scheduling::find_next_available;
+use audiocloud_api::{DomainId, FixedInstanceId, Task, TaskReservation, TaskSecurity, TimeRange, Timestamp};
+use chrono::Duration;
+
+fn at(time: &str) -> Timestamp {
+    time.parse().expect("parse timestamp")
+}
+
+fn range(from: &str, to: &str) -> TimeRange {
+    TimeRange::new(at(from), at(to))
+}
+
+fn instance(name: &str) -> FixedInstanceId {
+    FixedInstanceId::new("acme".to_owned(), name.to_owned(), "1".to_owned())
+}
+
+fn task(from: &str, to: &str, instance_name: &str) -> Task {
+    Task { domain_id:    DomainId::new("studio".to_owned()),
+           reservations: TaskReservation { from:            at(from),
+                                           to:              at(to),
+                                           fixed_instances: HashSet::from([instance(instance_name)]),
+                                           revision:        0, },
+           spec:         Default::default(),
+           security:     TaskSecurity { security: HashMap::new(),
+                                        revision: 0, },
+           metadata:     Default::default(),
+           labels:       Default::default(), }
+}
+
+fn maintenance(from: &str, to: &str, recurrence: Option<MaintenanceRecurrence>) -> Maintenance {
+    Maintenance { time: range(from, to),
+                  reason: "calibration".to_owned(),
+                  recurrence }
+}
+
+#[test]
+fn next_available_slot_skips_tasks_reserving_the_same_instances() {
+    let existing = [task("2024-03-01T10:00:00Z", "2024-03-01T11:00:00Z", "comp"),
+                    task("2024-03-01T11:30:00Z", "2024-03-01T12:00:00Z", "comp"),
+                    task("2024-03-01T12:00:00Z", "2024-03-01T15:00:00Z", "eq")];
+
+    assert_eq!(find_next_available(at("2024-03-01T10:30:00Z"),
+                                   Duration::minutes(45),
+                                   &HashSet::from([instance("comp")]),
+                                   &existing,
+                                   &[]),
+               Some(range("2024-03-01T12:00:00Z", "2024-03-01T12:45:00Z")));
+
+    assert_eq!(find_next_available(at("2024-03-01T11:00:00Z"),
+                                   Duration::minutes(30),
+                                   &HashSet::from([instance("comp")]),
+                                   &existing,
+                                   &[]),
+               Some(range("2024-03-01T11:00:00Z", "2024-03-01T11:30:00Z")));
+}
+
+#[test]
+fn next_available_slot_skips_recurring_maintenance() {
+    let nightly = maintenance("2024-03-01T22:00:00Z",
+                              "2024-03-02T06:00:00Z",
+                              Some(MaintenanceRecurrence { frequency: RecurrenceFrequency::Daily,
+                                                           interval:  1,
+                                                           until:     None, }));
+    let existing = [task("2024-03-02T06:00:00Z", "2024-03-02T20:00:00Z", "comp")];
+
+    assert_eq!(find_next_available(at("2024-03-01T21:00:00Z"),
+                                   Duration::hours(3),
+                                   &HashSet::from([instance("comp")]),
+                                   &existing,
+                                   &[nightly]),
+               Some(range("2024-03-03T06:00:00Z", "2024-03-03T09:00:00Z")));
+}
+
+#[test]
+fn no_slot_is_available_when_maintenance_never_leaves_enough_time() {
+    let daily = maintenance("2024-03-01T00:00:00Z",
+                            "2024-03-01T23:00:00Z",
+                            Some(MaintenanceRecurrence { frequency: RecurrenceFrequency::Daily,
+                                                         interval:  1,
+                                                         until:     None, }));
+
+    assert_eq!(find_next_available(at("2024-03-01T00:00:00Z"), Duration::hours(2), &HashSet::new(), &[], &[daily]),
+               None);
+    assert_eq!(find_next_available(at("2024-03-01T00:00:00Z"), Duration::zero(), &HashSet::new(), &[], &[]),
+               None);
+}
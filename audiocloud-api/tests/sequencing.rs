@@ -0,0 +1,86 @@
+use audiocloud_api::domain::streaming::StreamCursor;
+use audiocloud_api::{
+    EventSequencer, PacketSequenceTracker, PlayId, SequenceCheck, SequenceTracker, StreamIntegrityError, StreamingPacketBuilder,
+    MAX_SEQUENCE_GAP,
+};
+
+#[test]
+fn tracker_detects_gaps_late_arrivals_and_duplicates() {
+    let mut sequencer = EventSequencer::starting_at(10);
+    let events = (0..4).map(|i| sequencer.stamp(i)).collect::<Vec<_>>();
+    assert_eq!(events.iter().map(|event| event.seq).collect::<Vec<_>>(), vec![10, 11, 12, 13]);
+
+    let mut tracker = SequenceTracker::default();
+    assert_eq!(tracker.receive(10), SequenceCheck::InOrder);
+    assert_eq!(tracker.receive(13), SequenceCheck::Gap { missing: 11..13 });
+    assert_eq!(tracker.missing().collect::<Vec<_>>(), vec![11, 12]);
+    assert_eq!(tracker.receive(12), SequenceCheck::Late);
+    assert_eq!(tracker.receive(12), SequenceCheck::Duplicate);
+    assert_eq!(tracker.receive(13), SequenceCheck::Duplicate);
+    assert_eq!(tracker.receive(14), SequenceCheck::InOrder);
+    assert_eq!(tracker.missing().collect::<Vec<_>>(), vec![11]);
+}
+
+#[test]
+fn tracker_resets_after_huge_jumps_instead_of_tracking_every_gap() {
+    let mut tracker = SequenceTracker::default();
+    assert_eq!(tracker.receive(5), SequenceCheck::InOrder);
+    assert_eq!(tracker.receive(8), SequenceCheck::Gap { missing: 6..8 });
+
+    assert_eq!(tracker.receive(u64::MAX), SequenceCheck::Reset { last: 8 });
+    assert_eq!(tracker.missing().count(), 0);
+    assert_eq!(tracker.receive(u64::MAX), SequenceCheck::Duplicate);
+
+    assert_eq!(tracker.receive(0), SequenceCheck::Reset { last: u64::MAX });
+    assert_eq!(tracker.receive(1), SequenceCheck::InOrder);
+    assert_eq!(tracker.receive(1 + MAX_SEQUENCE_GAP),
+               SequenceCheck::Gap { missing: 2..1 + MAX_SEQUENCE_GAP, });
+}
+
+#[test]
+fn tracker_forgets_missing_sequence_numbers_older_than_the_largest_gap() {
+    let mut tracker = SequenceTracker::default();
+    for seq in (0..=10 * MAX_SEQUENCE_GAP).step_by(2) {
+        tracker.receive(seq);
+    }
+
+    let oldest = 9 * MAX_SEQUENCE_GAP;
+    assert_eq!(tracker.missing().count() as u64, MAX_SEQUENCE_GAP / 2);
+    assert!(tracker.missing().all(|seq| seq > oldest));
+    assert_eq!(tracker.receive(oldest + 1), SequenceCheck::Late);
+}
+
+#[test]
+fn sequencer_wraps_after_the_last_sequence_number() {
+    let mut sequencer = EventSequencer::starting_at(u64::MAX);
+    assert_eq!(sequencer.stamp(()).seq, u64::MAX);
+    assert_eq!(sequencer.stamp(()).seq, 0);
+}
+
+#[test]
+fn packet_tracker_ends_the_play_at_the_last_serial() {
+    let play_id = PlayId::new(1);
+    let packet = |serial| StreamingPacketBuilder::new(play_id).with_serial(serial).build().expect("packet");
+
+    let mut tracker = PacketSequenceTracker::default();
+    assert_eq!(tracker.track(&packet(u64::MAX - 2)), Ok(()));
+    assert_eq!(tracker.track(&packet(u64::MAX)),
+               Err(StreamIntegrityError::Gap { play_id,
+                                               expected: u64::MAX - 1,
+                                               received: u64::MAX }));
+    assert_eq!(tracker.track(&packet(u64::MAX)),
+               Err(StreamIntegrityError::Duplicate { play_id,
+                                                     serial: u64::MAX }));
+
+    let mut tracker = PacketSequenceTracker::default();
+    assert_eq!(tracker.track(&packet(u64::MAX)), Ok(()));
+    assert_eq!(tracker.track(&packet(0)),
+               Err(StreamIntegrityError::Duplicate { play_id, serial: 0 }));
+}
+
+#[test]
+fn stream_cursor_after_the_last_serial_does_not_overflow() {
+    let packet = StreamingPacketBuilder::new(PlayId::new(1)).with_serial(u64::MAX).build().expect("packet");
+
+    assert_eq!(StreamCursor::after(&packet).next_serial(), u64::MAX);
+}
@@ -0,0 +1,37 @@
+use audiocloud_api::{RenderId, StopReason, TaskEvent};
+
+#[test]
+fn bare_deleted_events_of_older_peers_have_an_unknown_reason() {
+    let event = serde_json::from_str::<TaskEvent>(r#""deleted""#).expect("deserialize bare event");
+    assert_eq!(event, TaskEvent::Deleted { reason: StopReason::Unknown, });
+
+    let json = serde_json::to_string(&event).expect("serialize event");
+    assert_eq!(json, r#"{"deleted":{"reason":"unknown"}}"#);
+    assert_eq!(serde_json::from_str::<TaskEvent>(&json).expect("deserialize event"), event);
+
+    let msgpack = rmp_serde::to_vec(&"deleted").expect("serialize bare event");
+    assert_eq!(rmp_serde::from_slice::<TaskEvent>(&msgpack).expect("deserialize bare event"), event);
+}
+
+#[test]
+fn deleted_events_keep_their_reason() {
+    let event = TaskEvent::Deleted { reason: StopReason::Admin };
+    let msgpack = rmp_serde::to_vec_named(&event).expect("serialize event");
+
+    assert_eq!(rmp_serde::from_slice::<TaskEvent>(&msgpack).expect("deserialize event"), event);
+    assert_eq!(serde_json::from_str::<TaskEvent>(r#"{"deleted":{}}"#).expect("deserialize event"),
+               TaskEvent::Deleted { reason: StopReason::Unknown, });
+}
+
+#[test]
+fn other_events_deserialize_as_before() {
+    let event = TaskEvent::RenderTimedOut { render_id:       RenderId::new(3),
+                                            max_duration_ms: 60_000, };
+    let json = serde_json::to_string(&event).expect("serialize event");
+
+    assert_eq!(serde_json::from_str::<TaskEvent>(&json).expect("deserialize event"), event);
+    assert!(serde_json::from_str::<TaskEvent>(r#""stopped""#).is_err());
+    assert!(serde_json::from_str::<TaskEvent>(r#"{"exploded":{}}"#).unwrap_err()
+                                                                   .to_string()
+                                                                   .contains("unknown variant"));
+}
@@ -0,0 +1,427 @@
+use std::collections::{HashMap, HashSet};
+
+use audiocloud_api::{
+    AppId, AppMediaObjectId, AppTaskId, AutomationInterpolation, AutomationLane, AutomationPoint, ChannelMask, CloudError, CompositeNode,
+    CompositeNodeId, ConnectionAutomation, ConnectionKind, ConnectionValues, ControlChannels, DynamicInstanceNode, DynamicInstanceNodeId,
+    InputPadId, InstanceParameters, MediaChannels, MediaObjectId, MixerNode, MixerNodeId, Model, ModelElementScope, ModelId, ModelInput,
+    ModelOutput, ModelParameter, ModelParameterRole, ModelValue, ModelValueOption, ModelValueUnit, ModifyTaskError, ModifyTaskSpec,
+    MultiChannelValue, NodeConnection, NodeConnectionId, NodePadId, OutputPadId, RecorderMediaNaming, RecorderNode, RecorderNodeId, TaskId,
+    TaskNodeId, TaskSpec, TrackMediaFormat, TrackNodeId, DEFAULT_MAX_CONNECTION_VOLUME,
+};
+
+fn mixer(id: &str) -> MixerNodeId {
+    MixerNodeId::new(id.to_owned())
+}
+
+fn dynamic(id: &str) -> DynamicInstanceNodeId {
+    DynamicInstanceNodeId::new(id.to_owned())
+}
+
+fn connection(from: OutputPadId, to: InputPadId, kind: ConnectionKind) -> NodeConnection {
+    NodeConnection { from,
+                     to,
+                     kind,
+                     from_channels: ChannelMask::Mono(0),
+                     to_channels: ChannelMask::Mono(0),
+                     volume: 1.0,
+                     pan: 0.0,
+                     pan_law: None,
+                     delay_ms: 0.0,
+                     automation: Default::default() }
+}
+
+fn connect(spec: &mut TaskSpec, connection_id: &str, from: &str, to: &str) -> Result<(), ModifyTaskError> {
+    spec.add_connection(connection_id.to_owned().into(),
+                        connection(mixer(from).output_flow(), mixer(to).input_flow(), ConnectionKind::Audio))
+}
+
+fn spec() -> TaskSpec {
+    let mut spec = TaskSpec::default();
+    for id in ["bus", "master", "fx"] {
+        spec.add_mixer(mixer(id),
+                       MixerNode { input_channels:  2,
+                                   output_channels: 2, })
+            .expect("add mixer");
+    }
+
+    spec.add_track(TrackNodeId::new("vocals".to_owned()), MediaChannels::Stereo)
+        .expect("add track");
+    spec.add_connection("vocals-to-bus".to_owned().into(),
+                        connection(TrackNodeId::new("vocals".to_owned()).source(),
+                                   mixer("bus").input_flow(),
+                                   ConnectionKind::Audio))
+        .expect("connect track");
+    connect(&mut spec, "bus-to-master", "bus", "master").expect("connect bus");
+
+    spec
+}
+
+#[test]
+fn connections_creating_cycles_are_rejected() {
+    let mut spec = spec();
+    connect(&mut spec, "bus-to-fx", "bus", "fx").expect("connect fx");
+    let before = spec.clone();
+
+    assert!(matches!(connect(&mut spec, "master-to-bus", "master", "bus"),
+                     Err(ModifyTaskError::CycleDetected)));
+    assert!(matches!(connect(&mut spec, "fx-to-fx", "fx", "fx"), Err(ModifyTaskError::CycleDetected)));
+    assert_eq!(spec, before);
+
+    assert!(connect(&mut spec, "fx-to-master", "fx", "master").is_ok());
+}
+
+#[test]
+fn topological_order_follows_connections() {
+    let mut spec = spec();
+    connect(&mut spec, "fx-to-bus", "fx", "bus").expect("connect fx");
+
+    assert_eq!(spec.topological_order(),
+               Some(vec![TaskNodeId::Mixer(mixer("fx")),
+                         TaskNodeId::Track(TrackNodeId::new("vocals".to_owned())),
+                         TaskNodeId::Mixer(mixer("bus")),
+                         TaskNodeId::Mixer(mixer("master"))]));
+}
+
+#[test]
+fn topological_order_is_none_for_cyclic_specs() {
+    let mut spec = spec();
+    let mut cyclic = spec.connections[&NodeConnectionId::new("bus-to-master".to_owned())].clone();
+    cyclic.from = mixer("master").output_flow();
+    cyclic.to = mixer("bus").input_flow();
+    spec.connections.insert("master-to-bus".to_owned().into(), cyclic);
+
+    assert_eq!(spec.topological_order(), None);
+}
+
+#[test]
+fn upstream_and_downstream_follow_connections_transitively() {
+    let mut spec = spec();
+    connect(&mut spec, "bus-to-fx", "bus", "fx").expect("connect fx");
+    connect(&mut spec, "fx-to-master", "fx", "master").expect("connect fx");
+
+    let reached = |nodes: Vec<(TaskNodeId, &NodeConnectionId)>| {
+        nodes.into_iter()
+             .map(|(node_id, connection_id)| (node_id, connection_id.to_string()))
+             .collect::<Vec<_>>()
+    };
+    let vocals = TaskNodeId::Track(TrackNodeId::new("vocals".to_owned()));
+
+    assert_eq!(reached(spec.upstream_of(&mixer("master").input_flow().into()).collect()),
+               vec![(TaskNodeId::Mixer(mixer("bus")), "bus-to-master".to_owned()),
+                    (TaskNodeId::Mixer(mixer("fx")), "fx-to-master".to_owned()),
+                    (vocals.clone(), "vocals-to-bus".to_owned()),
+                    (TaskNodeId::Mixer(mixer("bus")), "bus-to-fx".to_owned())]);
+
+    let vocals_out: NodePadId = TrackNodeId::new("vocals".to_owned()).source().into();
+    assert_eq!(reached(spec.downstream_of(&vocals_out).collect()),
+               vec![(TaskNodeId::Mixer(mixer("bus")), "vocals-to-bus".to_owned()),
+                    (TaskNodeId::Mixer(mixer("fx")), "bus-to-fx".to_owned()),
+                    (TaskNodeId::Mixer(mixer("master")), "bus-to-master".to_owned()),
+                    (TaskNodeId::Mixer(mixer("master")), "fx-to-master".to_owned())]);
+
+    assert_eq!(spec.downstream_of(&mixer("master").output_flow().into()).count(), 0);
+    assert_eq!(spec.upstream_of(&vocals_out).count(), 0);
+}
+
+#[test]
+fn orphan_nodes_have_no_connections() {
+    let mut spec = spec();
+    assert_eq!(spec.orphan_nodes().collect::<Vec<_>>(), vec![TaskNodeId::Mixer(mixer("fx"))]);
+
+    connect(&mut spec, "fx-to-master", "fx", "master").expect("connect fx");
+    assert_eq!(spec.orphan_nodes().count(), 0);
+}
+
+#[test]
+fn midi_and_sidechain_connections_require_matching_pads() {
+    let sequencer = ModelId { manufacturer: "acme".to_owned(),
+                              name:         "sequencer".to_owned(), };
+    let synth = ModelId { manufacturer: "acme".to_owned(),
+                          name:         "synth".to_owned(), };
+    let models = HashMap::from([(sequencer.clone(),
+                                 Model { outputs: vec![ModelOutput::Midi],
+                                         ..Default::default() }),
+                                (synth.clone(),
+                                 Model { inputs: vec![ModelInput::Midi, ModelInput::Sidechain],
+                                         outputs: vec![ModelOutput::Audio(ControlChannels::Left),
+                                                       ModelOutput::Audio(ControlChannels::Right)],
+                                         ..Default::default() })]);
+
+    let mut spec = spec();
+    for (id, model_id) in [("sequencer", sequencer), ("synth", synth)] {
+        spec.add_dynamic_instance(dynamic(id),
+                                  DynamicInstanceNode { model_id,
+                                                        parameters: Default::default(),
+                                                        parameter_automation: Default::default() })
+            .expect("add instance");
+    }
+
+    let mut routed = spec.clone();
+    for (id, connection) in
+        [("sequencer-to-synth",
+          connection(dynamic("sequencer").output_flow(),
+                     dynamic("synth").input_flow(),
+                     ConnectionKind::Midi)),
+         ("bus-to-synth", connection(mixer("bus").output_flow(), dynamic("synth").input_flow(), ConnectionKind::Sidechain)),
+         ("synth-to-master", connection(dynamic("synth").output_flow(), mixer("master").input_flow(), ConnectionKind::Audio))]
+    {
+        routed.add_connection(id.to_owned().into(), connection).expect("connect");
+    }
+    assert!(routed.validate(&models).is_ok());
+
+    let not_supported = |from: OutputPadId, to: InputPadId, kind: ConnectionKind| {
+        let mut spec = spec.clone();
+        spec.add_connection("invalid".to_owned().into(), connection(from, to, kind))
+            .expect("connect");
+
+        match spec.validate(&models) {
+            Err(CloudError::ConnectionError { error, .. }) => matches!(*error, CloudError::ConnectionKindNotSupported { .. }),
+            _ => false,
+        }
+    };
+
+    assert!(not_supported(dynamic("sequencer").output_flow(), mixer("fx").input_flow(), ConnectionKind::Midi));
+    assert!(not_supported(dynamic("synth").output_flow(), mixer("fx").input_flow(), ConnectionKind::Midi));
+    assert!(not_supported(mixer("bus").output_flow(),
+                          dynamic("sequencer").input_flow(),
+                          ConnectionKind::Sidechain));
+}
+
+fn lane(interpolation: AutomationInterpolation, points: &[(f64, f64)]) -> AutomationLane {
+    let points = points.iter().map(|&(time, value)| AutomationPoint { time, value }).collect();
+
+    AutomationLane { points, interpolation }
+}
+
+#[test]
+fn connection_automation_interpolates_and_is_validated() {
+    let volume = lane(AutomationInterpolation::Linear, &[(10.0, 1.0), (20.0, 2.0)]);
+
+    assert_eq!(volume.value_at(0.0), Some(1.0));
+    assert_eq!(volume.value_at(15.0), Some(1.5));
+    assert_eq!(volume.value_at(30.0), Some(2.0));
+    assert_eq!(lane(AutomationInterpolation::Step, &[(10.0, 1.0), (20.0, 2.0)]).value_at(15.0),
+               Some(1.0));
+
+    let mut spec = spec();
+    let bus_to_master = NodeConnectionId::new("bus-to-master".to_owned());
+    let automate =
+        |spec: &mut TaskSpec, volume, pan| spec.set_connection_automation(bus_to_master.clone(), ConnectionAutomation { volume, pan });
+
+    assert!(automate(&mut spec, Some(volume), None).is_ok());
+    assert!(matches!(automate(&mut spec,
+                              None,
+                              Some(lane(AutomationInterpolation::Linear, &[(10.0, 0.0), (5.0, 1.0)]))),
+                     Err(ModifyTaskError::AutomationMalformed { .. })));
+    assert!(matches!(automate(&mut spec, None, Some(lane(AutomationInterpolation::Linear, &[(10.0, 2.0)]))),
+                     Err(ModifyTaskError::PanOutOfRange { .. })));
+    assert!(spec.connections[&bus_to_master].automation.volume.is_some());
+}
+
+#[test]
+fn connection_volumes_are_limited_to_the_configured_maximum() {
+    let mut spec = spec();
+    let bus_to_master = NodeConnectionId::new("bus-to-master".to_owned());
+    let set_volume = |volume| ModifyTaskSpec::SetConnectionParameterValues { connection_id: bus_to_master.clone(),
+                                                                             values:        ConnectionValues { volume:  Some(volume),
+                                                                                                               pan:     None,
+                                                                                                               pan_law: None, }, };
+
+    assert!(matches!(spec.modify(set_volume(DEFAULT_MAX_CONNECTION_VOLUME + 1.0)),
+                     Err(ModifyTaskError::VolumeOutOfRange { max, .. }) if max == DEFAULT_MAX_CONNECTION_VOLUME));
+    assert!(matches!(spec.apply_transaction(vec![set_volume(1.0), set_volume(f64::NAN)]),
+                     Err(ModifyTaskError::TransactionFailed { index: 1, .. })));
+    assert_eq!(spec.connections[&bus_to_master].volume, 1.0);
+
+    assert!(spec.modify_with_max_volume(set_volume(DEFAULT_MAX_CONNECTION_VOLUME + 1.0), 8.0)
+                .is_ok());
+    assert!(spec.validate_with_max_volume(&HashMap::new(), 8.0).is_ok());
+    assert!(spec.validate_with_max_volume(&HashMap::new(), DEFAULT_MAX_CONNECTION_VOLUME)
+                .is_err());
+}
+
+fn gain(value: f64) -> InstanceParameters {
+    InstanceParameters(HashMap::from([("gain".into(), MultiChannelValue(vec![Some(ModelValue::Number(value))]))]))
+}
+
+fn instance(name: &str) -> DynamicInstanceNode {
+    DynamicInstanceNode { model_id:             ModelId { manufacturer: "acme".to_owned(),
+                                                          name:         name.to_owned(), },
+                          parameters:           gain(1.0),
+                          parameter_automation: Default::default(), }
+}
+
+#[test]
+fn snapshots_restore_all_or_selected_nodes() {
+    let mut spec = spec();
+    for id in ["eq", "comp"] {
+        spec.add_dynamic_instance(dynamic(id), instance(id)).expect("add instance");
+    }
+
+    let snapshot = spec.snapshot_parameters();
+    for id in ["eq", "comp"] {
+        spec.set_dynamic_instance_parameter_values(dynamic(id), gain(5.0))
+            .expect("set parameters");
+    }
+
+    let mut partial = spec.clone();
+    partial.modify(ModifyTaskSpec::ApplySnapshot { parameters: snapshot.clone(),
+                                                   nodes:      HashSet::from([dynamic("eq").into()]), })
+           .expect("apply snapshot");
+    assert_eq!(partial.dynamic[&dynamic("eq")].parameters, gain(1.0));
+    assert_eq!(partial.dynamic[&dynamic("comp")].parameters, gain(5.0));
+    assert_eq!(partial.revision, spec.revision + 1);
+
+    spec.apply_snapshot(snapshot.clone(), HashSet::new()).expect("apply snapshot");
+    assert_eq!(spec.snapshot_parameters(), snapshot);
+
+    let before = spec.clone();
+    assert!(matches!(spec.apply_snapshot(snapshot.clone(), HashSet::from([dynamic("eq").into(), mixer("bus").into()])),
+                     Err(ModifyTaskError::NodeHasNoParameters { .. })));
+    spec.add_dynamic_instance(dynamic("late"), instance("late")).expect("add instance");
+    assert!(matches!(spec.apply_snapshot(snapshot, HashSet::from([dynamic("late").into()])),
+                     Err(ModifyTaskError::NodeNotInSnapshot { .. })));
+    assert_eq!(spec.dynamic[&dynamic("eq")], before.dynamic[&dynamic("eq")]);
+}
+
+#[test]
+fn resetting_node_parameters_applies_the_model_defaults() {
+    let model = Model { parameters: HashMap::from([("gain".into(),
+                                                    ModelParameter { scope:   ModelElementScope::Global,
+                                                                     unit:    ModelValueUnit::Decibels,
+                                                                     role:    ModelParameterRole::NoRole,
+                                                                     values:  vec![ModelValueOption::num_range(-20.0, 20.0)],
+                                                                     default: Some(ModelValue::Number(1.0)),
+                                                                     linked:  false,
+                                                                     ui:      None, })]),
+                        ..Default::default() };
+
+    let mut spec = spec();
+    spec.add_dynamic_instance(dynamic("eq"), instance("eq")).expect("add instance");
+    spec.set_dynamic_instance_parameter_values(dynamic("eq"), gain(5.0))
+        .expect("set parameters");
+
+    let revision = spec.revision;
+    spec.modify(ModifyTaskSpec::reset_node_parameters(dynamic("eq").into(), &model))
+        .expect("reset parameters");
+    assert_eq!(spec.dynamic[&dynamic("eq")].parameters, gain(1.0));
+    assert_eq!(spec.revision, revision + 1);
+
+    assert!(matches!(spec.modify(ModifyTaskSpec::reset_node_parameters(mixer("bus").into(), &model)),
+                     Err(ModifyTaskError::NodeHasNoParameters { .. })));
+}
+
+#[test]
+fn composites_insert_and_delete_with_mangled_ids() {
+    let strip = CompositeNode { dynamic: HashMap::from([(dynamic("eq"), instance("eq")), (dynamic("comp"), instance("comp"))]),
+                                connections: HashMap::from([("eq-to-comp".to_owned().into(),
+                                                             connection(dynamic("eq").output_flow(),
+                                                                        dynamic("comp").input_flow(),
+                                                                        ConnectionKind::Audio))]),
+                                ..Default::default() };
+    let composite_id = CompositeNodeId::new("vocal-strip".to_owned());
+
+    let mut spec = spec();
+    let revision = spec.revision;
+    spec.modify(ModifyTaskSpec::AddComposite { composite_id: composite_id.clone(),
+                                               spec:         strip.clone(), })
+        .expect("add composite");
+
+    let eq = dynamic("vocal-strip/eq");
+    assert!(spec.dynamic.contains_key(&eq));
+    assert_eq!(composite_id.unmangle(&eq), Some("eq"));
+    let internal = &spec.connections[&NodeConnectionId::new("vocal-strip/eq-to-comp".to_owned())];
+    assert_eq!(internal.from, eq.output_flow());
+    assert_eq!(internal.to, dynamic("vocal-strip/comp").input_flow());
+    assert_eq!(spec.revision, revision + 1);
+
+    let before = spec.clone();
+    assert!(matches!(spec.add_composite(composite_id.clone(), strip.clone()),
+                     Err(ModifyTaskError::DynamicInstanceExists { .. })));
+    let mut escaping = strip;
+    escaping.connections.insert("comp-to-master".to_owned().into(),
+                                connection(dynamic("comp").output_flow(), mixer("master").input_flow(), ConnectionKind::Audio));
+    assert!(matches!(spec.add_composite(CompositeNodeId::new("other".to_owned()), escaping),
+                     Err(ModifyTaskError::CompositeConnectionEscapes { .. })));
+    assert_eq!(spec, before);
+
+    spec.add_connection("strip-to-master".to_owned().into(),
+                        connection(dynamic("vocal-strip/comp").output_flow(),
+                                   mixer("master").input_flow(),
+                                   ConnectionKind::Audio))
+        .expect("connect composite");
+    spec.delete_composite(composite_id.clone()).expect("delete composite");
+    assert!(spec.dynamic.is_empty());
+    assert_eq!(spec.connections.len(), 2);
+    assert!(matches!(spec.delete_composite(composite_id),
+                     Err(ModifyTaskError::CompositeDoesNotExist { .. })));
+}
+
+#[test]
+fn deleting_a_composite_keeps_nodes_of_other_composites() {
+    let strip = CompositeNode { dynamic: HashMap::from([(dynamic("eq"), instance("eq"))]),
+                                ..Default::default() };
+    let vocals = CompositeNodeId::new("vocals".to_owned());
+
+    let mut spec = spec();
+    spec.add_composite(vocals.clone(), strip.clone()).expect("add composite");
+    spec.add_composite(CompositeNodeId::new("vocals-2".to_owned()), strip.clone())
+        .expect("add composite");
+
+    assert!(matches!(spec.add_composite(CompositeNodeId::new("vocals/eq".to_owned()), strip.clone()),
+                     Err(ModifyTaskError::CompositeIdMalformed { .. })));
+    let mut nested = strip.clone();
+    nested.dynamic.insert(dynamic("inner/eq"), instance("eq"));
+    assert!(matches!(spec.add_composite(CompositeNodeId::new("other".to_owned()), nested),
+                     Err(ModifyTaskError::NodeIdReserved { .. })));
+    assert!(matches!(spec.add_dynamic_instance(dynamic("vocals/manual"), instance("manual")),
+                     Err(ModifyTaskError::NodeIdReserved { .. })));
+    assert_eq!(vocals.unmangle("vocals/eq/inner"), None);
+
+    spec.delete_composite(vocals).expect("delete composite");
+    assert_eq!(spec.dynamic.keys().cloned().collect::<HashSet<_>>(),
+               HashSet::from([dynamic("vocals-2/eq")]));
+}
+
+#[test]
+fn recorders_capture_connected_audio_into_named_media() {
+    let recorder_id = RecorderNodeId::new("vocal-take".to_owned());
+    let recorder = RecorderNode { channels: MediaChannels::Mono,
+                                  format:   TrackMediaFormat::Wave,
+                                  naming:   Default::default(), };
+
+    let mut spec = spec();
+    spec.modify(ModifyTaskSpec::AddRecorder { recorder_id: recorder_id.clone(),
+                                              spec:        recorder.clone(), })
+        .expect("add recorder");
+    assert!(matches!(spec.add_recorder(recorder_id.clone(), recorder.clone()),
+                     Err(ModifyTaskError::RecorderExists { .. })));
+
+    spec.add_connection("master-to-recorder".to_owned().into(),
+                        connection(mixer("master").output_flow(),
+                                   recorder_id.clone().input_flow(),
+                                   ConnectionKind::Audio))
+        .expect("connect recorder");
+    assert!(spec.validate(&HashMap::new()).is_ok());
+    assert_eq!(spec.topological_order().and_then(|order| order.last().cloned()),
+               Some(TaskNodeId::Recorder(recorder_id.clone())));
+
+    let mut stereo = spec.clone();
+    stereo.connections
+          .get_mut(&NodeConnectionId::new("master-to-recorder".to_owned()))
+          .unwrap()
+          .to_channels = ChannelMask::Stereo(0);
+    assert!(matches!(stereo.validate(&HashMap::new()), Err(CloudError::ConnectionError { .. })));
+
+    let task_id = AppTaskId::new(AppId::new("app".to_owned()), TaskId::new("session".to_owned()));
+    assert_eq!(recorder.naming.media_object_id(&task_id, &recorder_id, 3).expect("valid media id"),
+               AppMediaObjectId::new(AppId::new("app".to_owned()), MediaObjectId::new("session-vocal-take-3".to_owned())));
+    let naming = RecorderMediaNaming { template: "{recorder_id}/{take}".to_owned(), };
+    assert!(naming.media_object_id(&task_id, &recorder_id, 1).is_err());
+
+    spec.modify(ModifyTaskSpec::DeleteRecorder { recorder_id: recorder_id.clone(), })
+        .expect("delete recorder");
+    assert!(spec.recorders.is_empty());
+    assert_eq!(spec.connections.len(), 2);
+    assert!(matches!(spec.delete_recorder(recorder_id), Err(ModifyTaskError::RecorderDoesNotExist { .. })));
+}
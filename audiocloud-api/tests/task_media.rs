@@ -0,0 +1,302 @@
+use std::collections::{HashMap, HashSet};
+
+use audiocloud_api::cloud::apps::UpdateApp;
+use audiocloud_api::{
+    AppId, CloudError, LoopRegion, MediaChannels, MediaMetadata, MediaObjectId, MixerNode, MixerNodeId, ModifyTaskError, PlayBitDepth,
+    PlayId, PlayTimecode, PunchRange, RenderId, RequestChangeMixer, RequestPlay, RequestRender, SampleRate, StorageQuota, StorageUsage,
+    TaskPlayState, TaskSpec, TimeSegment, Timecode, TimecodeError, TimecodeFormat, Timestamp, TrackMedia, TrackMediaFormat, TrackNode,
+    TrackNodeId, UpdateTaskPlay, UpdateTaskTrack, DEFAULT_MAX_CONNECTION_VOLUME,
+};
+
+fn media(object_id: &str, start: f64, length: f64) -> TrackMedia {
+    TrackMedia { channels:         MediaChannels::Stereo,
+                 format:           TrackMediaFormat::Wave,
+                 media_segment:    segment(0.0, length),
+                 timeline_segment: segment(start, length),
+                 object_id:        MediaObjectId::new(object_id.to_owned()), }
+}
+
+fn track(media: impl IntoIterator<Item = TrackMedia>) -> TrackNode {
+    TrackNode { media: media.into_iter()
+                            .enumerate()
+                            .map(|(i, media)| (format!("media-{i}").into(), media))
+                            .collect(),
+                ..TrackNode::new(MediaChannels::Stereo) }
+}
+
+fn segment(start: f64, length: f64) -> TimeSegment {
+    TimeSegment { start, length }
+}
+
+fn spec() -> TaskSpec {
+    let mut spec = TaskSpec::default();
+    spec.tracks.insert("drums".to_owned().into(),
+                       track([media("kick", 0.0, 30.0), media("snare", 90.0, 30.0)]));
+    spec.tracks.insert("bass".to_owned().into(), track([media("bass", 45.0, 60.0)]));
+    spec.tracks.insert("empty".to_owned().into(), track([]));
+    spec
+}
+
+fn ids<'a>(ids: impl IntoIterator<Item = &'a str>) -> HashSet<MediaObjectId> {
+    ids.into_iter().map(|id| MediaObjectId::new(id.to_owned())).collect()
+}
+
+fn owned(media: HashSet<&MediaObjectId>) -> HashSet<MediaObjectId> {
+    media.into_iter().cloned().collect()
+}
+
+#[test]
+fn media_needed_in_returns_overlapping_media() {
+    let spec = spec();
+
+    assert_eq!(owned(spec.media_needed_in(segment(0.0, 60.0))), ids(["kick", "bass"]));
+    assert_eq!(owned(spec.media_needed_in(segment(60.0, 60.0))), ids(["bass", "snare"]));
+    assert_eq!(owned(spec.media_needed_in(segment(200.0, 60.0))), ids([]));
+}
+
+#[test]
+fn media_needed_in_excludes_media_touching_the_segment() {
+    let spec = spec();
+
+    assert_eq!(owned(spec.media_needed_in(segment(30.0, 15.0))), ids([]));
+}
+
+#[test]
+fn media_by_track_groups_media_per_track() {
+    let spec = spec();
+    let by_track = spec.media_by_track()
+                       .into_iter()
+                       .map(|(track_id, media)| (track_id.clone(), owned(media)))
+                       .collect::<HashMap<TrackNodeId, _>>();
+
+    assert_eq!(by_track,
+               HashMap::from([("drums".to_owned().into(), ids(["kick", "snare"])),
+                              ("bass".to_owned().into(), ids(["bass"])),
+                              ("empty".to_owned().into(), ids([]))]));
+}
+
+#[test]
+fn monitored_mixers_must_exist_and_have_gains_within_range() {
+    let mut spec = spec();
+    let master = MixerNodeId::new("master".to_owned());
+    spec.add_mixer(master.clone(),
+                   MixerNode { input_channels:  2,
+                               output_channels: 2, })
+        .expect("add mixer");
+    let mixers = |gain| HashMap::from([(master.clone(), gain)]);
+
+    assert!(spec.validate_monitored_mixers(&mixers(1.0), DEFAULT_MAX_CONNECTION_VOLUME).is_ok());
+    assert!(spec.validate_monitored_mixers(&mixers(6.0), 8.0).is_ok());
+    assert!(matches!(spec.validate_monitored_mixers(&mixers(6.0), DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::VolumeOutOfRange { .. })));
+    assert!(matches!(spec.validate_monitored_mixers(&HashMap::new(), DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::NoMonitoredMixers)));
+    assert!(matches!(spec.validate_monitored_mixers(&HashMap::from([(MixerNodeId::new("cue".to_owned()), 1.0)]),
+                                                    DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::MixerNodeNotFound { .. })));
+}
+
+#[test]
+fn single_mixer_ids_of_older_requests_are_monitored_at_unity_gain() {
+    let master = MixerNodeId::new("master".to_owned());
+
+    let change = serde_json::from_str::<RequestChangeMixer>(r#"{"play_id":1,"mixer_id":"master"}"#).expect("deserialize change");
+    assert_eq!(change.mixers, HashMap::from([(master.clone(), 1.0)]));
+
+    let update = serde_json::from_str::<UpdateTaskPlay>(r#"{"play_id":1,"mixer_id":"master","segment":null,"start_at":null,"looping":null}"#)
+        .expect("deserialize update");
+    assert_eq!(update.mixers, Some(HashMap::from([(master.clone(), 1.0)])));
+
+    let change = RequestChangeMixer { play_id: PlayId::new(1),
+                                      mixers:  HashMap::from([(master, 0.5)]), };
+    let json = serde_json::to_string(&change).expect("serialize change");
+    assert_eq!(serde_json::from_str::<RequestChangeMixer>(&json).expect("deserialize change"),
+               change);
+}
+
+fn play_update(loop_region: Option<TimeSegment>, punch: Option<PunchRange>) -> UpdateTaskPlay {
+    let loop_region = loop_region.map(|segment| LoopRegion { segment,
+                                                             repetitions: None });
+
+    UpdateTaskPlay { play_id: PlayId::new(1),
+                     mixers: None,
+                     segment: None,
+                     start_at: None,
+                     looping: None,
+                     loop_region,
+                     punch }
+}
+
+fn punch(punch_in: Option<f64>, punch_out: Option<f64>) -> Option<PunchRange> {
+    Some(PunchRange { punch_in, punch_out })
+}
+
+fn playing(segment: TimeSegment) -> RequestPlay {
+    RequestPlay { play_id: PlayId::new(1),
+                  mixers: HashMap::new(),
+                  segment,
+                  start_at: segment.start,
+                  looping: true,
+                  loop_region: None,
+                  punch: None,
+                  sample_rate: SampleRate::SR48,
+                  bit_depth: PlayBitDepth::PD24,
+                  position_update_hz: None,
+                  scheduled_at: None,
+                  click: None,
+                  timecode: None }
+}
+
+#[test]
+fn play_regions_must_be_within_the_timeline() {
+    let spec = spec();
+    let play = playing(segment(0.0, 120.0));
+    let validate = |update| spec.validate_play_update(&play, &update, DEFAULT_MAX_CONNECTION_VOLUME);
+    let outside = |update| matches!(validate(update), Err(CloudError::PlayRegionOutsideTimeline { .. }));
+
+    assert_eq!(spec.timeline_length(), 120.0);
+    assert!(validate(play_update(Some(segment(30.0, 90.0)), None)).is_ok());
+    assert!(validate(play_update(None, punch(None, None))).is_ok());
+    assert!(validate(play_update(None, punch(Some(10.0), None))).is_ok());
+
+    assert!(outside(play_update(Some(segment(100.0, 30.0)), None)));
+    assert!(outside(play_update(Some(segment(10.0, 0.0)), None)));
+    assert!(outside(play_update(None, punch(Some(20.0), Some(10.0)))));
+    assert!(TaskSpec::default().validate_play_update(&play, &play_update(Some(segment(0.0, 1.0)), None), DEFAULT_MAX_CONNECTION_VOLUME)
+                               .is_err());
+}
+
+#[test]
+fn loop_regions_must_be_within_the_played_segment() {
+    let spec = spec();
+    let play = playing(segment(30.0, 60.0));
+    let outside_segment = |play: &RequestPlay, update| {
+        matches!(spec.validate_play_update(play, &update, DEFAULT_MAX_CONNECTION_VOLUME),
+                 Err(CloudError::LoopRegionOutsideSegment { .. }))
+    };
+
+    assert!(spec.validate_play_update(&play, &play_update(Some(segment(40.0, 20.0)), None), DEFAULT_MAX_CONNECTION_VOLUME)
+                .is_ok());
+    assert!(outside_segment(&play, play_update(Some(segment(20.0, 20.0)), None)));
+    assert!(outside_segment(&play, play_update(Some(segment(80.0, 20.0)), None)));
+
+    let looping = RequestPlay { loop_region: Some(LoopRegion { segment:     segment(40.0, 20.0),
+                                                               repetitions: None, }),
+                                ..play };
+    let shrink = UpdateTaskPlay { segment: Some(segment(30.0, 15.0)),
+                                  ..play_update(None, None) };
+    assert!(outside_segment(&looping, shrink.clone()));
+
+    assert!(TaskPlayState::Playing(looping.clone()).validate_update(&spec, &play_update(None, None), DEFAULT_MAX_CONNECTION_VOLUME)
+                                                   .is_ok());
+    assert!(matches!(TaskPlayState::Playing(looping).validate_update(&spec, &shrink, DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::LoopRegionOutsideSegment { .. })));
+    assert!(matches!(TaskPlayState::Stopped.validate_update(&spec, &shrink, DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::PlayNotActive { .. })));
+}
+
+#[test]
+fn mute_and_solo_silence_tracks() {
+    let mut spec = spec();
+    let [drums, bass, empty] = ["drums", "bass", "empty"].map(|id| TrackNodeId::new(id.to_owned()));
+    let update = |gain, mute, solo| UpdateTaskTrack { gain, mute, solo };
+
+    spec.update_track(drums.clone(), update(Some(0.5), None, None)).expect("set gain");
+    assert_eq!(spec.track_output_gain(&drums), Some(0.5));
+
+    spec.update_track(bass.clone(), update(None, None, Some(true))).expect("solo");
+    assert_eq!(spec.track_output_gain(&drums), Some(0.0));
+    assert_eq!(spec.track_output_gain(&bass), Some(1.0));
+
+    spec.update_track(bass.clone(), update(None, Some(true), None)).expect("mute");
+    assert_eq!(spec.track_output_gain(&bass), Some(0.0));
+    assert_eq!(spec.track_output_gain(&empty), Some(0.0));
+
+    assert!(matches!(spec.update_track(drums.clone(), update(Some(-1.0), None, None)),
+                     Err(ModifyTaskError::TrackGainOutOfRange { .. })));
+    assert!(matches!(spec.update_track(drums.clone(), update(Some(DEFAULT_MAX_CONNECTION_VOLUME * 2.0), None, None)),
+                     Err(ModifyTaskError::TrackGainOutOfRange { max, .. }) if max == DEFAULT_MAX_CONNECTION_VOLUME));
+    assert!(spec.update_track_with_max_volume(drums, update(Some(DEFAULT_MAX_CONNECTION_VOLUME * 2.0), None, None), 8.0)
+                .is_ok());
+    assert_eq!(spec.track_output_gain(&"missing".to_owned().into()), None);
+}
+
+#[test]
+fn play_timecode_counts_from_origin_or_task_start() {
+    let start = Timecode::parse(TimecodeFormat::Fps25, "01:00:00:00").expect("parse timecode");
+    let timecode = PlayTimecode { format: TimecodeFormat::Fps25,
+                                  origin: None, };
+
+    assert_eq!(timecode.timecode_at(None, 2.0).to_string(), "00:00:02:00");
+    assert_eq!(timecode.timecode_at(Some(start), 2.4).to_string(), "01:00:02:10");
+
+    let origin = Timecode::parse(TimecodeFormat::Fps24, "10:00:00:00").expect("parse timecode");
+    let timecode = PlayTimecode { origin: Some(origin),
+                                  ..timecode };
+    assert_eq!(timecode.timecode_at(Some(start), 1.0).to_string(), "10:00:01:00");
+}
+
+#[test]
+fn parsing_huge_timecode_hours_is_out_of_range() {
+    assert!(matches!(Timecode::parse(TimecodeFormat::Fps25, "999999999999999999:00:00:00"),
+                     Err(TimecodeError::OutOfRange { .. })));
+    assert!(matches!(Timecode::parse(TimecodeFormat::Fps29_97DropFrame, "18446744073709551615:59:59;29"),
+                     Err(TimecodeError::OutOfRange { .. })));
+    assert_eq!(Timecode::parse(TimecodeFormat::Fps25, "100:00:00:00").map(|timecode| timecode.frames),
+               Ok(100 * 3600 * 25));
+}
+
+fn render(max_duration_ms: Option<u64>) -> RequestRender {
+    RequestRender { render_id: RenderId::new(1),
+                    mixer_id: MixerNodeId::new("master".to_owned()),
+                    segment: segment(0.0, 60.0),
+                    object_id: (AppId::new("app".to_owned()), MediaObjectId::new("render".to_owned())).into(),
+                    max_duration_ms,
+                    scheduled_at: None,
+                    output_format: Default::default() }
+}
+
+#[test]
+fn render_deadlines_do_not_overflow() {
+    let started_at: Timestamp = "2024-03-01T10:00:00Z".parse().expect("parse timestamp");
+
+    assert_eq!(render(Some(60_000)).deadline(started_at),
+               Some("2024-03-01T10:01:00Z".parse().expect("parse timestamp")));
+    assert_eq!(render(None).deadline(started_at), None);
+    assert_eq!(render(Some(i64::MAX as u64)).deadline(started_at), None);
+    assert_eq!(render(Some(u64::MAX)).deadline(started_at), None);
+    assert!(!render(Some(u64::MAX)).is_timed_out(started_at));
+}
+
+#[test]
+fn storage_usage_saturates_instead_of_overflowing() {
+    let usage = StorageUsage { bytes:   u64::MAX - 10,
+                               objects: u64::MAX, };
+    let existing = MediaMetadata { channels:    MediaChannels::Stereo,
+                                   format:      TrackMediaFormat::Wave,
+                                   seconds:     1.0,
+                                   sample_rate: 48_000,
+                                   bytes:       5,
+                                   checksum:    None, };
+
+    assert_eq!(usage.with_upload(100, None),
+               StorageUsage { bytes:   u64::MAX,
+                              objects: u64::MAX, });
+    assert_eq!(usage.with_upload(100, Some(&existing)),
+               StorageUsage { bytes:   u64::MAX,
+                              objects: u64::MAX, });
+    assert_eq!(StorageUsage::default().with_upload(100, Some(&existing)),
+               StorageUsage { bytes: 100, objects: 0 });
+}
+
+#[test]
+fn app_updates_leave_clear_or_set_the_storage_quota() {
+    let update = |json| serde_json::from_str::<UpdateApp>(json).expect("deserialize update").storage_quota;
+    let quota = StorageQuota { max_bytes:   1 << 30,
+                               max_objects: 100, };
+
+    assert_eq!(update(r#"{"enabled": true}"#), None);
+    assert_eq!(update(r#"{"storage_quota": null}"#), Some(None));
+    assert_eq!(update(r#"{"storage_quota": {"max_bytes": 1073741824, "max_objects": 100}}"#),
+               Some(Some(quota)));
+}
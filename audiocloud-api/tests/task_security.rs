@@ -0,0 +1,52 @@
+use std::net::IpAddr;
+
+use audiocloud_api::{KeySource, TaskKeyGrant, TaskPermissions};
+
+fn network(address: &str, prefix_len: u8) -> KeySource {
+    KeySource::IpNetwork { address: address.parse().expect("parse address"),
+                           prefix_len }
+}
+
+fn ip(address: &str) -> Option<IpAddr> {
+    Some(address.parse().expect("parse address"))
+}
+
+#[test]
+fn ip_networks_match_addresses_within_the_prefix() {
+    assert!(network("10.1.0.0", 16).matches(ip("10.1.200.3"), None));
+    assert!(!network("10.1.0.0", 16).matches(ip("10.2.0.1"), None));
+    assert!(network("10.1.0.0", 12).matches(ip("10.15.0.1"), None));
+    assert!(network("2001:db8::", 32).matches(ip("2001:db8::1"), None));
+    assert!(!network("2001:db8::", 32).matches(ip("10.1.0.1"), None));
+}
+
+#[test]
+fn prefixes_longer_than_the_address_never_match() {
+    for prefix_len in [33, 39] {
+        let source = network("10.1.2.3", prefix_len);
+        assert!(!source.is_well_formed());
+        assert!(!source.matches(ip("10.1.2.3"), None));
+    }
+
+    for prefix_len in [129, 135] {
+        let source = network("2001:db8::1", prefix_len);
+        assert!(!source.is_well_formed());
+        assert!(!source.matches(ip("2001:db8::1"), None));
+    }
+}
+
+#[test]
+fn grants_without_restrictions_read_like_bare_permissions() {
+    let grant = serde_json::from_str::<TaskKeyGrant>(r#"{"structure":true,"media":false,"parameters":true,"transport":false,"audio":true}"#)
+        .expect("deserialize grant");
+
+    assert_eq!(grant,
+               TaskKeyGrant::from(TaskPermissions { structure:  true,
+                                                    media:      false,
+                                                    parameters: true,
+                                                    transport:  false,
+                                                    audio:      true, }));
+    assert!(grant.permissions.can(TaskPermissions { audio: true,
+                                                    ..TaskPermissions::empty() }));
+    assert!(grant.allows_source(None, None));
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use audiocloud_api::{
+    CloudError, DesiredTaskPlayState, MixerNode, MixerNodeId, ModifyTaskError, PlayBitDepth, PlayClick, PlayId, RequestPlay, SampleRate,
+    TaskReservation, TaskSpec, TempoMap, TempoMapError, TempoPoint, TimeSegment, TimeSignature, DEFAULT_MAX_CONNECTION_VOLUME,
+};
+
+fn point(time: f64, bpm: f64, numerator: u8, denominator: u8) -> TempoPoint {
+    TempoPoint { time,
+                 bpm,
+                 time_signature: TimeSignature { numerator, denominator } }
+}
+
+fn tempo_map(points: impl IntoIterator<Item = TempoPoint>) -> TempoMap {
+    TempoMap { points: points.into_iter().collect(), }
+}
+
+fn play(click: Option<PlayClick>) -> RequestPlay {
+    RequestPlay { play_id: PlayId::new(1),
+                  mixers: HashMap::from([(MixerNodeId::new("master".to_owned()), 1.0)]),
+                  segment: TimeSegment { start: 0.0, length: 0.0 },
+                  start_at: 0.0,
+                  looping: false,
+                  loop_region: None,
+                  punch: None,
+                  sample_rate: SampleRate::SR48,
+                  bit_depth: PlayBitDepth::PD24,
+                  position_update_hz: None,
+                  scheduled_at: None,
+                  click,
+                  timecode: None }
+}
+
+#[test]
+fn tempo_maps_count_beats_across_changes() {
+    let map = tempo_map([point(0.0, 120.0, 4, 4), point(10.0, 60.0, 6, 8)]);
+    assert!(map.validate().is_ok());
+
+    assert_eq!(map.point_at(5.0).map(|point| point.bpm), Some(120.0));
+    assert_eq!(map.point_at(10.0).map(|point| point.time_signature),
+               Some(TimeSignature { numerator:   6,
+                                    denominator: 8, }));
+    assert_eq!(map.beats_at(5.0), Some(10.0));
+    assert_eq!(map.beats_at(14.0), Some(24.0));
+    assert_eq!(TempoMap::default().beats_at(1.0), None);
+
+    assert_eq!(tempo_map([point(1.0, 120.0, 4, 4)]).validate(),
+               Err(TempoMapError::NotStartingAtZero { time: 1.0 }));
+    assert_eq!(tempo_map([point(0.0, 120.0, 4, 4), point(0.0, 90.0, 4, 4)]).validate(),
+               Err(TempoMapError::NotIncreasing { time: 0.0 }));
+    assert_eq!(tempo_map([point(0.0, 1000.0, 4, 4)]).validate(),
+               Err(TempoMapError::TempoOutOfRange { bpm: 1000.0 }));
+    assert_eq!(tempo_map([point(0.0, 120.0, 4, 3)]).validate(),
+               Err(TempoMapError::InvalidTimeSignature { numerator:   4,
+                                                         denominator: 3, }));
+
+    let mut spec = TaskSpec::default();
+    assert!(matches!(spec.set_tempo_map(tempo_map([point(0.0, 0.0, 4, 4)])),
+                     Err(ModifyTaskError::InvalidTempoMap { .. })));
+    spec.set_tempo_map(map.clone()).expect("set tempo map");
+    assert_eq!(spec.tempo_map, map);
+}
+
+#[test]
+fn click_requires_a_tempo_map_and_an_existing_mixer() {
+    let master = MixerNodeId::new("master".to_owned());
+    let click = |gain, mixer_id: &MixerNodeId| {
+        Some(PlayClick { enabled: true,
+                         gain,
+                         mixer_id: mixer_id.clone() })
+    };
+
+    let mut spec = TaskSpec::default();
+    spec.add_mixer(master.clone(),
+                   MixerNode { input_channels:  2,
+                               output_channels: 2, })
+        .expect("add mixer");
+
+    assert!(matches!(spec.validate_play(&play(click(1.0, &master)), DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::ClickWithoutTempoMap)));
+
+    spec.set_tempo_map(tempo_map([point(0.0, 100.0, 3, 4)])).expect("set tempo map");
+    assert!(spec.validate_play(&play(click(1.0, &master)), DEFAULT_MAX_CONNECTION_VOLUME)
+                .is_ok());
+    assert!(matches!(spec.validate_play(&play(click(1.0, &MixerNodeId::new("cue".to_owned()))),
+                                        DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::MixerNodeNotFound { .. })));
+    assert!(matches!(spec.validate_play(&play(click(-1.0, &master)), DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::VolumeOutOfRange { .. })));
+    assert!(matches!(spec.validate_play(&play(click(6.0, &master)), DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::VolumeOutOfRange { .. })));
+    assert!(spec.validate_play(&play(click(6.0, &master)), 8.0).is_ok());
+    assert!(spec.validate_play(&play(None), DEFAULT_MAX_CONNECTION_VOLUME).is_ok());
+}
+
+#[test]
+fn desired_play_states_are_validated_against_the_task() {
+    let master = MixerNodeId::new("master".to_owned());
+    let mut spec = TaskSpec::default();
+    spec.add_mixer(master.clone(),
+                   MixerNode { input_channels:  2,
+                               output_channels: 2, })
+        .expect("add mixer");
+    let reservation = TaskReservation { from:            "2024-03-01T10:00:00Z".parse().expect("parse timestamp"),
+                                        to:              "2024-03-01T11:00:00Z".parse().expect("parse timestamp"),
+                                        fixed_instances: Default::default(),
+                                        revision:        0, };
+    let click = PlayClick { enabled:  true,
+                            gain:     1.0,
+                            mixer_id: master, };
+
+    assert!(DesiredTaskPlayState::Play(play(None)).validate(&spec, &reservation, DEFAULT_MAX_CONNECTION_VOLUME)
+                                                  .is_ok());
+    assert!(DesiredTaskPlayState::Stopped.validate(&spec, &reservation, DEFAULT_MAX_CONNECTION_VOLUME)
+                                         .is_ok());
+    assert!(matches!(DesiredTaskPlayState::Play(play(Some(click))).validate(&spec, &reservation, DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::ClickWithoutTempoMap)));
+
+    let late = RequestPlay { scheduled_at: Some("2024-03-01T12:00:00Z".parse().expect("parse timestamp")),
+                             ..play(None) };
+    assert!(matches!(DesiredTaskPlayState::Play(late).validate(&spec, &reservation, DEFAULT_MAX_CONNECTION_VOLUME),
+                     Err(CloudError::ScheduledOutsideReservation { .. })));
+}
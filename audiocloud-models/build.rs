@@ -133,6 +133,30 @@ struct RustGeneratedTemplate<'a> {
     models: &'a HashMap<String, HashMap<String, Model>>,
 }
 
+impl<'a> RustGeneratedTemplate<'a> {
+    /// Parameters of a model that no other model of the manufacturer has, sorted
+    ///
+    /// Their constants are re-exported from the manufacturer module, where they were generated before models had
+    /// their own constants modules.
+    pub fn flat_parameters(&self, manufacturer: &str, name: &str) -> Vec<String> {
+        let models = match self.models.get(manufacturer) {
+            Some(models) => models,
+            None => return vec![],
+        };
+
+        models.get(name)
+              .into_iter()
+              .flat_map(|model| model.parameters.keys())
+              .filter(|parameter_id| {
+                  !models.iter()
+                         .any(|(other, model)| other != name && model.parameters.contains_key(*parameter_id))
+              })
+              .map(ToString::to_string)
+              .sorted()
+              .collect()
+    }
+}
+
 #[derive(Template)]
 #[template(path = "ts_generated.ts", escape = "none")]
 struct TSGeneratedTemplate<'a> {
@@ -56,7 +56,8 @@ pub fn distopik_xfilter_model() -> Model {
           resources:  Default::default(),
           reports:    Default::default(),
           media:      false,
-          capabilities: Default::default(),  }
+          capabilities: Default::default(),
+          nominal_latency_ms: None, }
 }
 
 fn ch_on() -> ModelParameter {
@@ -64,7 +64,8 @@ pub fn distopik_xfilter_model() -> Model {
           resources:  Default::default(),
           reports:    Default::default(),
           media:      false,
-          capabilities: Default::default(), }
+          capabilities: Default::default(),
+          nominal_latency_ms: None, }
 }
 
 fn hit_it() -> ModelParameter {
@@ -10,8 +10,42 @@ pub mod audiocloud {
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct Insert1X1Preset {}
+
+    impl ToParameterMap for Insert1X1Preset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            ParameterValues::new()
+        }
+    }
+
+    impl FromParameterMap for Insert1X1Preset {
+        fn from_parameter_map(_values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self {})
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct Insert1X1Parameters {}
+
+    impl ToParameterMap for Insert1X1Parameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            ParameterValues::new()
+        }
+    }
+
+    impl FromParameterMap for Insert1X1Parameters {
+        fn from_parameter_map(_values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self {})
+        }
+    }
+
+    impl Patch for Insert1X1Parameters {
+        fn merge(self, _other: Self) -> Self {
+            self
+        }
+
+        fn is_empty(&self) -> bool {
+            true
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct Insert1X1Reports {
         pub insert_input:  Option<f64>,
@@ -20,8 +54,42 @@ pub mod audiocloud {
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct Insert24X2Preset {}
+
+    impl ToParameterMap for Insert24X2Preset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            ParameterValues::new()
+        }
+    }
+
+    impl FromParameterMap for Insert24X2Preset {
+        fn from_parameter_map(_values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self {})
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct Insert24X2Parameters {}
+
+    impl ToParameterMap for Insert24X2Parameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            ParameterValues::new()
+        }
+    }
+
+    impl FromParameterMap for Insert24X2Parameters {
+        fn from_parameter_map(_values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self {})
+        }
+    }
+
+    impl Patch for Insert24X2Parameters {
+        fn merge(self, _other: Self) -> Self {
+            self
+        }
+
+        fn is_empty(&self) -> bool {
+            true
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct Insert24X2Reports {
         pub insert_input:  Option<Vec<f64>>,
@@ -30,8 +98,42 @@ pub mod audiocloud {
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct Insert2X2Preset {}
+
+    impl ToParameterMap for Insert2X2Preset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            ParameterValues::new()
+        }
+    }
+
+    impl FromParameterMap for Insert2X2Preset {
+        fn from_parameter_map(_values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self {})
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct Insert2X2Parameters {}
+
+    impl ToParameterMap for Insert2X2Parameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            ParameterValues::new()
+        }
+    }
+
+    impl FromParameterMap for Insert2X2Parameters {
+        fn from_parameter_map(_values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self {})
+        }
+    }
+
+    impl Patch for Insert2X2Parameters {
+        fn merge(self, _other: Self) -> Self {
+            self
+        }
+
+        fn is_empty(&self) -> bool {
+            true
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct Insert2X2Reports {
         pub insert_input:  Option<Stereo<f64>>,
@@ -39,6 +141,652 @@ pub mod audiocloud {
     }
 }
 
+pub mod cockos {
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct ReacompPreset {
+        pub attack:             Vec<f64>,
+        pub auto_make_up:       Vec<bool>,
+        pub auto_release:       Vec<bool>,
+        pub bypass:             Vec<bool>,
+        pub detector_high_pass: Vec<ToggleOr<f64>>,
+        pub knee:               Vec<f64>,
+        pub output_gain:        Vec<f64>,
+        pub pre_comp:           Vec<f64>,
+        pub ratio:              Vec<f64>,
+        pub release:            Vec<f64>,
+        pub rms_size:           Vec<f64>,
+        pub threshold:          Vec<f64>,
+    }
+
+    impl ToParameterMap for ReacompPreset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            insert_parameter_value(&mut values, "attack", &self.attack);
+            insert_parameter_value(&mut values, "auto_make_up", &self.auto_make_up);
+            insert_parameter_value(&mut values, "auto_release", &self.auto_release);
+            insert_parameter_value(&mut values, "bypass", &self.bypass);
+            insert_parameter_value(&mut values, "detector_high_pass", &self.detector_high_pass);
+            insert_parameter_value(&mut values, "knee", &self.knee);
+            insert_parameter_value(&mut values, "output_gain", &self.output_gain);
+            insert_parameter_value(&mut values, "pre_comp", &self.pre_comp);
+            insert_parameter_value(&mut values, "ratio", &self.ratio);
+            insert_parameter_value(&mut values, "release", &self.release);
+            insert_parameter_value(&mut values, "rms_size", &self.rms_size);
+            insert_parameter_value(&mut values, "threshold", &self.threshold);
+            values
+        }
+    }
+
+    impl FromParameterMap for ReacompPreset {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { attack:             require_parameter_value(values, "attack")?,
+                      auto_make_up:       require_parameter_value(values, "auto_make_up")?,
+                      auto_release:       require_parameter_value(values, "auto_release")?,
+                      bypass:             require_parameter_value(values, "bypass")?,
+                      detector_high_pass: require_parameter_value(values, "detector_high_pass")?,
+                      knee:               require_parameter_value(values, "knee")?,
+                      output_gain:        require_parameter_value(values, "output_gain")?,
+                      pre_comp:           require_parameter_value(values, "pre_comp")?,
+                      ratio:              require_parameter_value(values, "ratio")?,
+                      release:            require_parameter_value(values, "release")?,
+                      rms_size:           require_parameter_value(values, "rms_size")?,
+                      threshold:          require_parameter_value(values, "threshold")?, })
+        }
+    }
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+    pub struct ReacompParameters {
+        pub attack:             Option<Vec<f64>>,
+        pub auto_make_up:       Option<Vec<bool>>,
+        pub auto_release:       Option<Vec<bool>>,
+        pub bypass:             Option<Vec<bool>>,
+        pub detector_high_pass: Option<Vec<ToggleOr<f64>>>,
+        pub knee:               Option<Vec<f64>>,
+        pub output_gain:        Option<Vec<f64>>,
+        pub pre_comp:           Option<Vec<f64>>,
+        pub ratio:              Option<Vec<f64>>,
+        pub release:            Option<Vec<f64>>,
+        pub rms_size:           Option<Vec<f64>>,
+        pub threshold:          Option<Vec<f64>>,
+    }
+
+    impl ToParameterMap for ReacompParameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            if let Some(value) = &self.attack {
+                insert_parameter_value(&mut values, "attack", value);
+            }
+            if let Some(value) = &self.auto_make_up {
+                insert_parameter_value(&mut values, "auto_make_up", value);
+            }
+            if let Some(value) = &self.auto_release {
+                insert_parameter_value(&mut values, "auto_release", value);
+            }
+            if let Some(value) = &self.bypass {
+                insert_parameter_value(&mut values, "bypass", value);
+            }
+            if let Some(value) = &self.detector_high_pass {
+                insert_parameter_value(&mut values, "detector_high_pass", value);
+            }
+            if let Some(value) = &self.knee {
+                insert_parameter_value(&mut values, "knee", value);
+            }
+            if let Some(value) = &self.output_gain {
+                insert_parameter_value(&mut values, "output_gain", value);
+            }
+            if let Some(value) = &self.pre_comp {
+                insert_parameter_value(&mut values, "pre_comp", value);
+            }
+            if let Some(value) = &self.ratio {
+                insert_parameter_value(&mut values, "ratio", value);
+            }
+            if let Some(value) = &self.release {
+                insert_parameter_value(&mut values, "release", value);
+            }
+            if let Some(value) = &self.rms_size {
+                insert_parameter_value(&mut values, "rms_size", value);
+            }
+            if let Some(value) = &self.threshold {
+                insert_parameter_value(&mut values, "threshold", value);
+            }
+            values
+        }
+    }
+
+    impl FromParameterMap for ReacompParameters {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { attack:             get_parameter_value(values, "attack")?,
+                      auto_make_up:       get_parameter_value(values, "auto_make_up")?,
+                      auto_release:       get_parameter_value(values, "auto_release")?,
+                      bypass:             get_parameter_value(values, "bypass")?,
+                      detector_high_pass: get_parameter_value(values, "detector_high_pass")?,
+                      knee:               get_parameter_value(values, "knee")?,
+                      output_gain:        get_parameter_value(values, "output_gain")?,
+                      pre_comp:           get_parameter_value(values, "pre_comp")?,
+                      ratio:              get_parameter_value(values, "ratio")?,
+                      release:            get_parameter_value(values, "release")?,
+                      rms_size:           get_parameter_value(values, "rms_size")?,
+                      threshold:          get_parameter_value(values, "threshold")?, })
+        }
+    }
+
+    impl Patch for ReacompParameters {
+        fn merge(self, other: Self) -> Self {
+            Self { attack:             other.attack.or(self.attack),
+                   auto_make_up:       other.auto_make_up.or(self.auto_make_up),
+                   auto_release:       other.auto_release.or(self.auto_release),
+                   bypass:             other.bypass.or(self.bypass),
+                   detector_high_pass: other.detector_high_pass.or(self.detector_high_pass),
+                   knee:               other.knee.or(self.knee),
+                   output_gain:        other.output_gain.or(self.output_gain),
+                   pre_comp:           other.pre_comp.or(self.pre_comp),
+                   ratio:              other.ratio.or(self.ratio),
+                   release:            other.release.or(self.release),
+                   rms_size:           other.rms_size.or(self.rms_size),
+                   threshold:          other.threshold.or(self.threshold), }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.attack.is_none()
+            && self.auto_make_up.is_none()
+            && self.auto_release.is_none()
+            && self.bypass.is_none()
+            && self.detector_high_pass.is_none()
+            && self.knee.is_none()
+            && self.output_gain.is_none()
+            && self.pre_comp.is_none()
+            && self.ratio.is_none()
+            && self.release.is_none()
+            && self.rms_size.is_none()
+            && self.threshold.is_none()
+        }
+    }
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+    pub struct ReacompReports {
+        pub gain_reduction: Option<Vec<f64>>,
+        pub output_peak:    Option<Stereo<f64>>,
+    }
+    pub mod reacomp {
+        use super::*;
+
+        pub const ATTACK_NAME: &str = "attack";
+        pub const ATTACK_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(0_f64), ModelValue::Number(500_f64))];
+        pub const AUTO_MAKE_UP_NAME: &str = "auto_make_up";
+        pub const AUTO_MAKE_UP_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const AUTO_RELEASE_NAME: &str = "auto_release";
+        pub const AUTO_RELEASE_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const BYPASS_NAME: &str = "bypass";
+        pub const BYPASS_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                          ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const DETECTOR_HIGH_PASS_NAME: &str = "detector_high_pass";
+        pub const DETECTOR_HIGH_PASS_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                      ModelValueOption::Range(ModelValue::Number(20_f64),
+                                                                                              ModelValue::Number(2000_f64))];
+        pub const KNEE_NAME: &str = "knee";
+        pub const KNEE_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(0_f64), ModelValue::Number(24_f64))];
+        pub const OUTPUT_GAIN_NAME: &str = "output_gain";
+        pub const OUTPUT_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-60_f64), ModelValue::Number(24_f64))];
+        pub const PRE_COMP_NAME: &str = "pre_comp";
+        pub const PRE_COMP_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(0_f64), ModelValue::Number(250_f64))];
+        pub const RATIO_NAME: &str = "ratio";
+        pub const RATIO_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(1_f64), ModelValue::Number(100_f64))];
+        pub const RELEASE_NAME: &str = "release";
+        pub const RELEASE_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(0_f64), ModelValue::Number(5000_f64))];
+        pub const RMS_SIZE_NAME: &str = "rms_size";
+        pub const RMS_SIZE_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(0_f64), ModelValue::Number(1000_f64))];
+        pub const THRESHOLD_NAME: &str = "threshold";
+        pub const THRESHOLD_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-60_f64), ModelValue::Number(0_f64))];
+    }
+    pub use self::reacomp::{AUTO_MAKE_UP_NAME, AUTO_MAKE_UP_VALUES};
+    pub use self::reacomp::{AUTO_RELEASE_NAME, AUTO_RELEASE_VALUES};
+    pub use self::reacomp::{DETECTOR_HIGH_PASS_NAME, DETECTOR_HIGH_PASS_VALUES};
+    pub use self::reacomp::{PRE_COMP_NAME, PRE_COMP_VALUES};
+    pub use self::reacomp::{RMS_SIZE_NAME, RMS_SIZE_VALUES};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct ReaeqPreset {
+        pub bypass:           Vec<bool>,
+        pub high_freq:        Vec<f64>,
+        pub high_gain:        Vec<f64>,
+        pub high_mid_freq:    Vec<f64>,
+        pub high_mid_gain:    Vec<f64>,
+        pub high_mid_width:   Vec<f64>,
+        pub high_pass_filter: Vec<ToggleOr<f64>>,
+        pub high_width:       Vec<f64>,
+        pub low_freq:         Vec<f64>,
+        pub low_gain:         Vec<f64>,
+        pub low_mid_freq:     Vec<f64>,
+        pub low_mid_gain:     Vec<f64>,
+        pub low_mid_width:    Vec<f64>,
+        pub low_width:        Vec<f64>,
+        pub output_gain:      Vec<f64>,
+    }
+
+    impl ToParameterMap for ReaeqPreset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            insert_parameter_value(&mut values, "bypass", &self.bypass);
+            insert_parameter_value(&mut values, "high_freq", &self.high_freq);
+            insert_parameter_value(&mut values, "high_gain", &self.high_gain);
+            insert_parameter_value(&mut values, "high_mid_freq", &self.high_mid_freq);
+            insert_parameter_value(&mut values, "high_mid_gain", &self.high_mid_gain);
+            insert_parameter_value(&mut values, "high_mid_width", &self.high_mid_width);
+            insert_parameter_value(&mut values, "high_pass_filter", &self.high_pass_filter);
+            insert_parameter_value(&mut values, "high_width", &self.high_width);
+            insert_parameter_value(&mut values, "low_freq", &self.low_freq);
+            insert_parameter_value(&mut values, "low_gain", &self.low_gain);
+            insert_parameter_value(&mut values, "low_mid_freq", &self.low_mid_freq);
+            insert_parameter_value(&mut values, "low_mid_gain", &self.low_mid_gain);
+            insert_parameter_value(&mut values, "low_mid_width", &self.low_mid_width);
+            insert_parameter_value(&mut values, "low_width", &self.low_width);
+            insert_parameter_value(&mut values, "output_gain", &self.output_gain);
+            values
+        }
+    }
+
+    impl FromParameterMap for ReaeqPreset {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { bypass:           require_parameter_value(values, "bypass")?,
+                      high_freq:        require_parameter_value(values, "high_freq")?,
+                      high_gain:        require_parameter_value(values, "high_gain")?,
+                      high_mid_freq:    require_parameter_value(values, "high_mid_freq")?,
+                      high_mid_gain:    require_parameter_value(values, "high_mid_gain")?,
+                      high_mid_width:   require_parameter_value(values, "high_mid_width")?,
+                      high_pass_filter: require_parameter_value(values, "high_pass_filter")?,
+                      high_width:       require_parameter_value(values, "high_width")?,
+                      low_freq:         require_parameter_value(values, "low_freq")?,
+                      low_gain:         require_parameter_value(values, "low_gain")?,
+                      low_mid_freq:     require_parameter_value(values, "low_mid_freq")?,
+                      low_mid_gain:     require_parameter_value(values, "low_mid_gain")?,
+                      low_mid_width:    require_parameter_value(values, "low_mid_width")?,
+                      low_width:        require_parameter_value(values, "low_width")?,
+                      output_gain:      require_parameter_value(values, "output_gain")?, })
+        }
+    }
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+    pub struct ReaeqParameters {
+        pub bypass:           Option<Vec<bool>>,
+        pub high_freq:        Option<Vec<f64>>,
+        pub high_gain:        Option<Vec<f64>>,
+        pub high_mid_freq:    Option<Vec<f64>>,
+        pub high_mid_gain:    Option<Vec<f64>>,
+        pub high_mid_width:   Option<Vec<f64>>,
+        pub high_pass_filter: Option<Vec<ToggleOr<f64>>>,
+        pub high_width:       Option<Vec<f64>>,
+        pub low_freq:         Option<Vec<f64>>,
+        pub low_gain:         Option<Vec<f64>>,
+        pub low_mid_freq:     Option<Vec<f64>>,
+        pub low_mid_gain:     Option<Vec<f64>>,
+        pub low_mid_width:    Option<Vec<f64>>,
+        pub low_width:        Option<Vec<f64>>,
+        pub output_gain:      Option<Vec<f64>>,
+    }
+
+    impl ToParameterMap for ReaeqParameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            if let Some(value) = &self.bypass {
+                insert_parameter_value(&mut values, "bypass", value);
+            }
+            if let Some(value) = &self.high_freq {
+                insert_parameter_value(&mut values, "high_freq", value);
+            }
+            if let Some(value) = &self.high_gain {
+                insert_parameter_value(&mut values, "high_gain", value);
+            }
+            if let Some(value) = &self.high_mid_freq {
+                insert_parameter_value(&mut values, "high_mid_freq", value);
+            }
+            if let Some(value) = &self.high_mid_gain {
+                insert_parameter_value(&mut values, "high_mid_gain", value);
+            }
+            if let Some(value) = &self.high_mid_width {
+                insert_parameter_value(&mut values, "high_mid_width", value);
+            }
+            if let Some(value) = &self.high_pass_filter {
+                insert_parameter_value(&mut values, "high_pass_filter", value);
+            }
+            if let Some(value) = &self.high_width {
+                insert_parameter_value(&mut values, "high_width", value);
+            }
+            if let Some(value) = &self.low_freq {
+                insert_parameter_value(&mut values, "low_freq", value);
+            }
+            if let Some(value) = &self.low_gain {
+                insert_parameter_value(&mut values, "low_gain", value);
+            }
+            if let Some(value) = &self.low_mid_freq {
+                insert_parameter_value(&mut values, "low_mid_freq", value);
+            }
+            if let Some(value) = &self.low_mid_gain {
+                insert_parameter_value(&mut values, "low_mid_gain", value);
+            }
+            if let Some(value) = &self.low_mid_width {
+                insert_parameter_value(&mut values, "low_mid_width", value);
+            }
+            if let Some(value) = &self.low_width {
+                insert_parameter_value(&mut values, "low_width", value);
+            }
+            if let Some(value) = &self.output_gain {
+                insert_parameter_value(&mut values, "output_gain", value);
+            }
+            values
+        }
+    }
+
+    impl FromParameterMap for ReaeqParameters {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { bypass:           get_parameter_value(values, "bypass")?,
+                      high_freq:        get_parameter_value(values, "high_freq")?,
+                      high_gain:        get_parameter_value(values, "high_gain")?,
+                      high_mid_freq:    get_parameter_value(values, "high_mid_freq")?,
+                      high_mid_gain:    get_parameter_value(values, "high_mid_gain")?,
+                      high_mid_width:   get_parameter_value(values, "high_mid_width")?,
+                      high_pass_filter: get_parameter_value(values, "high_pass_filter")?,
+                      high_width:       get_parameter_value(values, "high_width")?,
+                      low_freq:         get_parameter_value(values, "low_freq")?,
+                      low_gain:         get_parameter_value(values, "low_gain")?,
+                      low_mid_freq:     get_parameter_value(values, "low_mid_freq")?,
+                      low_mid_gain:     get_parameter_value(values, "low_mid_gain")?,
+                      low_mid_width:    get_parameter_value(values, "low_mid_width")?,
+                      low_width:        get_parameter_value(values, "low_width")?,
+                      output_gain:      get_parameter_value(values, "output_gain")?, })
+        }
+    }
+
+    impl Patch for ReaeqParameters {
+        fn merge(self, other: Self) -> Self {
+            Self { bypass:           other.bypass.or(self.bypass),
+                   high_freq:        other.high_freq.or(self.high_freq),
+                   high_gain:        other.high_gain.or(self.high_gain),
+                   high_mid_freq:    other.high_mid_freq.or(self.high_mid_freq),
+                   high_mid_gain:    other.high_mid_gain.or(self.high_mid_gain),
+                   high_mid_width:   other.high_mid_width.or(self.high_mid_width),
+                   high_pass_filter: other.high_pass_filter.or(self.high_pass_filter),
+                   high_width:       other.high_width.or(self.high_width),
+                   low_freq:         other.low_freq.or(self.low_freq),
+                   low_gain:         other.low_gain.or(self.low_gain),
+                   low_mid_freq:     other.low_mid_freq.or(self.low_mid_freq),
+                   low_mid_gain:     other.low_mid_gain.or(self.low_mid_gain),
+                   low_mid_width:    other.low_mid_width.or(self.low_mid_width),
+                   low_width:        other.low_width.or(self.low_width),
+                   output_gain:      other.output_gain.or(self.output_gain), }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.bypass.is_none()
+            && self.high_freq.is_none()
+            && self.high_gain.is_none()
+            && self.high_mid_freq.is_none()
+            && self.high_mid_gain.is_none()
+            && self.high_mid_width.is_none()
+            && self.high_pass_filter.is_none()
+            && self.high_width.is_none()
+            && self.low_freq.is_none()
+            && self.low_gain.is_none()
+            && self.low_mid_freq.is_none()
+            && self.low_mid_gain.is_none()
+            && self.low_mid_width.is_none()
+            && self.low_width.is_none()
+            && self.output_gain.is_none()
+        }
+    }
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+    pub struct ReaeqReports {
+        pub output_peak: Option<Stereo<f64>>,
+    }
+    pub mod reaeq {
+        use super::*;
+
+        pub const BYPASS_NAME: &str = "bypass";
+        pub const BYPASS_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                          ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const HIGH_FREQ_NAME: &str = "high_freq";
+        pub const HIGH_FREQ_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(20_f64), ModelValue::Number(24000_f64))];
+        pub const HIGH_GAIN_NAME: &str = "high_gain";
+        pub const HIGH_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-24_f64), ModelValue::Number(24_f64))];
+        pub const HIGH_MID_FREQ_NAME: &str = "high_mid_freq";
+        pub const HIGH_MID_FREQ_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(20_f64), ModelValue::Number(24000_f64))];
+        pub const HIGH_MID_GAIN_NAME: &str = "high_mid_gain";
+        pub const HIGH_MID_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-24_f64), ModelValue::Number(24_f64))];
+        pub const HIGH_MID_WIDTH_NAME: &str = "high_mid_width";
+        pub const HIGH_MID_WIDTH_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(0.05_f64), ModelValue::Number(4_f64))];
+        pub const HIGH_PASS_FILTER_NAME: &str = "high_pass_filter";
+        pub const HIGH_PASS_FILTER_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                    ModelValueOption::Range(ModelValue::Number(20_f64),
+                                                                                            ModelValue::Number(2000_f64))];
+        pub const HIGH_WIDTH_NAME: &str = "high_width";
+        pub const HIGH_WIDTH_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(0.05_f64), ModelValue::Number(4_f64))];
+        pub const LOW_FREQ_NAME: &str = "low_freq";
+        pub const LOW_FREQ_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(20_f64), ModelValue::Number(24000_f64))];
+        pub const LOW_GAIN_NAME: &str = "low_gain";
+        pub const LOW_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-24_f64), ModelValue::Number(24_f64))];
+        pub const LOW_MID_FREQ_NAME: &str = "low_mid_freq";
+        pub const LOW_MID_FREQ_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(20_f64), ModelValue::Number(24000_f64))];
+        pub const LOW_MID_GAIN_NAME: &str = "low_mid_gain";
+        pub const LOW_MID_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-24_f64), ModelValue::Number(24_f64))];
+        pub const LOW_MID_WIDTH_NAME: &str = "low_mid_width";
+        pub const LOW_MID_WIDTH_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(0.05_f64), ModelValue::Number(4_f64))];
+        pub const LOW_WIDTH_NAME: &str = "low_width";
+        pub const LOW_WIDTH_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(0.05_f64), ModelValue::Number(4_f64))];
+        pub const OUTPUT_GAIN_NAME: &str = "output_gain";
+        pub const OUTPUT_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-60_f64), ModelValue::Number(24_f64))];
+    }
+    pub use self::reaeq::{HIGH_FREQ_NAME, HIGH_FREQ_VALUES};
+    pub use self::reaeq::{HIGH_GAIN_NAME, HIGH_GAIN_VALUES};
+    pub use self::reaeq::{HIGH_MID_FREQ_NAME, HIGH_MID_FREQ_VALUES};
+    pub use self::reaeq::{HIGH_MID_GAIN_NAME, HIGH_MID_GAIN_VALUES};
+    pub use self::reaeq::{HIGH_MID_WIDTH_NAME, HIGH_MID_WIDTH_VALUES};
+    pub use self::reaeq::{HIGH_PASS_FILTER_NAME, HIGH_PASS_FILTER_VALUES};
+    pub use self::reaeq::{HIGH_WIDTH_NAME, HIGH_WIDTH_VALUES};
+    pub use self::reaeq::{LOW_FREQ_NAME, LOW_FREQ_VALUES};
+    pub use self::reaeq::{LOW_GAIN_NAME, LOW_GAIN_VALUES};
+    pub use self::reaeq::{LOW_MID_FREQ_NAME, LOW_MID_FREQ_VALUES};
+    pub use self::reaeq::{LOW_MID_GAIN_NAME, LOW_MID_GAIN_VALUES};
+    pub use self::reaeq::{LOW_MID_WIDTH_NAME, LOW_MID_WIDTH_VALUES};
+    pub use self::reaeq::{LOW_WIDTH_NAME, LOW_WIDTH_VALUES};
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+    pub struct ReaxcompPreset {
+        pub attack:      Vec<f64>,
+        pub band_enable: Vec<bool>,
+        pub band_gain:   Vec<f64>,
+        pub bypass:      Vec<bool>,
+        pub crossover:   Vec<f64>,
+        pub knee:        Vec<f64>,
+        pub output_gain: Vec<f64>,
+        pub ratio:       Vec<f64>,
+        pub release:     Vec<f64>,
+        pub threshold:   Vec<f64>,
+    }
+
+    impl ToParameterMap for ReaxcompPreset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            insert_parameter_value(&mut values, "attack", &self.attack);
+            insert_parameter_value(&mut values, "band_enable", &self.band_enable);
+            insert_parameter_value(&mut values, "band_gain", &self.band_gain);
+            insert_parameter_value(&mut values, "bypass", &self.bypass);
+            insert_parameter_value(&mut values, "crossover", &self.crossover);
+            insert_parameter_value(&mut values, "knee", &self.knee);
+            insert_parameter_value(&mut values, "output_gain", &self.output_gain);
+            insert_parameter_value(&mut values, "ratio", &self.ratio);
+            insert_parameter_value(&mut values, "release", &self.release);
+            insert_parameter_value(&mut values, "threshold", &self.threshold);
+            values
+        }
+    }
+
+    impl FromParameterMap for ReaxcompPreset {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { attack:      require_parameter_value(values, "attack")?,
+                      band_enable: require_parameter_value(values, "band_enable")?,
+                      band_gain:   require_parameter_value(values, "band_gain")?,
+                      bypass:      require_parameter_value(values, "bypass")?,
+                      crossover:   require_parameter_value(values, "crossover")?,
+                      knee:        require_parameter_value(values, "knee")?,
+                      output_gain: require_parameter_value(values, "output_gain")?,
+                      ratio:       require_parameter_value(values, "ratio")?,
+                      release:     require_parameter_value(values, "release")?,
+                      threshold:   require_parameter_value(values, "threshold")?, })
+        }
+    }
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+    pub struct ReaxcompParameters {
+        pub attack:      Option<Vec<f64>>,
+        pub band_enable: Option<Vec<bool>>,
+        pub band_gain:   Option<Vec<f64>>,
+        pub bypass:      Option<Vec<bool>>,
+        pub crossover:   Option<Vec<f64>>,
+        pub knee:        Option<Vec<f64>>,
+        pub output_gain: Option<Vec<f64>>,
+        pub ratio:       Option<Vec<f64>>,
+        pub release:     Option<Vec<f64>>,
+        pub threshold:   Option<Vec<f64>>,
+    }
+
+    impl ToParameterMap for ReaxcompParameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            if let Some(value) = &self.attack {
+                insert_parameter_value(&mut values, "attack", value);
+            }
+            if let Some(value) = &self.band_enable {
+                insert_parameter_value(&mut values, "band_enable", value);
+            }
+            if let Some(value) = &self.band_gain {
+                insert_parameter_value(&mut values, "band_gain", value);
+            }
+            if let Some(value) = &self.bypass {
+                insert_parameter_value(&mut values, "bypass", value);
+            }
+            if let Some(value) = &self.crossover {
+                insert_parameter_value(&mut values, "crossover", value);
+            }
+            if let Some(value) = &self.knee {
+                insert_parameter_value(&mut values, "knee", value);
+            }
+            if let Some(value) = &self.output_gain {
+                insert_parameter_value(&mut values, "output_gain", value);
+            }
+            if let Some(value) = &self.ratio {
+                insert_parameter_value(&mut values, "ratio", value);
+            }
+            if let Some(value) = &self.release {
+                insert_parameter_value(&mut values, "release", value);
+            }
+            if let Some(value) = &self.threshold {
+                insert_parameter_value(&mut values, "threshold", value);
+            }
+            values
+        }
+    }
+
+    impl FromParameterMap for ReaxcompParameters {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { attack:      get_parameter_value(values, "attack")?,
+                      band_enable: get_parameter_value(values, "band_enable")?,
+                      band_gain:   get_parameter_value(values, "band_gain")?,
+                      bypass:      get_parameter_value(values, "bypass")?,
+                      crossover:   get_parameter_value(values, "crossover")?,
+                      knee:        get_parameter_value(values, "knee")?,
+                      output_gain: get_parameter_value(values, "output_gain")?,
+                      ratio:       get_parameter_value(values, "ratio")?,
+                      release:     get_parameter_value(values, "release")?,
+                      threshold:   get_parameter_value(values, "threshold")?, })
+        }
+    }
+
+    impl Patch for ReaxcompParameters {
+        fn merge(self, other: Self) -> Self {
+            Self { attack:      other.attack.or(self.attack),
+                   band_enable: other.band_enable.or(self.band_enable),
+                   band_gain:   other.band_gain.or(self.band_gain),
+                   bypass:      other.bypass.or(self.bypass),
+                   crossover:   other.crossover.or(self.crossover),
+                   knee:        other.knee.or(self.knee),
+                   output_gain: other.output_gain.or(self.output_gain),
+                   ratio:       other.ratio.or(self.ratio),
+                   release:     other.release.or(self.release),
+                   threshold:   other.threshold.or(self.threshold), }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.attack.is_none()
+            && self.band_enable.is_none()
+            && self.band_gain.is_none()
+            && self.bypass.is_none()
+            && self.crossover.is_none()
+            && self.knee.is_none()
+            && self.output_gain.is_none()
+            && self.ratio.is_none()
+            && self.release.is_none()
+            && self.threshold.is_none()
+        }
+    }
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+    pub struct ReaxcompReports {
+        pub gain_reduction: Option<Vec<f64>>,
+        pub output_peak:    Option<Stereo<f64>>,
+    }
+    pub mod reaxcomp {
+        use super::*;
+
+        pub const ATTACK_NAME: &str = "attack";
+        pub const ATTACK_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(0_f64), ModelValue::Number(500_f64))];
+        pub const BAND_ENABLE_NAME: &str = "band_enable";
+        pub const BAND_ENABLE_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                               ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const BAND_GAIN_NAME: &str = "band_gain";
+        pub const BAND_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-24_f64), ModelValue::Number(24_f64))];
+        pub const BYPASS_NAME: &str = "bypass";
+        pub const BYPASS_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                          ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const CROSSOVER_NAME: &str = "crossover";
+        pub const CROSSOVER_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(20_f64), ModelValue::Number(24000_f64))];
+        pub const KNEE_NAME: &str = "knee";
+        pub const KNEE_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(0_f64), ModelValue::Number(24_f64))];
+        pub const OUTPUT_GAIN_NAME: &str = "output_gain";
+        pub const OUTPUT_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-60_f64), ModelValue::Number(24_f64))];
+        pub const RATIO_NAME: &str = "ratio";
+        pub const RATIO_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(1_f64), ModelValue::Number(100_f64))];
+        pub const RELEASE_NAME: &str = "release";
+        pub const RELEASE_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(0_f64), ModelValue::Number(5000_f64))];
+        pub const THRESHOLD_NAME: &str = "threshold";
+        pub const THRESHOLD_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-60_f64), ModelValue::Number(0_f64))];
+    }
+    pub use self::reaxcomp::{BAND_ENABLE_NAME, BAND_ENABLE_VALUES};
+    pub use self::reaxcomp::{BAND_GAIN_NAME, BAND_GAIN_VALUES};
+    pub use self::reaxcomp::{CROSSOVER_NAME, CROSSOVER_VALUES};
+}
+
 pub mod distopik {
 
     use super::*;
@@ -60,6 +808,46 @@ pub mod distopik {
         pub low_mid_width:    Stereo<bool>,
         pub output_pad:       Stereo<ToggleOr<i64>>,
     }
+
+    impl ToParameterMap for Dual1084Preset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            insert_parameter_value(&mut values, "eql_toggle", &self.eql_toggle);
+            insert_parameter_value(&mut values, "high_freq", &self.high_freq);
+            insert_parameter_value(&mut values, "high_gain", &self.high_gain);
+            insert_parameter_value(&mut values, "high_mid_freq", &self.high_mid_freq);
+            insert_parameter_value(&mut values, "high_mid_gain", &self.high_mid_gain);
+            insert_parameter_value(&mut values, "high_mid_width", &self.high_mid_width);
+            insert_parameter_value(&mut values, "high_pass_filter", &self.high_pass_filter);
+            insert_parameter_value(&mut values, "input_gain", &self.input_gain);
+            insert_parameter_value(&mut values, "low_freq", &self.low_freq);
+            insert_parameter_value(&mut values, "low_gain", &self.low_gain);
+            insert_parameter_value(&mut values, "low_mid_freq", &self.low_mid_freq);
+            insert_parameter_value(&mut values, "low_mid_gain", &self.low_mid_gain);
+            insert_parameter_value(&mut values, "low_mid_width", &self.low_mid_width);
+            insert_parameter_value(&mut values, "output_pad", &self.output_pad);
+            values
+        }
+    }
+
+    impl FromParameterMap for Dual1084Preset {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { eql_toggle:       require_parameter_value(values, "eql_toggle")?,
+                      high_freq:        require_parameter_value(values, "high_freq")?,
+                      high_gain:        require_parameter_value(values, "high_gain")?,
+                      high_mid_freq:    require_parameter_value(values, "high_mid_freq")?,
+                      high_mid_gain:    require_parameter_value(values, "high_mid_gain")?,
+                      high_mid_width:   require_parameter_value(values, "high_mid_width")?,
+                      high_pass_filter: require_parameter_value(values, "high_pass_filter")?,
+                      input_gain:       require_parameter_value(values, "input_gain")?,
+                      low_freq:         require_parameter_value(values, "low_freq")?,
+                      low_gain:         require_parameter_value(values, "low_gain")?,
+                      low_mid_freq:     require_parameter_value(values, "low_mid_freq")?,
+                      low_mid_gain:     require_parameter_value(values, "low_mid_gain")?,
+                      low_mid_width:    require_parameter_value(values, "low_mid_width")?,
+                      output_pad:       require_parameter_value(values, "output_pad")?, })
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct Dual1084Parameters {
         pub eql_toggle:       Option<Stereo<bool>>,
@@ -77,88 +865,211 @@ pub mod distopik {
         pub low_mid_width:    Option<Stereo<bool>>,
         pub output_pad:       Option<Stereo<ToggleOr<i64>>>,
     }
+
+    impl ToParameterMap for Dual1084Parameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            if let Some(value) = &self.eql_toggle {
+                insert_parameter_value(&mut values, "eql_toggle", value);
+            }
+            if let Some(value) = &self.high_freq {
+                insert_parameter_value(&mut values, "high_freq", value);
+            }
+            if let Some(value) = &self.high_gain {
+                insert_parameter_value(&mut values, "high_gain", value);
+            }
+            if let Some(value) = &self.high_mid_freq {
+                insert_parameter_value(&mut values, "high_mid_freq", value);
+            }
+            if let Some(value) = &self.high_mid_gain {
+                insert_parameter_value(&mut values, "high_mid_gain", value);
+            }
+            if let Some(value) = &self.high_mid_width {
+                insert_parameter_value(&mut values, "high_mid_width", value);
+            }
+            if let Some(value) = &self.high_pass_filter {
+                insert_parameter_value(&mut values, "high_pass_filter", value);
+            }
+            if let Some(value) = &self.input_gain {
+                insert_parameter_value(&mut values, "input_gain", value);
+            }
+            if let Some(value) = &self.low_freq {
+                insert_parameter_value(&mut values, "low_freq", value);
+            }
+            if let Some(value) = &self.low_gain {
+                insert_parameter_value(&mut values, "low_gain", value);
+            }
+            if let Some(value) = &self.low_mid_freq {
+                insert_parameter_value(&mut values, "low_mid_freq", value);
+            }
+            if let Some(value) = &self.low_mid_gain {
+                insert_parameter_value(&mut values, "low_mid_gain", value);
+            }
+            if let Some(value) = &self.low_mid_width {
+                insert_parameter_value(&mut values, "low_mid_width", value);
+            }
+            if let Some(value) = &self.output_pad {
+                insert_parameter_value(&mut values, "output_pad", value);
+            }
+            values
+        }
+    }
+
+    impl FromParameterMap for Dual1084Parameters {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { eql_toggle:       get_parameter_value(values, "eql_toggle")?,
+                      high_freq:        get_parameter_value(values, "high_freq")?,
+                      high_gain:        get_parameter_value(values, "high_gain")?,
+                      high_mid_freq:    get_parameter_value(values, "high_mid_freq")?,
+                      high_mid_gain:    get_parameter_value(values, "high_mid_gain")?,
+                      high_mid_width:   get_parameter_value(values, "high_mid_width")?,
+                      high_pass_filter: get_parameter_value(values, "high_pass_filter")?,
+                      input_gain:       get_parameter_value(values, "input_gain")?,
+                      low_freq:         get_parameter_value(values, "low_freq")?,
+                      low_gain:         get_parameter_value(values, "low_gain")?,
+                      low_mid_freq:     get_parameter_value(values, "low_mid_freq")?,
+                      low_mid_gain:     get_parameter_value(values, "low_mid_gain")?,
+                      low_mid_width:    get_parameter_value(values, "low_mid_width")?,
+                      output_pad:       get_parameter_value(values, "output_pad")?, })
+        }
+    }
+
+    impl Patch for Dual1084Parameters {
+        fn merge(self, other: Self) -> Self {
+            Self { eql_toggle:       other.eql_toggle.or(self.eql_toggle),
+                   high_freq:        other.high_freq.or(self.high_freq),
+                   high_gain:        other.high_gain.or(self.high_gain),
+                   high_mid_freq:    other.high_mid_freq.or(self.high_mid_freq),
+                   high_mid_gain:    other.high_mid_gain.or(self.high_mid_gain),
+                   high_mid_width:   other.high_mid_width.or(self.high_mid_width),
+                   high_pass_filter: other.high_pass_filter.or(self.high_pass_filter),
+                   input_gain:       other.input_gain.or(self.input_gain),
+                   low_freq:         other.low_freq.or(self.low_freq),
+                   low_gain:         other.low_gain.or(self.low_gain),
+                   low_mid_freq:     other.low_mid_freq.or(self.low_mid_freq),
+                   low_mid_gain:     other.low_mid_gain.or(self.low_mid_gain),
+                   low_mid_width:    other.low_mid_width.or(self.low_mid_width),
+                   output_pad:       other.output_pad.or(self.output_pad), }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.eql_toggle.is_none()
+            && self.high_freq.is_none()
+            && self.high_gain.is_none()
+            && self.high_mid_freq.is_none()
+            && self.high_mid_gain.is_none()
+            && self.high_mid_width.is_none()
+            && self.high_pass_filter.is_none()
+            && self.input_gain.is_none()
+            && self.low_freq.is_none()
+            && self.low_gain.is_none()
+            && self.low_mid_freq.is_none()
+            && self.low_mid_gain.is_none()
+            && self.low_mid_width.is_none()
+            && self.output_pad.is_none()
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct Dual1084Reports {}
+    pub mod dual1084 {
+        use super::*;
 
-    pub const EQL_TOGGLE_NAME: &str = "eql_toggle";
-    pub const EQL_TOGGLE_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                          ModelValueOption::Single(ModelValue::Bool(true))];
-    pub const HIGH_FREQ_NAME: &str = "high_freq";
-    pub const HIGH_FREQ_VALUES: [ModelValueOption; 6] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                         ModelValueOption::Single(ModelValue::Number(8000_f64)),
-                                                         ModelValueOption::Single(ModelValue::Number(10000_f64)),
-                                                         ModelValueOption::Single(ModelValue::Number(12000_f64)),
-                                                         ModelValueOption::Single(ModelValue::Number(16000_f64)),
-                                                         ModelValueOption::Single(ModelValue::Number(20000_f64))];
-    pub const HIGH_GAIN_NAME: &str = "high_gain";
-    pub const HIGH_GAIN_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(-16_f64), ModelValue::Number(16_f64))];
-    pub const HIGH_MID_FREQ_NAME: &str = "high_mid_freq";
-    pub const HIGH_MID_FREQ_VALUES: [ModelValueOption; 12] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                              ModelValueOption::Single(ModelValue::Number(360_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(480_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(720_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(1600_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(2400_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(3200_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(3900_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(4800_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(6400_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(7200_f64)),
-                                                              ModelValueOption::Single(ModelValue::Number(8400_f64))];
-    pub const HIGH_MID_GAIN_NAME: &str = "high_mid_gain";
-    pub const HIGH_MID_GAIN_VALUES: [ModelValueOption; 1] =
-        [ModelValueOption::Range(ModelValue::Number(-12_f64), ModelValue::Number(12_f64))];
-    pub const HIGH_MID_WIDTH_NAME: &str = "high_mid_width";
-    pub const HIGH_MID_WIDTH_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+        pub const EQL_TOGGLE_NAME: &str = "eql_toggle";
+        pub const EQL_TOGGLE_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
                                                               ModelValueOption::Single(ModelValue::Bool(true))];
-    pub const HIGH_PASS_FILTER_NAME: &str = "high_pass_filter";
-    pub const HIGH_PASS_FILTER_VALUES: [ModelValueOption; 6] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                                ModelValueOption::Single(ModelValue::Number(22_f64)),
-                                                                ModelValueOption::Single(ModelValue::Number(45_f64)),
-                                                                ModelValueOption::Single(ModelValue::Number(70_f64)),
-                                                                ModelValueOption::Single(ModelValue::Number(160_f64)),
-                                                                ModelValueOption::Single(ModelValue::Number(360_f64))];
-    pub const INPUT_GAIN_NAME: &str = "input_gain";
-    pub const INPUT_GAIN_VALUES: [ModelValueOption; 8] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                          ModelValueOption::Single(ModelValue::Number(-10_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(-5_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(0_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(5_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(10_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(15_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(20_f64))];
-    pub const LOW_FREQ_NAME: &str = "low_freq";
-    pub const LOW_FREQ_VALUES: [ModelValueOption; 6] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                        ModelValueOption::Single(ModelValue::Number(20_f64)),
-                                                        ModelValueOption::Single(ModelValue::Number(35_f64)),
-                                                        ModelValueOption::Single(ModelValue::Number(60_f64)),
-                                                        ModelValueOption::Single(ModelValue::Number(110_f64)),
-                                                        ModelValueOption::Single(ModelValue::Number(220_f64))];
-    pub const LOW_GAIN_NAME: &str = "low_gain";
-    pub const LOW_GAIN_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(-16_f64), ModelValue::Number(16_f64))];
-    pub const LOW_MID_FREQ_NAME: &str = "low_mid_freq";
-    pub const LOW_MID_FREQ_VALUES: [ModelValueOption; 12] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                             ModelValueOption::Single(ModelValue::Number(120_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(180_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(240_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(360_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(480_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(720_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(1600_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(2400_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(3200_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(4800_f64)),
-                                                             ModelValueOption::Single(ModelValue::Number(7200_f64))];
-    pub const LOW_MID_GAIN_NAME: &str = "low_mid_gain";
-    pub const LOW_MID_GAIN_VALUES: [ModelValueOption; 1] =
-        [ModelValueOption::Range(ModelValue::Number(-12_f64), ModelValue::Number(12_f64))];
-    pub const LOW_MID_WIDTH_NAME: &str = "low_mid_width";
-    pub const LOW_MID_WIDTH_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                             ModelValueOption::Single(ModelValue::Bool(true))];
-    pub const OUTPUT_PAD_NAME: &str = "output_pad";
-    pub const OUTPUT_PAD_VALUES: [ModelValueOption; 3] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                          ModelValueOption::Single(ModelValue::Number(-10_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(-20_f64))];
+        pub const HIGH_FREQ_NAME: &str = "high_freq";
+        pub const HIGH_FREQ_VALUES: [ModelValueOption; 6] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                             ModelValueOption::Single(ModelValue::Number(8000_f64)),
+                                                             ModelValueOption::Single(ModelValue::Number(10000_f64)),
+                                                             ModelValueOption::Single(ModelValue::Number(12000_f64)),
+                                                             ModelValueOption::Single(ModelValue::Number(16000_f64)),
+                                                             ModelValueOption::Single(ModelValue::Number(20000_f64))];
+        pub const HIGH_GAIN_NAME: &str = "high_gain";
+        pub const HIGH_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-16_f64), ModelValue::Number(16_f64))];
+        pub const HIGH_MID_FREQ_NAME: &str = "high_mid_freq";
+        pub const HIGH_MID_FREQ_VALUES: [ModelValueOption; 12] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                  ModelValueOption::Single(ModelValue::Number(360_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(480_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(720_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(1600_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(2400_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(3200_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(3900_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(4800_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(6400_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(7200_f64)),
+                                                                  ModelValueOption::Single(ModelValue::Number(8400_f64))];
+        pub const HIGH_MID_GAIN_NAME: &str = "high_mid_gain";
+        pub const HIGH_MID_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-12_f64), ModelValue::Number(12_f64))];
+        pub const HIGH_MID_WIDTH_NAME: &str = "high_mid_width";
+        pub const HIGH_MID_WIDTH_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                  ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const HIGH_PASS_FILTER_NAME: &str = "high_pass_filter";
+        pub const HIGH_PASS_FILTER_VALUES: [ModelValueOption; 6] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                    ModelValueOption::Single(ModelValue::Number(22_f64)),
+                                                                    ModelValueOption::Single(ModelValue::Number(45_f64)),
+                                                                    ModelValueOption::Single(ModelValue::Number(70_f64)),
+                                                                    ModelValueOption::Single(ModelValue::Number(160_f64)),
+                                                                    ModelValueOption::Single(ModelValue::Number(360_f64))];
+        pub const INPUT_GAIN_NAME: &str = "input_gain";
+        pub const INPUT_GAIN_VALUES: [ModelValueOption; 8] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                              ModelValueOption::Single(ModelValue::Number(-10_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(-5_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(0_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(5_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(10_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(15_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(20_f64))];
+        pub const LOW_FREQ_NAME: &str = "low_freq";
+        pub const LOW_FREQ_VALUES: [ModelValueOption; 6] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                            ModelValueOption::Single(ModelValue::Number(20_f64)),
+                                                            ModelValueOption::Single(ModelValue::Number(35_f64)),
+                                                            ModelValueOption::Single(ModelValue::Number(60_f64)),
+                                                            ModelValueOption::Single(ModelValue::Number(110_f64)),
+                                                            ModelValueOption::Single(ModelValue::Number(220_f64))];
+        pub const LOW_GAIN_NAME: &str = "low_gain";
+        pub const LOW_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-16_f64), ModelValue::Number(16_f64))];
+        pub const LOW_MID_FREQ_NAME: &str = "low_mid_freq";
+        pub const LOW_MID_FREQ_VALUES: [ModelValueOption; 12] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                 ModelValueOption::Single(ModelValue::Number(120_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(180_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(240_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(360_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(480_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(720_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(1600_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(2400_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(3200_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(4800_f64)),
+                                                                 ModelValueOption::Single(ModelValue::Number(7200_f64))];
+        pub const LOW_MID_GAIN_NAME: &str = "low_mid_gain";
+        pub const LOW_MID_GAIN_VALUES: [ModelValueOption; 1] =
+            [ModelValueOption::Range(ModelValue::Number(-12_f64), ModelValue::Number(12_f64))];
+        pub const LOW_MID_WIDTH_NAME: &str = "low_mid_width";
+        pub const LOW_MID_WIDTH_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                                 ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const OUTPUT_PAD_NAME: &str = "output_pad";
+        pub const OUTPUT_PAD_VALUES: [ModelValueOption; 3] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                              ModelValueOption::Single(ModelValue::Number(-10_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(-20_f64))];
+    }
+    pub use self::dual1084::{EQL_TOGGLE_NAME, EQL_TOGGLE_VALUES};
+    pub use self::dual1084::{HIGH_FREQ_NAME, HIGH_FREQ_VALUES};
+    pub use self::dual1084::{HIGH_GAIN_NAME, HIGH_GAIN_VALUES};
+    pub use self::dual1084::{HIGH_MID_FREQ_NAME, HIGH_MID_FREQ_VALUES};
+    pub use self::dual1084::{HIGH_MID_GAIN_NAME, HIGH_MID_GAIN_VALUES};
+    pub use self::dual1084::{HIGH_MID_WIDTH_NAME, HIGH_MID_WIDTH_VALUES};
+    pub use self::dual1084::{HIGH_PASS_FILTER_NAME, HIGH_PASS_FILTER_VALUES};
+    pub use self::dual1084::{INPUT_GAIN_NAME, INPUT_GAIN_VALUES};
+    pub use self::dual1084::{LOW_FREQ_NAME, LOW_FREQ_VALUES};
+    pub use self::dual1084::{LOW_GAIN_NAME, LOW_GAIN_VALUES};
+    pub use self::dual1084::{LOW_MID_FREQ_NAME, LOW_MID_FREQ_VALUES};
+    pub use self::dual1084::{LOW_MID_GAIN_NAME, LOW_MID_GAIN_VALUES};
+    pub use self::dual1084::{LOW_MID_WIDTH_NAME, LOW_MID_WIDTH_VALUES};
+    pub use self::dual1084::{OUTPUT_PAD_NAME, OUTPUT_PAD_VALUES};
 
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
     pub struct SummatraPreset {
@@ -166,23 +1077,83 @@ pub mod distopik {
         pub input:      Vec<f64>,
         pub pan:        Vec<f64>,
     }
+
+    impl ToParameterMap for SummatraPreset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            insert_parameter_value(&mut values, "bus_assign", &self.bus_assign);
+            insert_parameter_value(&mut values, "input", &self.input);
+            insert_parameter_value(&mut values, "pan", &self.pan);
+            values
+        }
+    }
+
+    impl FromParameterMap for SummatraPreset {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { bus_assign: require_parameter_value(values, "bus_assign")?,
+                      input:      require_parameter_value(values, "input")?,
+                      pan:        require_parameter_value(values, "pan")?, })
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct SummatraParameters {
         pub bus_assign: Option<Vec<u64>>,
         pub input:      Option<Vec<f64>>,
         pub pan:        Option<Vec<f64>>,
     }
+
+    impl ToParameterMap for SummatraParameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            if let Some(value) = &self.bus_assign {
+                insert_parameter_value(&mut values, "bus_assign", value);
+            }
+            if let Some(value) = &self.input {
+                insert_parameter_value(&mut values, "input", value);
+            }
+            if let Some(value) = &self.pan {
+                insert_parameter_value(&mut values, "pan", value);
+            }
+            values
+        }
+    }
+
+    impl FromParameterMap for SummatraParameters {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { bus_assign: get_parameter_value(values, "bus_assign")?,
+                      input:      get_parameter_value(values, "input")?,
+                      pan:        get_parameter_value(values, "pan")?, })
+        }
+    }
+
+    impl Patch for SummatraParameters {
+        fn merge(self, other: Self) -> Self {
+            Self { bus_assign: other.bus_assign.or(self.bus_assign),
+                   input:      other.input.or(self.input),
+                   pan:        other.pan.or(self.pan), }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.bus_assign.is_none() && self.input.is_none() && self.pan.is_none()
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct SummatraReports {}
+    pub mod summatra {
+        use super::*;
 
-    pub const BUS_ASSIGN_NAME: &str = "bus_assign";
-    pub const BUS_ASSIGN_VALUES: [ModelValueOption; 3] = [ModelValueOption::Single(ModelValue::Number(0_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(1_f64)),
-                                                          ModelValueOption::Single(ModelValue::Number(2_f64))];
-    pub const INPUT_NAME: &str = "input";
-    pub const INPUT_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(-48_f64), ModelValue::Number(10_f64))];
-    pub const PAN_NAME: &str = "pan";
-    pub const PAN_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(-1_f64), ModelValue::Number(1_f64))];
+        pub const BUS_ASSIGN_NAME: &str = "bus_assign";
+        pub const BUS_ASSIGN_VALUES: [ModelValueOption; 3] = [ModelValueOption::Single(ModelValue::Number(0_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(1_f64)),
+                                                              ModelValueOption::Single(ModelValue::Number(2_f64))];
+        pub const INPUT_NAME: &str = "input";
+        pub const INPUT_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(-48_f64), ModelValue::Number(10_f64))];
+        pub const PAN_NAME: &str = "pan";
+        pub const PAN_VALUES: [ModelValueOption; 1] = [ModelValueOption::Range(ModelValue::Number(-1_f64), ModelValue::Number(1_f64))];
+    }
+    pub use self::summatra::{BUS_ASSIGN_NAME, BUS_ASSIGN_VALUES};
+    pub use self::summatra::{INPUT_NAME, INPUT_VALUES};
+    pub use self::summatra::{PAN_NAME, PAN_VALUES};
 }
 
 pub mod netio {
@@ -193,10 +1164,50 @@ pub mod netio {
     pub struct PowerPdu4CPreset {
         pub power: Vec<bool>,
     }
+
+    impl ToParameterMap for PowerPdu4CPreset {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            insert_parameter_value(&mut values, "power", &self.power);
+            values
+        }
+    }
+
+    impl FromParameterMap for PowerPdu4CPreset {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { power: require_parameter_value(values, "power")?, })
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct PowerPdu4CParameters {
         pub power: Option<Vec<bool>>,
     }
+
+    impl ToParameterMap for PowerPdu4CParameters {
+        fn to_parameter_map(&self) -> ParameterValues {
+            let mut values = ParameterValues::new();
+            if let Some(value) = &self.power {
+                insert_parameter_value(&mut values, "power", value);
+            }
+            values
+        }
+    }
+
+    impl FromParameterMap for PowerPdu4CParameters {
+        fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+            Ok(Self { power: get_parameter_value(values, "power")?, })
+        }
+    }
+
+    impl Patch for PowerPdu4CParameters {
+        fn merge(self, other: Self) -> Self {
+            Self { power: other.power.or(self.power), }
+        }
+
+        fn is_empty(&self) -> bool {
+            self.power.is_none()
+        }
+    }
     #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
     pub struct PowerPdu4CReports {
         pub current:      Option<Vec<f64>>,
@@ -204,10 +1215,14 @@ pub mod netio {
         pub power:        Option<Vec<bool>>,
         pub power_factor: Option<Vec<f64>>,
     }
+    pub mod power_pdu_4c {
+        use super::*;
 
-    pub const POWER_NAME: &str = "power";
-    pub const POWER_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
-                                                     ModelValueOption::Single(ModelValue::Bool(true))];
+        pub const POWER_NAME: &str = "power";
+        pub const POWER_VALUES: [ModelValueOption; 2] = [ModelValueOption::Single(ModelValue::Bool(false)),
+                                                         ModelValueOption::Single(ModelValue::Bool(true))];
+    }
+    pub use self::power_pdu_4c::{POWER_NAME, POWER_VALUES};
 }
 
 pub fn schemas() -> RootSchema {
@@ -220,6 +1235,15 @@ pub fn schemas() -> RootSchema {
                    schema_for!(self::audiocloud::Insert2X2Preset),
                    schema_for!(self::audiocloud::Insert2X2Parameters),
                    schema_for!(self::audiocloud::Insert2X2Reports),
+                   schema_for!(self::cockos::ReacompPreset),
+                   schema_for!(self::cockos::ReacompParameters),
+                   schema_for!(self::cockos::ReacompReports),
+                   schema_for!(self::cockos::ReaeqPreset),
+                   schema_for!(self::cockos::ReaeqParameters),
+                   schema_for!(self::cockos::ReaeqReports),
+                   schema_for!(self::cockos::ReaxcompPreset),
+                   schema_for!(self::cockos::ReaxcompParameters),
+                   schema_for!(self::cockos::ReaxcompReports),
                    schema_for!(self::distopik::Dual1084Preset),
                    schema_for!(self::distopik::Dual1084Parameters),
                    schema_for!(self::distopik::Dual1084Reports),
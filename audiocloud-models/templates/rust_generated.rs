@@ -12,7 +12,15 @@ use super::*;
 {{ RustPresetModelTemplate::new(name, model) }}
 {{ RustParamsModelTemplate::new(name, model) }}
 {{ RustReportsModelTemplate::new(name, model) }}
+{%- if !model.parameters.is_empty() %}
+pub mod {{ name|lowercase }} {
+use super::*;
 {{ RustConstantsTemplate::new(model) }}
+}
+{%- for parameter_id in self.flat_parameters(manufacturer, name) %}
+pub use self::{{ name|lowercase }}::{ {{ parameter_id|screaming_snake }}_NAME, {{ parameter_id|screaming_snake }}_VALUES };
+{%- endfor %}
+{%- endif %}
 {% endfor %}
 }
 {% endfor %}
@@ -4,3 +4,63 @@ pub struct {{ rust_name }}Parameters {
     pub {{property_id}}: Option<{{ (property_spec, model)|rust_param_type }}>,
 {%- endfor -%}
 }
+
+impl ToParameterMap for {{ rust_name }}Parameters {
+{%- if model.parameters.is_empty() %}
+    fn to_parameter_map(&self) -> ParameterValues {
+        ParameterValues::new()
+    }
+{%- else %}
+    fn to_parameter_map(&self) -> ParameterValues {
+        let mut values = ParameterValues::new();
+{%- for (property_id, property_spec) in model.parameters.iter().sorted_by_key(self::get_key) %}
+        if let Some(value) = &self.{{property_id}} {
+            insert_parameter_value(&mut values, "{{ property_id }}", value);
+        }
+{%- endfor %}
+        values
+    }
+{%- endif %}
+}
+
+impl FromParameterMap for {{ rust_name }}Parameters {
+{%- if model.parameters.is_empty() %}
+    fn from_parameter_map(_values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+        Ok(Self {})
+    }
+{%- else %}
+    fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+        Ok(Self {
+{%- for (property_id, property_spec) in model.parameters.iter().sorted_by_key(self::get_key) %}
+            {{property_id}}: get_parameter_value(values, "{{ property_id }}")?,
+{%- endfor %}
+        })
+    }
+{%- endif %}
+}
+
+impl Patch for {{ rust_name }}Parameters {
+{%- if model.parameters.is_empty() %}
+    fn merge(self, _other: Self) -> Self {
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        true
+    }
+{%- else %}
+    fn merge(self, other: Self) -> Self {
+        Self {
+{%- for (property_id, property_spec) in model.parameters.iter().sorted_by_key(self::get_key) %}
+            {{property_id}}: other.{{property_id}}.or(self.{{property_id}}),
+{%- endfor %}
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+{%- for (property_id, property_spec) in model.parameters.iter().sorted_by_key(self::get_key) %}
+        {% if !loop.first %}&& {% endif %}self.{{property_id}}.is_none()
+{%- endfor %}
+    }
+{%- endif %}
+}
@@ -4,3 +4,35 @@ pub struct {{ rust_name }}Preset {
     pub {{property_id}}: {{ (property_spec, model)|rust_preset_type }},
 {%- endfor -%}
 }
+
+impl ToParameterMap for {{ rust_name }}Preset {
+{%- if model.parameters.is_empty() %}
+    fn to_parameter_map(&self) -> ParameterValues {
+        ParameterValues::new()
+    }
+{%- else %}
+    fn to_parameter_map(&self) -> ParameterValues {
+        let mut values = ParameterValues::new();
+{%- for (property_id, property_spec) in model.parameters.iter().sorted_by_key(self::get_key) %}
+        insert_parameter_value(&mut values, "{{ property_id }}", &self.{{property_id}});
+{%- endfor %}
+        values
+    }
+{%- endif %}
+}
+
+impl FromParameterMap for {{ rust_name }}Preset {
+{%- if model.parameters.is_empty() %}
+    fn from_parameter_map(_values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+        Ok(Self {})
+    }
+{%- else %}
+    fn from_parameter_map(values: &ParameterValues) -> Result<Self, ParameterValuesError> {
+        Ok(Self {
+{%- for (property_id, property_spec) in model.parameters.iter().sorted_by_key(self::get_key) %}
+            {{property_id}}: require_parameter_value(values, "{{ property_id }}")?,
+{%- endfor %}
+        })
+    }
+{%- endif %}
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use audiocloud_api::cloud::domains::{DomainConfig, DomainEngineConfig};
+use audiocloud_api::cloud::CloudError;
+use audiocloud_api::common::model::ResourceId;
+use audiocloud_api::{DynamicInstanceNode, Model, ModelId, TaskSpec};
+use serde_json::json;
+
+fn load_model(yaml: &str, resources: impl IntoIterator<Item = (ResourceId, f64)>) -> Model {
+    let mut model: Model = serde_yaml::from_str(yaml).expect("parse model");
+    model.resources = resources.into_iter().collect();
+    model
+}
+
+fn models() -> HashMap<ModelId, Model> {
+    HashMap::from([(ModelId::new("distopik".to_owned(), "summatra".to_owned()),
+                    load_model(include_str!("../models/distopik_summatra.yaml"),
+                               [(ResourceId::CPU, 1.5), (ResourceId::Memory, 0.5)])),
+                   (ModelId::new("distopik".to_owned(), "dual1084".to_owned()),
+                    load_model(include_str!("../models/distopik_dual1084.yaml"), [(ResourceId::CPU, 0.75)]))])
+}
+
+fn task_with_dynamic(model_names: &[&str]) -> TaskSpec {
+    let mut spec = TaskSpec::default();
+    for (i, name) in model_names.iter().enumerate() {
+        spec.dynamic.insert(format!("dyn-{i}").into(),
+                            DynamicInstanceNode { model_id:             ModelId::new("distopik".to_owned(), name.to_string()),
+                                                  parameters:           Default::default(),
+                                                  parameter_automation: Default::default(), });
+    }
+    spec
+}
+
+fn engine(resources: impl IntoIterator<Item = (ResourceId, f64)>) -> DomainEngineConfig {
+    DomainEngineConfig { dynamic_instances:    Default::default(),
+                         max_concurrent_tasks: 4,
+                         resources:            resources.into_iter().collect(),
+                         sample_rate:          192_000, }
+}
+
+#[test]
+fn estimate_sums_dynamic_instances() {
+    let resources = task_with_dynamic(&["summatra", "dual1084", "dual1084"]).estimate_resources(&models());
+
+    assert_eq!(resources.get(&ResourceId::CPU), Some(&3.0));
+    assert_eq!(resources.get(&ResourceId::Memory), Some(&0.5));
+    assert_eq!(resources.get(&ResourceId::GPU), None);
+}
+
+#[test]
+fn estimate_ignores_unknown_models() {
+    let resources = task_with_dynamic(&["summatra", "unknown"]).estimate_resources(&models());
+
+    assert_eq!(resources.get(&ResourceId::CPU), Some(&1.5));
+}
+
+#[test]
+fn engine_with_enough_resources_accepts_task() {
+    let required = task_with_dynamic(&["summatra", "dual1084"]).estimate_resources(&models());
+
+    assert!(engine([(ResourceId::CPU, 4.0), (ResourceId::Memory, 1.0)]).check_resources(&required)
+                                                                       .is_ok());
+}
+
+#[test]
+fn engine_without_enough_resources_rejects_task() {
+    let required = task_with_dynamic(&["summatra", "summatra"]).estimate_resources(&models());
+
+    assert!(matches!(engine([(ResourceId::CPU, 4.0)]).check_resources(&required),
+                     Err(CloudError::OutOfResource { resource: ResourceId::Memory,
+                                                     requested, .. }) if requested == 1.0));
+}
+
+#[test]
+fn domain_finds_engine_that_fits_task() {
+    let domain: DomainConfig = serde_json::from_value(json!({
+        "domain_id": "test",
+        "engines": {
+            "small": { "max_concurrent_tasks": 1, "resources": { "cpu": 1.0 }, "sample_rate": 192000 },
+            "large": { "max_concurrent_tasks": 1, "resources": { "cpu": 8.0, "ram": 2.0 }, "sample_rate": 192000 },
+        },
+        "models": { "inline": { "models": {} } },
+        "public_host": "localhost",
+    })).expect("parse domain config");
+
+    let fits = task_with_dynamic(&["summatra", "dual1084"]).estimate_resources(&models());
+    assert_eq!(domain.find_engine_for_resources(&fits).map(ToString::to_string).ok(),
+               Some("large".to_owned()));
+
+    let too_large = task_with_dynamic(&["summatra"; 6]).estimate_resources(&models());
+    assert!(matches!(domain.find_engine_for_resources(&too_large),
+                     Err(CloudError::OutOfResource { resource: ResourceId::Memory,
+                                                     available,
+                                                     requested, }) if available == 2.0 && requested == 3.0));
+}
+
+#[test]
+fn reaper_models_declare_their_resources() {
+    let models = ["reaeq", "reacomp", "reaxcomp"].into_iter()
+                                                 .zip([include_str!("../models/cockos_reaeq.yaml"),
+                                                       include_str!("../models/cockos_reacomp.yaml"),
+                                                       include_str!("../models/cockos_reaxcomp.yaml")])
+                                                 .map(|(name, yaml)| {
+                                                     (ModelId::new("cockos".to_owned(), name.to_owned()),
+                                                      serde_yaml::from_str::<Model>(yaml).expect("parse model"))
+                                                 })
+                                                 .collect::<HashMap<_, _>>();
+
+    let mut spec = TaskSpec::default();
+    for (i, model_id) in models.keys().enumerate() {
+        spec.dynamic.insert(format!("dyn-{i}").into(),
+                            DynamicInstanceNode { model_id:             model_id.clone(),
+                                                  parameters:           Default::default(),
+                                                  parameter_automation: Default::default(), });
+    }
+
+    let resources = spec.estimate_resources(&models);
+    assert!((resources[&ResourceId::CPU] - 0.65).abs() < 1e-9);
+    assert!((resources[&ResourceId::Memory] - 0.2).abs() < 1e-9);
+}
+
+#[test]
+fn parameter_constants_keep_their_manufacturer_paths() {
+    assert_eq!(audiocloud_models::distopik::HIGH_FREQ_NAME, "high_freq");
+    assert_eq!(audiocloud_models::distopik::dual1084::HIGH_FREQ_NAME, "high_freq");
+    assert_eq!(audiocloud_models::cockos::reacomp::BYPASS_NAME, "bypass");
+}